@@ -1,25 +1,47 @@
 //! CLI entrypoint for Nexus Local.
 
 
-use clap::{Parser, Subcommand};
-use anyhow::Result;
-use nexus_core::{IndexOptions, Indexer, Embedder, IndexEvent, SyncTextExtractor, VectorStore, PagedExtractor, ExtractedPage, LexicalIndex, NexusConfig, FileWatcher, ServiceManager};
-use ocr::{PlainTextExtractor, SyncOcrEngine};
-use embed::{LocalEmbedder, Embedder as EmbedderTrait};
-use store::{LanceVectorStore, StateManager};
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand};
+use anyhow::{Context, Result};
+use nexus_core::{ChangeDetection, ChunkStrategy, deduplicate_index, delete_indexed_file, IndexOptions, IndexPreset, Indexer, RerankStrategy, ScoredResult, mmr_rerank, context_snippets, Embedder, IndexEvent, SyncTextExtractor, VectorStore, PagedExtractor, ExtractedPage, LexicalIndex, NexusConfig, FileWatcher, ServiceManager};
+use ocr::{ExtractorOptions, OcrOptions, PlainTextExtractor, SyncOcrEngine};
+use embed::{LocalEmbedder, OllamaEmbedder, Embedder as EmbedderTrait};
+use search::{RrfParams, group_by_file, reciprocal_rank_fusion};
+use store::{DistanceMetric, DocumentMetadata, IndexStats, LanceVectorStore, ListFilter, SearchMode, SearchOptions, SearchResult, SortField, StateManager};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use async_trait::async_trait;
 use sysinfo::System;
 
+mod serve;
+
 /// Result from hybrid search combining vector and lexical results.
 struct HybridResult {
     doc_id: String,
     file_path: PathBuf,
     chunk_index: usize,
     snippet: Option<String>,
+    /// Byte ranges of matched query terms within `snippet` (lexical results only).
+    highlights: Vec<Range<usize>>,
     score: f32,
     source: String,
+    context_chunks: Vec<String>,
+    /// Document title and author, where the file records them (PDFs).
+    title: Option<String>,
+    author: Option<String>,
+}
+
+impl HybridResult {
+    /// What the result is called in listings: its title (and author) if known, else its path.
+    fn display_name(&self) -> String {
+        match (&self.title, &self.author) {
+            (Some(title), Some(author)) => format!("{} ({})", title, author),
+            (Some(title), None) => title.clone(),
+            _ => self.file_path.display().to_string(),
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -28,6 +50,9 @@ struct HybridResult {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Load the config file even if it has invalid settings
+    #[arg(long, global = true)]
+    skip_config_validation: bool,
 }
 
 #[derive(Subcommand)]
@@ -53,12 +78,136 @@ enum Commands {
         /// Use GPU (CUDA) for embedding acceleration
         #[arg(long)]
         gpu: bool,
+        /// Use the INT8-quantized embedding model (needs a build with --features quantized)
+        #[arg(long)]
+        quantized: bool,
+        #[command(flatten)]
+        backend: EmbedderArgs,
         /// Maximum chunks per file (default: 500). Files generating more are skipped.
-        #[arg(long, default_value = "500")]
-        max_chunks: usize,
+        #[arg(long)]
+        max_chunks: Option<usize>,
+        /// Index files inside .zip, .tar.gz and .tar.bz2 archives
+        #[arg(long)]
+        follow_archives: bool,
+        /// How many levels of nested archives to open (default: 1)
+        #[arg(long, default_value = "1")]
+        archive_depth: usize,
+        /// Only index files changed between this git ref and HEAD
+        #[arg(long)]
+        since_commit: Option<String>,
+        /// Maximum snippet length stored for result previews
+        #[arg(long, default_value = "200")]
+        snippet_length: usize,
+        /// Tuning preset: cpu, balanced or quality. Explicit flags override preset values.
+        #[arg(long, default_value = "balanced")]
+        preset: String,
+        /// Threads for text extraction (default: one per CPU core)
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Maximum chunks per embedding call (default: whole file)
+        #[arg(long)]
+        batch_size: Option<usize>,
+        /// Downscale images larger than this many pixels before OCR (default: 2000)
+        #[arg(long)]
+        max_image_dimension: Option<u32>,
+        /// Also compare file content hashes, not just modification times
+        /// (same as --change-detection hash)
+        #[arg(long)]
+        content_hash: bool,
+        /// How changed files are detected: mtime, hash or mtime-then-hash
+        #[arg(long, default_value = "mtime")]
+        change_detection: String,
+        /// Stop after indexing this many chunks; the next run picks up the remaining files
+        #[arg(long)]
+        max_total_chunks: Option<usize>,
+        /// Give up on files whose text extraction takes longer than this many seconds (0 = no limit)
+        #[arg(long, default_value_t = 30)]
+        file_timeout: u64,
+        /// Skip files marked linguist-generated or linguist-documentation in .gitattributes
+        #[arg(long)]
+        exclude_generated: bool,
+        /// Only descend this many directory levels below the root (1 = files directly in it);
+        /// overrides index.max_depth in the config
+        #[arg(long)]
+        max_depth: Option<usize>,
+        /// Index hidden files and directories (names starting with a dot) too;
+        /// overrides index.skip_hidden in the config
+        #[arg(long)]
+        include_hidden: bool,
+        /// Index files matched by .gitignore files too
+        #[arg(long)]
+        no_gitignore: bool,
+        /// Index files matched by .nexusignore files too
+        #[arg(long)]
+        no_nexusignore: bool,
+        /// Extract and chunk PDF pages in parallel (uses more memory)
+        #[arg(long)]
+        parallel_pages: bool,
+        /// Password to try on encrypted PDFs (can be repeated); ~/.nexus/pdf_passwords.txt is also read
+        #[arg(long)]
+        pdf_password: Vec<String>,
+        /// Index what Jupyter notebook cells printed along with their code
+        #[arg(long)]
+        notebook_outputs: bool,
+        /// Text files that aren't UTF-8: skip, detect the encoding, or decode as the named one (e.g. latin1)
+        #[arg(long, default_value = "skip")]
+        encoding_fallback: String,
+        /// Tesseract language(s) for OCR of images, e.g. "fra" or "eng+deu"
+        #[arg(long, default_value = "eng")]
+        ocr_lang: String,
+        /// Warn about files whose chunks are longer than the embedding model reads
+        #[arg(long)]
+        warn_on_truncation: bool,
+        /// After indexing, check that each file's first chunk is its own top search hit
+        #[arg(long)]
+        verify: bool,
+        /// Print the session ID and every embedded chunk
+        #[arg(short, long)]
+        verbose: bool,
+        /// Chunk by model tokens (at most this many per chunk) instead of characters
+        #[arg(long)]
+        max_tokens: Option<usize>,
+        /// Report the files and chunks that would be indexed without embedding or storing anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Show indexer/search status
-    Status,
+    Status {
+        /// Keep refreshing the status until Ctrl-C
+        #[arg(long)]
+        watch: bool,
+        /// Refresh interval in seconds for --watch
+        #[arg(long, default_value = "5")]
+        interval: u64,
+    },
+    /// Show detailed index statistics
+    Stats,
+    /// List the local embedding models that can be loaded by name
+    Models,
+    /// List indexed files with their chunk counts and index times
+    List {
+        /// Output one JSON object per line
+        #[arg(long)]
+        json: bool,
+        /// Only files with this extension (e.g. pdf)
+        #[arg(long)]
+        file_type: Option<String>,
+        /// Order: path, chunks (most first) or date (most recently indexed first)
+        #[arg(long, default_value = "path")]
+        sort_by: String,
+        /// Show at most this many files
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Remove a file (or, with --recursive, a directory) from the index
+    Delete {
+        path: String,
+        /// Remove every indexed file under the directory
+        #[arg(short, long)]
+        recursive: bool,
+    },
+    /// Remove index entries for files that no longer exist on disk
+    Vacuum,
     /// Search for a query
     Search {
         query: String,
@@ -70,11 +219,104 @@ enum Commands {
         /// Number of results to return
         #[arg(long, short = 'n', default_value = "5")]
         limit: usize,
+        /// Show this many neighbouring chunks on each side of every result
+        #[arg(long, default_value = "0")]
+        context: usize,
+        /// Result order: score (best match first) or date (most recently indexed first)
+        #[arg(long, default_value = "score")]
+        sort_by: String,
+        /// Vector distance for semantic ranking: l2 or cosine
+        #[arg(long, default_value = "l2")]
+        distance_metric: String,
+        /// Only return chunks indexed at or after this time: a date (2024-05-01),
+        /// an age (7d, 12h, 2w) or Unix seconds
+        #[arg(long)]
+        since: Option<String>,
+        /// Only return chunks indexed before this time (same formats as --since)
+        #[arg(long)]
+        before: Option<String>,
+        /// Only return results from files whose path matches this glob,
+        /// e.g. "~/Documents/Work/**"
+        #[arg(long)]
+        filter_path: Option<String>,
+        /// Only return results from these file types (comma-separated extensions, e.g. "pdf,md")
+        #[arg(long = "type", value_delimiter = ',')]
+        file_type: Option<Vec<String>>,
+        /// Write results to this file instead of stdout; .json, .jsonl or .csv picks the format
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// With --output, print results to stdout as well
+        #[arg(long, requires = "output")]
+        tee: bool,
+        /// Group results by file, showing each file's best chunks under it
+        #[arg(long, conflicts_with = "output")]
+        group: bool,
+        /// Reciprocal Rank Fusion constant for hybrid mode; lower favours top-ranked
+        /// results (default: search.rrf_k from the config, 60)
+        #[arg(long)]
+        rrf_k: Option<f64>,
+        /// Re-rank results to drop near-duplicates: mmr (default: search.rerank from the config)
+        #[arg(long)]
+        rerank: Option<String>,
+        /// MMR trade-off between relevance (1.0) and diversity (0.0)
+        /// (default: search.mmr_lambda from the config, 0.7)
+        #[arg(long)]
+        mmr_lambda: Option<f32>,
+        #[command(flatten)]
+        backend: EmbedderArgs,
     },
+    /// Repair the index after a crash
+    Repair {
+        /// Remove a stale lexical index writer lock
+        #[arg(long)]
+        lexical_unlock: bool,
+        /// Rebuild the lexical index from the chunks stored in the vector store
+        #[arg(long)]
+        rebuild_lexical: bool,
+    },
+    /// Re-embed every indexed file in place
+    Reindex {
+        /// The embedding model changed, so all existing embeddings must be replaced
+        #[arg(long)]
+        model_changed: bool,
+        /// Use GPU (CUDA) for embedding acceleration
+        #[arg(long)]
+        gpu: bool,
+        #[command(flatten)]
+        backend: EmbedderArgs,
+    },
+    /// Check the index for inconsistencies and suggest fixes
+    Doctor,
     /// Explain a document by ID
     Explain {
         doc_id: String,
     },
+    /// Optimize the index
+    Optimize {
+        /// Remove near-duplicate embeddings (expensive on large indexes)
+        #[arg(long)]
+        dedup: bool,
+        /// Cosine similarity above which embeddings count as duplicates
+        #[arg(long, default_value = "0.98")]
+        threshold: f32,
+        /// Merge all lexical index segments into one
+        #[arg(long)]
+        merge_segments: bool,
+        /// Build an IVF-PQ vector index (needs at least 256 embeddings, worthwhile from ~10 000)
+        #[arg(long)]
+        vector_index: bool,
+        /// IVF partitions for --vector-index (default: chosen from the embedding count)
+        #[arg(long, requires = "vector_index")]
+        num_partitions: Option<u32>,
+        /// PQ sub-vectors for --vector-index; must divide the embedding dimension
+        #[arg(long, requires = "vector_index")]
+        num_sub_vectors: Option<u32>,
+        /// Distance the vector index is built for; match the --distance-metric you search with
+        #[arg(long, default_value = "l2", requires = "vector_index")]
+        distance_metric: String,
+    },
+    /// Reclaim the disk space left by deleted embeddings
+    Compact,
     /// Watch directories for changes and auto-index
     Watch {
         /// Override config roots with specific paths
@@ -90,6 +332,58 @@ enum Commands {
         #[command(subcommand)]
         action: ServiceAction,
     },
+    /// Serve search, status and indexing over a local HTTP API
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value = "7878")]
+        port: u16,
+        /// Address to bind; keep the default unless other machines need access
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        #[command(flatten)]
+        backend: EmbedderArgs,
+    },
+}
+
+/// Which model embeds chunks and queries. Search with the embedder the index was built with.
+#[derive(Args)]
+struct EmbedderArgs {
+    /// Embedding backend: local (built-in model) or ollama
+    #[arg(long, default_value = "local")]
+    embedder: String,
+    /// Ollama server for --embedder ollama
+    #[arg(long, default_value = embed::OLLAMA_BASE_URL)]
+    ollama_url: String,
+    /// Ollama embedding model for --embedder ollama
+    #[arg(long, default_value = "nomic-embed-text")]
+    ollama_model: String,
+    /// Dimension of --ollama-model's embeddings
+    #[arg(long, default_value = "768")]
+    ollama_dim: usize,
+}
+
+impl EmbedderArgs {
+    /// Human-readable name of the chosen embedder, for progress messages.
+    fn describe(&self, gpu: bool, quantized: bool) -> String {
+        if self.embedder == "ollama" {
+            format!(" (Ollama {} at {})", self.ollama_model, self.ollama_url)
+        } else if quantized {
+            " (INT8)".to_string()
+        } else if gpu {
+            " (GPU)".to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Build the chosen embedder; `gpu` and `quantized` only apply to the local model.
+    fn load(&self, gpu: bool, quantized: bool) -> Result<Box<dyn EmbedderTrait>> {
+        match self.embedder.as_str() {
+            "local" => Ok(Box::new(load_embedder(gpu, quantized)?)),
+            "ollama" => Ok(Box::new(OllamaEmbedder::new(&self.ollama_url, &self.ollama_model, self.ollama_dim))),
+            other => anyhow::bail!("Unknown embedder '{}', expected local or ollama", other),
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -104,6 +398,17 @@ enum ConfigAction {
     Show,
     /// Show the default config file path
     Path,
+    /// Show only the settings that differ from the defaults
+    Diff,
+    /// Change a single setting, e.g. `nexus config set index.chunk_size 2000`
+    Set {
+        /// Dotted key, e.g. index.chunk_size
+        key: String,
+        /// New value; lists are comma-separated
+        value: String,
+    },
+    /// Check the config file for invalid settings
+    Validate,
 }
 
 #[derive(Subcommand)]
@@ -114,6 +419,12 @@ enum ServiceAction {
     Uninstall,
     /// Show service status
     Status,
+    /// Show recent service log lines
+    Logs {
+        /// Number of lines to show
+        #[arg(long, short = 'n', default_value = "50")]
+        lines: usize,
+    },
 }
 
 /// Wrapper to adapt PlainTextExtractor (SyncOcrEngine) to SyncTextExtractor trait.
@@ -135,10 +446,22 @@ impl PagedExtractor for OcrExtractor {
     fn is_paged(&self, path: &PathBuf) -> bool {
         ocr::PagedExtractor::is_paged(&self.0, path)
     }
+    
+    fn extract_info(&self, path: &PathBuf) -> anyhow::Result<ocr::DocumentInfo> {
+        ocr::PagedExtractor::extract_info(&self.0, path)
+    }
+    
+    fn extract_pages_with_info(&self, path: &PathBuf) -> anyhow::Result<(Vec<ExtractedPage>, anyhow::Result<ocr::DocumentInfo>)> {
+        ocr::PagedExtractor::extract_pages_with_info(&self.0, path)
+    }
+    
+    fn extract_pages_parallel(&self, path: &PathBuf) -> anyhow::Result<(Vec<ExtractedPage>, anyhow::Result<ocr::DocumentInfo>)> {
+        ocr::PagedExtractor::extract_pages_parallel(&self.0, path)
+    }
 }
 
-/// Wrapper to adapt LocalEmbedder to nexus_core::Embedder trait.
-struct EmbedWrapper(LocalEmbedder);
+/// Wrapper to adapt an embed::Embedder to nexus_core::Embedder trait.
+struct EmbedWrapper(Box<dyn EmbedderTrait>);
 
 #[async_trait]
 impl Embedder for EmbedWrapper {
@@ -151,38 +474,542 @@ impl Embedder for EmbedWrapper {
     fn dimension(&self) -> usize {
         self.0.dimension()
     }
+    fn max_input_tokens(&self) -> Option<usize> {
+        self.0.max_input_tokens()
+    }
+    fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        self.0.token_spans(text)
+    }
+}
+
+/// Parameters shared by `nexus search` and the `nexus serve` search endpoint.
+struct SearchRequest<'a> {
+    query: &'a str,
+    mode: &'a str,
+    limit: usize,
+    sort_by: SortField,
+    after: Option<i64>,
+    before: Option<i64>,
+    /// Only results from files whose path matches this glob.
+    filter_path: Option<&'a glob::Pattern>,
+    /// Only results from files with one of these extensions.
+    file_types: Option<&'a [String]>,
+    /// How hybrid search fuses the semantic and lexical rankings.
+    rrf: RrfParams,
+    /// Re-ranking applied to the results, if any.
+    rerank: Option<RerankStrategy>,
+    mmr_lambda: f32,
+}
+
+impl SearchRequest<'_> {
+    /// Search options for `top_k` results with this request's filters.
+    fn search_options(&self, top_k: usize) -> SearchOptions {
+        SearchOptions::new(top_k)
+            .with_date_range(self.after, self.before)
+            .with_filter_path(self.filter_path.cloned())
+            .with_filter_type(self.file_types.map(<[String]>::to_vec))
+    }
+}
+
+/// Chunks shown under each file by `search --group`.
+const GROUPED_CHUNKS_PER_FILE: usize = 3;
+
+/// Candidates fetched per requested result when re-ranking.
+const RERANK_CANDIDATES: usize = 3;
+
+/// Run a semantic, lexical or hybrid (RRF) search against the index, then re-rank.
+async fn run_search(embedder: &dyn EmbedderTrait, store: &LanceVectorStore, lexical: &LexicalIndex, request: &SearchRequest<'_>) -> Result<Vec<HybridResult>> {
+    let Some(RerankStrategy::Mmr) = request.rerank else {
+        return fetch_results(embedder, store, lexical, request).await;
+    };
+    let candidates = SearchRequest { limit: request.limit * RERANK_CANDIDATES, ..*request };
+    let results = fetch_results(embedder, store, lexical, &candidates).await?;
+
+    let doc_ids: Vec<String> = results.iter().map(|r| r.doc_id.clone()).collect();
+    let mut stored = store.get_embeddings_by_doc_ids(&doc_ids).await?;
+    let embeddings: Vec<Vec<f32>> = doc_ids.iter().map(|id| stored.remove(id).unwrap_or_default()).collect();
+    let scored = results.iter().map(|r| ScoredResult { doc_id: r.doc_id.clone(), score: r.score }).collect();
+    let order = mmr_rerank(scored, &embeddings, request.mmr_lambda, request.limit);
+    let mut by_id: HashMap<String, HybridResult> = results.into_iter().map(|r| (r.doc_id.clone(), r)).collect();
+    Ok(order.into_iter().filter_map(|r| by_id.remove(&r.doc_id)).collect())
+}
+
+/// Retrieve up to `request.limit` results in the requested mode.
+async fn fetch_results(embedder: &dyn EmbedderTrait, store: &LanceVectorStore, lexical: &LexicalIndex, request: &SearchRequest<'_>) -> Result<Vec<HybridResult>> {
+    // Collect results based on mode
+    let results = match request.mode {
+        "semantic" | "vector" => {
+            // Vector-only search
+            let query_embedding = embedder.embed(request.query).await?;
+            let vector_results = store.search(query_embedding, &request.search_options(request.limit)).await?;
+            vector_results.into_iter().map(|r| HybridResult {
+                doc_id: r.doc_id,
+                file_path: r.metadata.file_path,
+                chunk_index: r.metadata.chunk_index,
+                snippet: r.snippet,
+                highlights: Vec::new(),
+                score: r.score,
+                source: "semantic".to_string(),
+                context_chunks: Vec::new(),
+                title: r.metadata.title,
+                author: r.metadata.author,
+            }).collect()
+        }
+        "lexical" | "keyword" => {
+            // Lexical-only search
+            let lexical_results = lexical.search_with_highlights(request.query, request.limit, SearchMode::Auto, request.sort_by, &request.search_options(request.limit))?;
+            // The lexical index has no document properties, and indexes without
+            // stored content have no excerpt; both come from the vector store
+            let doc_ids: Vec<String> = lexical_results.iter().map(|r| r.doc_id.clone()).collect();
+            let mut metadata = store.get_metadata_by_doc_ids(&doc_ids).await?;
+            let mut results = Vec::new();
+            for r in lexical_results {
+                let meta = metadata.remove(&r.doc_id);
+                let (snippet, highlights) = match r.snippet {
+                    Some(snippet) => (Some(snippet), r.highlights),
+                    None => (meta.as_ref().and_then(|m| m.snippet.clone()), Vec::new()),
+                };
+                results.push(HybridResult {
+                    doc_id: r.doc_id,
+                    file_path: PathBuf::from(r.file_path),
+                    chunk_index: r.chunk_index,
+                    snippet,
+                    highlights,
+                    score: r.score,
+                    source: "lexical".to_string(),
+                    context_chunks: Vec::new(),
+                    title: meta.as_ref().and_then(|m| m.title.clone()),
+                    author: meta.and_then(|m| m.author),
+                });
+            }
+            results
+        }
+        "hybrid" | _ => {
+            // Hybrid search with RRF
+            let query_embedding = embedder.embed(request.query).await?;
+            let vector_results = store.search(query_embedding, &request.search_options(request.limit * 2)).await?;
+            let lexical_results = lexical.search_sorted(request.query, request.limit * 2, SearchMode::Auto, SortField::Score, &request.search_options(request.limit * 2))?;
+            
+            // Apply Reciprocal Rank Fusion (RRF)
+            let semantic_ids: Vec<&str> = vector_results.iter().map(|r| r.doc_id.as_str()).collect();
+            let lexical_ids: Vec<&str> = lexical_results.iter().map(|r| r.doc_id.as_str()).collect();
+            let mut fused = reciprocal_rank_fusion(&semantic_ids, &lexical_ids, &request.rrf);
+            fused.truncate(request.limit);
+            
+            // Document properties are only kept in the vector store
+            let lexical_only: Vec<String> = fused.iter()
+                .filter(|f| f.semantic_rank.is_none())
+                .map(|f| f.doc_id.clone())
+                .collect();
+            let mut metadata = store.get_metadata_by_doc_ids(&lexical_only).await?;
+            let mut results = Vec::new();
+            for f in fused {
+                let (snippet, file_path, chunk_index, title, author) = match (f.semantic_rank, f.lexical_rank) {
+                    (Some(rank), _) => {
+                        let r = &vector_results[rank];
+                        (r.snippet.clone(), r.metadata.file_path.clone(), r.metadata.chunk_index, r.metadata.title.clone(), r.metadata.author.clone())
+                    }
+                    (None, Some(rank)) => {
+                        let r = &lexical_results[rank];
+                        let meta = metadata.remove(&f.doc_id);
+                        let title = meta.as_ref().and_then(|m| m.title.clone());
+                        (None, PathBuf::from(&r.file_path), r.chunk_index, title, meta.and_then(|m| m.author))
+                    }
+                    (None, None) => unreachable!("fused results come from one of the rankings"),
+                };
+                results.push(HybridResult {
+                    doc_id: f.doc_id,
+                    file_path,
+                    chunk_index,
+                    snippet,
+                    highlights: Vec::new(),
+                    score: f.score,
+                    source: "hybrid".to_string(),
+                    context_chunks: Vec::new(),
+                    title,
+                    author,
+                });
+            }
+            results
+        }
+    };
+    Ok(results)
+}
+
+/// Parse a `--filter-path` glob, expanding a leading `~`.
+fn parse_filter_path(pattern: &str) -> Result<glob::Pattern> {
+    glob::Pattern::new(&shellexpand::tilde(pattern))
+        .with_context(|| format!("Invalid path filter '{}'", pattern))
+}
+
+/// JSON shape of a search result, as printed by `search --json`.
+fn result_json(r: &HybridResult) -> serde_json::Value {
+    serde_json::json!({
+        "doc_id": r.doc_id,
+        "score": r.score,
+        "file_path": r.file_path,
+        "chunk_index": r.chunk_index,
+        "snippet": r.snippet,
+        "source": r.source,
+        "context_chunks": r.context_chunks,
+        "title": r.title,
+        "author": r.author
+    })
+}
+
+/// Group results by file, best file first, keeping each file's `max_chunks_per_file` best chunks.
+fn group_results(results: Vec<HybridResult>, max_chunks_per_file: usize) -> Vec<(PathBuf, f32, Vec<HybridResult>)> {
+    let chunks = results.iter().map(|r| SearchResult {
+        doc_id: r.doc_id.clone(),
+        score: r.score,
+        snippet: None,
+        metadata: DocumentMetadata {
+            doc_id: r.doc_id.clone(),
+            file_path: r.file_path.clone(),
+            file_type: String::new(),
+            chunk_index: r.chunk_index,
+            snippet: None,
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        },
+        context_chunks: Vec::new(),
+    }).collect();
+    let mut by_id: HashMap<String, HybridResult> = results.into_iter().map(|r| (r.doc_id.clone(), r)).collect();
+    group_by_file(chunks, max_chunks_per_file)
+        .into_iter()
+        .map(|file| {
+            let chunks = file.chunks.iter().filter_map(|c| by_id.remove(&c.doc_id)).collect();
+            (file.file_path, file.score, chunks)
+        })
+        .collect()
+}
+
+/// Print a result's snippet and context lines below its header.
+fn print_result_body(result: &HybridResult) {
+    if let Some(snippet) = &result.snippet {
+        if result.highlights.is_empty() {
+            let preview: String = snippet.chars().take(80).collect();
+            println!("     > {}...", preview.replace('\n', " "));
+        } else {
+            println!("     > {}", bold_ranges(snippet, &result.highlights).replace('\n', " "));
+        }
+    }
+    for chunk in &result.context_chunks {
+        let preview: String = chunk.chars().take(80).collect();
+        println!("     | {}...", preview.replace('\n', " "));
+    }
+}
+
+/// Read the vector and lexical document counts and the file stats from the data directory.
+async fn read_status_counts(data_dir: &Path) -> Result<(usize, usize, IndexStats)> {
+    let store = LanceVectorStore::open_existing(data_dir.to_path_buf()).await?;
+    let lexical = LexicalIndex::new(data_dir.to_path_buf())?;
+    let stats = StateManager::new(data_dir)?.stats_with_lexical(&lexical)?;
+    Ok((store.count().await, lexical.count().unwrap_or(0), stats))
+}
+
+/// Print the status block, with deltas against the previous refresh if given.
+fn print_status(data_dir: &Path, count: usize, lexical_count: usize, stats: &IndexStats, previous: Option<(usize, usize)>) {
+    let trend = |now: usize, before: Option<usize>| match before {
+        Some(b) if now != b => format!(" ({:+} since last refresh)", now as i64 - b as i64),
+        _ => String::new(),
+    };
+    println!("nexus status");
+    println!("  store: {:?}", data_dir);
+    println!("  vector embeddings: {}{}", count, trend(count, previous.map(|p| p.0)));
+    let lexical_files = stats.lexical_docs_by_file.as_ref().map(|m| format!(" from {} files", m.len())).unwrap_or_default();
+    println!("  lexical documents: {}{}{}", lexical_count, lexical_files, trend(lexical_count, previous.map(|p| p.1)));
+    println!("  indexed files: {} ({} chunks)", stats.total_files, stats.total_doc_ids);
+    if !stats.by_extension.is_empty() {
+        let mut extensions: Vec<(&String, &usize)> = stats.by_extension.iter().collect();
+        extensions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let breakdown: Vec<String> = extensions.iter()
+            .map(|(ext, n)| format!("{}: {} {}", ext, n, if **n == 1 { "file" } else { "files" }))
+            .collect();
+        println!("    {}", breakdown.join(", "));
+    }
+}
+
+/// Parse a `--since`/`--before` value into Unix seconds.
+/// Accepts a UTC date (`2024-05-01`), an age relative to `now` (`30m`, `12h`, `7d`, `2w`)
+/// or plain Unix seconds.
+fn parse_time_bound(value: &str, now: i64) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<i64>() {
+        return Ok(secs);
+    }
+    let unit = match value.chars().last() {
+        Some('m') => Some(60),
+        Some('h') => Some(3600),
+        Some('d') => Some(86_400),
+        Some('w') => Some(7 * 86_400),
+        _ => None,
+    };
+    if let Some(unit) = unit {
+        if let Ok(n) = value[..value.len() - 1].parse::<i64>() {
+            return Ok(now - n * unit);
+        }
+    }
+    let parts: Vec<&str> = value.split('-').collect();
+    if let [y, m, d] = parts[..] {
+        if let (Ok(y), Ok(m), Ok(d)) = (y.parse::<i64>(), m.parse::<u32>(), d.parse::<u32>()) {
+            if (1..=12).contains(&m) && (1..=31).contains(&d) {
+                return Ok(days_from_civil(y, m, d) * 86_400);
+            }
+        }
+    }
+    anyhow::bail!("invalid time '{}': expected a date (2024-05-01), an age (7d) or Unix seconds", value)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of `days_from_civil`: `(year, month, day)` for days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+/// Unix seconds as `YYYY-MM-DD HH:MM` (UTC).
+fn format_timestamp(secs: i64) -> String {
+    let (y, m, d) = civil_from_days(secs.div_euclid(86_400));
+    let minutes = secs.rem_euclid(86_400) / 60;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", y, m, d, minutes / 60, minutes % 60)
+}
+
+/// Write search results to `path`, formatted by its extension: `.jsonl` (one
+/// object per line), `.csv` (header row, context chunks omitted) or a JSON array.
+fn write_results(path: &Path, results: &[serde_json::Value]) -> Result<()> {
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    let contents = match extension.as_deref() {
+        Some("jsonl") => results.iter()
+            .map(|r| serde_json::to_string(r).map(|line| line + "\n"))
+            .collect::<serde_json::Result<String>>()?,
+        Some("csv") => {
+            let columns = ["doc_id", "score", "file_path", "chunk_index", "source", "snippet"];
+            let mut csv = columns.join(",") + "\n";
+            for result in results {
+                let row: Vec<String> = columns.iter()
+                    .map(|column| match &result[*column] {
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::String(s) => csv_field(s),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+            csv
+        }
+        _ => serde_json::to_string_pretty(results)? + "\n",
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("failed to write results to {}", path.display()))
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wrap the byte `ranges` of `text` in ANSI bold.
+fn bold_ranges(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut out = String::with_capacity(text.len() + ranges.len() * 8);
+    let mut pos = 0;
+    for range in ranges {
+        out.push_str(&text[pos..range.start]);
+        out.push_str("\x1b[1m");
+        out.push_str(&text[range.clone()]);
+        out.push_str("\x1b[0m");
+        pos = range.end;
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// Load the embedding model. The quantized model always runs on the CPU.
+fn load_embedder(gpu: bool, quantized: bool) -> Result<LocalEmbedder> {
+    if !quantized {
+        return LocalEmbedder::new_with_options(gpu);
+    }
+    #[cfg(feature = "quantized")]
+    {
+        LocalEmbedder::new_quantized()
+    }
+    #[cfg(not(feature = "quantized"))]
+    {
+        anyhow::bail!("the quantized model requires building with --features quantized")
+    }
+}
+
+/// Load the config, checking it unless `--skip-config-validation` was given.
+fn load_config(skip_validation: bool) -> Result<NexusConfig> {
+    if skip_validation {
+        NexusConfig::load_unvalidated()
+    } else {
+        NexusConfig::load()
+    }
+}
+
+/// The extractor for an indexing run, set up from the run's options.
+fn build_extractor(options: &IndexOptions) -> OcrExtractor {
+    OcrExtractor(options.plain_text_extractor())
+}
+
+/// Passwords for encrypted PDFs saved in `~/.nexus/pdf_passwords.txt`.
+fn saved_pdf_passwords() -> Result<Vec<String>> {
+    match NexusConfig::pdf_passwords_path() {
+        Some(path) => NexusConfig::load_pdf_passwords(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn chunks_per_sec(chunks: usize, elapsed_ms: u64) -> f64 {
+    chunks as f64 * 1000.0 / elapsed_ms.max(1) as f64
+}
+
+/// Size in the largest unit that keeps it at or above 1 (B, KB, MB, GB).
+fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KB", "MB"] {
+        if size < 1024.0 {
+            return if unit == "B" { format!("{} B", bytes) } else { format!("{:.1} {}", size, unit) };
+        }
+        size /= 1024.0;
+    }
+    format!("{:.1} GB", size)
+}
+
+/// Table of the files a dry run would index, with chunk counts and sizes.
+/// Files inside archives have no size of their own and show `-`.
+fn print_dry_run_summary(chunks_by_file: &BTreeMap<PathBuf, usize>) {
+    let mut total_chunks = 0;
+    let mut total_bytes = 0;
+    println!("{:>8}  {:>10}  path", "chunks", "size");
+    for (path, chunks) in chunks_by_file {
+        let size = std::fs::metadata(path).map(|m| m.len()).ok();
+        total_chunks += chunks;
+        total_bytes += size.unwrap_or(0);
+        let size = size.map(format_size).unwrap_or_else(|| "-".to_string());
+        println!("{:>8}  {:>10}  {}", chunks, size, path.display());
+    }
+    println!("would index {} files, {} chunks, {}", chunks_by_file.len(), total_chunks, format_size(total_bytes));
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
+    if let Err(e) = nexus_core::telemetry::init_tracing() {
+        eprintln!("warning: tracing disabled: {}", e);
+    }
     let cli = Cli::parse();
+    let skip_config_validation = cli.skip_config_validation;
 
     match cli.command {
-        Commands::Index { path, max_memory_mb, max_file_mb, skip_ext, skip_file, skip_images, gpu, max_chunks } => {
+        Commands::Index { path, max_memory_mb, max_file_mb, skip_ext, skip_file, skip_images, gpu, quantized, backend, max_chunks, follow_archives, archive_depth, since_commit, snippet_length, preset, threads, batch_size, max_image_dimension, content_hash, change_detection, max_total_chunks, file_timeout, exclude_generated, max_depth, include_hidden, no_gitignore, no_nexusignore, parallel_pages, pdf_password, notebook_outputs, encoding_fallback, ocr_lang, warn_on_truncation, verify, verbose, max_tokens, dry_run } => {
             // Get system memory info
             let sys = System::new_all();
             let total_mem_mb = sys.total_memory() / 1024 / 1024;
             let max_mem = max_memory_mb.unwrap_or(total_mem_mb * 3 / 4);
             
+            anyhow::ensure!(max_depth != Some(0), "--max-depth must be at least 1");
+            let config = load_config(skip_config_validation)?;
+            let preset = IndexPreset::from_name(&preset)?;
+            let change_detection = if content_hash {
+                ChangeDetection::Hash
+            } else {
+                change_detection.parse()?
+            };
+            let mut options = IndexOptions { 
+                root: PathBuf::from(&path), 
+                max_file_size_bytes: max_file_mb * 1024 * 1024,
+                max_memory_bytes: max_mem * 1024 * 1024,
+                skip_files: skip_file,
+                snippet_length,
+                follow_archive_contents: follow_archives,
+                archive_depth,
+                change_detection,
+                max_total_chunks,
+                file_timeout: (file_timeout > 0).then(|| std::time::Duration::from_secs(file_timeout)),
+                auto_exclude_generated: exclude_generated,
+                max_depth: max_depth.or(config.index.max_depth),
+                exclude_hidden: config.index.skip_hidden && !include_hidden,
+                respect_gitignore: !no_gitignore,
+                use_nexusignore: !no_nexusignore,
+                parallel_pages,
+                extractor: ExtractorOptions {
+                    pdf_passwords: pdf_password,
+                    notebook_outputs,
+                    encoding_fallback: encoding_fallback.parse()?,
+                    ocr: OcrOptions::new(&ocr_lang)?,
+                    ..Default::default()
+                },
+                warn_on_truncation,
+                verbose_events: verbose,
+                dry_run,
+                ..Default::default()
+            };
+            preset.apply_to_options(&mut options);
+            
+            // Explicit flags override the preset
+            if let Some(n) = max_chunks {
+                options.max_chunks_per_file = n;
+            }
+            if let Some(n) = threads {
+                options.threads = n;
+            }
+            if let Some(n) = batch_size {
+                options.embed_batch_size = n;
+            }
+            if let Some(n) = max_image_dimension {
+                options.extractor.max_image_dimension = n;
+            }
+            if let Some(n) = max_tokens {
+                options.chunk_strategy = ChunkStrategy::Tokens(n);
+            }
+            
             // Build skip extensions list
-            let mut skip_extensions: Vec<String> = skip_ext;
+            let mut extra_skips = skip_ext;
             if skip_images {
-                for ext in ["png", "jpg", "jpeg"] {
-                    if !skip_extensions.iter().any(|s| s.to_lowercase() == ext) {
-                        skip_extensions.push(ext.to_string());
-                    }
+                extra_skips.extend(["png", "jpg", "jpeg"].map(String::from));
+            }
+            for ext in extra_skips {
+                if !options.skip_extensions.iter().any(|s| s.eq_ignore_ascii_case(&ext)) {
+                    options.skip_extensions.push(ext);
                 }
             }
             
-            eprintln!("info: indexing {}", path);
+            eprintln!("info: indexing {} (preset: {})", path, preset.name);
             eprintln!("info: memory limit {}MB (system: {}MB), max file: {}MB, max chunks: {}", 
-                max_mem, total_mem_mb, max_file_mb, max_chunks);
-            if !skip_extensions.is_empty() {
-                eprintln!("info: skipping extensions: {}", skip_extensions.join(", "));
+                max_mem, total_mem_mb, max_file_mb, options.max_chunks_per_file);
+            if !options.skip_extensions.is_empty() {
+                eprintln!("info: skipping extensions: {}", options.skip_extensions.join(", "));
             }
-            if !skip_file.is_empty() {
-                eprintln!("info: skipping files matching: {}", skip_file.join(", "));
+            if !options.skip_files.is_empty() {
+                eprintln!("info: skipping files matching: {}", options.skip_files.join(", "));
             }
 
             // Initialize data directory
@@ -191,12 +1018,12 @@ async fn main() -> Result<()> {
                 .join("nexus_local");
             std::fs::create_dir_all(&data_dir)?;
 
-            eprintln!("info: loading embedding model{}...", if gpu { " (GPU)" } else { "" });
-            let embedder = LocalEmbedder::new_with_options(gpu)?;
+            eprintln!("info: loading embedding model{}...", backend.describe(gpu, quantized));
+            let embedder = backend.load(gpu, quantized)?;
             eprintln!("info: model loaded (dim={})", embedder.dimension());
 
             eprintln!("info: opening store at {:?}", data_dir);
-            let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await?);
+            let store = Arc::new(LanceVectorStore::new(data_dir.clone(), embedder.dimension()).await?);
             eprintln!("info: {} existing embeddings", store.count().await);
 
             // Initialize state manager
@@ -207,38 +1034,41 @@ async fn main() -> Result<()> {
             let lexical = Arc::new(LexicalIndex::new(data_dir.clone())?);
             eprintln!("info: lexical index ready");
 
-            let options = IndexOptions { 
-                root: PathBuf::from(&path), 
-                chunk_size: 1500,
-                max_file_size_bytes: max_file_mb * 1024 * 1024,
-                max_memory_bytes: max_mem * 1024 * 1024,
-                max_chunks_per_file: max_chunks,
-                skip_extensions,
-                skip_files: skip_file,
-            };
-            let extractor = OcrExtractor(PlainTextExtractor);
+            options.extractor.pdf_passwords.extend(saved_pdf_passwords()?);
+
+            let extractor = build_extractor(&options);
             let embedder = EmbedWrapper(embedder);
             let indexer = Indexer::new(options, extractor, embedder, store.clone())
                 .with_state(state)
                 .with_lexical(lexical);
 
             // Run garbage collection first to clean up stale embeddings
-            eprintln!("info: running garbage collection...");
-            let gc_result = indexer.garbage_collect().await?;
-            if gc_result.embeddings_removed > 0 {
-                eprintln!("  gc: removed {} embeddings ({} deleted files, {} modified files)",
-                    gc_result.embeddings_removed,
-                    gc_result.deleted_files,
-                    gc_result.modified_files
-                );
+            if dry_run {
+                eprintln!("info: dry run, nothing will be embedded or stored");
+            } else {
+                eprintln!("info: running garbage collection...");
+                let gc_result = indexer.garbage_collect().await?;
+                if gc_result.embeddings_removed > 0 {
+                    eprintln!("  gc: removed {} embeddings ({} deleted files, {} modified files)",
+                        gc_result.embeddings_removed,
+                        gc_result.deleted_files,
+                        gc_result.modified_files
+                    );
+                }
             }
 
             let mut indexer = indexer; // Make mutable for run_with_progress
             let mut memory_skipped = 0usize;
-            let result = indexer.run_with_progress(|e| {
+            let mut embedded_chunks = 0usize;
+            let mut embed_ms = 0u64;
+            let mut dry_run_chunks: BTreeMap<PathBuf, usize> = BTreeMap::new();
+            let progress = |e: IndexEvent| {
                 match &e {
+                    IndexEvent::IndexingStarted { session_id } if verbose => eprintln!("info: session {}", session_id),
                     IndexEvent::FileStarted(p) => eprintln!("  processing {}", p.display()),
+                    IndexEvent::FileIndexed(p) if dry_run => eprintln!("  would index {}", p.display()),
                     IndexEvent::FileIndexed(p) => eprintln!("  indexed {}", p.display()),
+                    IndexEvent::ChunkProcessed(p, _) => *dry_run_chunks.entry(p.clone()).or_default() += 1,
                     IndexEvent::PageProcessed(p, page, total) => {
                         eprintln!("    page {}/{} of {}", page + 1, total, p.file_name().unwrap_or_default().to_string_lossy());
                     }
@@ -249,11 +1079,28 @@ async fn main() -> Result<()> {
                     IndexEvent::FileUnchanged(p) => eprintln!("  unchanged {}", p.display()),
                     IndexEvent::MemoryPressure(_, _) => {} // Handled via FileSkipped
                     IndexEvent::ChunkEmbedded(_, i, id) => eprintln!("    chunk {} -> {}", i, &id[..8]),
-                    IndexEvent::FileError(p, err) => eprintln!("  error: {} - {}", p.display(), err),
+                    IndexEvent::BatchEmbedded { chunk_count, elapsed_ms, tokens_estimated, .. } => {
+                        embedded_chunks += chunk_count;
+                        embed_ms += elapsed_ms;
+                        eprintln!("    embedded {} chunks (~{} tokens) in {}ms, {:.1} chunks/s",
+                            chunk_count, tokens_estimated, elapsed_ms, chunks_per_sec(*chunk_count, *elapsed_ms));
+                    }
+                    // Summarised after the run; RUST_LOG=debug shows each one
+                    IndexEvent::FileError(p, err) => log::debug!("error: {} - {}", p.display(), err),
+                    IndexEvent::IndexingPaused(reason) => {
+                        eprintln!("  paused: {}, run 'nexus index' again to continue", reason);
+                    }
                     IndexEvent::Done => {},
                     _ => {}
                 }
-            }).await?;
+            };
+            let result = match since_commit {
+                Some(from_ref) => {
+                    eprintln!("info: only indexing files changed since {}", from_ref);
+                    indexer.index_git_diff_with_progress(&from_ref, "HEAD", progress).await?
+                }
+                None => indexer.run_with_progress(progress).await?,
+            };
 
             eprintln!("done: {} indexed, {} unchanged, {} skipped, {} chunks, {} embeddings, {} errors",
                 result.files_indexed,
@@ -263,13 +1110,182 @@ async fn main() -> Result<()> {
                 result.embeddings_stored,
                 result.errors.len()
             );
+            eprintln!("info: processed {} in {:.1}s", format_size(result.bytes_processed), result.elapsed.as_secs_f64());
+            for (message, count) in result.error_summary() {
+                eprintln!("  {} file{}: '{}'", count, if count == 1 { "" } else { "s" }, message);
+            }
+            if embedded_chunks > 0 {
+                eprintln!("info: embedding throughput: {:.1} chunks/s", chunks_per_sec(embedded_chunks, embed_ms));
+            }
             if memory_skipped > 0 {
                 eprintln!("warning: {} files skipped due to memory pressure", memory_skipped);
                 eprintln!("  hint: increase limit with --max-memory-mb or re-run later");
             }
+            if dry_run {
+                print_dry_run_summary(&dry_run_chunks);
+                return Ok(());
+            }
+            if verify {
+                eprintln!("info: verifying index (one search per file)...");
+                let verification = indexer.verify().await?;
+                eprintln!("verify: {} files retrievable, {} mismatches",
+                    verification.verified, verification.mismatches.len());
+                for path in &verification.mismatches {
+                    eprintln!("  mismatch: {}", path.display());
+                }
+                if !verification.mismatches.is_empty() {
+                    eprintln!("  hint: re-embed with 'nexus reindex --model-changed'");
+                }
+            }
             eprintln!("info: total embeddings in store: {}", store.count().await);
         }
-        Commands::Status => {
+        Commands::Status { watch, interval } => {
+            // Initialize data directory
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            if !watch {
+                let (count, lexical_count, stats) = read_status_counts(&data_dir).await?;
+                print_status(&data_dir, count, lexical_count, &stats, None);
+                return Ok(());
+            }
+
+            let interval = std::time::Duration::from_secs(interval.max(1));
+            let mut previous: Option<(usize, usize)> = None;
+            loop {
+                // Reopen each refresh so writes from another process are visible
+                let (count, lexical_count, stats) = read_status_counts(&data_dir).await?;
+                let mut stdout = std::io::stdout();
+                crossterm::execute!(
+                    stdout,
+                    crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+                    crossterm::cursor::MoveTo(0, 0)
+                )?;
+                print_status(&data_dir, count, lexical_count, &stats, previous);
+                println!();
+                println!("  refreshing every {}s, press Ctrl-C to exit", interval.as_secs());
+                previous = Some((count, lexical_count));
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+            }
+        }
+        Commands::Models => {
+            println!("{:<40} {:>9}  description", "name", "dimension");
+            for model in LocalEmbedder::list_models() {
+                println!("{:<40} {:>9}  {}", model.name, model.dimension, model.description);
+            }
+        }
+        Commands::List { json, file_type, sort_by, limit } => {
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            let state = StateManager::new(&data_dir)?;
+            let filter = ListFilter { file_type, sort_by: sort_by.parse()?, limit, ..Default::default() };
+            let files = state.list_files(&filter)?;
+            let file_type_of = |path: &Path| path.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "unknown".to_string());
+
+            if json {
+                for file in &files {
+                    println!("{}", serde_json::json!({
+                        "path": file.path.to_string_lossy(),
+                        "file_type": file_type_of(&file.path),
+                        "chunks": file.doc_ids.len(),
+                        "indexed_at": file.indexed_at,
+                    }));
+                }
+            } else {
+                println!("{:>8}  {:<16}  {:<8}  path", "chunks", "indexed (UTC)", "type");
+                for file in &files {
+                    let indexed = file.indexed_at.map(format_timestamp).unwrap_or_else(|| "-".to_string());
+                    println!("{:>8}  {:<16}  {:<8}  {}", file.doc_ids.len(), indexed, file_type_of(&file.path), file.path.display());
+                }
+                eprintln!("{} files", files.len());
+            }
+        }
+        Commands::Delete { path, recursive } => {
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            let path = PathBuf::from(shellexpand::tilde(&path).as_ref());
+            let state = StateManager::new(&data_dir)?;
+            let filter = ListFilter { path_prefix: Some(path.clone()), ..Default::default() };
+            let (exact, below): (Vec<_>, Vec<_>) = state.list_files(&filter)?
+                .into_iter()
+                .map(|f| f.path)
+                .partition(|p| *p == path);
+            let files_below = below.len();
+            let targets: Vec<PathBuf> = if recursive { exact.into_iter().chain(below).collect() } else { exact };
+
+            if targets.is_empty() {
+                if files_below == 0 {
+                    eprintln!("error: {} is not in the index", path.display());
+                } else {
+                    eprintln!("error: {} is a directory with {} indexed files", path.display(), files_below);
+                    eprintln!("hint: use --recursive to remove them all");
+                }
+                return Ok(());
+            }
+
+            let store = LanceVectorStore::open_existing(data_dir.clone()).await?;
+            let lexical = LexicalIndex::new(data_dir)?;
+            let mut files_removed = 0;
+            let mut embeddings_removed = 0;
+            for target in &targets {
+                let result = delete_indexed_file(&store, Some(&state), Some(&lexical), target).await?;
+                files_removed += result.files_removed;
+                embeddings_removed += result.embeddings_removed;
+                eprintln!("  removed {} ({} embeddings)", target.display(), result.embeddings_removed);
+            }
+            lexical.commit()?;
+            println!("deleted: {} files, {} embeddings", files_removed, embeddings_removed);
+        }
+        Commands::Vacuum => {
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            let result = StateManager::new(&data_dir)?.vacuum()?;
+            if result.removed_files == 0 {
+                println!("vacuum: no missing files");
+                return Ok(());
+            }
+
+            let store = LanceVectorStore::open_existing(data_dir.clone()).await?;
+            let embeddings_removed = store.delete_by_doc_ids(&result.removed_doc_ids).await?;
+            let lexical = LexicalIndex::new(data_dir)?;
+            lexical.delete_by_doc_ids(&result.removed_doc_ids)?;
+            lexical.commit()?;
+            println!("vacuum: removed {} missing files, {} embeddings", result.removed_files, embeddings_removed);
+        }
+        Commands::Stats => {
             // Initialize data directory
             let data_dir = dirs::data_local_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -280,16 +1296,29 @@ async fn main() -> Result<()> {
                 return Ok(());
             }
 
-            let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await?);
             let lexical = LexicalIndex::new(data_dir.clone())?;
-            let count = store.count().await;
-            let lexical_count = lexical.count().unwrap_or(0);
-            println!("nexus status");
+            let stats = lexical.stats()?;
+            println!("nexus stats");
             println!("  store: {:?}", data_dir);
-            println!("  vector embeddings: {}", count);
-            println!("  lexical documents: {}", lexical_count);
+            println!("  lexical index:");
+            println!("    documents: {}", stats.num_docs);
+            println!("    deleted documents: {}", stats.deleted_docs);
+            println!("    segments: {}", stats.num_segments);
+            println!("    size: {:.2} MB", stats.index_size_bytes as f64 / 1024.0 / 1024.0);
+
+            let store = LanceVectorStore::open_existing(data_dir.clone()).await?;
+            println!("  vector store:");
+            println!("    chunks: {}", store.count().await);
+            for file_type in store.file_types().await? {
+                println!("    {}: {}", file_type, store.count_by_file_type(&file_type).await?);
+            }
+
+            if stats.deleted_docs as f64 > 0.2 * stats.num_docs as f64 {
+                eprintln!("warning: {} deleted documents are still taking up space in the lexical index", stats.deleted_docs);
+                eprintln!("  hint: run 'nexus optimize --merge-segments' to reclaim it");
+            }
         }
-        Commands::Search { query, json, mode, limit } => {
+        Commands::Search { query, json, mode, limit, context, sort_by, distance_metric, since, before, filter_path, file_type, output, tee, group, rrf_k, rerank, mmr_lambda, backend } => {
             // Initialize data directory
             let data_dir = dirs::data_local_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -300,113 +1329,118 @@ async fn main() -> Result<()> {
                 return Ok(());
             }
 
+            let sort_by: SortField = sort_by.parse()?;
+            let distance_metric: DistanceMetric = distance_metric.parse()?;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+            let after = since.as_deref().map(|s| parse_time_bound(s, now)).transpose()?;
+            let before = before.as_deref().map(|s| parse_time_bound(s, now)).transpose()?;
+            let filter_path = filter_path.as_deref().map(parse_filter_path).transpose()?;
+            let config = load_config(skip_config_validation)?;
+            let mut rrf = config.search.rrf_params();
+            if let Some(k) = rrf_k {
+                anyhow::ensure!(k.is_finite() && k >= 0.0, "--rrf-k must be a non-negative number");
+                rrf.k = k;
+            }
+            let rerank = match rerank {
+                Some(strategy) => Some(strategy.parse::<RerankStrategy>()?),
+                None => config.search.rerank,
+            };
+            let mmr_lambda = mmr_lambda.unwrap_or(config.search.mmr_lambda as f32);
+            anyhow::ensure!((0.0..=1.0).contains(&mmr_lambda), "--mmr-lambda must be between 0 and 1");
+
             // Load embedder and store
-            let embedder = LocalEmbedder::new()?;
-            let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await?);
-            let lexical = LexicalIndex::new(data_dir)?;
+            let embedder = backend.load(false, false)?;
+            let store = Arc::new(LanceVectorStore::new_with_metric(data_dir.clone(), embedder.dimension(), distance_metric).await?);
+            let lexical = LexicalIndex::new(data_dir.clone())?;
 
-            // Collect results based on mode
-            let results = match mode.as_str() {
-                "semantic" | "vector" => {
-                    // Vector-only search
-                    let query_embedding = embedder.embed(&query).await?;
-                    let vector_results = store.search(query_embedding, limit).await?;
-                    vector_results.into_iter().map(|r| HybridResult {
-                        doc_id: r.doc_id,
-                        file_path: r.metadata.file_path,
-                        chunk_index: r.metadata.chunk_index,
-                        snippet: r.snippet,
-                        score: r.score,
-                        source: "semantic".to_string(),
-                    }).collect()
-                }
-                "lexical" | "keyword" => {
-                    // Lexical-only search
-                    let lexical_results = lexical.search(&query, limit)?;
-                    // Need to get snippets from vector store
-                    let mut results = Vec::new();
-                    for r in lexical_results {
-                        let snippet = if let Some(meta) = store.get_metadata(&r.doc_id).await? {
-                            meta.snippet
-                        } else {
-                            None
-                        };
-                        results.push(HybridResult {
-                            doc_id: r.doc_id,
-                            file_path: PathBuf::from(r.file_path),
-                            chunk_index: r.chunk_index,
-                            snippet,
-                            score: r.score,
-                            source: "lexical".to_string(),
-                        });
+            let request = SearchRequest {
+                query: &query,
+                mode: &mode,
+                limit,
+                sort_by,
+                after,
+                before,
+                filter_path: filter_path.as_ref(),
+                file_types: file_type.as_deref(),
+                rrf,
+                rerank,
+                mmr_lambda,
+            };
+            let mut results = run_search(embedder.as_ref(), store.as_ref(), &lexical, &request).await?;
+
+            // Lexical results are already sorted; other modes use per-file index times
+            if sort_by == SortField::Date && !matches!(mode.as_str(), "lexical" | "keyword") {
+                let state = StateManager::new(&data_dir)?;
+                let mut indexed_at = std::collections::HashMap::new();
+                for result in &results {
+                    if !indexed_at.contains_key(&result.file_path) {
+                        let at = state.indexed_at(&result.file_path)?.unwrap_or(0);
+                        indexed_at.insert(result.file_path.clone(), at);
                     }
-                    results
                 }
-                "hybrid" | _ => {
-                    // Hybrid search with RRF
-                    let query_embedding = embedder.embed(&query).await?;
-                    let vector_results = store.search(query_embedding, limit * 2).await?;
-                    let lexical_results = lexical.search(&query, limit * 2)?;
-                    
-                    // Apply Reciprocal Rank Fusion (RRF)
-                    let k = 60.0; // RRF constant
-                    let mut doc_scores: std::collections::HashMap<String, (f32, Option<String>, PathBuf, usize)> = 
-                        std::collections::HashMap::new();
-                    
-                    // Add vector results
-                    for (rank, r) in vector_results.iter().enumerate() {
-                        let rrf_score = 1.0 / (k + rank as f32 + 1.0);
-                        let entry = doc_scores.entry(r.doc_id.clone()).or_insert((
-                            0.0,
-                            r.snippet.clone(),
-                            r.metadata.file_path.clone(),
-                            r.metadata.chunk_index,
-                        ));
-                        entry.0 += rrf_score;
-                    }
-                    
-                    // Add lexical results
-                    for (rank, r) in lexical_results.iter().enumerate() {
-                        let rrf_score = 1.0 / (k + rank as f32 + 1.0);
-                        let entry = doc_scores.entry(r.doc_id.clone()).or_insert((
-                            0.0,
-                            None,
-                            PathBuf::from(&r.file_path),
-                            r.chunk_index,
-                        ));
-                        entry.0 += rrf_score;
+                results.sort_by_key(|r| std::cmp::Reverse(indexed_at[&r.file_path]));
+            }
+
+            if context > 0 {
+                for result in results.iter_mut() {
+                    result.context_chunks = context_snippets(
+                        store.as_ref(),
+                        &lexical,
+                        &result.file_path.to_string_lossy(),
+                        result.chunk_index,
+                        context,
+                    ).await?;
+                }
+            }
+
+            if group {
+                let files = group_results(results, GROUPED_CHUNKS_PER_FILE);
+                if json {
+                    let json_files: Vec<_> = files.iter().map(|(file_path, score, chunks)| serde_json::json!({
+                        "file_path": file_path,
+                        "score": score,
+                        "chunks": chunks.iter().map(result_json).collect::<Vec<_>>(),
+                    })).collect();
+                    println!("{}", serde_json::to_string_pretty(&json_files)?);
+                    return Ok(());
+                }
+
+                println!("search: \"{}\" (mode: {})", query, mode);
+                if files.is_empty() {
+                    println!("  (no results)");
+                    return Ok(());
+                }
+                for (i, (file_path, score, chunks)) in files.iter().enumerate() {
+                    println!();
+                    let name = chunks.first().map_or_else(|| file_path.display().to_string(), HybridResult::display_name);
+                    println!("  {}. {} (score: {:.4})", i + 1, name, score);
+                    for result in chunks {
+                        println!("     chunk {} (score: {:.4}, {}) | id {}",
+                            result.chunk_index,
+                            result.score,
+                            result.source,
+                            &result.doc_id[..8.min(result.doc_id.len())]
+                        );
+                        print_result_body(result);
                     }
-                    
-                    // Sort by combined RRF score
-                    let mut sorted: Vec<_> = doc_scores.into_iter().collect();
-                    sorted.sort_by(|a, b| b.1.0.partial_cmp(&a.1.0).unwrap_or(std::cmp::Ordering::Equal));
-                    
-                    sorted.into_iter()
-                        .take(limit)
-                        .map(|(doc_id, (score, snippet, file_path, chunk_index))| HybridResult {
-                            doc_id,
-                            file_path,
-                            chunk_index,
-                            snippet,
-                            score,
-                            source: "hybrid".to_string(),
-                        })
-                        .collect()
                 }
-            };
+                println!();
+                return Ok(());
+            }
+
+            let json_results: Vec<_> = results.iter().map(result_json).collect();
+
+            if let Some(path) = &output {
+                write_results(path, &json_results)?;
+                eprintln!("info: wrote {} results to {}", json_results.len(), path.display());
+                if !tee {
+                    return Ok(());
+                }
+            }
 
             if json {
-                // JSON output
-                let json_results: Vec<_> = results.iter().map(|r| {
-                    serde_json::json!({
-                        "doc_id": r.doc_id,
-                        "score": r.score,
-                        "file_path": r.file_path,
-                        "chunk_index": r.chunk_index,
-                        "snippet": r.snippet,
-                        "source": r.source
-                    })
-                }).collect();
                 println!("{}", serde_json::to_string_pretty(&json_results)?);
             } else {
                 // Human-readable output
@@ -419,7 +1453,7 @@ async fn main() -> Result<()> {
                         println!();
                         println!("  {}. {} (score: {:.4}, {})", 
                             i + 1, 
-                            result.file_path.display(),
+                            result.display_name(),
                             result.score,
                             result.source
                         );
@@ -427,15 +1461,227 @@ async fn main() -> Result<()> {
                             result.chunk_index, 
                             &result.doc_id[..8.min(result.doc_id.len())]
                         );
-                        if let Some(snippet) = &result.snippet {
-                            let preview: String = snippet.chars().take(80).collect();
-                            println!("     > {}...", preview.replace('\n', " "));
+                        // A title replaces the path in the header, so show the path here
+                        if result.title.is_some() {
+                            println!("     {}", result.file_path.display());
                         }
+                        print_result_body(result);
                     }
                     println!();
                 }
             }
         }
+        Commands::Optimize { dedup, threshold, merge_segments, vector_index, num_partitions, num_sub_vectors, distance_metric } => {
+            // Initialize data directory
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            if !dedup && !merge_segments && !vector_index {
+                eprintln!("nothing to do");
+                eprintln!("  hint: pass --dedup to remove near-duplicate embeddings");
+                eprintln!("  hint: pass --merge-segments to compact the lexical index");
+                eprintln!("  hint: pass --vector-index to speed up semantic search on large indexes");
+                return Ok(());
+            }
+
+            if merge_segments {
+                let lexical = LexicalIndex::new(data_dir.clone())?;
+                let before = lexical.stats()?.num_segments;
+                eprintln!("merging {} lexical index segments...", before);
+                lexical.optimize()?;
+                println!("lexical index: {} segments -> {}", before, lexical.stats()?.num_segments);
+            }
+            if vector_index {
+                let store = LanceVectorStore::open_existing_with_metric(data_dir.clone(), distance_metric.parse()?).await?;
+                eprintln!("building vector index over {} embeddings...", store.count().await);
+                store.create_vector_index(num_partitions, num_sub_vectors).await?;
+                println!("vector index built");
+            }
+            if !dedup {
+                return Ok(());
+            }
+
+            let state = Arc::new(StateManager::new(&data_dir)?);
+            let store = LanceVectorStore::open_existing(data_dir.clone()).await?
+                .with_state(state.clone());
+            let lexical = LexicalIndex::new(data_dir)?;
+            let before = store.count().await;
+            eprintln!("deduplicating {} embeddings (threshold {})...", before, threshold);
+            let removed = deduplicate_index(&store, Some(&state), Some(&lexical), threshold).await?;
+            lexical.commit()?;
+            println!("removed {} near-duplicate embeddings ({} remaining)", removed, before.saturating_sub(removed));
+        }
+        Commands::Compact => {
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            let store = LanceVectorStore::open_existing(data_dir).await?;
+            eprintln!("compacting {} embeddings...", store.count().await);
+            store.compact().await?;
+            println!("vector store compacted");
+        }
+        Commands::Repair { lexical_unlock, rebuild_lexical } => {
+            // Initialize data directory
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            if rebuild_lexical {
+                let store = LanceVectorStore::open_existing(data_dir.clone()).await?;
+                eprintln!("rebuilding lexical index from {} chunks...", store.count().await);
+                let lexical = LexicalIndex::create_from_lance_snapshot(&store, data_dir).await?;
+                println!("lexical index rebuilt ({} documents)", lexical.count()?);
+                return Ok(());
+            }
+
+            if !lexical_unlock {
+                eprintln!("nothing to do");
+                eprintln!("  hint: pass --lexical-unlock to clear a stale lexical index lock,");
+                eprintln!("        or --rebuild-lexical to rebuild the lexical index from the vector store");
+                return Ok(());
+            }
+
+            if LexicalIndex::remove_stale_writer_lock(&data_dir)? {
+                println!("removed stale lexical index lock");
+            } else {
+                println!("no stale lexical index lock found");
+            }
+            // Make sure the index opens cleanly now
+            let lexical = LexicalIndex::reopen_after_crash(data_dir)?;
+            println!("lexical index ok ({} documents)", lexical.count()?);
+        }
+        Commands::Reindex { model_changed, gpu, backend } => {
+            // Initialize data directory
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            if !model_changed {
+                eprintln!("nothing to do");
+                eprintln!("  hint: pass --model-changed to re-embed every indexed file with the current model");
+                return Ok(());
+            }
+
+            let state = Arc::new(StateManager::new(&data_dir)?);
+            let files: Vec<PathBuf> = state.get_all_files()?
+                .into_iter()
+                .map(|f| f.path)
+                .filter(|p| p.exists())
+                .collect();
+            if files.is_empty() {
+                println!("no indexed files to re-embed");
+                return Ok(());
+            }
+
+            eprintln!("info: loading embedding model{}...", backend.describe(gpu, false));
+            let embedder = EmbedWrapper(backend.load(gpu, false)?);
+            // The old embeddings and their vector index are useless to the new model,
+            // whose dimension may differ too
+            let store = Arc::new(LanceVectorStore::recreate(data_dir.clone(), embedder.dimension()).await?
+                .with_state(state.clone()));
+            let lexical = Arc::new(LexicalIndex::new(data_dir.clone())?);
+
+            let options = IndexOptions {
+                replace_existing: true,
+                // Tracked archives are re-expanded like any other file
+                follow_archive_contents: true,
+                extractor: ExtractorOptions { pdf_passwords: saved_pdf_passwords()?, ..Default::default() },
+                ..Default::default()
+            };
+            let extractor = build_extractor(&options);
+            let mut indexer = Indexer::new(options, extractor, embedder, store.clone())
+                .with_state(state)
+                .with_lexical(lexical);
+
+            eprintln!("re-embedding {} files...", files.len());
+            let result = indexer.index_files_with_progress(files, |e| {
+                if let IndexEvent::FileIndexed(p) = e {
+                    eprintln!("  reindexed {}", p.display());
+                }
+            }).await?;
+            println!("reindexed {} files ({} chunks, {} errors)",
+                result.files_indexed, result.chunks_indexed, result.errors.len());
+            for (message, count) in result.error_summary() {
+                eprintln!("  {} file{}: '{}'", count, if count == 1 { "" } else { "s" }, message);
+            }
+        }
+        Commands::Doctor => {
+            // Initialize data directory
+            let data_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("nexus_local");
+
+            if !data_dir.exists() {
+                eprintln!("error: no index found, run 'nexus index <path>' first");
+                return Ok(());
+            }
+
+            let store = LanceVectorStore::open_existing(data_dir.clone()).await?;
+            let state = StateManager::new(&data_dir)?;
+            let report = state.check_integrity(&store).await?;
+
+            println!("nexus doctor");
+            println!("  store: {:?}", data_dir);
+            if report.is_clean() {
+                println!("  no problems found");
+                return Ok(());
+            }
+
+            let print_paths = |paths: &[PathBuf]| {
+                for path in paths.iter().take(10) {
+                    println!("    {}", path.display());
+                }
+                if paths.len() > 10 {
+                    println!("    ... and {} more", paths.len() - 10);
+                }
+            };
+
+            if !report.missing_files.is_empty() {
+                println!("  {} tracked files no longer exist on disk:", report.missing_files.len());
+                print_paths(&report.missing_files);
+                eprintln!("  hint: run 'nexus index <path>' to remove their embeddings");
+            }
+            if !report.orphaned_doc_ids.is_empty() {
+                let affected: Vec<PathBuf> = state.get_all_files()?
+                    .into_iter()
+                    .filter(|f| f.doc_ids.iter().any(|id| report.orphaned_doc_ids.contains(id)))
+                    .map(|f| f.path)
+                    .collect();
+                println!("  {} chunk ids are missing from the vector store, in {} files:",
+                    report.orphaned_doc_ids.len(), affected.len());
+                print_paths(&affected);
+                eprintln!("  hint: touch these files and run 'nexus index <path>' to re-embed them");
+            }
+            if !report.extra_files.is_empty() {
+                println!("  {} paths have chunk records but are not tracked:", report.extra_files.len());
+                print_paths(&report.extra_files);
+                eprintln!("  hint: these are ignored by indexing; to remove them run");
+                eprintln!("    sqlite3 {:?} \"DELETE FROM file_docs WHERE path NOT IN (SELECT path FROM files)\"",
+                    data_dir.join("state.db"));
+            }
+        }
         Commands::Explain { doc_id } => {
             // Initialize data directory
             let data_dir = dirs::data_local_dir()
@@ -447,12 +1693,21 @@ async fn main() -> Result<()> {
                 return Ok(());
             }
 
-            let store = Arc::new(LanceVectorStore::new(data_dir).await?);
+            let store = Arc::new(LanceVectorStore::open_existing(data_dir).await?);
 
             // Find matching documents (partial ID match)
             if let Some(meta) = store.get_metadata(&doc_id).await? {
                 println!("document: {}", doc_id);
                 println!("  path: {}", meta.file_path.display());
+                if let Some(title) = &meta.title {
+                    println!("  title: {}", title);
+                }
+                if let Some(author) = &meta.author {
+                    println!("  author: {}", author);
+                }
+                if let Some(created_at) = meta.created_at {
+                    println!("  created: {}", format_timestamp(created_at));
+                }
                 println!("  type: {}", meta.file_type);
                 println!("  chunk: {}", meta.chunk_index);
                 if let Some(snippet) = &meta.snippet {
@@ -466,7 +1721,7 @@ async fn main() -> Result<()> {
             }
         }
         Commands::Watch { paths } => {
-            let config = NexusConfig::load()?;
+            let config = load_config(skip_config_validation)?;
             
             // Use CLI paths or config roots
             let roots: Vec<PathBuf> = if paths.is_empty() {
@@ -503,14 +1758,27 @@ async fn main() -> Result<()> {
             // Initialize indexing components once
             let data_dir = config.data_dir();
             std::fs::create_dir_all(&data_dir)?;
+            let extractor_options = ExtractorOptions { pdf_passwords: saved_pdf_passwords()?, ..Default::default() };
             
-            let embedder = LocalEmbedder::new_with_options(config.gpu.enabled)?;
-            let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await?);
+            let embedder = load_embedder(config.gpu.enabled, config.gpu.model_quantized)?;
+            let store = Arc::new(LanceVectorStore::new(data_dir.clone(), embedder.dimension()).await?);
             let state = Arc::new(StateManager::new(&data_dir)?);
             let lexical = Arc::new(LexicalIndex::new(data_dir.clone())?);
 
             loop {
-                let batch = watcher.wait_for_changes()?;
+                let batch = tokio::select! {
+                    batch = watcher.wait_for_changes() => batch?,
+                    _ = tokio::signal::ctrl_c() => {
+                        eprintln!("stopped watching");
+                        break;
+                    }
+                };
+                
+                for event in &batch.events {
+                    if let IndexEvent::WatchPathAdded(dir) = event {
+                        eprintln!("  watching: {}", dir.display());
+                    }
+                }
                 
                 if !batch.deleted.is_empty() {
                     eprintln!("  deleted: {} files", batch.deleted.len());
@@ -531,26 +1799,22 @@ async fn main() -> Result<()> {
                             .unwrap_or_else(|| path.parent().unwrap_or(path).to_path_buf());
                         
                         let options = IndexOptions {
-                            root,
-                            chunk_size: 1500,
-                            max_file_size_bytes: config.index.max_file_mb * 1024 * 1024,
                             max_memory_bytes: 4 * 1024 * 1024 * 1024,
-                            max_chunks_per_file: config.index.max_chunks,
-                            skip_extensions: config.index.skip_extensions.clone(),
-                            skip_files: config.index.skip_files.clone(),
+                            extractor: extractor_options.clone(),
+                            ..config.index_options(root)
                         };
                         
-                        let extractor = OcrExtractor(PlainTextExtractor);
-                        let embed_wrapper = EmbedWrapper(LocalEmbedder::new_with_options(config.gpu.enabled)?);
+                        let extractor = build_extractor(&options);
+                        let embed_wrapper = EmbedWrapper(Box::new(load_embedder(config.gpu.enabled, config.gpu.model_quantized)?));
                         
                         let indexer = Indexer::new(options, extractor, embed_wrapper, store.clone())
                             .with_state(state.clone())
                             .with_lexical(lexical.clone());
                         
-                        // TODO: Index single file instead of full directory scan
-                        // For now, run GC + full index which will pick up changes
                         let mut indexer = indexer;
-                        let _ = indexer.run_with_progress(|_| {}).await;
+                        if let Err(e) = indexer.index_single_file(path, |_| {}).await {
+                            eprintln!("    warning: failed to index {}: {}", path.display(), e);
+                        }
                     }
                     
                     eprintln!("  done\n");
@@ -599,6 +1863,70 @@ async fn main() -> Result<()> {
                         println!("\nRun 'nexus config init' to create one.");
                     }
                 }
+                ConfigAction::Diff => {
+                    let config = match NexusConfig::find_config_file() {
+                        Some(path) => {
+                            println!("Config file: {}\n", path.display());
+                            NexusConfig::load_from(&path)?
+                        }
+                        None => {
+                            println!("No config file found, all settings are defaults.");
+                            return Ok(());
+                        }
+                    };
+
+                    let diffs = config.diff_from_default()?;
+                    if diffs.is_empty() {
+                        println!("All settings match the defaults.");
+                        return Ok(());
+                    }
+
+                    for diff in diffs {
+                        let default = format!("{}\n", serde_json::to_string_pretty(&diff.default)?);
+                        let current = format!("{}\n", serde_json::to_string_pretty(&diff.current)?);
+                        println!("{}:", diff.key);
+                        for change in similar::TextDiff::from_lines(&default, &current).iter_all_changes() {
+                            let sign = match change.tag() {
+                                similar::ChangeTag::Delete => "-",
+                                similar::ChangeTag::Insert => "+",
+                                similar::ChangeTag::Equal => " ",
+                            };
+                            print!("  {} {}", sign, change);
+                        }
+                    }
+                }
+                ConfigAction::Validate => {
+                    let Some(path) = NexusConfig::find_config_file() else {
+                        println!("No config file found, all settings are defaults.");
+                        return Ok(());
+                    };
+                    let content = std::fs::read_to_string(&path)?;
+                    let config = NexusConfig::load_from(&path)
+                        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+                    let problems = config.validate().err().unwrap_or_default();
+                    for problem in &problems {
+                        let level = if problem.is_fatal() { "error" } else { "warning" };
+                        match nexus_core::config::key_line(&content, problem.key()) {
+                            Some(line) => println!("{}:{}: {}: {}", path.display(), line, level, problem),
+                            None => println!("{}: {}: {}", path.display(), level, problem),
+                        }
+                    }
+                    let errors = problems.iter().filter(|p| p.is_fatal()).count();
+                    anyhow::ensure!(errors == 0, "{} invalid setting(s) in {}", errors, path.display());
+                    println!("{} is valid", path.display());
+                }
+                ConfigAction::Set { key, value } => {
+                    let path = NexusConfig::find_config_file()
+                        .or_else(NexusConfig::default_config_path)
+                        .unwrap_or_else(|| PathBuf::from(NexusConfig::FILENAME));
+                    let (old, new) = NexusConfig::set_in_file(&path, &key, &value)?;
+                    match old {
+                        Some(old) => println!("Updated {}: {} → {}", key, old, new),
+                        None => println!("Updated {}: (unset) → {}", key, new),
+                    }
+                    println!("Config file: {}", path.display());
+                }
                 ConfigAction::Path => {
                     if let Some(path) = NexusConfig::find_config_file() {
                         println!("{}", path.display());
@@ -624,8 +1952,20 @@ async fn main() -> Result<()> {
                     let result = manager.status()?;
                     println!("{}", result);
                 }
+                ServiceAction::Logs { lines } => {
+                    let logs = manager.logs(lines)?;
+                    if logs.is_empty() {
+                        println!("(no service logs)");
+                    }
+                    for line in logs {
+                        println!("{}", line);
+                    }
+                }
             }
         }
+        Commands::Serve { port, host, backend } => {
+            serve::serve(&host, port, &backend, load_config(skip_config_validation)?).await?;
+        }
     }
     Ok(())
 }
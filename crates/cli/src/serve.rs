@@ -0,0 +1,261 @@
+//! `nexus serve`: a local HTTP API over the index, so editor plugins and scripts
+//! can search without starting a CLI process (and loading the model) per query.
+
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use embed::Embedder as EmbedderTrait;
+use futures::Stream;
+use nexus_core::{Embedder, ExtractorOptions, IndexEvent, IndexOptions, Indexer, LexicalIndex, NexusConfig, RerankStrategy, VectorStore};
+use search::RrfParams;
+use serde::Deserialize;
+use store::{LanceVectorStore, SortField, StateManager};
+use sysinfo::System;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::{build_extractor, parse_filter_path, result_json, run_search, saved_pdf_passwords, EmbedderArgs, SearchRequest};
+
+/// Progress events buffered per subscriber; slower readers skip ahead.
+const EVENT_BUFFER: usize = 1024;
+
+/// Everything the handlers share: the model is loaded once for the server's lifetime.
+struct AppState {
+    data_dir: PathBuf,
+    embedder: Arc<dyn EmbedderTrait>,
+    store: Arc<LanceVectorStore>,
+    lexical: Arc<LexicalIndex>,
+    state: Arc<StateManager>,
+    /// Hybrid search fusion settings, read from the config at startup.
+    rrf: RrfParams,
+    /// Re-ranking of search results, also from the config.
+    rerank: Option<RerankStrategy>,
+    mmr_lambda: f32,
+    /// Settings for `POST /index` runs from the config; each run fills in its root.
+    index_options: IndexOptions,
+    events: broadcast::Sender<IndexEvent>,
+    indexing: AtomicBool,
+}
+
+/// The server's embedder, shared between search requests and indexing runs.
+struct SharedEmbedder(Arc<dyn EmbedderTrait>);
+
+#[async_trait]
+impl Embedder for SharedEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.0.embed(text).await
+    }
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        self.0.embed_batch(texts).await
+    }
+    fn dimension(&self) -> usize {
+        self.0.dimension()
+    }
+    fn max_input_tokens(&self) -> Option<usize> {
+        self.0.max_input_tokens()
+    }
+    fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        self.0.token_spans(text)
+    }
+}
+
+type ApiError = (StatusCode, String);
+
+fn internal(e: anyhow::Error) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))
+}
+
+/// Open the index, load the model and serve until the process is stopped.
+/// The model is loaded like `nexus index` loads it, with the config's GPU settings.
+pub async fn serve(host: &str, port: u16, backend: &EmbedderArgs, config: NexusConfig) -> Result<()> {
+    let data_dir = dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("nexus_local");
+    std::fs::create_dir_all(&data_dir)?;
+
+    let (gpu, quantized) = (config.gpu.enabled, config.gpu.model_quantized);
+    eprintln!("info: loading embedding model{}...", backend.describe(gpu, quantized));
+    let embedder: Arc<dyn EmbedderTrait> = Arc::from(backend.load(gpu, quantized)?);
+    let store = Arc::new(LanceVectorStore::new(data_dir.clone(), embedder.dimension()).await?);
+    let lexical = Arc::new(LexicalIndex::new(data_dir.clone())?);
+    let state = Arc::new(StateManager::new(&data_dir)?);
+    let rrf = config.search.rrf_params();
+    let index_options = IndexOptions {
+        extractor: ExtractorOptions { pdf_passwords: saved_pdf_passwords()?, ..Default::default() },
+        ..config.index_options(PathBuf::new())
+    };
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+
+    let app = Arc::new(AppState {
+        data_dir,
+        embedder,
+        store,
+        lexical,
+        state,
+        rrf,
+        rerank: config.search.rerank,
+        mmr_lambda: config.search.mmr_lambda.clamp(0.0, 1.0) as f32,
+        index_options,
+        events,
+        indexing: AtomicBool::new(false),
+    });
+    let router = Router::new()
+        .route("/search", get(search))
+        .route("/status", get(status))
+        .route("/index", post(start_index))
+        .route("/index/progress", get(index_progress))
+        .with_state(app);
+
+    let listener = tokio::net::TcpListener::bind((host, port)).await
+        .with_context(|| format!("Failed to listen on {}:{}", host, port))?;
+    eprintln!("info: listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    #[serde(default = "default_mode")]
+    mode: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    filter_path: Option<String>,
+    /// Comma-separated file extensions, like `search --type`.
+    #[serde(rename = "type")]
+    file_type: Option<String>,
+}
+
+fn default_mode() -> String {
+    "hybrid".to_string()
+}
+
+fn default_limit() -> usize {
+    5
+}
+
+/// `GET /search?q=...&mode=...&limit=...&filter_path=...&type=...`: results in the `search --json` shape.
+async fn search(State(app): State<Arc<AppState>>, Query(params): Query<SearchParams>) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
+    let filter_path = params.filter_path.as_deref().map(parse_filter_path).transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+    let file_types: Option<Vec<String>> = params.file_type.as_deref()
+        .map(|types| types.split(',').map(str::to_string).collect());
+    let request = SearchRequest {
+        query: &params.q,
+        mode: &params.mode,
+        limit: params.limit,
+        sort_by: SortField::Score,
+        after: None,
+        before: None,
+        filter_path: filter_path.as_ref(),
+        file_types: file_types.as_deref(),
+        rrf: app.rrf,
+        rerank: app.rerank,
+        mmr_lambda: app.mmr_lambda,
+    };
+    let results = run_search(app.embedder.as_ref(), &app.store, &app.lexical, &request).await
+        .map_err(internal)?;
+    Ok(Json(results.iter().map(result_json).collect()))
+}
+
+/// `GET /status`: the same counts the desktop app shows, plus whether indexing is running.
+async fn status(State(app): State<Arc<AppState>>) -> Result<Json<serde_json::Value>, ApiError> {
+    let lexical_stats = app.lexical.stats().unwrap_or_default();
+    let stats = app.state.stats().map_err(internal)?;
+    Ok(Json(serde_json::json!({
+        "store_path": app.data_dir,
+        "vector_embeddings": app.store.count().await,
+        "lexical_documents": app.lexical.count().map_err(internal)?,
+        "lexical_segments": lexical_stats.num_segments,
+        "lexical_deleted_documents": lexical_stats.deleted_docs,
+        "lexical_size_bytes": lexical_stats.index_size_bytes,
+        "indexed_files": stats.total_files,
+        "indexed_chunks": stats.total_doc_ids,
+        "files_by_extension": stats.by_extension,
+        "indexing": app.indexing.load(Ordering::SeqCst),
+    })))
+}
+
+#[derive(Deserialize)]
+struct IndexRequest {
+    path: String,
+}
+
+/// `POST /index {"path": ...}`: start indexing in the background. One run at a time.
+async fn start_index(State(app): State<Arc<AppState>>, Json(request): Json<IndexRequest>) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let root = PathBuf::from(shellexpand::tilde(&request.path).as_ref());
+    if !root.exists() {
+        return Err((StatusCode::BAD_REQUEST, format!("Path does not exist: {}", root.display())));
+    }
+    if app.indexing.swap(true, Ordering::SeqCst) {
+        return Err((StatusCode::CONFLICT, "Indexing is already running".to_string()));
+    }
+
+    let response = serde_json::json!({ "status": "started", "path": root });
+    let task_app = app.clone();
+    tokio::spawn(async move {
+        if let Err(e) = index_root(&task_app, root).await {
+            eprintln!("error: indexing failed: {:#}", e);
+        }
+        task_app.indexing.store(false, Ordering::SeqCst);
+    });
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Index `root` into the server's store, publishing events to progress subscribers.
+async fn index_root(app: &AppState, root: PathBuf) -> Result<()> {
+    let max_memory_bytes = System::new_all().total_memory() * 3 / 4;
+    let options = IndexOptions {
+        root,
+        max_memory_bytes,
+        ..app.index_options.clone()
+    };
+    let extractor = build_extractor(&options);
+    let mut indexer = Indexer::new(options, extractor, SharedEmbedder(app.embedder.clone()), app.store.clone())
+        .with_state(app.state.clone())
+        .with_lexical(app.lexical.clone());
+    if let Err(e) = indexer.garbage_collect().await {
+        eprintln!("warning: garbage collection failed: {:#}", e);
+    }
+
+    let (tx, mut rx) = mpsc::channel::<IndexEvent>(EVENT_BUFFER);
+    let events = app.events.clone();
+    let forwarder = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            // No subscribers is fine; events are only for whoever is listening
+            let _ = events.send(event);
+        }
+    });
+    let result = indexer.run_with_channel(tx).await;
+    let _ = forwarder.await;
+    let result = result?;
+    eprintln!("info: indexed {} files ({} chunks), {} unchanged", result.files_indexed, result.chunks_indexed, result.files_unchanged);
+    Ok(())
+}
+
+/// `GET /index/progress`: indexing events as server-sent events, one JSON object each.
+async fn index_progress(State(app): State<Arc<AppState>>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(app.events.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let Ok(data) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    return Some((Ok::<_, Infallible>(Event::default().data(data)), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
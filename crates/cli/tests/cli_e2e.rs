@@ -26,3 +26,28 @@ fn cli_e2e_index_search_explain() {
     fs::remove_file(&test_file).unwrap();
     fs::remove_dir_all(test_dir).unwrap();
 }
+
+#[test]
+fn cli_search_writes_output_file() {
+    let test_dir = "test_output_dir";
+    fs::create_dir_all(test_dir).unwrap();
+    fs::write(format!("{}/file.txt", test_dir), "quarterly budget spreadsheet").unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let results_path = out.path().join("results.json");
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.args(["index", test_dir]).assert().success();
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.args(["search", "budget", "--output"])
+        .arg(&results_path)
+        .assert()
+        .success()
+        .stdout(predicates::str::is_empty());
+
+    let contents = fs::read_to_string(&results_path).unwrap();
+    let results: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    assert!(results.is_array());
+
+    fs::remove_dir_all(test_dir).unwrap();
+}
@@ -0,0 +1,39 @@
+#![cfg(target_os = "linux")]
+
+use assert_cmd::Command;
+
+#[test]
+fn cli_index_and_search_with_ollama() {
+    let data = tempfile::tempdir().unwrap();
+    let docs = tempfile::tempdir().unwrap();
+    std::fs::write(docs.path().join("lease.txt"), "lease agreement security deposit").unwrap();
+
+    // Every prompt gets the same synthetic embedding
+    let mut server = mockito::Server::new();
+    let mock = server.mock("POST", "/api/embeddings")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "model": "nomic-embed-text" })))
+        .with_body(r#"{"embedding": [0.6, 0.8, 0.0]}"#)
+        .expect_at_least(2)
+        .create();
+    let ollama = ["--embedder", "ollama", "--ollama-url", &server.url(), "--ollama-dim", "3"];
+
+    Command::cargo_bin("cli").unwrap()
+        .arg("index")
+        .arg(docs.path())
+        .args(ollama)
+        .env("XDG_DATA_HOME", data.path())
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("cli").unwrap()
+        .args(["search", "deposit", "--mode", "semantic", "--json"])
+        .args(ollama)
+        .env("XDG_DATA_HOME", data.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert!(results[0]["file_path"].as_str().unwrap().ends_with("lease.txt"));
+    mock.assert();
+}
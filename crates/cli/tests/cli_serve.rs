@@ -0,0 +1,81 @@
+#![cfg(target_os = "linux")]
+
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Kills the server when the test ends, pass or fail.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Poll `/status` until `done` accepts it; the first call also waits out model loading.
+fn wait_for_status(client: &reqwest::blocking::Client, base: &str, done: impl Fn(&serde_json::Value) -> bool) -> serde_json::Value {
+    let deadline = Instant::now() + Duration::from_secs(300);
+    loop {
+        if let Ok(response) = client.get(format!("{}/status", base)).send() {
+            let status: serde_json::Value = response.json().unwrap();
+            if done(&status) {
+                return status;
+            }
+        }
+        assert!(Instant::now() < deadline, "server did not reach the expected state in time");
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn serve_indexes_and_searches() {
+    let data = tempfile::tempdir().unwrap();
+    let docs = tempfile::tempdir().unwrap();
+    std::fs::write(docs.path().join("lease.txt"), "lease agreement security deposit").unwrap();
+
+    let port = free_port();
+    let _server = Server(
+        Command::new(assert_cmd::cargo::cargo_bin("cli"))
+            .args(["serve", "--port", &port.to_string()])
+            .env("XDG_DATA_HOME", data.path())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap(),
+    );
+    let base = format!("http://127.0.0.1:{}", port);
+    let client = reqwest::blocking::Client::new();
+
+    let status = wait_for_status(&client, &base, |_| true);
+    assert_eq!(status["vector_embeddings"], 0);
+
+    let response = client.post(format!("{}/index", base))
+        .json(&serde_json::json!({ "path": docs.path() }))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let status = wait_for_status(&client, &base, |s| s["indexing"] == false && s["vector_embeddings"] != 0);
+    assert_eq!(status["lexical_documents"], 1);
+
+    let results: serde_json::Value = client.get(format!("{}/search", base))
+        .query(&[("q", "security deposit"), ("mode", "lexical")])
+        .send()
+        .unwrap()
+        .json()
+        .unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0]["file_path"].as_str().unwrap().ends_with("lease.txt"));
+
+    let response = client.post(format!("{}/index", base))
+        .json(&serde_json::json!({ "path": docs.path().join("missing") }))
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
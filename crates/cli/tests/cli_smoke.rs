@@ -29,3 +29,138 @@ fn invalid_command_fails() {
     let mut cmd = Command::cargo_bin("cli").unwrap();
     cmd.arg("foo").assert().failure();
 }
+
+#[test]
+fn config_set_updates_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nexus.config.toml");
+    std::fs::write(&path, "[index]\nmax_file_mb = 50\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.current_dir(dir.path())
+        .args(["config", "set", "index.max_file_mb", "100"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated index.max_file_mb: 50 → 100"));
+
+    let config = nexus_core::NexusConfig::load_from(&path).unwrap();
+    assert_eq!(config.index.max_file_mb, 100);
+}
+
+#[test]
+fn config_validate_reports_invalid_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("nexus.config.toml"), "[search]\ndefault_mode = \"invalid\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.current_dir(dir.path())
+        .args(["config", "validate"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("nexus.config.toml:2: error: search.default_mode"));
+
+    std::fs::write(dir.path().join("nexus.config.toml"), "[search]\ndefault_mode = \"lexical\"\n").unwrap();
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.current_dir(dir.path())
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is valid"));
+}
+
+#[test]
+fn list_help() {
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.args(["list", "--help"]).assert().success().stdout(predicates::str::contains("List indexed files"));
+}
+
+// The data directory follows XDG_DATA_HOME only on Linux
+#[cfg(target_os = "linux")]
+#[test]
+fn list_json_filters_and_sorts() {
+    let data_home = tempfile::tempdir().unwrap();
+    let state = store::StateManager::new(&data_home.path().join("nexus_local")).unwrap();
+    let mtime = std::time::SystemTime::now();
+    state.mark_indexed(std::path::Path::new("/docs/a.txt"), mtime, &["a0".to_string()]).unwrap();
+    state.mark_indexed(std::path::Path::new("/docs/b.txt"), mtime, &["b0".to_string(), "b1".to_string()]).unwrap();
+    state.mark_indexed(std::path::Path::new("/docs/c.pdf"), mtime, &["c0".to_string()]).unwrap();
+    drop(state);
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    let output = cmd.env("XDG_DATA_HOME", data_home.path())
+        .args(["list", "--json", "--file-type", "txt", "--sort-by", "chunks"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let lines: Vec<serde_json::Value> = String::from_utf8(output).unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["path"], "/docs/b.txt");
+    assert_eq!(lines[0]["chunks"], 2);
+    assert_eq!(lines[0]["file_type"], "txt");
+    assert_eq!(lines[1]["path"], "/docs/a.txt");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn delete_directory_needs_recursive() {
+    let data_home = tempfile::tempdir().unwrap();
+    let state = store::StateManager::new(&data_home.path().join("nexus_local")).unwrap();
+    let mtime = std::time::SystemTime::now();
+    for path in ["/docs/a.txt", "/docs/sub/b.txt", "/other/c.txt"] {
+        state.mark_indexed(std::path::Path::new(path), mtime, &[path.to_string()]).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.env("XDG_DATA_HOME", data_home.path())
+        .args(["delete", "/docs"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("use --recursive"));
+    assert_eq!(state.file_count().unwrap(), 3);
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.env("XDG_DATA_HOME", data_home.path())
+        .args(["delete", "/docs", "--recursive"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("deleted: 2 files"));
+    let remaining: Vec<_> = state.get_all_files().unwrap().into_iter().map(|f| f.path).collect();
+    assert_eq!(remaining, [std::path::PathBuf::from("/other/c.txt")]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn vacuum_removes_missing_files() {
+    let data_home = tempfile::tempdir().unwrap();
+    let docs = tempfile::tempdir().unwrap();
+    let kept = docs.path().join("kept.txt");
+    std::fs::write(&kept, "still here").unwrap();
+    let state = store::StateManager::new(&data_home.path().join("nexus_local")).unwrap();
+    let mtime = std::time::SystemTime::now();
+    state.mark_indexed(&kept, mtime, &["kept-0".to_string()]).unwrap();
+    state.mark_indexed(&docs.path().join("gone.txt"), mtime, &["gone-0".to_string(), "gone-1".to_string()]).unwrap();
+
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.env("XDG_DATA_HOME", data_home.path())
+        .arg("vacuum")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("removed 1 missing files"));
+    let remaining: Vec<_> = state.get_all_files().unwrap().into_iter().map(|f| f.path).collect();
+    assert_eq!(remaining, [kept]);
+}
+
+#[test]
+fn models_lists_default_model() {
+    let mut cmd = Command::cargo_bin("cli").unwrap();
+    cmd.arg("models")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("all-MiniLM-L6-v2"))
+        .stdout(predicates::str::contains("multilingual-e5-large"));
+}
@@ -12,7 +12,7 @@ async fn test_index_txt_file() {
 
     // Use OcrExtractor to extract text
     use ocr::{PlainTextExtractor, OcrEngine};
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&txt_path).await.unwrap();
     assert!(text.contains("Hello, Nexus!"), "Should extract text from .txt file");
 
@@ -28,7 +28,7 @@ async fn test_index_md_file() {
     fs::write(&md_path, "# Title\nSome **markdown** content.").unwrap();
 
     use ocr::{PlainTextExtractor, OcrEngine};
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&md_path).await.unwrap();
     assert!(text.contains("# Title"), "Should extract text from .md file");
 
@@ -45,7 +45,7 @@ async fn test_index_pdf_file() {
     }
 
     use ocr::{PlainTextExtractor, OcrEngine};
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&pdf_path).await.unwrap();
     assert!(text.to_lowercase().contains("brainfuck"), "Should extract text from PDF file");
 }
@@ -60,7 +60,7 @@ async fn test_index_image_file() {
     }
 
     use ocr::{PlainTextExtractor, OcrEngine};
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&img_path).await.unwrap();
     assert!(text.to_uppercase().contains("HOW TO COMBINE"), "Should extract text from image file");
 }
@@ -1,12 +1,29 @@
 //! Embedding abstraction for Nexus Local.
 //
 // Provides a trait for generating vector embeddings from text.
+//
+// Features: `full` (default) builds the fastembed-backed `LocalEmbedder`.
+// `quantized` adds `LocalEmbedder::new_quantized` for the INT8 model.
+// With `default-features = false, features = ["stub"]`, `LocalEmbedder` is an
+// alias for `DummyEmbedder` and no model code is compiled.
+// `OpenAiEmbedder` and `OllamaEmbedder` call HTTP APIs and are always available.
+
+#[cfg(not(any(feature = "full", feature = "stub")))]
+compile_error!("the embed crate requires either the \"full\" or the \"stub\" feature");
 
+#[cfg(feature = "full")]
 use std::sync::Mutex;
 use async_trait::async_trait;
 use anyhow::Result;
+#[cfg(feature = "full")]
 use fastembed::{TextEmbedding, InitOptions, EmbeddingModel};
 
+mod ollama;
+mod openai;
+
+pub use ollama::{OllamaEmbedder, OLLAMA_BASE_URL};
+pub use openai::{OpenAiEmbedder, OPENAI_BASE_URL};
+
 /// Trait for generating embeddings from text.
 #[async_trait]
 pub trait Embedder: Send + Sync {
@@ -15,21 +32,145 @@ pub trait Embedder: Send + Sync {
 	async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
 	/// Return the embedding dimension.
 	fn dimension(&self) -> usize;
+	/// Most tokens the model reads from one input; the rest is silently truncated.
+	/// `None` if the limit is unknown.
+	fn max_input_tokens(&self) -> Option<usize> {
+		None
+	}
+	/// Byte ranges of the tokens the model's tokenizer splits `text` into,
+	/// without special tokens. `None` if the embedder has no tokenizer.
+	fn token_spans(&self, _text: &str) -> Option<Vec<(usize, usize)>> {
+		None
+	}
+}
+
+/// An embedding model `LocalEmbedder::from_name` can load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedModelInfo {
+	/// Name accepted by `LocalEmbedder::from_name` (case-insensitive)
+	pub name: String,
+	pub dimension: usize,
+	pub description: String,
+}
+
+/// Hand-maintained list of the fastembed models offered by name: name, dimension, description.
+/// `fastembed_model` must map every name here to its `EmbeddingModel` variant.
+const MODELS: &[(&str, usize, &str)] = &[
+	("all-MiniLM-L6-v2", 384, "Default. Small, fast English model"),
+	("all-MiniLM-L12-v2", 384, "Deeper MiniLM, a little slower and more accurate"),
+	("all-mpnet-base-v2", 768, "Sentence Transformers mpnet base model"),
+	("bge-small-en-v1.5", 384, "BGE small English model"),
+	("bge-base-en-v1.5", 768, "BGE base English model"),
+	("bge-large-en-v1.5", 1024, "BGE large English model"),
+	("multilingual-e5-small", 384, "Small multilingual E5 model"),
+	("multilingual-e5-base", 768, "Base multilingual E5 model"),
+	("multilingual-e5-large", 1024, "Large multilingual E5 model"),
+	("paraphrase-multilingual-MiniLM-L12-v2", 384, "Multilingual paraphrase MiniLM"),
+	("nomic-embed-text-v1.5", 768, "English model with an 8192-token context"),
+	("mxbai-embed-large-v1", 1024, "Large English model from Mixedbread"),
+];
+
+fn model_table() -> Vec<EmbedModelInfo> {
+	MODELS.iter()
+		.map(|&(name, dimension, description)| EmbedModelInfo {
+			name: name.to_string(),
+			dimension,
+			description: description.to_string(),
+		})
+		.collect()
+}
+
+/// Look up a model by name, ignoring case.
+fn find_model(name: &str) -> Result<EmbedModelInfo> {
+	model_table()
+		.into_iter()
+		.find(|m| m.name.eq_ignore_ascii_case(name))
+		.ok_or_else(|| {
+			let names: Vec<&str> = MODELS.iter().map(|m| m.0).collect();
+			anyhow::anyhow!("Unknown embedding model '{}', expected one of: {}", name, names.join(", "))
+		})
+}
+
+/// The fastembed variant for a name from `MODELS`.
+#[cfg(feature = "full")]
+fn fastembed_model(name: &str) -> Option<EmbeddingModel> {
+	Some(match name {
+		"all-MiniLM-L6-v2" => EmbeddingModel::AllMiniLML6V2,
+		"all-MiniLM-L12-v2" => EmbeddingModel::AllMiniLML12V2,
+		"all-mpnet-base-v2" => EmbeddingModel::AllMpnetBaseV2,
+		"bge-small-en-v1.5" => EmbeddingModel::BGESmallENV15,
+		"bge-base-en-v1.5" => EmbeddingModel::BGEBaseENV15,
+		"bge-large-en-v1.5" => EmbeddingModel::BGELargeENV15,
+		"multilingual-e5-small" => EmbeddingModel::MultilingualE5Small,
+		"multilingual-e5-base" => EmbeddingModel::MultilingualE5Base,
+		"multilingual-e5-large" => EmbeddingModel::MultilingualE5Large,
+		"paraphrase-multilingual-MiniLM-L12-v2" => EmbeddingModel::ParaphraseMLMiniLML12V2,
+		"nomic-embed-text-v1.5" => EmbeddingModel::NomicEmbedTextV15,
+		"mxbai-embed-large-v1" => EmbeddingModel::MxbaiEmbedLargeV1,
+		_ => return None,
+	})
+}
+
+/// Sequence length all-MiniLM-L6-v2 was trained with; longer inputs lose their tail.
+#[cfg(feature = "full")]
+const MINILM_MAX_TOKENS: usize = 256;
+
+/// The longest prefix of `text` that fits in `limit` tokens, ending at a word boundary.
+/// `spans` are the byte ranges of `text`'s tokens. A first word longer than the
+/// limit is cut mid-word rather than dropped.
+#[cfg(any(feature = "full", test))]
+fn truncate_to_tokens<'a>(text: &'a str, spans: &[(usize, usize)], limit: usize) -> &'a str {
+	let Some(&(cut, _)) = spans.get(limit) else {
+		return text;
+	};
+	let head = &text[..cut];
+	if text[cut..].starts_with(char::is_whitespace) || head.ends_with(char::is_whitespace) {
+		return head.trim_end();
+	}
+	match head.rfind(char::is_whitespace) {
+		Some(end) => head[..end].trim_end(),
+		None => head,
+	}
+}
+
+/// Characters of a chunk quoted in the truncation warning.
+#[cfg(any(feature = "full", test))]
+const WARNING_PREVIEW_CHARS: usize = 40;
+
+/// Warning for a `text` cut down to `kept`, naming the chunk by its first words
+/// and counting the tokens dropped; `None` if nothing was cut.
+#[cfg(any(feature = "full", test))]
+fn truncation_warning(text: &str, kept: &str, spans: &[(usize, usize)], max_tokens: usize) -> Option<String> {
+	if kept.len() == text.len() {
+		return None;
+	}
+	let dropped = spans.iter().filter(|(start, _)| *start >= kept.len()).count();
+	let words: Vec<&str> = text.split_whitespace().collect();
+	let mut preview: String = words.join(" ").chars().take(WARNING_PREVIEW_CHARS).collect();
+	if preview.chars().count() == WARNING_PREVIEW_CHARS {
+		preview.push_str("...");
+	}
+	Some(format!("chunk \"{}\" is over the embedding model's {}-token limit, dropped its last {} tokens", preview, max_tokens, dropped))
 }
 
 /// Local embedder using fastembed (runs entirely offline).
+#[cfg(feature = "full")]
 pub struct LocalEmbedder {
 	model: Mutex<TextEmbedding>,
 	dim: usize,
+	/// The model's tokenizer with truncation and padding off, for counting tokens
+	tokenizer: tokenizers::Tokenizer,
+	max_tokens: usize,
 }
 
+#[cfg(feature = "full")]
 impl LocalEmbedder {
 	/// Create a new LocalEmbedder with the default model (all-MiniLM-L6-v2, 384 dimensions).
 	pub fn new() -> Result<Self> {
 		let options = InitOptions::new(EmbeddingModel::AllMiniLML6V2)
 			.with_show_download_progress(true);
 		let model = TextEmbedding::try_new(options)?;
-		Ok(Self { model: Mutex::new(model), dim: 384 })
+		Self::from_model(model, 384, Some(MINILM_MAX_TOKENS))
 	}
 
 	/// Create a LocalEmbedder, optionally with GPU acceleration.
@@ -51,7 +192,7 @@ impl LocalEmbedder {
 				match TextEmbedding::try_new(options) {
 					Ok(model) => {
 						eprintln!("  ✓ CUDA acceleration enabled");
-						return Ok(Self { model: Mutex::new(model), dim: 384 });
+						return Self::from_model(model, 384, Some(MINILM_MAX_TOKENS));
 					}
 					Err(e) => {
 						eprintln!("  ✗ CUDA init failed: {}", e);
@@ -71,37 +212,140 @@ impl LocalEmbedder {
 		Self::new()
 	}
 
+	/// Create a LocalEmbedder with the INT8-quantized all-MiniLM-L6-v2 (~6 MB instead of ~23 MB).
+	/// Its embeddings are within 1% cosine distance of the FP32 model's, so both can
+	/// share an index. Always runs on the CPU.
+	#[cfg(feature = "quantized")]
+	pub fn new_quantized() -> Result<Self> {
+		let options = InitOptions::new(EmbeddingModel::AllMiniLML6V2Q)
+			.with_show_download_progress(true);
+		let model = TextEmbedding::try_new(options)?;
+		Self::from_model(model, 384, Some(MINILM_MAX_TOKENS))
+	}
+
 	/// Create a LocalEmbedder with a specific model.
+	/// Its token limit is taken from the tokenizer's truncation length.
 	pub fn with_model(model_name: EmbeddingModel, dim: usize) -> Result<Self> {
 		let options = InitOptions::new(model_name)
 			.with_show_download_progress(true);
 		let model = TextEmbedding::try_new(options)?;
-		Ok(Self { model: Mutex::new(model), dim })
+		Self::from_model(model, dim, None)
+	}
+
+	/// Create a LocalEmbedder from a model name as listed by `list_models`, e.g. in a config file.
+	pub fn from_name(name: &str) -> Result<Self> {
+		let info = find_model(name)?;
+		if info.name == MODELS[0].0 {
+			return Self::new();
+		}
+		let model = fastembed_model(&info.name)
+			.ok_or_else(|| anyhow::anyhow!("No fastembed model for '{}'", info.name))?;
+		Self::with_model(model, info.dimension)
+	}
+
+	/// The models `from_name` accepts, with their dimensions.
+	pub fn list_models() -> Vec<EmbedModelInfo> {
+		model_table()
+	}
+
+	/// Most tokens the model reads from one input, including its [CLS]/[SEP] tokens.
+	pub fn max_tokens(&self) -> usize {
+		self.max_tokens
+	}
+
+	/// `text` cut at a word boundary so the model sees all of what is embedded,
+	/// instead of fastembed silently dropping the tokens past the limit. Warns
+	/// when anything is cut.
+	fn truncate<'a>(&self, text: &'a str) -> &'a str {
+		let Some(spans) = self.token_spans(text) else {
+			return text;
+		};
+		let kept = truncate_to_tokens(text, &spans, self.max_tokens.saturating_sub(2));
+		if let Some(warning) = truncation_warning(text, kept, &spans, self.max_tokens) {
+			eprintln!("  warning: {}", warning);
+		}
+		kept
+	}
+
+	/// Wrap a loaded model. Without `max_tokens`, the tokenizer's truncation length is used.
+	fn from_model(model: TextEmbedding, dim: usize, max_tokens: Option<usize>) -> Result<Self> {
+		let mut tokenizer = model.tokenizer.clone();
+		let truncation = tokenizer.get_truncation().map(|t| t.max_length);
+		tokenizer
+			.with_truncation(None)
+			.map_err(|e| anyhow::anyhow!("Failed to configure tokenizer: {}", e))?
+			.with_padding(None);
+		let max_tokens = max_tokens.or(truncation).unwrap_or(MINILM_MAX_TOKENS);
+		Ok(Self { model: Mutex::new(model), dim, tokenizer, max_tokens })
 	}
 }
 
+#[cfg(feature = "full")]
 #[async_trait]
 impl Embedder for LocalEmbedder {
 	async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+		let text = self.truncate(text);
 		let mut model = self.model.lock().map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?;
 		let embeddings = model.embed(vec![text], None)?;
 		Ok(embeddings.into_iter().next().unwrap_or_default())
 	}
 
 	async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+		let texts: Vec<&str> = texts.iter().map(|text| self.truncate(text)).collect();
 		let mut model = self.model.lock().map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?;
-		let embeddings = model.embed(texts.to_vec(), None)?;
+		let embeddings = model.embed(texts, None)?;
 		Ok(embeddings)
 	}
 
 	fn dimension(&self) -> usize {
 		self.dim
 	}
+
+	fn max_input_tokens(&self) -> Option<usize> {
+		Some(self.max_tokens)
+	}
+
+	fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+		let encoding = self.tokenizer.encode(text, false).ok()?;
+		Some(encoding.get_offsets().iter().copied().filter(|(start, end)| end > start).collect())
+	}
 }
 
+/// Without the `full` feature, `LocalEmbedder` is the zero-vector stub.
+#[cfg(not(feature = "full"))]
+pub type LocalEmbedder = DummyEmbedder;
+
 // Example stub implementation (for testing without model download)
 pub struct DummyEmbedder;
 
+impl DummyEmbedder {
+	/// Mirrors `LocalEmbedder::new` so callers compile against either feature.
+	pub fn new() -> Result<Self> {
+		Ok(Self)
+	}
+
+	/// Mirrors `LocalEmbedder::new_with_options`; the GPU flag is ignored.
+	pub fn new_with_options(_use_gpu: bool) -> Result<Self> {
+		Ok(Self)
+	}
+
+	/// Mirrors `LocalEmbedder::new_quantized`.
+	pub fn new_quantized() -> Result<Self> {
+		Ok(Self)
+	}
+
+	/// Mirrors `LocalEmbedder::from_name`; unknown names are still rejected.
+	pub fn from_name(name: &str) -> Result<Self> {
+		find_model(name)?;
+		Ok(Self)
+	}
+
+	/// Mirrors `LocalEmbedder::list_models`.
+	pub fn list_models() -> Vec<EmbedModelInfo> {
+		model_table()
+	}
+}
+
 #[async_trait]
 impl Embedder for DummyEmbedder {
 	async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
@@ -117,3 +361,57 @@ impl Embedder for DummyEmbedder {
 	}
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Whitespace-separated words as tokens, with words over 4 bytes split in two.
+	fn spans(text: &str) -> Vec<(usize, usize)> {
+		let mut spans = Vec::new();
+		let mut start = None;
+		for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+			match (c.is_whitespace(), start) {
+				(true, Some(s)) => {
+					if i - s > 4 {
+						spans.push((s, s + 4));
+						spans.push((s + 4, i));
+					} else {
+						spans.push((s, i));
+					}
+					start = None;
+				}
+				(false, None) => start = Some(i),
+				_ => {}
+			}
+		}
+		spans
+	}
+
+	#[test]
+	fn test_truncate_to_tokens_at_word_boundary() {
+		let text = "one two three four";
+		// Tokens: one, two, thre, e, four
+		assert_eq!(truncate_to_tokens(text, &spans(text), 10), text);
+		assert_eq!(truncate_to_tokens(text, &spans(text), 2), "one two");
+		// The limit falls inside "three": the whole word is dropped
+		assert_eq!(truncate_to_tokens(text, &spans(text), 3), "one two");
+		assert_eq!(truncate_to_tokens(text, &spans(text), 4), "one two three");
+		// A single long word is cut rather than emptied
+		assert_eq!(truncate_to_tokens("unbelievable", &spans("unbelievable"), 1), "unbe");
+	}
+
+	#[test]
+	fn test_truncation_warning() {
+		let text = "one two three four";
+		assert_eq!(truncation_warning(text, text, &spans(text), 10), None);
+		// Tokens: one, two, thre, e, four
+		let warning = truncation_warning(text, "one two", &spans(text), 4).unwrap();
+		assert_eq!(warning, "chunk \"one two three four\" is over the embedding model's 4-token limit, dropped its last 3 tokens");
+
+		let long = "lorem  ipsum\n".repeat(10);
+		// Whitespace is collapsed and the chunk quoted by its first 40 characters
+		let warning = truncation_warning(&long, "lorem  ipsum", &spans(&long), 6).unwrap();
+		assert_eq!(warning, "chunk \"lorem ipsum lorem ipsum lorem ipsum lore...\" is over the embedding model's 6-token limit, dropped its last 36 tokens");
+	}
+}
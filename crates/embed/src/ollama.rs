@@ -0,0 +1,77 @@
+//! Embedder for Ollama's native `/api/embeddings` endpoint.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::Embedder;
+
+/// Where `ollama serve` listens by default.
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Embeds text with a model served by Ollama.
+pub struct OllamaEmbedder {
+	base_url: String,
+	model: String,
+	dim: usize,
+	client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+	model: &'a str,
+	prompt: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+	embedding: Vec<f32>,
+}
+
+impl OllamaEmbedder {
+	/// Use `model` on the Ollama server at `base_url`; it returns `dim`-dimensional vectors.
+	pub fn new(base_url: &str, model: &str, dim: usize) -> Self {
+		Self {
+			base_url: base_url.trim_end_matches('/').to_string(),
+			model: model.to_string(),
+			dim,
+			client: reqwest::Client::new(),
+		}
+	}
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+	async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+		let endpoint = format!("{}/api/embeddings", self.base_url);
+		let response = self.client.post(&endpoint)
+			.json(&EmbeddingRequest { model: &self.model, prompt: text })
+			.send().await
+			.with_context(|| format!("Failed to reach Ollama at {}", self.base_url))?;
+		let status = response.status();
+		if !status.is_success() {
+			let body = response.text().await.unwrap_or_default();
+			anyhow::bail!("Ollama returned {}: {}", status, body.trim());
+		}
+		let embedding = response.json::<EmbeddingResponse>().await
+			.context("Failed to parse Ollama response")?
+			.embedding;
+		anyhow::ensure!(
+			embedding.len() == self.dim,
+			"Ollama model {} returned {} dimensions, expected {}", self.model, embedding.len(), self.dim
+		);
+		Ok(embedding)
+	}
+
+	/// `/api/embeddings` takes one prompt per request, so the batch is sent concurrently.
+	async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+		futures::future::join_all(texts.iter().map(|text| self.embed(text)))
+			.await
+			.into_iter()
+			.collect()
+	}
+
+	fn dimension(&self) -> usize {
+		self.dim
+	}
+}
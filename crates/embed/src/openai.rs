@@ -0,0 +1,123 @@
+//! Embedder for OpenAI-compatible `/v1/embeddings` endpoints: the OpenAI API
+//! itself, or a local llama.cpp, Ollama or LM Studio server.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::Embedder;
+
+/// Base URL of the hosted OpenAI API.
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com";
+
+/// Embeds text by calling an OpenAI-compatible HTTP API.
+pub struct OpenAiEmbedder {
+	base_url: String,
+	api_key: Option<String>,
+	model: String,
+	dim: usize,
+	client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+	input: &'a [&'a str],
+	model: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+	data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+	embedding: Vec<f32>,
+	/// Position of the input this embedding belongs to; servers may reorder them.
+	#[serde(default)]
+	index: Option<usize>,
+}
+
+impl OpenAiEmbedder {
+	/// Use the server at `base_url` (e.g. `http://localhost:8080` for llama.cpp,
+	/// `http://localhost:11434` for Ollama), which returns `dim`-dimensional vectors for `model`.
+	pub fn new(base_url: impl Into<String>, model: impl Into<String>, dim: usize) -> Self {
+		Self {
+			base_url: base_url.into(),
+			api_key: None,
+			model: model.into(),
+			dim,
+			client: reqwest::Client::new(),
+		}
+	}
+
+	/// Use the hosted OpenAI API. The dimension is looked up from the model name;
+	/// unknown models are assumed to return 1536 dimensions.
+	pub fn new_openai(api_key: impl Into<String>, model: impl Into<String>) -> Self {
+		let model = model.into();
+		let dim = match model.as_str() {
+			"text-embedding-3-large" => 3072,
+			_ => 1536,
+		};
+		Self::new(OPENAI_BASE_URL, model, dim).with_api_key(api_key)
+	}
+
+	/// Send `key` as a bearer token with every request.
+	pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+		self.api_key = Some(key.into());
+		self
+	}
+
+	/// The embeddings endpoint; `base_url` may be given with or without the `/v1` suffix.
+	fn endpoint(&self) -> String {
+		let base = self.base_url.trim_end_matches('/');
+		let base = base.strip_suffix("/v1").unwrap_or(base);
+		format!("{}/v1/embeddings", base)
+	}
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+	async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+		self.embed_batch(&[text]).await?
+			.pop()
+			.context("Embeddings API returned no embedding")
+	}
+
+	async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+		if texts.is_empty() {
+			return Ok(Vec::new());
+		}
+		let endpoint = self.endpoint();
+		let mut request = self.client.post(&endpoint)
+			.json(&EmbeddingRequest { input: texts, model: &self.model });
+		if let Some(key) = &self.api_key {
+			request = request.bearer_auth(key);
+		}
+		let response = request.send().await
+			.with_context(|| format!("Failed to reach embeddings API at {}", endpoint))?;
+		let status = response.status();
+		if !status.is_success() {
+			let body = response.text().await.unwrap_or_default();
+			anyhow::bail!("Embeddings API returned {}: {}", status, body.trim());
+		}
+		let mut data = response.json::<EmbeddingResponse>().await
+			.context("Failed to parse embeddings API response")?
+			.data;
+		anyhow::ensure!(
+			data.len() == texts.len(),
+			"Embeddings API returned {} embeddings for {} inputs", data.len(), texts.len()
+		);
+		data.sort_by_key(|d| d.index);
+
+		let embeddings: Vec<Vec<f32>> = data.into_iter().map(|d| d.embedding).collect();
+		if let Some(wrong) = embeddings.iter().find(|e| e.len() != self.dim) {
+			anyhow::bail!("Embeddings API returned {} dimensions, expected {}", wrong.len(), self.dim);
+		}
+		Ok(embeddings)
+	}
+
+	fn dimension(&self) -> usize {
+		self.dim
+	}
+}
@@ -16,6 +16,7 @@ async fn test_dummy_embedder_batch() {
     assert_eq!(vecs[0].len(), 384);
 }
 
+#[cfg(feature = "full")]
 #[tokio::test]
 async fn test_local_embedder() {
     // This test downloads the model on first run (~23MB)
@@ -32,6 +33,52 @@ async fn test_local_embedder() {
     assert!(vec.iter().any(|&x| x != 0.0), "Embedding should not be all zeros");
 }
 
+#[cfg(feature = "full")]
+#[test]
+fn test_local_embedder_token_limit() {
+    let embedder = LocalEmbedder::new();
+    if embedder.is_err() {
+        eprintln!("Skipping LocalEmbedder token test");
+        return;
+    }
+    let embedder = embedder.unwrap();
+    assert_eq!(embedder.max_input_tokens(), Some(256));
+    // Spans cover the whole text and are not truncated at the model limit
+    let text = "token ".repeat(600);
+    let spans = embedder.token_spans(&text).unwrap();
+    assert_eq!(spans.len(), 600);
+    assert_eq!(&text[spans[0].0..spans[0].1], "token");
+}
+
+#[cfg(feature = "full")]
+#[tokio::test]
+async fn test_local_embedder_truncates_long_input() {
+    let embedder = LocalEmbedder::new();
+    if embedder.is_err() {
+        eprintln!("Skipping LocalEmbedder truncation test");
+        return;
+    }
+    let embedder = embedder.unwrap();
+    assert_eq!(embedder.max_tokens(), 256);
+    // ~300 tokens about cats, then ~1700 about revenue that the model cannot see
+    let head = "Cats are small furry pets that purr and sleep all day. ".repeat(25);
+    let tail = "Quarterly revenue grew by twelve percent over the fiscal year. ".repeat(150);
+    let long = format!("{}{}", head, tail);
+    assert!(embedder.token_spans(&long).unwrap().len() > 2000);
+
+    let vecs = embedder.embed_batch(&[&long, &head, &tail]).await.unwrap();
+    let cosine = |a: &[f32], b: &[f32]| {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b))
+    };
+    // Only the head was embedded: the result matches it, not the full text's main topic
+    assert!(cosine(&vecs[0], &vecs[1]) > 0.99);
+    assert!(cosine(&vecs[0], &vecs[2]) < 0.5);
+    assert_eq!(embedder.embed(&long).await.unwrap().len(), 384);
+}
+
+#[cfg(feature = "full")]
 #[tokio::test]
 async fn test_local_embedder_batch() {
     let embedder = LocalEmbedder::new();
@@ -46,3 +93,52 @@ async fn test_local_embedder_batch() {
         assert_eq!(v.len(), 384);
     }
 }
+
+#[cfg(feature = "quantized")]
+#[tokio::test]
+async fn test_quantized_matches_fp32() {
+    // Downloads both models on first run (~23MB + ~6MB)
+    let (fp32, int8) = match (LocalEmbedder::new(), LocalEmbedder::new_quantized()) {
+        (Ok(a), Ok(b)) => (a, b),
+        _ => {
+            eprintln!("Skipping quantized embedder test (model download may have failed)");
+            return;
+        }
+    };
+    let texts = ["The quick brown fox jumps over the lazy dog.", "Quarterly revenue grew by 12%.", "fn main() { println!(\"hi\"); }"];
+    let a = fp32.embed_batch(&texts).await.unwrap();
+    let b = int8.embed_batch(&texts).await.unwrap();
+    for (x, y) in a.iter().zip(&b) {
+        assert_eq!(y.len(), 384);
+        let dot: f32 = x.iter().zip(y).map(|(p, q)| p * q).sum();
+        let norm = |v: &[f32]| v.iter().map(|p| p * p).sum::<f32>().sqrt();
+        let cosine = dot / (norm(x) * norm(y));
+        assert!(cosine > 0.99, "cosine similarity {} too low", cosine);
+    }
+}
+
+#[cfg(not(feature = "full"))]
+#[tokio::test]
+async fn test_stub_local_embedder() {
+    // With the "stub" feature LocalEmbedder never loads a model
+    let embedder = LocalEmbedder::new_with_options(true).unwrap();
+    let vec = embedder.embed("hello world").await.unwrap();
+    assert_eq!(vec.len(), 384);
+    assert!(vec.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn test_list_models() {
+    let models = LocalEmbedder::list_models();
+    assert!(!models.is_empty());
+    assert!(models.iter().all(|m| m.dimension > 0 && !m.description.is_empty()));
+    for name in ["all-MiniLM-L6-v2", "all-MiniLM-L12-v2", "bge-base-en-v1.5", "bge-small-en-v1.5", "multilingual-e5-large"] {
+        assert!(models.iter().any(|m| m.name == name), "missing {}", name);
+    }
+}
+
+#[test]
+fn test_from_name_rejects_unknown_model() {
+    let err = LocalEmbedder::from_name("not-a-model").err().unwrap();
+    assert!(err.to_string().contains("all-MiniLM-L6-v2"), "{}", err);
+}
@@ -0,0 +1,49 @@
+use embed::{Embedder, OllamaEmbedder};
+use mockito::Matcher;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_ollama_embedder_batch() {
+    let mut server = mockito::Server::new_async().await;
+    let mut mocks = Vec::new();
+    for (prompt, embedding) in [("hello", [1.0, 0.0, 0.0]), ("world", [0.0, 1.0, 0.0])] {
+        mocks.push(server.mock("POST", "/api/embeddings")
+            .match_body(Matcher::Json(json!({ "model": "nomic-embed-text", "prompt": prompt })))
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "embedding": embedding }).to_string())
+            .create_async()
+            .await);
+    }
+
+    let embedder = OllamaEmbedder::new(&server.url(), "nomic-embed-text", 3);
+    let vecs = embedder.embed_batch(&["hello", "world"]).await.unwrap();
+    assert_eq!(vecs, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+    assert_eq!(embedder.dimension(), 3);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_ollama_embedder_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let _missing = server.mock("POST", "/api/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "model": "missing" })))
+        .with_status(404)
+        .with_body(r#"{"error":"model \"missing\" not found, try pulling it first"}"#)
+        .create_async()
+        .await;
+    let _wrong_dim = server.mock("POST", "/api/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "model": "all-minilm" })))
+        .with_body(json!({ "embedding": [0.5, 0.5] }).to_string())
+        .create_async()
+        .await;
+
+    let err = OllamaEmbedder::new(&server.url(), "missing", 3).embed("hi").await.unwrap_err();
+    assert!(err.to_string().contains("try pulling it first"), "{}", err);
+
+    // One bad response fails the whole batch
+    let embedder = OllamaEmbedder::new(&server.url(), "all-minilm", 3);
+    let err = embedder.embed_batch(&["a", "b"]).await.unwrap_err();
+    assert!(err.to_string().contains("2 dimensions, expected 3"), "{}", err);
+}
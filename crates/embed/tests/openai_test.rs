@@ -0,0 +1,72 @@
+use embed::{Embedder, OpenAiEmbedder};
+use mockito::Matcher;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_openai_embedder_batch() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("POST", "/v1/embeddings")
+        .match_header("authorization", "Bearer sk-test")
+        .match_body(Matcher::Json(json!({ "input": ["hello", "world"], "model": "nomic-embed-text" })))
+        .with_header("content-type", "application/json")
+        // Out of order on purpose: results are matched to inputs by index
+        .with_body(json!({
+            "object": "list",
+            "data": [
+                { "object": "embedding", "index": 1, "embedding": [0.0, 1.0, 0.0] },
+                { "object": "embedding", "index": 0, "embedding": [1.0, 0.0, 0.0] }
+            ],
+            "model": "nomic-embed-text"
+        }).to_string())
+        .create_async()
+        .await;
+
+    let embedder = OpenAiEmbedder::new(server.url(), "nomic-embed-text", 3).with_api_key("sk-test");
+    let vecs = embedder.embed_batch(&["hello", "world"]).await.unwrap();
+    assert_eq!(vecs, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+    assert_eq!(embedder.dimension(), 3);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_openai_embedder_base_url_with_v1() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("POST", "/v1/embeddings")
+        .with_body(json!({ "data": [{ "embedding": [0.5, 0.5] }] }).to_string())
+        .create_async()
+        .await;
+
+    // LM Studio and Ollama document their base URL with the /v1 suffix
+    let embedder = OpenAiEmbedder::new(format!("{}/v1/", server.url()), "local", 2);
+    assert_eq!(embedder.embed("hello").await.unwrap(), vec![0.5, 0.5]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_openai_embedder_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let _unauthorized = server.mock("POST", "/v1/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "model": "private" })))
+        .with_status(401)
+        .with_body("invalid api key")
+        .create_async()
+        .await;
+    let _wrong_dim = server.mock("POST", "/v1/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "model": "small" })))
+        .with_body(json!({ "data": [{ "embedding": [1.0] }] }).to_string())
+        .create_async()
+        .await;
+
+    let err = OpenAiEmbedder::new(server.url(), "private", 3).embed("hi").await.unwrap_err();
+    assert!(err.to_string().contains("401"), "{}", err);
+    assert!(err.to_string().contains("invalid api key"), "{}", err);
+
+    let err = OpenAiEmbedder::new(server.url(), "small", 3).embed("hi").await.unwrap_err();
+    assert!(err.to_string().contains("1 dimensions, expected 3"), "{}", err);
+}
+
+#[test]
+fn test_new_openai_dimensions() {
+    assert_eq!(OpenAiEmbedder::new_openai("sk-test", "text-embedding-3-small").dimension(), 1536);
+    assert_eq!(OpenAiEmbedder::new_openai("sk-test", "text-embedding-3-large").dimension(), 3072);
+}
@@ -0,0 +1,202 @@
+//! Archive traversal for indexing files stored inside `.tar.gz` and `.tar.bz2` archives.
+//!
+//! Each supported entry is unpacked to a temporary directory and passed through the
+//! regular extractor. Entries are addressed by a virtual path of the form
+//! `<archive_path>!<inner_path>`, which is what ends up in `DocumentMetadata::file_path`.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+
+use crate::SyncTextExtractor;
+
+/// Separator between the archive path and the entry path in a virtual path.
+pub const ARCHIVE_PATH_SEPARATOR: &str = "!";
+
+/// Text extracted from a single archive entry.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+	/// Virtual path (`<archive_path>!<inner_path>`).
+	pub virtual_path: PathBuf,
+	/// Extracted text content.
+	pub text: String,
+}
+
+/// Supported archive compression formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+	TarGz,
+	TarBz2,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+	let name = path.file_name()?.to_str()?.to_lowercase();
+	if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+		Some(ArchiveKind::TarGz)
+	} else if name.ends_with(".tar.bz2") || name.ends_with(".tbz2") {
+		Some(ArchiveKind::TarBz2)
+	} else {
+		None
+	}
+}
+
+/// Check if a path looks like a supported archive.
+pub fn is_archive(path: &Path) -> bool {
+	archive_kind(path).is_some()
+}
+
+/// Build the virtual path for an entry inside an archive.
+pub fn virtual_path(archive: &Path, inner: &Path) -> PathBuf {
+	PathBuf::from(format!(
+		"{}{}{}",
+		archive.display(),
+		ARCHIVE_PATH_SEPARATOR,
+		inner.display()
+	))
+}
+
+/// Extract text from every supported entry of an archive.
+///
+/// `depth` is the number of archive levels to open: `1` reads the entries of `path`
+/// but skips archives nested inside it. To guard against decompression bombs, entries
+/// larger than `max_bytes` are skipped, and extraction fails once the entries unpacked
+/// so far (nested archives and their entries included) add up to more than `max_bytes`.
+pub fn extract_entries<E: SyncTextExtractor + ?Sized>(
+	path: &Path,
+	extractor: &E,
+	depth: usize,
+	max_bytes: u64,
+) -> Result<Vec<ArchiveEntry>> {
+	let mut results = Vec::new();
+	let mut remaining = max_bytes;
+	extract_into(path, path, extractor, depth, max_bytes, &mut remaining, &mut results)?;
+	Ok(results)
+}
+
+/// Walk the archive at `path`, naming entries relative to `display_path` and taking
+/// the size of each unpacked entry off `remaining`.
+fn extract_into<E: SyncTextExtractor + ?Sized>(
+	path: &Path,
+	display_path: &Path,
+	extractor: &E,
+	depth: usize,
+	max_bytes: u64,
+	remaining: &mut u64,
+	results: &mut Vec<ArchiveEntry>,
+) -> Result<()> {
+	let kind = match archive_kind(path) {
+		Some(k) => k,
+		None => anyhow::bail!("Not a supported archive: {}", path.display()),
+	};
+	if depth == 0 {
+		return Ok(());
+	}
+
+	let file = File::open(path)?;
+	let reader: Box<dyn Read> = match kind {
+		ArchiveKind::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+		ArchiveKind::TarBz2 => Box::new(bzip2::read::BzDecoder::new(file)),
+	};
+	let mut archive = tar::Archive::new(reader);
+
+	// Entries are unpacked under their original file name so extension and
+	// filename based detection in the extractor keep working.
+	let temp_dir = tempfile::tempdir()?;
+
+	for (i, entry) in archive.entries()?.enumerate() {
+		let mut entry = entry?;
+		if entry.header().entry_type() != tar::EntryType::Regular {
+			continue;
+		}
+		if entry.header().size()? > max_bytes {
+			continue;
+		}
+
+		let inner_path = entry.path()?.to_path_buf();
+		let file_name = match inner_path.file_name() {
+			Some(n) => n.to_owned(),
+			None => continue,
+		};
+		let nested = is_archive(&inner_path);
+		if nested && depth <= 1 {
+			continue;
+		}
+		if !nested && !crate::is_supported_file(&inner_path) {
+			continue;
+		}
+
+		let entry_dir = temp_dir.path().join(i.to_string());
+		std::fs::create_dir_all(&entry_dir)?;
+		let temp_path = entry_dir.join(file_name);
+		let mut data = Vec::new();
+		entry.read_to_end(&mut data)?;
+		*remaining = remaining.checked_sub(data.len() as u64).ok_or_else(|| {
+			anyhow::anyhow!("Archive {} decompresses to more than {} bytes", display_path.display(), max_bytes)
+		})?;
+		std::fs::write(&temp_path, &data)?;
+
+		let entry_virtual_path = virtual_path(display_path, &inner_path);
+		if nested {
+			extract_into(&temp_path, &entry_virtual_path, extractor, depth - 1, max_bytes, remaining, results)?;
+		} else {
+			let text = extractor.extract_text_sync(&temp_path)?;
+			results.push(ArchiveEntry {
+				virtual_path: entry_virtual_path,
+				text,
+			});
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_archive() {
+		assert!(is_archive(Path::new("/tmp/docs.tar.gz")));
+		assert!(is_archive(Path::new("/tmp/docs.TGZ")));
+		assert!(is_archive(Path::new("/tmp/docs.tar.bz2")));
+		assert!(!is_archive(Path::new("/tmp/docs.gz")));
+		assert!(!is_archive(Path::new("/tmp/docs.txt")));
+	}
+
+	#[test]
+	fn test_virtual_path() {
+		let path = virtual_path(Path::new("/tmp/docs.tar.gz"), Path::new("notes/a.txt"));
+		assert_eq!(path, PathBuf::from("/tmp/docs.tar.gz!notes/a.txt"));
+	}
+
+	struct ReadToString;
+	impl SyncTextExtractor for ReadToString {
+		fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+			Ok(std::fs::read_to_string(path)?)
+		}
+	}
+
+	#[test]
+	fn test_total_size_limit() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("notes.tar.gz");
+		let encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+		let mut builder = tar::Builder::new(encoder);
+		for name in ["a.txt", "b.txt"] {
+			let mut header = tar::Header::new_gnu();
+			header.set_size(30);
+			header.set_mode(0o644);
+			header.set_cksum();
+			builder.append_data(&mut header, name, [b'x'; 30].as_slice()).unwrap();
+		}
+		builder.into_inner().unwrap().finish().unwrap();
+
+		assert_eq!(extract_entries(&path, &ReadToString, 1, 60).unwrap().len(), 2);
+		// Each entry fits on its own, but not both together
+		let err = extract_entries(&path, &ReadToString, 1, 59).unwrap_err();
+		assert!(err.to_string().contains("decompresses to more than 59 bytes"));
+		// Entries over the limit on their own are skipped
+		assert!(extract_entries(&path, &ReadToString, 1, 29).unwrap().is_empty());
+	}
+}
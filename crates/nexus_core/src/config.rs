@@ -4,12 +4,96 @@
 //! 1. Current directory
 //! 2. ~/.config/nexus/nexus.config.toml (Linux/macOS)
 //! 3. %APPDATA%\nexus\nexus.config.toml (Windows)
+//!
+//! `NEXUS_*` environment variables are layered on top of the file, see
+//! [`NexusConfig::from_env`].
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::fs;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 
+use crate::{IndexOptions, RerankStrategy};
+use search::RrfParams;
+
+/// A config field whose value differs from the default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiff {
+    /// Dotted field path, e.g. `index.max_file_mb`.
+    pub key: String,
+    pub default: serde_json::Value,
+    pub current: serde_json::Value,
+}
+
+/// A problem found by [`NexusConfig::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// `search.default_mode` is not one of [`SEARCH_MODES`].
+    InvalidSearchMode(String),
+    /// `index.max_file_mb` is 0, which would skip every file.
+    ZeroMaxFileMb,
+    /// `index.max_chunks` is 0, which would skip every file.
+    ZeroMaxChunks,
+    /// `index.chunk_size` is 0.
+    ZeroChunkSize,
+    /// `search.results_count` is 0.
+    ZeroResultsCount,
+    /// `search.mmr_lambda` is outside 0.0..=1.0.
+    InvalidMmrLambda(f64),
+    /// The same directory is listed twice in `index.roots`.
+    DuplicateRoot(PathBuf),
+    /// A root does not exist (yet). Only a warning, as it may be an unmounted drive.
+    NonExistentRoot(PathBuf),
+    /// An extension is listed twice in `index.skip_extensions`. Only a warning.
+    DuplicateSkipExtension(String),
+}
+
+impl ConfigError {
+    /// Whether the config should be refused, rather than loaded with a warning.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, Self::NonExistentRoot(_) | Self::DuplicateSkipExtension(_))
+    }
+
+    /// Dotted key of the setting the problem is about.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::InvalidSearchMode(_) => "search.default_mode",
+            Self::ZeroMaxFileMb => "index.max_file_mb",
+            Self::ZeroMaxChunks => "index.max_chunks",
+            Self::ZeroChunkSize => "index.chunk_size",
+            Self::ZeroResultsCount => "search.results_count",
+            Self::InvalidMmrLambda(_) => "search.mmr_lambda",
+            Self::DuplicateRoot(_) | Self::NonExistentRoot(_) => "index.roots",
+            Self::DuplicateSkipExtension(_) => "index.skip_extensions",
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSearchMode(mode) => write!(
+                f, "search.default_mode = \"{}\" is not one of {}", mode, SEARCH_MODES.join(", ")
+            ),
+            Self::ZeroMaxFileMb => write!(f, "index.max_file_mb must be positive"),
+            Self::ZeroMaxChunks => write!(f, "index.max_chunks must be positive"),
+            Self::ZeroChunkSize => write!(f, "index.chunk_size must be positive"),
+            Self::ZeroResultsCount => write!(f, "search.results_count must be positive"),
+            Self::InvalidMmrLambda(lambda) => write!(f, "search.mmr_lambda = {} is not between 0 and 1", lambda),
+            Self::DuplicateRoot(root) => write!(f, "index.roots lists {} more than once", root.display()),
+            Self::NonExistentRoot(root) => write!(f, "index root {} does not exist", root.display()),
+            Self::DuplicateSkipExtension(ext) => write!(f, "index.skip_extensions lists '{}' more than once", ext),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Accepted values of `search.default_mode`.
+pub const SEARCH_MODES: &[&str] = &["hybrid", "semantic", "lexical"];
+
 /// Main configuration structure.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -33,10 +117,16 @@ pub struct IndexConfig {
     pub skip_files: Vec<String>,
     /// Skip hidden files and directories.
     pub skip_hidden: bool,
+    /// How many directory levels below each root to descend (unlimited if unset).
+    pub max_depth: Option<usize>,
     /// Maximum file size in MB.
     pub max_file_mb: u64,
     /// Maximum chunks per file (skip files exceeding this).
     pub max_chunks: usize,
+    /// Target chunk size in characters.
+    pub chunk_size: usize,
+    /// Characters shared between adjacent chunks (at most half of `chunk_size`).
+    pub chunk_overlap: usize,
 }
 
 impl Default for IndexConfig {
@@ -46,8 +136,11 @@ impl Default for IndexConfig {
             skip_extensions: vec!["exe".into(), "dll".into(), "so".into(), "o".into(), "pyc".into()],
             skip_files: vec!["node_modules".into(), ".git".into(), "target".into(), "__pycache__".into()],
             skip_hidden: true,
+            max_depth: None,
             max_file_mb: 50,
             max_chunks: 500,
+            chunk_size: 1500,
+            chunk_overlap: 150,
         }
     }
 }
@@ -62,6 +155,9 @@ pub struct WatchConfig {
     pub debounce_secs: u64,
     /// Patterns to ignore during watch (glob syntax).
     pub ignore_patterns: Vec<String>,
+    /// Start watching directories created under a watched root, so files added to
+    /// them are picked up without restarting.
+    pub auto_watch_new_dirs: bool,
 }
 
 impl Default for WatchConfig {
@@ -76,6 +172,7 @@ impl Default for WatchConfig {
                 ".#*".into(),
                 "*.lock".into(),
             ],
+            auto_watch_new_dirs: true,
         }
     }
 }
@@ -88,6 +185,17 @@ pub struct SearchConfig {
     pub default_mode: String,
     /// Default number of results.
     pub results_count: usize,
+    /// Reciprocal Rank Fusion constant for hybrid search. Lower values favour
+    /// results near the top of either ranking, higher values flatten ranks.
+    pub rrf_k: f64,
+    /// Multiplier for the semantic ranking in hybrid search (0 ignores it).
+    pub semantic_weight: f64,
+    /// Multiplier for the lexical ranking in hybrid search (0 ignores it).
+    pub lexical_weight: f64,
+    /// Re-ranking applied after retrieval, none by default.
+    pub rerank: Option<RerankStrategy>,
+    /// MMR trade-off between relevance (1.0) and diversity (0.0).
+    pub mmr_lambda: f64,
 }
 
 impl Default for SearchConfig {
@@ -95,6 +203,22 @@ impl Default for SearchConfig {
         Self {
             default_mode: "hybrid".into(),
             results_count: 5,
+            rrf_k: 60.0,
+            semantic_weight: 1.0,
+            lexical_weight: 1.0,
+            rerank: None,
+            mmr_lambda: 0.7,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Hybrid search fusion settings from `rrf_k` and the two weights.
+    pub fn rrf_params(&self) -> RrfParams {
+        RrfParams {
+            k: self.rrf_k,
+            semantic_weight: self.semantic_weight,
+            lexical_weight: self.lexical_weight,
         }
     }
 }
@@ -107,6 +231,9 @@ pub struct GpuConfig {
     pub enabled: bool,
     /// CUDA device ID.
     pub device_id: u32,
+    /// Use the INT8-quantized embedding model (smaller and faster on CPU).
+    /// Requires a build with the `quantized` feature.
+    pub model_quantized: bool,
 }
 
 impl Default for GpuConfig {
@@ -114,6 +241,7 @@ impl Default for GpuConfig {
         Self {
             enabled: false,
             device_id: 0,
+            model_quantized: false,
         }
     }
 }
@@ -138,14 +266,162 @@ impl NexusConfig {
     /// Config file name.
     pub const FILENAME: &'static str = "nexus.config.toml";
 
-    /// Load configuration from file, searching standard locations.
-    /// Returns default config if no file found.
+    /// Load configuration from file, searching standard locations, then apply
+    /// `NEXUS_*` environment variables on top.
+    /// Returns default config (plus environment) if no file found.
+    ///
+    /// The result is checked with [`NexusConfig::validate`]: fatal problems fail
+    /// the load, the others are printed to stderr as warnings.
     pub fn load() -> Result<Self> {
-        if let Some(path) = Self::find_config_file() {
-            Self::load_from(&path)
+        let config = Self::load_unvalidated()?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// [`NexusConfig::load`] without the validation step.
+    pub fn load_unvalidated() -> Result<Self> {
+        let mut config = if let Some(path) = Self::find_config_file() {
+            Self::load_from(&path)?
         } else {
-            Ok(Self::default())
+            Self::default()
+        };
+        config.apply_env_from(|key| std::env::var(key).ok())?;
+        Ok(config)
+    }
+
+    /// Check the settings for values that cannot work, collecting every problem found.
+    /// See [`ConfigError::is_fatal`] for which ones [`NexusConfig::load`] refuses.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        if !SEARCH_MODES.contains(&self.search.default_mode.as_str()) {
+            errors.push(ConfigError::InvalidSearchMode(self.search.default_mode.clone()));
+        }
+        if self.index.max_file_mb == 0 {
+            errors.push(ConfigError::ZeroMaxFileMb);
+        }
+        if self.index.max_chunks == 0 {
+            errors.push(ConfigError::ZeroMaxChunks);
+        }
+        if self.index.chunk_size == 0 {
+            errors.push(ConfigError::ZeroChunkSize);
+        }
+        if self.search.results_count == 0 {
+            errors.push(ConfigError::ZeroResultsCount);
+        }
+        if !(0.0..=1.0).contains(&self.search.mmr_lambda) {
+            errors.push(ConfigError::InvalidMmrLambda(self.search.mmr_lambda));
+        }
+
+        let mut roots = HashSet::new();
+        for root in &self.index.roots {
+            if !roots.insert(root) {
+                errors.push(ConfigError::DuplicateRoot(root.clone()));
+            } else if !root.exists() {
+                errors.push(ConfigError::NonExistentRoot(root.clone()));
+            }
+        }
+        let mut extensions = HashSet::new();
+        let mut reported = HashSet::new();
+        for ext in &self.index.skip_extensions {
+            let ext = ext.to_lowercase();
+            if !extensions.insert(ext.clone()) && reported.insert(ext.clone()) {
+                errors.push(ConfigError::DuplicateSkipExtension(ext));
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Run [`NexusConfig::validate`], printing warnings and failing on fatal problems.
+    fn check(&self) -> Result<()> {
+        let Err(problems) = self.validate() else {
+            return Ok(());
+        };
+        let (fatal, warnings): (Vec<_>, Vec<_>) = problems.into_iter().partition(ConfigError::is_fatal);
+        for warning in &warnings {
+            eprintln!("warning: config: {}", warning);
+        }
+        if !fatal.is_empty() {
+            let list: Vec<String> = fatal.iter().map(|e| format!("  - {}", e)).collect();
+            anyhow::bail!("Invalid configuration:\n{}", list.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Build a config from the defaults and `NEXUS_*` environment variables only.
+    ///
+    /// Unset or empty variables keep the default value.
+    ///
+    /// | variable              | field                   | format                               |
+    /// |-----------------------|-------------------------|--------------------------------------|
+    /// | `NEXUS_ROOT`          | `index.roots`           | path list, separated like `PATH`     |
+    /// | `NEXUS_CHUNK_SIZE`    | `index.chunk_size`      | integer                              |
+    /// | `NEXUS_SKIP_EXT`      | `index.skip_extensions` | comma-separated, e.g. `exe,dll`      |
+    /// | `NEXUS_SKIP_FILES`    | `index.skip_files`      | comma-separated                      |
+    /// | `NEXUS_SKIP_HIDDEN`   | `index.skip_hidden`     | bool (`1`/`0`, `true`/`false`, ...)  |
+    /// | `NEXUS_MAX_FILE_MB`   | `index.max_file_mb`     | integer                              |
+    /// | `NEXUS_MAX_CHUNKS`    | `index.max_chunks`      | integer                              |
+    /// | `NEXUS_WATCH`         | `watch.enabled`         | bool                                 |
+    /// | `NEXUS_DEBOUNCE_SECS` | `watch.debounce_secs`   | integer                              |
+    /// | `NEXUS_SEARCH_MODE`   | `search.default_mode`   | `hybrid`, `semantic` or `lexical`    |
+    /// | `NEXUS_RESULTS`       | `search.results_count`  | integer                              |
+    /// | `NEXUS_GPU`           | `gpu.enabled`           | bool                                 |
+    /// | `NEXUS_GPU_DEVICE`    | `gpu.device_id`         | integer                              |
+    /// | `NEXUS_STORAGE_PATH`  | `storage.path`          | path                                 |
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        config.apply_env_from(|key| std::env::var(key).ok())?;
+        Ok(config)
+    }
+
+    /// Overwrite fields from the variables listed in [`NexusConfig::from_env`],
+    /// reading them through `lookup`.
+    fn apply_env_from(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        let var = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+
+        if let Some(v) = var("NEXUS_ROOT") {
+            self.index.roots = std::env::split_paths(&v).collect();
+        }
+        if let Some(v) = var("NEXUS_CHUNK_SIZE") {
+            self.index.chunk_size = parse_env("NEXUS_CHUNK_SIZE", &v)?;
+        }
+        if let Some(v) = var("NEXUS_SKIP_EXT") {
+            self.index.skip_extensions = split_list(&v);
+        }
+        if let Some(v) = var("NEXUS_SKIP_FILES") {
+            self.index.skip_files = split_list(&v);
+        }
+        if let Some(v) = var("NEXUS_SKIP_HIDDEN") {
+            self.index.skip_hidden = parse_bool("NEXUS_SKIP_HIDDEN", &v)?;
         }
+        if let Some(v) = var("NEXUS_MAX_FILE_MB") {
+            self.index.max_file_mb = parse_env("NEXUS_MAX_FILE_MB", &v)?;
+        }
+        if let Some(v) = var("NEXUS_MAX_CHUNKS") {
+            self.index.max_chunks = parse_env("NEXUS_MAX_CHUNKS", &v)?;
+        }
+        if let Some(v) = var("NEXUS_WATCH") {
+            self.watch.enabled = parse_bool("NEXUS_WATCH", &v)?;
+        }
+        if let Some(v) = var("NEXUS_DEBOUNCE_SECS") {
+            self.watch.debounce_secs = parse_env("NEXUS_DEBOUNCE_SECS", &v)?;
+        }
+        if let Some(v) = var("NEXUS_SEARCH_MODE") {
+            self.search.default_mode = v.trim().to_lowercase();
+        }
+        if let Some(v) = var("NEXUS_RESULTS") {
+            self.search.results_count = parse_env("NEXUS_RESULTS", &v)?;
+        }
+        if let Some(v) = var("NEXUS_GPU") {
+            self.gpu.enabled = parse_bool("NEXUS_GPU", &v)?;
+        }
+        if let Some(v) = var("NEXUS_GPU_DEVICE") {
+            self.gpu.device_id = parse_env("NEXUS_GPU_DEVICE", &v)?;
+        }
+        if let Some(v) = var("NEXUS_STORAGE_PATH") {
+            self.storage.path = Some(PathBuf::from(v));
+        }
+        Ok(())
     }
 
     /// Load configuration from a specific path.
@@ -197,6 +473,27 @@ impl NexusConfig {
         dirs::config_dir().map(|d| d.join("nexus").join(Self::FILENAME))
     }
 
+    /// Path of the password list for encrypted PDFs (`~/.nexus/pdf_passwords.txt`).
+    pub fn pdf_passwords_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".nexus").join("pdf_passwords.txt"))
+    }
+
+    /// Read passwords for encrypted PDFs, one per line. Blank lines are skipped
+    /// and a missing file yields no passwords.
+    pub fn load_pdf_passwords(path: &Path) -> Result<Vec<String>> {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
     /// Get the data directory path (uses storage.path or default).
     pub fn data_dir(&self) -> PathBuf {
         self.storage.path.clone().unwrap_or_else(|| {
@@ -206,6 +503,93 @@ impl NexusConfig {
         })
     }
 
+    /// Indexing options for `root` as set in the `[index]` section. Settings the
+    /// config doesn't cover keep their `IndexOptions` defaults.
+    pub fn index_options(&self, root: PathBuf) -> IndexOptions {
+        IndexOptions {
+            root,
+            skip_extensions: self.index.skip_extensions.clone(),
+            skip_files: self.index.skip_files.clone(),
+            exclude_hidden: self.index.skip_hidden,
+            max_depth: self.index.max_depth,
+            max_file_size_bytes: self.index.max_file_mb * 1024 * 1024,
+            max_chunks_per_file: self.index.max_chunks,
+            chunk_size: self.index.chunk_size,
+            chunk_overlap: self.index.chunk_overlap,
+            ..Default::default()
+        }
+    }
+
+    /// List the fields whose values differ from `NexusConfig::default()`.
+    pub fn diff_from_default(&self) -> Result<Vec<ConfigDiff>> {
+        let default = serde_json::to_value(Self::default())?;
+        let current = serde_json::to_value(self)?;
+        let mut diffs = Vec::new();
+        collect_diffs("", &default, &current, &mut diffs);
+        Ok(diffs)
+    }
+
+    /// Set one dotted key (e.g. `index.chunk_size`) in the config file at `path`,
+    /// creating the file if it does not exist. Other settings are kept, but comments are not.
+    ///
+    /// `value` is parsed according to the field's type: integers must be non-negative
+    /// (and positive for sizes and counts), booleans accept `true`/`false`/`yes`/`no`/`1`/`0`,
+    /// and lists are comma-separated. Returns the previous value (`None` if unset) and the new one.
+    pub fn set_in_file(path: &PathBuf, key: &str, value: &str) -> Result<(Option<toml::Value>, toml::Value)> {
+        let defaults = serde_json::to_value(Self::default())?;
+        let default = key
+            .split('.')
+            .try_fold(&defaults, |v, part| v.get(part))
+            .filter(|v| !v.is_object())
+            .ok_or_else(|| anyhow::anyhow!("Unknown config key '{}'", key))?;
+
+        let new_value = match default {
+            serde_json::Value::Bool(_) => toml::Value::Boolean(parse_bool(key, value)?),
+            serde_json::Value::Number(n) if n.is_f64() => {
+                let f: f64 = parse_env(key, value)?;
+                if !f.is_finite() || f < 0.0 {
+                    anyhow::bail!("Invalid {}='{}': must be a non-negative number", key, value);
+                }
+                toml::Value::Float(f)
+            }
+            serde_json::Value::Number(_) | serde_json::Value::Null if default.is_number() || OPTIONAL_INTEGER_KEYS.contains(&key) => {
+                let n: u64 = parse_env(key, value)?;
+                if n == 0 && POSITIVE_KEYS.contains(&key) {
+                    anyhow::bail!("Invalid {}='{}': must be a positive integer", key, value);
+                }
+                toml::Value::Integer(i64::try_from(n)?)
+            }
+            serde_json::Value::Array(_) => toml::Value::Array(
+                split_list(value).into_iter().map(toml::Value::String).collect(),
+            ),
+            // Strings and unset optional paths
+            _ => toml::Value::String(value.to_string()),
+        };
+
+        let mut doc: toml::Table = if path.exists() {
+            toml::from_str(&fs::read_to_string(path)?)?
+        } else {
+            toml::Table::new()
+        };
+        let (section, field) = key.split_once('.').unwrap_or(("", key));
+        let table = doc
+            .entry(section.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("'{}' in {} is not a table", section, path.display()))?;
+        let previous = table
+            .insert(field.to_string(), new_value.clone())
+            .or_else(|| toml::Value::try_from(default).ok());
+
+        // Make sure the whole file still loads before writing it
+        let _: NexusConfig = doc.clone().try_into()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(&doc)?)?;
+        Ok((previous, new_value))
+    }
+
     /// Generate a default config file with comments.
     pub fn generate_default_config() -> String {
         r#"# Nexus Local Configuration
@@ -227,12 +611,21 @@ skip_files = ["node_modules", ".git", "target", "__pycache__", "venv", ".venv"]
 # Skip hidden files (starting with .)
 skip_hidden = true
 
+# Only descend this many directory levels below each root (1 = files directly in it)
+# max_depth = 5
+
 # Maximum file size in MB
 max_file_mb = 50
 
 # Skip files that produce more than this many chunks
 max_chunks = 500
 
+# Target chunk size in characters
+chunk_size = 1500
+
+# Characters repeated from the end of each chunk at the start of the next
+chunk_overlap = 150
+
 [watch]
 # Enable watch mode
 enabled = false
@@ -243,6 +636,9 @@ debounce_secs = 2
 # Patterns to ignore during watch (glob syntax)
 ignore_patterns = ["*.tmp", "*.swp", "*~", ".#*", "*.lock"]
 
+# Watch directories created after startup
+auto_watch_new_dirs = true
+
 [search]
 # Default search mode: "hybrid", "semantic", or "lexical"
 default_mode = "hybrid"
@@ -250,6 +646,18 @@ default_mode = "hybrid"
 # Default number of results
 results_count = 5
 
+# Hybrid search fuses the semantic and lexical rankings with Reciprocal Rank
+# Fusion: each result scores weight / (rrf_k + rank). A lower rrf_k favours top
+# ranks, which suits mostly lexical corpora; raise it for semantic ones
+rrf_k = 60.0
+semantic_weight = 1.0
+lexical_weight = 1.0
+
+# Re-rank results with Maximal Marginal Relevance to drop near-duplicate chunks.
+# mmr_lambda trades relevance (1.0) against diversity (0.0)
+# rerank = "mmr"
+mmr_lambda = 0.7
+
 [gpu]
 # Enable CUDA GPU acceleration
 enabled = false
@@ -257,6 +665,9 @@ enabled = false
 # CUDA device ID (for multi-GPU systems)
 device_id = 0
 
+# Use the INT8-quantized embedding model (needs a build with --features quantized)
+model_quantized = false
+
 [storage]
 # Path for index data (default: ~/.local/share/nexus_local)
 # path = "/custom/path/to/nexus_data"
@@ -264,6 +675,89 @@ device_id = 0
     }
 }
 
+/// 1-based line on which the dotted `key` is set in the TOML document `content`.
+/// `None` if the key is not in the document or the document does not parse.
+pub fn key_line(content: &str, key: &str) -> Option<usize> {
+    let doc = toml::de::DeTable::parse(content).ok()?;
+    let (parents, field) = key.rsplit_once('.').unwrap_or(("", key));
+    let mut table = doc.get_ref();
+    for part in parents.split('.').filter(|p| !p.is_empty()) {
+        table = table.iter().find(|(k, _)| k.get_ref() == part)?.1.get_ref().as_table()?;
+    }
+    let (name, _) = table.iter().find(|(k, _)| k.get_ref() == field)?;
+    Some(content[..name.span().start].matches('\n').count() + 1)
+}
+
+/// Integer settings for which zero makes no sense.
+const POSITIVE_KEYS: &[&str] = &[
+    "index.max_file_mb",
+    "index.max_chunks",
+    "index.chunk_size",
+    "watch.debounce_secs",
+    "search.results_count",
+    "index.max_depth",
+];
+
+/// Integer settings that are unset by default.
+const OPTIONAL_INTEGER_KEYS: &[&str] = &["index.max_depth"];
+
+/// Parse a numeric environment variable, naming it in the error.
+fn parse_env<T: std::str::FromStr>(key: &str, value: &str) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid {}='{}': {}", key, value, e))
+}
+
+/// Parse a boolean environment variable (`1`/`0`, `true`/`false`, `yes`/`no`, `on`/`off`).
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("Invalid {}='{}': expected true or false", key, value),
+    }
+}
+
+/// Split a comma-separated list, dropping empty entries.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Walk two JSON trees, recording leaves that differ under dotted keys.
+/// Arrays are compared as a whole.
+fn collect_diffs(prefix: &str, default: &serde_json::Value, current: &serde_json::Value, out: &mut Vec<ConfigDiff>) {
+    use serde_json::Value;
+
+    if let (Value::Object(d), Value::Object(c)) = (default, current) {
+        let mut keys: Vec<&String> = d.keys().chain(c.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            collect_diffs(
+                &path,
+                d.get(key).unwrap_or(&Value::Null),
+                c.get(key).unwrap_or(&Value::Null),
+                out,
+            );
+        }
+    } else if default != current {
+        out.push(ConfigDiff {
+            key: prefix.to_string(),
+            default: default.clone(),
+            current: current.clone(),
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,4 +785,242 @@ mod tests {
         assert_eq!(config.index.max_file_mb, 100);
         assert_eq!(config.search.default_mode, "semantic");
     }
+
+    #[test]
+    fn test_index_options() {
+        let mut config = NexusConfig::default();
+        config.index.max_file_mb = 2;
+        config.index.chunk_size = 800;
+        config.index.skip_hidden = false;
+        let options = config.index_options(PathBuf::from("/docs"));
+        assert_eq!(options.root, PathBuf::from("/docs"));
+        assert_eq!(options.max_file_size_bytes, 2 * 1024 * 1024);
+        assert_eq!(options.chunk_size, 800);
+        assert!(!options.exclude_hidden);
+        assert_eq!(options.skip_files, config.index.skip_files);
+    }
+
+    #[test]
+    fn test_rrf_params() {
+        assert_eq!(SearchConfig::default().rrf_params(), RrfParams::default());
+        let config = SearchConfig { rrf_k: 10.0, lexical_weight: 0.0, ..Default::default() };
+        assert_eq!(config.rrf_params(), RrfParams { k: 10.0, semantic_weight: 1.0, lexical_weight: 0.0 });
+    }
+
+    #[test]
+    fn test_diff_from_default() {
+        assert!(NexusConfig::default().diff_from_default().unwrap().is_empty());
+
+        let mut config = NexusConfig::default();
+        config.index.max_file_mb = 100;
+        config.gpu.enabled = true;
+        let diffs = config.diff_from_default().unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].key, "gpu.enabled");
+        assert_eq!(diffs[1].key, "index.max_file_mb");
+        assert_eq!(diffs[1].default, serde_json::json!(50));
+        assert_eq!(diffs[1].current, serde_json::json!(100));
+    }
+
+    #[test]
+    fn test_from_env() {
+        // Only this test sets NEXUS_* variables, so it cannot race with others.
+        let root = std::env::join_paths(["/data/docs", "/data/notes"]).unwrap();
+        std::env::set_var("NEXUS_ROOT", &root);
+        std::env::set_var("NEXUS_CHUNK_SIZE", "800");
+        std::env::set_var("NEXUS_GPU", "true");
+        std::env::set_var("NEXUS_SKIP_EXT", "png, jpg,,zip");
+        std::env::set_var("NEXUS_STORAGE_PATH", "/var/lib/nexus");
+        std::env::set_var("NEXUS_RESULTS", "");
+        let config = NexusConfig::from_env();
+        for key in ["NEXUS_ROOT", "NEXUS_CHUNK_SIZE", "NEXUS_GPU", "NEXUS_SKIP_EXT", "NEXUS_STORAGE_PATH", "NEXUS_RESULTS"] {
+            std::env::remove_var(key);
+        }
+
+        let config = config.unwrap();
+        assert_eq!(config.index.roots, vec![PathBuf::from("/data/docs"), PathBuf::from("/data/notes")]);
+        assert_eq!(config.index.chunk_size, 800);
+        assert!(config.gpu.enabled);
+        assert_eq!(config.index.skip_extensions, vec!["png", "jpg", "zip"]);
+        assert_eq!(config.storage.path, Some(PathBuf::from("/var/lib/nexus")));
+        // Empty values keep the default
+        assert_eq!(config.search.results_count, 5);
+    }
+
+    #[test]
+    fn test_env_layers_over_file_config() {
+        let mut config: NexusConfig = toml::from_str("[index]\nmax_file_mb = 100\nmax_chunks = 20\n").unwrap();
+        config.apply_env_from(|key| match key {
+            "NEXUS_MAX_CHUNKS" => Some("40".to_string()),
+            "NEXUS_WATCH" => Some("on".to_string()),
+            _ => None,
+        }).unwrap();
+        assert_eq!(config.index.max_file_mb, 100);
+        assert_eq!(config.index.max_chunks, 40);
+        assert!(config.watch.enabled);
+    }
+
+    #[test]
+    fn test_set_in_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(NexusConfig::FILENAME);
+        fs::write(&path, "[index]\nroots = [\"/docs\"]\n").unwrap();
+
+        let (old, new) = NexusConfig::set_in_file(&path, "index.max_file_mb", "100").unwrap();
+        assert_eq!(old, Some(toml::Value::Integer(50)));
+        assert_eq!(new, toml::Value::Integer(100));
+        NexusConfig::set_in_file(&path, "gpu.enabled", "yes").unwrap();
+        NexusConfig::set_in_file(&path, "index.skip_extensions", "png,jpg").unwrap();
+        NexusConfig::set_in_file(&path, "storage.path", "/var/lib/nexus").unwrap();
+        let (old, new) = NexusConfig::set_in_file(&path, "search.rrf_k", "20").unwrap();
+        assert_eq!(old, Some(toml::Value::Float(60.0)));
+        assert_eq!(new, toml::Value::Float(20.0));
+        NexusConfig::set_in_file(&path, "search.rerank", "mmr").unwrap();
+        let (old, new) = NexusConfig::set_in_file(&path, "index.max_depth", "3").unwrap();
+        assert_eq!(old, None);
+        assert_eq!(new, toml::Value::Integer(3));
+
+        let config = NexusConfig::load_from(&path).unwrap();
+        assert_eq!(config.index.max_file_mb, 100);
+        assert_eq!(config.index.roots, vec![PathBuf::from("/docs")]);
+        assert!(config.gpu.enabled);
+        assert_eq!(config.index.skip_extensions, vec!["png", "jpg"]);
+        assert_eq!(config.storage.path, Some(PathBuf::from("/var/lib/nexus")));
+        assert_eq!(config.search.rrf_k, 20.0);
+        assert_eq!(config.search.rerank, Some(RerankStrategy::Mmr));
+        assert_eq!(config.index.max_depth, Some(3));
+    }
+
+    #[test]
+    fn test_set_in_file_rejects_invalid_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(NexusConfig::FILENAME);
+        assert!(NexusConfig::set_in_file(&path, "index.chunk_size", "0").is_err());
+        assert!(NexusConfig::set_in_file(&path, "index.chunk_size", "-5").is_err());
+        assert!(NexusConfig::set_in_file(&path, "gpu.enabled", "maybe").is_err());
+        assert!(NexusConfig::set_in_file(&path, "search.lexical_weight", "-1").is_err());
+        assert!(NexusConfig::set_in_file(&path, "search.rerank", "bm25").is_err());
+        assert!(NexusConfig::set_in_file(&path, "index.max_depth", "0").is_err());
+        assert!(NexusConfig::set_in_file(&path, "index.nope", "1").is_err());
+        assert!(NexusConfig::set_in_file(&path, "index", "1").is_err());
+        // Nothing was written
+        assert!(!path.exists());
+    }
+
+    /// Validate `config`, expecting exactly one problem.
+    fn single_problem(config: &NexusConfig) -> ConfigError {
+        let mut problems = config.validate().unwrap_err();
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        problems.remove(0)
+    }
+
+    #[test]
+    fn test_validate_default_config() {
+        assert_eq!(NexusConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_invalid_search_mode() {
+        let mut config = NexusConfig::default();
+        config.search.default_mode = "fuzzy".into();
+        let problem = single_problem(&config);
+        assert_eq!(problem, ConfigError::InvalidSearchMode("fuzzy".into()));
+        assert!(problem.is_fatal());
+        assert!(problem.to_string().contains("fuzzy"));
+    }
+
+    #[test]
+    fn test_validate_zero_values() {
+        let mut config = NexusConfig::default();
+        config.index.max_file_mb = 0;
+        assert_eq!(single_problem(&config), ConfigError::ZeroMaxFileMb);
+
+        let mut config = NexusConfig::default();
+        config.index.max_chunks = 0;
+        assert_eq!(single_problem(&config), ConfigError::ZeroMaxChunks);
+
+        let mut config = NexusConfig::default();
+        config.index.chunk_size = 0;
+        assert_eq!(single_problem(&config), ConfigError::ZeroChunkSize);
+
+        let mut config = NexusConfig::default();
+        config.search.results_count = 0;
+        assert_eq!(single_problem(&config), ConfigError::ZeroResultsCount);
+    }
+
+    #[test]
+    fn test_validate_invalid_mmr_lambda() {
+        let mut config = NexusConfig::default();
+        config.search.mmr_lambda = 1.5;
+        assert_eq!(single_problem(&config), ConfigError::InvalidMmrLambda(1.5));
+        config.search.mmr_lambda = 1.0;
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = NexusConfig::default();
+        config.index.roots = vec![dir.path().to_path_buf(), dir.path().to_path_buf()];
+        let problem = single_problem(&config);
+        assert_eq!(problem, ConfigError::DuplicateRoot(dir.path().to_path_buf()));
+        assert!(problem.is_fatal());
+
+        let missing = dir.path().join("missing");
+        config.index.roots = vec![missing.clone()];
+        let problem = single_problem(&config);
+        assert_eq!(problem, ConfigError::NonExistentRoot(missing));
+        assert!(!problem.is_fatal());
+    }
+
+    #[test]
+    fn test_validate_duplicate_skip_extension() {
+        let mut config = NexusConfig::default();
+        config.index.skip_extensions = vec!["png".into(), "PNG".into(), "png".into(), "jpg".into()];
+        let problem = single_problem(&config);
+        assert_eq!(problem, ConfigError::DuplicateSkipExtension("png".into()));
+        assert!(!problem.is_fatal());
+    }
+
+    #[test]
+    fn test_check_fails_only_on_fatal_problems() {
+        let mut config = NexusConfig::default();
+        config.index.skip_extensions = vec!["png".into(), "png".into()];
+        assert!(config.check().is_ok());
+
+        config.index.max_file_mb = 0;
+        config.search.default_mode = "fuzzy".into();
+        let err = config.check().unwrap_err().to_string();
+        assert!(err.contains("index.max_file_mb"));
+        assert!(err.contains("fuzzy"));
+    }
+
+    #[test]
+    fn test_key_line() {
+        let content = "[index]\nroots = [\n  \"/a\",\n]\nmax_file_mb = 0\n\n[search]\ndefault_mode = \"fuzzy\"\n";
+        assert_eq!(key_line(content, "index.roots"), Some(2));
+        assert_eq!(key_line(content, "index.max_file_mb"), Some(5));
+        assert_eq!(key_line(content, ConfigError::InvalidSearchMode("fuzzy".into()).key()), Some(8));
+        assert_eq!(key_line(content, "search.results_count"), None);
+        assert_eq!(key_line("[index\n", "index.roots"), None);
+    }
+
+    #[test]
+    fn test_load_pdf_passwords() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pdf_passwords.txt");
+        assert!(NexusConfig::load_pdf_passwords(&path).unwrap().is_empty());
+
+        fs::write(&path, "hunter2\r\n\n correct horse \nlast").unwrap();
+        assert_eq!(NexusConfig::load_pdf_passwords(&path).unwrap(), vec!["hunter2", " correct horse ", "last"]);
+    }
+
+    #[test]
+    fn test_invalid_env_value() {
+        let mut config = NexusConfig::default();
+        let err = config.apply_env_from(|key| (key == "NEXUS_GPU").then(|| "maybe".to_string())).unwrap_err();
+        assert!(err.to_string().contains("NEXUS_GPU"));
+        let err = config.apply_env_from(|key| (key == "NEXUS_CHUNK_SIZE").then(|| "big".to_string())).unwrap_err();
+        assert!(err.to_string().contains("NEXUS_CHUNK_SIZE"));
+    }
 }
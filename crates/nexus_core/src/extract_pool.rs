@@ -0,0 +1,181 @@
+//! Bounded thread pool for running extractions under a timeout.
+//!
+//! Threads can't be killed, so an extraction that times out keeps its worker
+//! busy until it returns by itself. Workers are started on demand and reused,
+//! and the pool never grows past its limit, so hung extractions can't pile up
+//! threads: once every worker is stuck, new extractions wait in the queue and
+//! time out in turn.
+
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use anyhow::Result;
+
+/// Workers kept beyond one per caller thread, so a few hung extractions don't
+/// hold up the rest of the run.
+const SPARE_WORKERS: usize = 4;
+
+/// Runs an extraction, returning what hands its result to the caller. Kept apart
+/// so the worker counts as free again before the caller can queue the next job.
+type Job = Box<dyn FnOnce() -> Delivery + Send>;
+type Delivery = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct Queue {
+	jobs: VecDeque<Job>,
+	/// Workers started and not yet exited.
+	workers: usize,
+	/// Workers running a job.
+	busy: usize,
+	closed: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+	queue: Mutex<Queue>,
+	ready: Condvar,
+}
+
+/// Runs extractions on at most `max_workers` threads, failing those that take
+/// longer than their timeout.
+pub(crate) struct ExtractPool {
+	shared: Arc<Shared>,
+	max_workers: usize,
+}
+
+impl ExtractPool {
+	/// Pool for `callers` threads extracting at once (0 = one per CPU, as rayon does).
+	pub(crate) fn new(callers: usize) -> Self {
+		let callers = if callers == 0 { rayon::current_num_threads() } else { callers };
+		Self { shared: Arc::default(), max_workers: callers + SPARE_WORKERS }
+	}
+
+	/// Run `extract` on a worker, failing with "timeout after ..." if it hasn't
+	/// returned within `timeout` of being queued. `None` runs it inline.
+	pub(crate) fn run<T: Send + 'static>(&self, timeout: Option<Duration>, extract: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+		let Some(timeout) = timeout else {
+			return extract();
+		};
+		let (tx, rx) = std::sync::mpsc::channel();
+		self.submit(Box::new(move || {
+			let result = extract();
+			Box::new(move || { let _ = tx.send(result); })
+		}))?;
+		match rx.recv_timeout(timeout) {
+			Ok(result) => result,
+			Err(std::sync::mpsc::RecvTimeoutError::Timeout) => anyhow::bail!("timeout after {:?}", timeout),
+			Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => anyhow::bail!("extractor panicked"),
+		}
+	}
+
+	/// [`run`](Self::run) for async callers: waits without blocking the runtime.
+	pub(crate) async fn run_async<T: Send + 'static>(&self, timeout: Option<Duration>, extract: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+		let Some(timeout) = timeout else {
+			return extract();
+		};
+		let (tx, rx) = tokio::sync::oneshot::channel();
+		self.submit(Box::new(move || {
+			let result = extract();
+			Box::new(move || { let _ = tx.send(result); })
+		}))?;
+		match tokio::time::timeout(timeout, rx).await {
+			Ok(Ok(result)) => result,
+			Ok(Err(_)) => anyhow::bail!("extractor panicked"),
+			Err(_) => anyhow::bail!("timeout after {:?}", timeout),
+		}
+	}
+
+	/// Queue `job`, starting a worker if none is free and the limit allows.
+	fn submit(&self, job: Job) -> Result<()> {
+		let mut queue = self.shared.queue.lock().unwrap();
+		queue.jobs.push_back(job);
+		let free = queue.workers - queue.busy;
+		if queue.jobs.len() > free && queue.workers < self.max_workers {
+			let shared = self.shared.clone();
+			std::thread::Builder::new()
+				.name("nexus-extract".to_string())
+				.spawn(move || work(&shared))?;
+			queue.workers += 1;
+		}
+		drop(queue);
+		self.shared.ready.notify_one();
+		Ok(())
+	}
+
+	/// Workers started so far, including any stuck on a hung extraction.
+	#[cfg(test)]
+	fn workers(&self) -> usize {
+		self.shared.queue.lock().unwrap().workers
+	}
+}
+
+impl Drop for ExtractPool {
+	/// Let idle workers exit; busy ones exit when their job returns.
+	fn drop(&mut self) {
+		self.shared.queue.lock().unwrap().closed = true;
+		self.shared.ready.notify_all();
+	}
+}
+
+/// Worker loop: run queued jobs until the pool is dropped and the queue is empty.
+fn work(shared: &Shared) {
+	let mut queue = shared.queue.lock().unwrap();
+	loop {
+		if let Some(job) = queue.jobs.pop_front() {
+			queue.busy += 1;
+			drop(queue);
+			// A panicking extractor drops its result sender, which the caller reports
+			let delivery = std::panic::catch_unwind(AssertUnwindSafe(job));
+			queue = shared.queue.lock().unwrap();
+			queue.busy -= 1;
+			if let Ok(deliver) = delivery {
+				deliver();
+			}
+		} else if queue.closed {
+			queue.workers -= 1;
+			return;
+		} else {
+			queue = shared.ready.wait(queue).unwrap();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_workers_are_reused() {
+		let pool = ExtractPool::new(1);
+		for i in 0..20 {
+			assert_eq!(pool.run(Some(Duration::from_secs(5)), move || Ok(i)).unwrap(), i);
+		}
+		assert_eq!(pool.workers(), 1);
+	}
+
+	#[test]
+	fn test_hung_extractions_are_bounded() {
+		let pool = ExtractPool::new(1);
+		let (release, hold) = std::sync::mpsc::channel::<()>();
+		let hold = Arc::new(Mutex::new(hold));
+		for _ in 0..10 {
+			let hold = hold.clone();
+			let result = pool.run(Some(Duration::from_millis(20)), move || {
+				let _ = hold.lock().unwrap().recv();
+				Ok(())
+			});
+			assert_eq!(result.unwrap_err().to_string(), "timeout after 20ms");
+		}
+		assert_eq!(pool.workers(), 1 + SPARE_WORKERS);
+		drop(release);
+	}
+
+	#[test]
+	fn test_panic_is_reported() {
+		let pool = ExtractPool::new(1);
+		let result: Result<()> = pool.run(Some(Duration::from_secs(5)), || panic!("bad document"));
+		assert_eq!(result.unwrap_err().to_string(), "extractor panicked");
+		assert_eq!(pool.run(Some(Duration::from_secs(5)), || Ok(1)).unwrap(), 1);
+	}
+}
@@ -0,0 +1,111 @@
+//! Skipping generated and vendored documentation files marked in `.gitattributes`.
+//!
+//! Paths tagged `linguist-generated` or `linguist-documentation` (the markers GitHub
+//! uses to hide files from diffs and language stats) are excluded from indexing.
+
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use gix_attributes::glob::{pattern::Case, wildmatch, Pattern};
+use gix_attributes::parse::Kind;
+use gix_attributes::StateRef;
+
+/// Attributes that mark a path as not worth indexing.
+const EXCLUDE_ATTRIBUTES: &[&str] = &["linguist-generated", "linguist-documentation"];
+
+/// A single pattern from a `.gitattributes` file that sets or unsets an exclude attribute.
+#[derive(Debug, Clone)]
+struct Rule {
+	/// Directory containing the `.gitattributes` file; patterns are relative to it.
+	base: PathBuf,
+	pattern: Pattern,
+	excluded: bool,
+}
+
+/// Exclusion rules collected from the `.gitattributes` files seen so far.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedFilter {
+	rules: Vec<Rule>,
+}
+
+impl GeneratedFilter {
+	/// Load `dir/.gitattributes` if it exists. Call this for parent directories
+	/// before their children, so deeper files take precedence as in git.
+	pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+		let path = dir.join(".gitattributes");
+		if !path.is_file() {
+			return Ok(());
+		}
+		let contents = std::fs::read(&path)?;
+		self.add_rules(dir, &contents);
+		Ok(())
+	}
+
+	/// Parse `.gitattributes` contents whose patterns are relative to `base`.
+	/// Malformed lines are ignored, as git does.
+	pub fn add_rules(&mut self, base: &Path, contents: &[u8]) {
+		for (kind, assignments, _line) in gix_attributes::parse(contents).filter_map(Result::ok) {
+			let pattern = match kind {
+				Kind::Pattern(p) => p,
+				Kind::Macro(_) => continue,
+			};
+			for assignment in assignments.filter_map(Result::ok) {
+				if !EXCLUDE_ATTRIBUTES.contains(&assignment.name.as_str()) {
+					continue;
+				}
+				let excluded = match assignment.state {
+					StateRef::Set => true,
+					StateRef::Value(v) => v.as_bstr() != "false",
+					StateRef::Unset | StateRef::Unspecified => false,
+				};
+				self.rules.push(Rule { base: base.to_path_buf(), pattern: pattern.clone(), excluded });
+			}
+		}
+	}
+
+	/// Whether `path` is marked generated or documentation. The last matching rule wins.
+	pub fn is_excluded(&self, path: &Path) -> bool {
+		for rule in self.rules.iter().rev() {
+			let relative = match path.strip_prefix(&rule.base) {
+				Ok(r) => r.to_string_lossy().replace('\\', "/"),
+				Err(_) => continue,
+			};
+			let basename_start = relative.rfind('/').map(|i| i + 1);
+			if rule.pattern.matches_repo_relative_path(
+				relative.as_bytes().into(),
+				basename_start,
+				Some(false),
+				Case::Sensitive,
+				wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+			) {
+				return rule.excluded;
+			}
+		}
+		false
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_linguist_markers() {
+		let mut filter = GeneratedFilter::default();
+		filter.add_rules(Path::new("/repo"), b"\
+# protobuf output
+*.pb.rs linguist-generated
+src/generated.rs linguist-generated=true
+docs/** linguist-documentation
+docs/keep.md -linguist-documentation
+*.txt text eol=lf
+");
+		assert!(filter.is_excluded(Path::new("/repo/src/api.pb.rs")));
+		assert!(filter.is_excluded(Path::new("/repo/src/generated.rs")));
+		assert!(filter.is_excluded(Path::new("/repo/docs/guide/intro.md")));
+		assert!(!filter.is_excluded(Path::new("/repo/docs/keep.md")));
+		assert!(!filter.is_excluded(Path::new("/repo/src/main.rs")));
+		assert!(!filter.is_excluded(Path::new("/repo/notes.txt")));
+		// Patterns don't apply outside the directory of their .gitattributes
+		assert!(!filter.is_excluded(Path::new("/other/src/generated.rs")));
+	}
+}
@@ -2,31 +2,182 @@
 //
 // High-level API for orchestrating file indexing, chunking, and embedding.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use async_trait::async_trait;
 use anyhow::Result;
 use std::ffi::OsStr;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 use sysinfo::System;
 use rayon::prelude::*;
-pub use store::{VectorStore, DocumentMetadata, SearchResult, StateManager, FileState, LexicalIndex, LexicalDoc, LexicalSearchResult};
+use serde::{Deserialize, Serialize};
+pub use uuid::Uuid;
+pub use tokio_util::sync::CancellationToken;
+use futures::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+pub use store::{VectorStore, InMemoryVectorStore, DocumentMetadata, SearchOptions, SearchResult, StateManager, FileState, IntegrityReport, LexicalIndex, LexicalDoc, LexicalSearchResult, LexicalStats, SearchMode, SortField, DistanceMetric};
 // Re-export paged extraction types from ocr crate
-pub use ocr::{ExtractedPage, PagedExtractor};
+pub use ocr::{DocumentInfo, EncodingFallback, ExtractedPage, ExtractorOptions, OcrOptions, PagedExtractor, PasswordProtected, PlainTextExtractor, DEFAULT_OCR_LANGUAGE, MAX_IMAGE_DIMENSION};
+use extract_pool::ExtractPool;
 
 // Configuration, watch mode, and service modules
 pub mod config;
 pub mod watch;
 pub mod service;
+pub mod archive;
+pub mod preset;
+pub mod telemetry;
+pub mod gitattributes;
+pub mod rerank;
+mod extract_pool;
 
-pub use config::NexusConfig;
+pub use config::{NexusConfig, ConfigDiff, ConfigError};
 pub use watch::{FileWatcher, ChangeBatch};
-pub use service::ServiceManager;
+pub use service::{ServiceManager, ServiceStatus};
+pub use preset::IndexPreset;
+pub use rerank::{RerankStrategy, ScoredResult, mmr_rerank};
+
+/// How extracted text is split into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkStrategy {
+	/// Prefer paragraph breaks, then whitespace near the size limit.
+	#[default]
+	WordBoundary,
+	/// Split at exactly `chunk_size` characters with no boundary search.
+	/// Much faster for data files with long unbroken tokens.
+	Fixed,
+	/// At most this many tokens per chunk, counted with the embedder's tokenizer
+	/// and breaking between words, so no chunk is truncated by the model.
+	/// Capped at `Embedder::max_input_tokens`; ignores `chunk_size` and `chunk_overlap`.
+	Tokens(usize),
+	/// Split source code before top-level definitions (functions, classes, impls),
+	/// so a definition is only cut when it is longer than `chunk_size` by itself.
+	/// Picked automatically for code files when the strategy is `WordBoundary`.
+	Code(CodeLanguage),
+	/// Split Markdown at its headings, starting every chunk with the path of headings
+	/// above it (`# Guide > ## Install`). Picked automatically for Markdown files when
+	/// the strategy is `WordBoundary`.
+	Markdown,
+}
+
+/// Languages whose top-level definitions `ChunkStrategy::Code` recognises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+	Rust,
+	Python,
+	/// JavaScript and TypeScript.
+	JavaScript,
+}
+
+impl CodeLanguage {
+	/// Whether `line` starts a top-level definition. Indented lines never do.
+	fn starts_definition(self, line: &str) -> bool {
+		let prefixes: &[&str] = match self {
+			CodeLanguage::Rust => &["fn ", "async fn ", "const fn ", "unsafe fn ", "impl ", "impl<", "struct ", "enum ", "trait "],
+			CodeLanguage::Python => &["def ", "async def ", "class "],
+			CodeLanguage::JavaScript => &["function ", "function*", "async function ", "class "],
+		};
+		let line = match self {
+			CodeLanguage::Rust => strip_rust_visibility(line),
+			CodeLanguage::Python => line,
+			CodeLanguage::JavaScript => {
+				let line = line.strip_prefix("export ").unwrap_or(line);
+				line.strip_prefix("default ").unwrap_or(line)
+			}
+		};
+		prefixes.iter().any(|prefix| line.starts_with(prefix))
+	}
+
+	/// Whether `line` is a comment, attribute or decorator that belongs to the
+	/// definition below it.
+	fn is_annotation(self, line: &str) -> bool {
+		let prefixes: &[&str] = match self {
+			CodeLanguage::Rust => &["//", "/*", " *", "#["],
+			CodeLanguage::Python => &["#", "@"],
+			CodeLanguage::JavaScript => &["//", "/*", " *", "@"],
+		};
+		prefixes.iter().any(|prefix| line.starts_with(prefix))
+	}
+}
+
+/// `line` without a leading `pub `, `pub(crate) ` or similar.
+fn strip_rust_visibility(line: &str) -> &str {
+	if let Some(rest) = line.strip_prefix("pub ") {
+		return rest;
+	}
+	line.strip_prefix("pub(")
+		.and_then(|rest| rest.split_once(") "))
+		.map_or(line, |(_, rest)| rest)
+}
+
+/// How the indexer decides whether a previously indexed file changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeDetection {
+	/// Re-index when the modification time is newer than the stored one.
+	#[default]
+	Mtime,
+	/// Always compare a SHA-256 of the file's first 64 KB with the stored hash, so
+	/// touched-but-identical files are skipped and edits that keep the mtime are caught.
+	Hash,
+	/// Hash only files whose mtime changed: skips touched-but-identical files
+	/// without reading every unchanged file, but misses edits that keep the mtime.
+	MtimeThenHash,
+}
+
+impl ChangeDetection {
+	/// Whether content hashes are compared (and so must be recorded).
+	pub fn uses_hash(self) -> bool {
+		self != ChangeDetection::Mtime
+	}
+}
+
+impl std::str::FromStr for ChangeDetection {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		match s.to_lowercase().as_str() {
+			"mtime" => Ok(ChangeDetection::Mtime),
+			"hash" => Ok(ChangeDetection::Hash),
+			"mtime-then-hash" => Ok(ChangeDetection::MtimeThenHash),
+			_ => anyhow::bail!("Unknown change detection '{}' (expected mtime, hash or mtime-then-hash)", s),
+		}
+	}
+}
+
+/// Characters per token assumed when the embedder can't tokenize.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Extensions that always use `ChunkStrategy::Fixed` (row-oriented data files).
+const BINARY_LIKE_EXTENSIONS: &[&str] = &["csv", "tsv", "jsonl"];
+
+/// Extensions chunked with `ChunkStrategy::Markdown` in place of `WordBoundary`.
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Extensions chunked with `ChunkStrategy::Code` in place of `WordBoundary`.
+const CODE_EXTENSIONS: &[(&str, CodeLanguage)] = &[
+	("rs", CodeLanguage::Rust),
+	("py", CodeLanguage::Python),
+	("js", CodeLanguage::JavaScript),
+	("jsx", CodeLanguage::JavaScript),
+	("mjs", CodeLanguage::JavaScript),
+	("ts", CodeLanguage::JavaScript),
+	("tsx", CodeLanguage::JavaScript),
+];
 
 /// Options for configuring the indexer.
+#[derive(Debug, Clone)]
 pub struct IndexOptions {
 	pub root: PathBuf,
 	pub chunk_size: usize,
+	/// Characters each chunk repeats from the end of the previous one, so matches
+	/// near a boundary keep their context. Capped at half of `chunk_size`;
+	/// `ChunkStrategy::Fixed` chunks never overlap.
+	pub chunk_overlap: usize,
+	/// Chunking strategy. Data files (csv, tsv, jsonl) always use `Fixed`.
+	pub chunk_strategy: ChunkStrategy,
 	/// Maximum file size to process (bytes). Files larger are skipped.
 	pub max_file_size_bytes: u64,
 	/// Maximum memory to use (bytes). Used for throttling.
@@ -36,8 +187,68 @@ pub struct IndexOptions {
 	pub max_chunks_per_file: usize,
 	/// File extensions to skip (e.g., ["png", "jpg"] to skip images).
 	pub skip_extensions: Vec<String>,
-	/// File name patterns to skip (substring match).
+	/// File name patterns to skip (substring match). Directories named exactly
+	/// like a pattern are skipped along with everything in them.
 	pub skip_files: Vec<String>,
+	/// Maximum snippet length (chars) stored with each chunk for previews.
+	pub snippet_length: usize,
+	/// Index supported files stored inside `.tar.gz`/`.tar.bz2` archives.
+	pub follow_archive_contents: bool,
+	/// How many levels of nested archives to open (1 = top-level archives only).
+	pub archive_depth: usize,
+	/// Threads used for parallel text extraction (0 = one per CPU core).
+	pub threads: usize,
+	/// Maximum chunks sent to the embedder per call (0 = a whole file or page at once).
+	pub embed_batch_size: usize,
+	/// How changed files are detected (see `ChangeDetection`).
+	pub change_detection: ChangeDetection,
+	/// Emit a `ChunkEmbedded` event for every chunk in addition to the
+	/// per-batch `BatchEmbedded` summary.
+	pub verbose_events: bool,
+	/// Stop the run once this many chunks have been indexed. Checked between files,
+	/// so a file is never left half-indexed; the rest are picked up by the next run.
+	pub max_total_chunks: Option<usize>,
+	/// Skip files marked `linguist-generated` or `linguist-documentation` in any
+	/// `.gitattributes` found while walking the root.
+	pub auto_exclude_generated: bool,
+	/// Skip files and directories whose name starts with a dot, such as `.env`
+	/// or `.ssh/`. The root itself is walked even if it is hidden.
+	pub exclude_hidden: bool,
+	/// How many directory levels below the root to descend: 1 finds only the files
+	/// directly in it. `None` walks the whole tree.
+	pub max_depth: Option<usize>,
+	/// Skip paths matched by `.gitignore` files (also outside a git repository),
+	/// `.git/info/exclude` and the global gitignore, as well as `.git` itself.
+	pub respect_gitignore: bool,
+	/// Skip paths matched by `.nexusignore` files, which use gitignore syntax.
+	pub use_nexusignore: bool,
+	/// Extract (see `PagedExtractor::extract_pages_parallel`) and chunk the pages of
+	/// paged files such as PDFs in parallel, then embed them in order. Holds every
+	/// page's chunks in memory at once, and falls back to sequential processing when
+	/// memory use is already above `max_memory_bytes`.
+	pub parallel_pages: bool,
+	/// Re-embed every file, even unchanged ones, replacing its existing embeddings
+	/// through `VectorStore::reindex_file` (paged files and archives, stored in several
+	/// batches, have theirs removed first). Used after switching embedding models.
+	pub replace_existing: bool,
+	/// Warn when a file has chunks longer than the embedder's `max_input_tokens`,
+	/// whose tails the model never sees. Counted with the embedder's tokenizer if
+	/// it has one, otherwise estimated at ~4 chars per token.
+	pub warn_on_truncation: bool,
+	/// Discover, extract and chunk files as usual but embed and store nothing:
+	/// the store, lexical index and state are left untouched. Each chunk is
+	/// reported as `ChunkProcessed` and counted in `chunks_indexed`.
+	pub dry_run: bool,
+	/// Compact the vector store at the end of `run_with_progress` once
+	/// `garbage_collect` has removed more than this many embeddings since the
+	/// last compaction (see `VectorStore::compact`). `None` never compacts.
+	pub auto_compact_threshold: Option<usize>,
+	/// Give up on extracting a file after this long and report it as an error, so
+	/// a malformed document can't hang the run. `None` waits indefinitely.
+	pub file_timeout: Option<Duration>,
+	/// How files are read. The indexer takes a ready-made extractor, so these only
+	/// take effect through `plain_text_extractor`.
+	pub extractor: ExtractorOptions,
 }
 
 impl Default for IndexOptions {
@@ -45,18 +256,148 @@ impl Default for IndexOptions {
 		Self { 
 			root: PathBuf::new(), 
 			chunk_size: 1500, // ~375 tokens, good balance of context vs granularity
+			chunk_overlap: 150,
+			chunk_strategy: ChunkStrategy::default(),
 			max_file_size_bytes: 50 * 1024 * 1024, // 50MB
 			max_memory_bytes: 4 * 1024 * 1024 * 1024, // 4GB
 			max_chunks_per_file: 500, // Skip files that would create >500 chunks
 			skip_extensions: Vec::new(),
 			skip_files: Vec::new(),
+			snippet_length: 200,
+			follow_archive_contents: false,
+			archive_depth: 1,
+			threads: 0,
+			embed_batch_size: 0,
+			change_detection: ChangeDetection::default(),
+			verbose_events: false,
+			max_total_chunks: None,
+			auto_exclude_generated: false,
+			exclude_hidden: true,
+			max_depth: None,
+			respect_gitignore: true,
+			use_nexusignore: true,
+			parallel_pages: false,
+			replace_existing: false,
+			warn_on_truncation: false,
+			dry_run: false,
+			auto_compact_threshold: Some(1000),
+			file_timeout: Some(Duration::from_secs(30)),
+			extractor: ExtractorOptions::default(),
+		}
+	}
+}
+
+impl IndexOptions {
+	/// A `PlainTextExtractor` configured from `extractor`, rejecting ZIP archives
+	/// that decompress to more than `max_file_size_bytes`.
+	pub fn plain_text_extractor(&self) -> PlainTextExtractor {
+		PlainTextExtractor::from_options(&self.extractor).with_max_archive_bytes(self.max_file_size_bytes)
+	}
+}
+
+/// Snippets of the chunks within `window` positions of `chunk_index` in the same file.
+pub async fn context_snippets<S: VectorStore + ?Sized>(
+	store: &S,
+	lexical: &LexicalIndex,
+	file_path: &str,
+	chunk_index: usize,
+	window: usize,
+) -> Result<Vec<String>> {
+	let mut snippets = Vec::new();
+	for neighbour in lexical.search_near_chunk(file_path, chunk_index, window)? {
+		if let Some(snippet) = store.get_metadata(&neighbour.doc_id).await?.and_then(|m| m.snippet) {
+			snippets.push(snippet);
 		}
 	}
+	Ok(snippets)
+}
+
+/// Fill `context_chunks` of each result according to `options.context_window`.
+pub async fn populate_context_chunks<S: VectorStore + ?Sized>(
+	results: &mut [SearchResult],
+	store: &S,
+	lexical: &LexicalIndex,
+	options: &SearchOptions,
+) -> Result<()> {
+	if options.context_window == 0 {
+		return Ok(());
+	}
+	for result in results.iter_mut() {
+		let file_path = result.metadata.file_path.to_string_lossy();
+		result.context_chunks = context_snippets(
+			store,
+			lexical,
+			&file_path,
+			result.metadata.chunk_index,
+			options.context_window,
+		).await?;
+	}
+	Ok(())
+}
+
+/// Remove one file from the index: its embeddings, lexical entries and state record.
+/// Embeddings are found through the doc_ids in `state` (so entries for files inside
+/// an archive go with the archive) and by path, for rows the state lost track of.
+/// The lexical index is not committed, so callers removing many files commit once.
+pub async fn delete_indexed_file<S: VectorStore + ?Sized>(
+	store: &S,
+	state: Option<&StateManager>,
+	lexical: Option<&LexicalIndex>,
+	path: &Path,
+) -> Result<DeletionResult> {
+	let doc_ids = match state {
+		Some(state) => state.get_doc_ids(path)?,
+		None => Vec::new(),
+	};
+	let tracked = state.map(|s| s.get_file_state(path)).transpose()?
+		.is_some_and(|s| s != FileState::NotIndexed);
+
+	let mut embeddings_removed = store.delete_by_doc_ids(&doc_ids).await?;
+	embeddings_removed += store.delete_by_file_path(path).await?;
+	if let Some(lexical) = lexical {
+		lexical.delete_by_doc_ids(&doc_ids)?;
+		lexical.delete_by_file_path(&path.to_string_lossy())?;
+	}
+	if let Some(state) = state {
+		state.remove_file(path)?;
+	}
+
+	Ok(DeletionResult {
+		files_removed: usize::from(tracked || embeddings_removed > 0),
+		embeddings_removed,
+	})
+}
+
+/// Remove near-duplicate embeddings (see [`VectorStore::deduplicate`]) along with
+/// their lexical entries and state rows, returning how many were removed. As with
+/// [`delete_indexed_file`], the lexical index is left for the caller to commit.
+pub async fn deduplicate_index<S: VectorStore + ?Sized>(
+	store: &S,
+	state: Option<&StateManager>,
+	lexical: Option<&LexicalIndex>,
+	similarity_threshold: f32,
+) -> Result<usize> {
+	let removed = store.deduplicate(similarity_threshold).await?;
+	if let Some(lexical) = lexical {
+		lexical.delete_by_doc_ids(&removed)?;
+	}
+	if let Some(state) = state {
+		state.remove_doc_ids(&removed)?;
+	}
+	Ok(removed.len())
 }
 
 /// Events emitted during indexing for progress reporting and resumability.
-#[derive(Debug)]
+///
+/// Serializes as a JSON object with a kebab-case `type` tag and named fields
+/// (e.g. `{"type": "file-skipped", "path": "...", "reason": "..."}`) for IPC and logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "IndexEventRepr", into = "IndexEventRepr")]
 pub enum IndexEvent {
+	/// A run began; `session_id` matches the run's `IndexResult` for log correlation.
+	IndexingStarted { session_id: Uuid },
+	/// Files found for this run, sent once discovery finishes so progress can be determinate.
+	Started { total_files: usize },
 	FileStarted(PathBuf),
 	FileIndexed(PathBuf),
 	FileError(PathBuf, String),
@@ -64,19 +405,194 @@ pub enum IndexEvent {
 	FileUnchanged(PathBuf), // File already indexed and not modified
 	MemoryPressure(u64, u64), // (used_mb, limit_mb) - pausing due to memory pressure
 	PageProcessed(PathBuf, usize, usize), // (path, page_num, total_pages)
-	ChunkProcessed(PathBuf, usize),
-	ChunkEmbedded(PathBuf, usize, String), // path, chunk_index, doc_id
+	ChunkProcessed(PathBuf, usize), // path, chunk_index (only with `dry_run`, in place of `ChunkIndexed`)
+	ChunkEmbedded(PathBuf, usize, String), // path, chunk_index, doc_id (only with `verbose_events`)
+	/// One `embed_batch` call finished. `tokens_estimated` assumes ~4 chars per token.
+	BatchEmbedded { path: PathBuf, chunk_count: usize, elapsed_ms: u64, tokens_estimated: usize },
+	/// Chunk stored, with its byte range in the extracted text (for editor navigation).
+	ChunkIndexed { path: PathBuf, chunk_index: usize, byte_start: usize, byte_end: usize },
+	/// The run stopped early (e.g. `max_total_chunks` reached or the run was cancelled);
+	/// remaining files are left for the next run.
+	IndexingPaused(String),
+	/// Watch mode started watching a newly created directory.
+	WatchPathAdded(PathBuf),
+	Done,
+	/// Last item of `Indexer::stream`, after `Done`: the run's summary.
+	Finished(IndexResult),
+	/// Last item of `Indexer::stream` when the run fails, in place of `Finished`.
+	Failed(String),
+}
+
+/// Wire form of `IndexEvent`: tuple variants get named fields, since an
+/// internally tagged enum can't hold them. Paths are written lossily.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum IndexEventRepr {
+	IndexingStarted { session_id: Uuid },
+	Started { total_files: usize },
+	FileStarted { path: String },
+	FileIndexed { path: String },
+	FileError { path: String, error: String },
+	FileSkipped { path: String, reason: String },
+	FileUnchanged { path: String },
+	MemoryPressure { used_mb: u64, limit_mb: u64 },
+	PageProcessed { path: String, page: usize, total: usize },
+	ChunkProcessed { path: String, chunk_index: usize },
+	ChunkEmbedded { path: String, chunk_index: usize, doc_id: String },
+	BatchEmbedded { path: String, chunk_count: usize, elapsed_ms: u64, tokens_estimated: usize },
+	ChunkIndexed { path: String, chunk_index: usize, byte_start: usize, byte_end: usize },
+	IndexingPaused { reason: String },
+	WatchPathAdded { path: String },
 	Done,
+	Finished {
+		session_id: Uuid,
+		files_indexed: usize,
+		files_skipped: usize,
+		files_unchanged: usize,
+		chunks_indexed: usize,
+		embeddings_stored: usize,
+		errors: Vec<FileErrorRepr>,
+		elapsed_ms: u64,
+		bytes_processed: u64,
+	},
+	Failed { error: String },
+}
+
+/// Wire form of one entry of `IndexResult::errors`.
+#[derive(Serialize, Deserialize)]
+struct FileErrorRepr {
+	path: String,
+	error: String,
+}
+
+impl From<IndexEvent> for IndexEventRepr {
+	fn from(event: IndexEvent) -> Self {
+		let lossy = |path: PathBuf| path.to_string_lossy().into_owned();
+		match event {
+			IndexEvent::IndexingStarted { session_id } => Self::IndexingStarted { session_id },
+			IndexEvent::Started { total_files } => Self::Started { total_files },
+			IndexEvent::FileStarted(path) => Self::FileStarted { path: lossy(path) },
+			IndexEvent::FileIndexed(path) => Self::FileIndexed { path: lossy(path) },
+			IndexEvent::FileError(path, error) => Self::FileError { path: lossy(path), error },
+			IndexEvent::FileSkipped(path, reason) => Self::FileSkipped { path: lossy(path), reason },
+			IndexEvent::FileUnchanged(path) => Self::FileUnchanged { path: lossy(path) },
+			IndexEvent::MemoryPressure(used_mb, limit_mb) => Self::MemoryPressure { used_mb, limit_mb },
+			IndexEvent::PageProcessed(path, page, total) => Self::PageProcessed { path: lossy(path), page, total },
+			IndexEvent::ChunkProcessed(path, chunk_index) => Self::ChunkProcessed { path: lossy(path), chunk_index },
+			IndexEvent::ChunkEmbedded(path, chunk_index, doc_id) => Self::ChunkEmbedded { path: lossy(path), chunk_index, doc_id },
+			IndexEvent::BatchEmbedded { path, chunk_count, elapsed_ms, tokens_estimated } => {
+				Self::BatchEmbedded { path: lossy(path), chunk_count, elapsed_ms, tokens_estimated }
+			}
+			IndexEvent::ChunkIndexed { path, chunk_index, byte_start, byte_end } => {
+				Self::ChunkIndexed { path: lossy(path), chunk_index, byte_start, byte_end }
+			}
+			IndexEvent::IndexingPaused(reason) => Self::IndexingPaused { reason },
+			IndexEvent::WatchPathAdded(path) => Self::WatchPathAdded { path: lossy(path) },
+			IndexEvent::Done => Self::Done,
+			IndexEvent::Finished(result) => Self::Finished {
+				session_id: result.session_id,
+				files_indexed: result.files_indexed,
+				files_skipped: result.files_skipped,
+				files_unchanged: result.files_unchanged,
+				chunks_indexed: result.chunks_indexed,
+				embeddings_stored: result.embeddings_stored,
+				errors: result.errors.into_iter().map(|(path, error)| FileErrorRepr { path: lossy(path), error }).collect(),
+				elapsed_ms: u64::try_from(result.elapsed.as_millis()).unwrap_or(u64::MAX),
+				bytes_processed: result.bytes_processed,
+			},
+			IndexEvent::Failed(error) => Self::Failed { error },
+		}
+	}
+}
+
+impl From<IndexEventRepr> for IndexEvent {
+	fn from(repr: IndexEventRepr) -> Self {
+		match repr {
+			IndexEventRepr::IndexingStarted { session_id } => Self::IndexingStarted { session_id },
+			IndexEventRepr::Started { total_files } => Self::Started { total_files },
+			IndexEventRepr::FileStarted { path } => Self::FileStarted(path.into()),
+			IndexEventRepr::FileIndexed { path } => Self::FileIndexed(path.into()),
+			IndexEventRepr::FileError { path, error } => Self::FileError(path.into(), error),
+			IndexEventRepr::FileSkipped { path, reason } => Self::FileSkipped(path.into(), reason),
+			IndexEventRepr::FileUnchanged { path } => Self::FileUnchanged(path.into()),
+			IndexEventRepr::MemoryPressure { used_mb, limit_mb } => Self::MemoryPressure(used_mb, limit_mb),
+			IndexEventRepr::PageProcessed { path, page, total } => Self::PageProcessed(path.into(), page, total),
+			IndexEventRepr::ChunkProcessed { path, chunk_index } => Self::ChunkProcessed(path.into(), chunk_index),
+			IndexEventRepr::ChunkEmbedded { path, chunk_index, doc_id } => Self::ChunkEmbedded(path.into(), chunk_index, doc_id),
+			IndexEventRepr::BatchEmbedded { path, chunk_count, elapsed_ms, tokens_estimated } => {
+				Self::BatchEmbedded { path: path.into(), chunk_count, elapsed_ms, tokens_estimated }
+			}
+			IndexEventRepr::ChunkIndexed { path, chunk_index, byte_start, byte_end } => {
+				Self::ChunkIndexed { path: path.into(), chunk_index, byte_start, byte_end }
+			}
+			IndexEventRepr::IndexingPaused { reason } => Self::IndexingPaused(reason),
+			IndexEventRepr::WatchPathAdded { path } => Self::WatchPathAdded(path.into()),
+			IndexEventRepr::Done => Self::Done,
+			IndexEventRepr::Finished { session_id, files_indexed, files_skipped, files_unchanged, chunks_indexed, embeddings_stored, errors, elapsed_ms, bytes_processed } => {
+				Self::Finished(IndexResult {
+					session_id,
+					files_indexed,
+					files_skipped,
+					files_unchanged,
+					chunks_indexed,
+					embeddings_stored,
+					errors: errors.into_iter().map(|e| (e.path.into(), e.error)).collect(),
+					elapsed: Duration::from_millis(elapsed_ms),
+					bytes_processed,
+				})
+			}
+			IndexEventRepr::Failed { error } => Self::Failed(error),
+		}
+	}
+}
+
+/// Stream returned by [`Indexer::stream`]; cancels the run when dropped.
+struct IndexStream {
+	events: UnboundedReceiverStream<IndexEvent>,
+	_cancel: tokio_util::sync::DropGuard,
+}
+
+impl Stream for IndexStream {
+	type Item = IndexEvent;
+
+	fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<IndexEvent>> {
+		std::pin::Pin::new(&mut self.events).poll_next(cx)
+	}
 }
 
 /// Summary of the indexing run.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexResult {
+	/// Identifies this run in events and log messages.
+	pub session_id: Uuid,
 	pub files_indexed: usize,
 	pub files_skipped: usize,
 	pub files_unchanged: usize,
 	pub chunks_indexed: usize,
 	pub embeddings_stored: usize,
 	pub errors: Vec<(PathBuf, String)>,
+	/// Wall-clock time from the start of the run (including file discovery) to `Done`.
+	pub elapsed: Duration,
+	/// Total on-disk size of the files counted in `files_indexed` (archives count once).
+	pub bytes_processed: u64,
+}
+
+/// Characters of an error message kept when grouping errors in `error_summary`.
+const ERROR_SUMMARY_PREFIX_LEN: usize = 60;
+
+impl IndexResult {
+	/// Group `errors` by the start of their message, with the file's path and name
+	/// removed, so systematic failures (e.g. every PDF failing) collapse into one entry.
+	/// Returns `(message_prefix, count)`, most frequent first.
+	pub fn error_summary(&self) -> Vec<(String, usize)> {
+		let mut counts: HashMap<String, usize> = HashMap::new();
+		for (path, message) in &self.errors {
+			*counts.entry(error_prefix(path, message)).or_default() += 1;
+		}
+		let mut summary: Vec<(String, usize)> = counts.into_iter().collect();
+		summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+		summary
+	}
 }
 
 /// Result of garbage collection.
@@ -90,6 +606,24 @@ pub struct GcResult {
 	pub embeddings_removed: usize,
 }
 
+/// Result of removing files from the index (see `Indexer::delete_file`).
+#[derive(Debug, Default)]
+pub struct DeletionResult {
+	/// Files that had state or embeddings to remove
+	pub files_removed: usize,
+	/// Total embeddings removed from store
+	pub embeddings_removed: usize,
+}
+
+/// Result of a post-index verification pass (see `Indexer::verify`).
+#[derive(Debug, Default)]
+pub struct VerificationResult {
+	/// Files whose first chunk came back as the top search hit
+	pub verified: usize,
+	/// Files whose first chunk did not come back as the top hit (corrupt embedding or storage)
+	pub mismatches: Vec<PathBuf>,
+}
+
 /// Main orchestrator for the indexing pipeline.
 /// Uses parallel text extraction with Rayon, followed by batched embedding.
 /// Supports page-by-page PDF processing for reduced memory usage and resumability.
@@ -100,11 +634,69 @@ pub struct Indexer<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: Vector
 	store: Arc<S>,
 	state: Option<Arc<StateManager>>,
 	lexical: Option<Arc<LexicalIndex>>,
+	/// Embeddings removed by `garbage_collect` since the store was last compacted.
+	removed_since_compact: AtomicUsize,
+	cancel_token: Option<CancellationToken>,
+	/// Threads extractions run on when `file_timeout` is set.
+	extract_pool: ExtractPool,
 }
 
-impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer<E, M, S> {
+impl<E: SyncTextExtractor + PagedExtractor + 'static, M: Embedder, S: VectorStore> Indexer<E, M, S> {
 	pub fn new(options: IndexOptions, extractor: E, embedder: M, store: Arc<S>) -> Self {
-		Self { options, extractor: Arc::new(extractor), embedder, store, state: None, lexical: None }
+		let extract_pool = ExtractPool::new(options.threads);
+		Self { options, extractor: Arc::new(extractor), embedder, store, state: None, lexical: None, removed_since_compact: AtomicUsize::new(0), cancel_token: None, extract_pool }
+	}
+
+	/// Embed chunks in batches of at most `embed_batch_size`, emitting a
+	/// `BatchEmbedded` event after each batch.
+	async fn embed_chunks<F: FnMut(IndexEvent)>(&self, session_id: Uuid, path: &Path, texts: &[&str], cb: &mut F) -> Result<Vec<Vec<f32>>> {
+		if self.options.warn_on_truncation {
+			let over = over_token_limit(&self.embedder, texts);
+			if let Some(longest) = over.iter().max() {
+				eprintln!("  warning [{}]: {} of {} chunks in {} exceed the embedding model's {}-token limit and lose their tails (longest: {} tokens)",
+					session_id, over.len(), texts.len(), path.display(), self.embedder.max_input_tokens().unwrap_or_default(), longest);
+			}
+		}
+		let batch_size = match self.options.embed_batch_size {
+			0 => texts.len().max(1),
+			n => n,
+		};
+		let mut embeddings = Vec::with_capacity(texts.len());
+		for batch in texts.chunks(batch_size) {
+			let started = Instant::now();
+			embeddings.extend(
+				self.embedder.embed_batch(batch)
+					.instrument(tracing::debug_span!("embed_batch", path = %path.display(), chunks = batch.len()))
+					.await?,
+			);
+			cb(IndexEvent::BatchEmbedded {
+				path: path.to_path_buf(),
+				chunk_count: batch.len(),
+				elapsed_ms: started.elapsed().as_millis() as u64,
+				tokens_estimated: batch.iter().map(|t| t.len()).sum::<usize>() / CHARS_PER_TOKEN,
+			});
+		}
+		Ok(embeddings)
+	}
+
+	/// Store one file's (or page's) embeddings in a single batch.
+	/// With `replace`, the file's existing embeddings (and lexical entries) are removed first.
+	async fn store_embeddings(&self, path: &Path, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>, replace: bool) -> Result<Vec<String>> {
+		if !replace {
+			let span = tracing::debug_span!("add_embeddings_batch", path = %path.display(), chunks = embeddings.len());
+			return self.store.add_embeddings_batch(embeddings, metadata).instrument(span).await;
+		}
+
+		let old_doc_ids = match &self.state {
+			Some(state) => state.get_doc_ids(path)?,
+			None => Vec::new(),
+		};
+		let span = tracing::debug_span!("reindex_file", path = %path.display(), chunks = embeddings.len());
+		let doc_ids = self.store.reindex_file(path, embeddings, metadata).instrument(span).await?;
+		if let Some(ref lexical) = self.lexical {
+			lexical.delete_by_doc_ids(&old_doc_ids)?;
+		}
+		Ok(doc_ids)
 	}
 	
 	/// Set the state manager for incremental indexing.
@@ -118,6 +710,13 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 		self.lexical = Some(lexical);
 		self
 	}
+	
+	/// Stop runs early once `token` is cancelled. Checked between files, so a file is
+	/// never left half-indexed; the run still saves what it indexed and emits `Done`.
+	pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+		self.cancel_token = Some(token);
+		self
+	}
 
 	/// Run the indexing pipeline (no progress reporting).
 	pub async fn run(&mut self) -> Result<IndexResult> {
@@ -137,10 +736,9 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 		// 1. Clean up embeddings for deleted files
 		let deleted_files = state.get_deleted_files()?;
 		for path in &deleted_files {
-			let doc_ids = state.remove_file(path)?;
-			if !doc_ids.is_empty() {
-				let removed = self.store.delete_by_doc_ids(&doc_ids).await?;
-				result.embeddings_removed += removed;
+			let removed = delete_indexed_file(self.store.as_ref(), Some(state), self.lexical.as_deref(), path).await?;
+			if removed.embeddings_removed > 0 {
+				result.embeddings_removed += removed.embeddings_removed;
 				result.deleted_files += 1;
 			}
 		}
@@ -148,28 +746,277 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 		// 2. Clean up old embeddings for modified files (they'll be re-indexed)
 		let all_files = state.get_all_files()?;
 		for file_info in all_files {
-			if file_info.file_state == FileState::Modified && !file_info.doc_ids.is_empty() {
-				let removed = self.store.delete_by_doc_ids(&file_info.doc_ids).await?;
-				result.embeddings_removed += removed;
-				result.modified_files += 1;
+			if file_info.file_state == FileState::Modified {
+				// A newer mtime with identical content keeps its embeddings
+				if self.options.change_detection.uses_hash()
+					&& !file_needs_indexing(state, &file_info.path, self.options.change_detection).unwrap_or(true)
+				{
+					continue;
+				}
+				// By doc_id, since entries inside an archive are stored under their
+				// virtual paths, and by path for rows the state lost track of
+				let mut removed = self.store.delete_by_doc_ids(&file_info.doc_ids).await?;
+				removed += self.store.delete_by_file_path(&file_info.path).await?;
+				if let Some(ref lexical) = self.lexical {
+					lexical.delete_by_doc_ids(&file_info.doc_ids)?;
+					lexical.delete_by_file_path(&file_info.path.to_string_lossy())?;
+				}
+				if removed > 0 {
+					result.embeddings_removed += removed;
+					result.modified_files += 1;
+				}
 			}
 		}
 
+		if let Some(ref lexical) = self.lexical {
+			lexical.commit()?;
+		}
+
+		self.removed_since_compact.fetch_add(result.embeddings_removed, Ordering::Relaxed);
+		Ok(result)
+	}
+
+	/// Remove a file from the store, lexical index and state, e.g. when the user
+	/// no longer wants it searchable. See `delete_indexed_file`.
+	pub async fn delete_file(&self, path: &Path) -> Result<DeletionResult> {
+		let result = delete_indexed_file(self.store.as_ref(), self.state.as_deref(), self.lexical.as_deref(), path).await?;
+		if let Some(ref lexical) = self.lexical {
+			lexical.commit()?;
+		}
+		Ok(result)
+	}
+
+	/// Check that every tracked file is retrievable: re-embed its first chunk,
+	/// search the store for the top hit and compare it with the stored doc_id.
+	/// Costs one embedding and one search per file. Files changed since indexing
+	/// or that can't be re-extracted (archives, extraction errors) are skipped.
+	pub async fn verify(&self) -> Result<VerificationResult> {
+		let state = match &self.state {
+			Some(s) => s,
+			None => return Ok(VerificationResult::default()),
+		};
+
+		let mut result = VerificationResult::default();
+		for file_info in state.get_all_files()? {
+			if file_info.file_state != FileState::Indexed {
+				continue;
+			}
+			if file_info.doc_ids.is_empty() {
+				continue;
+			}
+			let Ok(Some(chunk)) = self.first_chunk(&file_info.path) else {
+				continue;
+			};
+			// The state keeps doc_ids in no particular order, so ask the store which is chunk 0
+			let Some(stored_id) = self.store.get_by_file_path(&file_info.path).await?
+				.into_iter()
+				.find(|meta| meta.chunk_index == 0 && file_info.doc_ids.contains(&meta.doc_id))
+				.map(|meta| meta.doc_id)
+			else {
+				result.mismatches.push(file_info.path);
+				continue;
+			};
+			let embedding = self.embedder.embed(&chunk).await?;
+			let top = self.store.search(embedding, &SearchOptions::new(1)).await?;
+			if top.first().is_some_and(|hit| hit.doc_id == stored_id) {
+				result.verified += 1;
+			} else {
+				result.mismatches.push(file_info.path);
+			}
+		}
 		Ok(result)
 	}
 
+	/// Re-extract and chunk `path` the way indexing does, returning its first chunk.
+	fn first_chunk(&self, path: &Path) -> Result<Option<String>> {
+		let path = path.to_path_buf();
+		if archive::is_archive(&path) {
+			return Ok(None);
+		}
+		let chunks = if self.extractor.is_paged(&path) {
+			self.extractor
+				.extract_pages(&path)?
+				.into_iter()
+				.find(|page| !page.text.trim().is_empty())
+				.map(|page| chunk_with_strategy(&page.text, self.options.chunk_size, self.options.chunk_overlap, self.options.chunk_strategy, &self.embedder))
+				.unwrap_or_default()
+		} else {
+			let text = self.extractor.extract_text_sync(&path)?;
+			chunk_with_strategy(&text, self.options.chunk_size, self.options.chunk_overlap, chunk_strategy_for(&path, self.options.chunk_strategy), &self.embedder)
+		};
+		Ok(chunks.into_iter().next().map(|chunk| chunk.text))
+	}
+
 	/// Run the indexing pipeline, reporting progress via callback.
 	/// Uses parallel text extraction with Rayon for non-paged files.
 	/// For paged files (PDFs), processes page-by-page with checkpoints.
-	pub async fn run_with_progress<F>(&mut self, mut cb: F) -> Result<IndexResult>
+	#[tracing::instrument(skip_all, fields(root = %self.options.root.display()))]
+	pub async fn run_with_progress<F>(&mut self, cb: F) -> Result<IndexResult>
+	where
+		F: FnMut(IndexEvent) + Send,
+	{
+		let started = Instant::now();
+		let files = discover_files(&self.options)?;
+		let result = self.index_paths(files, started, cb).await?;
+		self.compact_if_needed().await;
+		Ok(result)
+	}
+
+	/// Compact the store once `auto_compact_threshold` deletions have piled up.
+	/// A failed compaction is only logged: the run itself succeeded and the
+	/// next one tries again.
+	async fn compact_if_needed(&self) {
+		let removed = self.removed_since_compact.load(Ordering::Relaxed);
+		match self.options.auto_compact_threshold {
+			Some(threshold) if removed > threshold && !self.options.dry_run => {}
+			_ => return,
+		}
+		match self.store.compact().await {
+			Ok(()) => {
+				tracing::info!(removed, "compacted vector store");
+				self.removed_since_compact.store(0, Ordering::Relaxed);
+			}
+			Err(e) => tracing::warn!(error = %e, "failed to compact vector store"),
+		}
+	}
+
+	/// Run the indexing pipeline on a spawned task, yielding its progress events as
+	/// a stream. The last item is `Finished` with the run's result, or `Failed` with
+	/// its error. Dropping the stream cancels the run, which stops after the current file.
+	pub fn stream(mut self) -> impl Stream<Item = IndexEvent> + Send
+	where
+		M: 'static,
+		S: 'static,
+	{
+		let cancel = self.cancel_token.as_ref().map_or_else(CancellationToken::new, CancellationToken::child_token);
+		self.cancel_token = Some(cancel.clone());
+		// Unbounded, since the progress callback can't wait for a slow consumer
+		let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+		tokio::spawn(async move {
+			let progress = tx.clone();
+			let last = match self.run_with_progress(|event| { let _ = progress.send(event); }).await {
+				Ok(result) => IndexEvent::Finished(result),
+				Err(e) => {
+					tracing::error!(root = %self.options.root.display(), "indexing failed: {}", e);
+					IndexEvent::Failed(format!("{:#}", e))
+				}
+			};
+			let _ = tx.send(last);
+		});
+		IndexStream { events: UnboundedReceiverStream::new(rx), _cancel: cancel.drop_guard() }
+	}
+
+	/// Run the indexing pipeline, forwarding progress events into a channel.
+	/// Progress events that don't fit in the channel (full or closed) are dropped
+	/// and counted rather than blocking the indexer; `Done` waits for room, so
+	/// receivers always see the end of a successful run.
+	pub async fn run_with_channel(&mut self, tx: tokio::sync::mpsc::Sender<IndexEvent>) -> Result<IndexResult> {
+		let mut dropped = 0usize;
+		let mut done = false;
+		let result = self
+			.run_with_progress(|event| {
+				if matches!(event, IndexEvent::Done) {
+					done = true;
+				} else if tx.try_send(event).is_err() {
+					dropped += 1;
+				}
+			})
+			.await;
+		if dropped > 0 {
+			let session_id = result.as_ref().map(|r| r.session_id.to_string()).unwrap_or_default();
+			tracing::warn!(dropped, session_id, "dropped progress events: channel full or closed");
+		}
+		if done {
+			let _ = tx.send(IndexEvent::Done).await;
+		}
+		result
+	}
+
+	/// Index an explicit list of files (no progress reporting).
+	pub async fn index_files(&mut self, files: Vec<PathBuf>) -> Result<IndexResult> {
+		self.index_files_with_progress(files, |_| ()).await
+	}
+
+	/// Index an explicit list of files, reporting progress via callback.
+	/// Files are filtered with the same rules as directory discovery.
+	pub async fn index_files_with_progress<F>(&mut self, files: Vec<PathBuf>, cb: F) -> Result<IndexResult>
+	where
+		F: FnMut(IndexEvent) + Send,
+	{
+		let started = Instant::now();
+		let files = files
+			.into_iter()
+			.filter(|path| path.is_file() && should_index(
+				path,
+				&self.options.skip_extensions,
+				&self.options.skip_files,
+				self.options.follow_archive_contents,
+			))
+			.collect();
+		self.index_paths(files, started, cb).await
+	}
+
+	/// Re-index one file, e.g. after a watch event, without scanning the root.
+	/// If the file changed, its state, embeddings and lexical entries are removed
+	/// first and it then goes through the same pipeline as a full run. A path that
+	/// no longer exists only has its entries removed.
+	pub async fn index_single_file<F>(&mut self, path: &Path, cb: F) -> Result<IndexResult>
+	where
+		F: FnMut(IndexEvent) + Send,
+	{
+		let changed = match &self.state {
+			Some(state) if path.exists() && !self.options.replace_existing => {
+				file_needs_indexing(state, path, self.options.change_detection).unwrap_or(true)
+			}
+			_ => true,
+		};
+		if changed {
+			delete_indexed_file(self.store.as_ref(), self.state.as_deref(), self.lexical.as_deref(), path).await?;
+		}
+		self.index_files_with_progress(vec![path.to_path_buf()], cb).await
+	}
+
+	/// Index only the files under `options.root` changed between two git refs
+	/// (no progress reporting).
+	pub async fn index_git_diff(&mut self, from_ref: &str, to_ref: &str) -> Result<IndexResult> {
+		self.index_git_diff_with_progress(from_ref, to_ref, |_| ()).await
+	}
+
+	/// Index only the files under `options.root` changed between two git refs,
+	/// reporting progress via callback. Files deleted in the range are ignored.
+	pub async fn index_git_diff_with_progress<F>(&mut self, from_ref: &str, to_ref: &str, cb: F) -> Result<IndexResult>
+	where
+		F: FnMut(IndexEvent) + Send,
+	{
+		let files = git_changed_files(&self.options.root, from_ref, to_ref)?;
+		self.index_files_with_progress(files, cb).await
+	}
+
+	/// Run the extraction, embedding and storage phases over `files`.
+	/// `started` is when the run began, for `IndexResult::elapsed`.
+	async fn index_paths<F>(&mut self, files: Vec<PathBuf>, started: Instant, mut cb: F) -> Result<IndexResult>
 	where
 		F: FnMut(IndexEvent) + Send,
 	{
-		let files = discover_files(&self.options.root, &self.options.skip_extensions, &self.options.skip_files)?;
 		let chunk_size = self.options.chunk_size;
+		let chunk_overlap = self.options.chunk_overlap;
 		let max_file_size = self.options.max_file_size_bytes;
 		let max_memory = self.options.max_memory_bytes;
 		let max_chunks = self.options.max_chunks_per_file;
+		let chunk_strategy = self.options.chunk_strategy;
+		let snippet_length = self.options.snippet_length;
+		let verbose_events = self.options.verbose_events;
+		let max_total_chunks = self.options.max_total_chunks;
+		let replace_existing = self.options.replace_existing;
+		let dry_run = self.options.dry_run;
+		let file_timeout = self.options.file_timeout;
+		let mut paused = false;
+		let cancel_token = self.cancel_token.clone();
+		let is_cancelled = || cancel_token.as_ref().is_some_and(CancellationToken::is_cancelled);
+
+		let session_id = Uuid::new_v4();
+		cb(IndexEvent::IndexingStarted { session_id });
+		cb(IndexEvent::Started { total_files: files.len() });
 
 		// Counters for skipped/unchanged (used in parallel phase)
 		let files_skipped = AtomicUsize::new(0);
@@ -179,13 +1026,20 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 		let mut sys = System::new();
 		sys.refresh_memory();
 		let used_mem = sys.used_memory();
-		if used_mem > max_memory {
+		let memory_pressure = used_mem > max_memory;
+		let parallel_pages = self.options.parallel_pages && !memory_pressure;
+		if memory_pressure {
 			let used_mb = used_mem / 1024 / 1024;
 			let limit_mb = max_memory / 1024 / 1024;
 			cb(IndexEvent::MemoryPressure(used_mb, limit_mb));
 			// Continue anyway but warn - parallel extraction will proceed
 		}
 
+		// Archives are expanded separately, after regular files
+		let (archive_files, files): (Vec<_>, Vec<_>) = files
+			.into_iter()
+			.partition(|path| archive::is_archive(path));
+
 		// Separate paged files (PDFs) from non-paged files
 		let (paged_files, non_paged_files): (Vec<_>, Vec<_>) = files
 			.into_iter()
@@ -193,11 +1047,22 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 
 		// Phase 1: Parallel text extraction with Rayon for non-paged files
 		let extractor = self.extractor.clone();
-		let state = self.state.clone();
+		// Without state every file counts as changed, which is what replacing needs
+		let state = if replace_existing { None } else { self.state.clone() };
+		let change_detection = self.options.change_detection;
+		// Rayon workers don't inherit the current span, so parent extraction spans explicitly
+		let parent_span = tracing::Span::current();
 		
-		let extraction_results: Vec<_> = non_paged_files
+		let embedder = &self.embedder;
+		let extract_pool = &self.extract_pool;
+		let extract_all = || non_paged_files
 			.par_iter()
 			.filter_map(|path| {
+				// Leave the rest of the files for the next run
+				if is_cancelled() {
+					return None;
+				}
+				
 				// Check file size
 				if let Ok(metadata) = std::fs::metadata(path) {
 					if metadata.len() > max_file_size {
@@ -208,7 +1073,7 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 				
 				// Check if file needs indexing
 				if let Some(ref state) = state {
-					match state.needs_indexing(path) {
+					match file_needs_indexing(state, path, change_detection) {
 						Ok(false) => {
 							files_unchanged.fetch_add(1, Ordering::Relaxed);
 							return None;
@@ -219,9 +1084,19 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 				}
 				
 				// Extract text (sync, CPU-bound)
-				match extractor.extract_text_sync(path) {
-					Ok(contents) => {
-						let chunks = chunk_text(&contents, chunk_size);
+				let extracted = tracing::debug_span!(parent: &parent_span, "extract_text_sync", path = %path.display())
+					.in_scope(|| {
+						let extractor = extractor.clone();
+						let path = path.clone();
+						extract_pool.run(file_timeout, move || {
+							let text = extractor.extract_text_sync(&path)?;
+							Ok((text, document_info_or_default(&path, extractor.extract_info(&path))))
+						})
+					});
+				match extracted {
+					Ok((contents, info)) => {
+						let strategy = chunk_strategy_for(path, chunk_strategy);
+						let chunks = chunk_with_strategy(&contents, chunk_size, chunk_overlap, strategy, embedder);
 						
 						// Skip files with too many chunks (e.g., dictionaries, wordlists)
 						if chunks.len() > max_chunks {
@@ -233,22 +1108,36 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 							.and_then(|e| e.to_str())
 							.unwrap_or("unknown")
 							.to_string();
-						Some(Ok((path.clone(), chunks, file_type)))
+						Some(Ok((path.clone(), chunks, file_type, info)))
 					}
-					Err(e) => Some(Err((path.clone(), format!("{}", e))))
+					Err(e) => Some(Err((path.clone(), e)))
 				}
 			})
-			.collect();
+			.collect::<Vec<_>>();
+		let extraction_results = match self.options.threads {
+			0 => extract_all(),
+			n => rayon::ThreadPoolBuilder::new().num_threads(n).build()?.install(extract_all),
+		};
 
 		// Phase 2: Sequential embedding and batch storage for non-paged files
 		let mut files_indexed = 0;
 		let mut chunks_indexed = 0;
 		let mut embeddings_stored = 0;
 		let mut errors: Vec<(PathBuf, String)> = vec![];
+		let mut bytes_processed = 0u64;
 
 		for result in extraction_results {
+			if is_cancelled() {
+				break;
+			}
+			if let Ok((_, chunks, _, _)) = &result {
+				if over_chunk_budget(max_total_chunks, chunks_indexed, chunks.len()) {
+					paused = true;
+					break;
+				}
+			}
 			match result {
-				Ok((path, chunks, file_type)) => {
+				Ok((path, chunks, file_type, info)) => {
 					cb(IndexEvent::FileStarted(path.clone()));
 					
 					if chunks.is_empty() {
@@ -256,9 +1145,20 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 						continue;
 					}
 
-					let chunk_refs: Vec<&str> = chunks.iter().map(|s| s.as_str()).collect();
+					if dry_run {
+						for i in 0..chunks.len() {
+							cb(IndexEvent::ChunkProcessed(path.clone(), i));
+						}
+						chunks_indexed += chunks.len();
+						files_indexed += 1;
+						bytes_processed += file_size(&path);
+						cb(IndexEvent::FileIndexed(path));
+						continue;
+					}
+
+					let chunk_refs: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
 					
-					match self.embedder.embed_batch(&chunk_refs).await {
+					match self.embed_chunks(session_id, &path, &chunk_refs, &mut cb).await {
 						Ok(embeddings) => {
 							chunks_indexed += chunks.len();
 							
@@ -266,24 +1166,23 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 							let metadata_batch: Vec<DocumentMetadata> = chunks.iter()
 								.enumerate()
 								.map(|(i, chunk)| {
-									let snippet = if chunk.chars().count() > 200 {
-										let truncated: String = chunk.chars().take(200).collect();
-										Some(format!("{}...", truncated))
-									} else {
-										Some(chunk.clone())
-									};
 									DocumentMetadata {
 										doc_id: String::new(),
 										file_path: path.clone(),
 										file_type: file_type.clone(),
 										chunk_index: i,
-										snippet,
+										snippet: Some(make_snippet(&chunk.text, snippet_length)),
+										byte_start: chunk.byte_start,
+										byte_end: chunk.byte_end,
+										title: info.title.clone(),
+										author: info.author.clone(),
+										created_at: info.created_at,
 									}
 								})
 								.collect();
 
 							// Batch insert all embeddings for this file at once
-							match self.store.add_embeddings_batch(embeddings, metadata_batch).await {
+							match self.store_embeddings(&path, embeddings, metadata_batch, replace_existing).await {
 								Ok(doc_ids) => {
 									embeddings_stored += doc_ids.len();
 									
@@ -295,7 +1194,7 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 											.map(|(i, (doc_id, chunk))| LexicalDoc {
 												doc_id: doc_id.clone(),
 												file_path: path.to_string_lossy().to_string(),
-												content: chunk.clone(),
+												content: chunk.text.clone(),
 												chunk_index: i,
 											})
 											.collect();
@@ -305,8 +1204,16 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 									}
 									
 									// Report progress for each chunk
-									for (i, doc_id) in doc_ids.iter().enumerate() {
-										cb(IndexEvent::ChunkEmbedded(path.clone(), i, doc_id.clone()));
+									for ((i, doc_id), chunk) in doc_ids.iter().enumerate().zip(chunks.iter()) {
+										if verbose_events {
+											cb(IndexEvent::ChunkEmbedded(path.clone(), i, doc_id.clone()));
+										}
+										cb(IndexEvent::ChunkIndexed {
+											path: path.clone(),
+											chunk_index: i,
+											byte_start: chunk.byte_start,
+											byte_end: chunk.byte_end,
+										});
 									}
 									
 									// Mark file as indexed in state manager
@@ -314,12 +1221,15 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 										if let Ok(meta) = std::fs::metadata(&path) {
 											if let Ok(mtime) = meta.modified() {
 												if let Err(e) = state.mark_indexed(&path, mtime, &doc_ids) {
-													eprintln!("  warning: failed to update state for {}: {}", path.display(), e);
+													eprintln!("  warning [{}]: failed to update state for {}: {}", session_id, path.display(), e);
+												} else if change_detection.uses_hash() {
+													record_content_hash(state, &path, session_id);
 												}
 											}
 										}
 									}
 									files_indexed += 1;
+									bytes_processed += file_size(&path);
 								}
 								Err(e) => {
 									let err_str = format!("Failed to store embeddings: {}", e);
@@ -337,7 +1247,12 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 					
 					cb(IndexEvent::FileIndexed(path));
 				}
-				Err((path, err_str)) => {
+				Err((path, e)) if e.is::<PasswordProtected>() => {
+					files_skipped.fetch_add(1, Ordering::Relaxed);
+					cb(IndexEvent::FileSkipped(path, e.to_string()));
+				}
+				Err((path, e)) => {
+					let err_str = e.to_string();
 					cb(IndexEvent::FileError(path.clone(), err_str.clone()));
 					errors.push((path, err_str));
 				}
@@ -346,6 +1261,14 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 
 		// Phase 3: Page-by-page processing for paged files (PDFs)
 		for path in paged_files {
+			if is_cancelled() {
+				break;
+			}
+			if paused || over_chunk_budget(max_total_chunks, chunks_indexed, 0) {
+				paused = true;
+				break;
+			}
+
 			// Check file size
 			if let Ok(metadata) = std::fs::metadata(&path) {
 				if metadata.len() > max_file_size {
@@ -364,8 +1287,8 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 			};
 			
 			// Check if file needs indexing (for full file)
-			if let Some(ref state) = self.state {
-				match state.needs_indexing(&path) {
+			if let Some(state) = self.state.as_ref().filter(|_| !replace_existing) {
+				match file_needs_indexing(state, &path, change_detection) {
 					Ok(false) => {
 						files_unchanged.fetch_add(1, Ordering::Relaxed);
 						continue;
@@ -375,17 +1298,40 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 				}
 			}
 
-			cb(IndexEvent::FileStarted(path.clone()));
-			
 			// Get resume page if interrupted previously
 			let resume_page = self.state.as_ref()
+				.filter(|_| !replace_existing)
 				.and_then(|s| s.get_resume_page(&path, mtime).ok())
 				.flatten()
 				.unwrap_or(0);
+			
+			// Chunk indices keep counting across pages so they never collide
+			let mut total_chunks_so_far = if resume_page > 0 {
+				self.state.as_ref()
+					.and_then(|s| s.get_chunk_offset(&path).ok())
+					.unwrap_or(0)
+			} else {
+				0
+			};
 
 			// Extract all pages
-			let pages = match self.extractor.extract_pages(&path) {
-				Ok(p) => p,
+			let extractor = self.extractor.clone();
+			let extract_path = path.clone();
+			let extracted = self.extract_pool.run_async(file_timeout, move || {
+				let (pages, info) = if parallel_pages {
+					extractor.extract_pages_parallel(&extract_path)?
+				} else {
+					extractor.extract_pages_with_info(&extract_path)?
+				};
+				Ok((pages, document_info_or_default(&extract_path, info)))
+			});
+			let (pages, info) = match extracted.await {
+				Ok(extracted) => extracted,
+				Err(e) if e.is::<PasswordProtected>() => {
+					files_skipped.fetch_add(1, Ordering::Relaxed);
+					cb(IndexEvent::FileSkipped(path, e.to_string()));
+					continue;
+				}
 				Err(e) => {
 					let err_str = format!("Failed to extract pages: {}", e);
 					cb(IndexEvent::FileError(path.clone(), err_str.clone()));
@@ -394,32 +1340,73 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 				}
 			};
 
+			let total_pages = pages.len();
+			// Chunk pages (blank pages get no chunks), skipping already indexed ones
+			let strategy = chunk_strategy_for(&path, chunk_strategy);
+			let chunk_page = move |page: &ExtractedPage| {
+				let chunks = if page.text.trim().is_empty() {
+					Vec::new()
+				} else {
+					chunk_with_strategy(&page.text, chunk_size, chunk_overlap, strategy, embedder)
+				};
+				(page.page_num, chunks)
+			};
+			let pending = &pages[resume_page.min(total_pages)..];
+			let page_chunks: Vec<PageChunks> = if parallel_pages {
+				pending.par_iter().map(chunk_page).collect()
+			} else {
+				pending.iter().map(chunk_page).collect()
+			};
+			// Checked before anything is replaced, so a file left for the next run keeps its embeddings
+			let new_chunks = page_chunks.iter().map(|(_, chunks)| chunks.len()).sum();
+			if over_chunk_budget(max_total_chunks, chunks_indexed, new_chunks) {
+				paused = true;
+				break;
+			}
+
+			cb(IndexEvent::FileStarted(path.clone()));
+
+			// Pages are stored in several batches, so replacing removes every
+			// earlier page up front rather than through `reindex_file`
+			if replace_existing && !dry_run {
+				if let Err(e) = delete_indexed_file(self.store.as_ref(), self.state.as_deref(), self.lexical.as_deref(), &path).await {
+					let err_str = format!("Failed to remove previous embeddings: {}", e);
+					cb(IndexEvent::FileError(path.clone(), err_str.clone()));
+					errors.push((path.clone(), err_str));
+					continue;
+				}
+			}
+
 			if pages.is_empty() {
 				cb(IndexEvent::FileIndexed(path));
 				continue;
 			}
 
-			let total_pages = pages.len();
 			let file_type = path.extension()
 				.and_then(|e| e.to_str())
 				.unwrap_or("pdf")
 				.to_string();
 
-			// Process each page
-			for page in pages.into_iter().skip(resume_page) {
-				// Skip already indexed pages
-				let page_num = page.page_num;
-				
-				if page.text.trim().is_empty() {
+			// Embed and store pages in order
+			for (page_num, chunks) in page_chunks {
+				if chunks.is_empty() {
+					cb(IndexEvent::PageProcessed(path.clone(), page_num, total_pages));
+					continue;
+				}
+
+				if dry_run {
+					for i in 0..chunks.len() {
+						cb(IndexEvent::ChunkProcessed(path.clone(), total_chunks_so_far + i));
+					}
+					chunks_indexed += chunks.len();
+					total_chunks_so_far += chunks.len();
 					cb(IndexEvent::PageProcessed(path.clone(), page_num, total_pages));
 					continue;
 				}
 
-				// Chunk the page text
-				let chunks = chunk_text(&page.text, chunk_size);
-				let chunk_refs: Vec<&str> = chunks.iter().map(|s| s.as_str()).collect();
+				let chunk_refs: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
 				
-				match self.embedder.embed_batch(&chunk_refs).await {
+				match self.embed_chunks(session_id, &path, &chunk_refs, &mut cb).await {
 					Ok(embeddings) => {
 						chunks_indexed += chunks.len();
 						
@@ -427,25 +1414,24 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 						let metadata_batch: Vec<DocumentMetadata> = chunks.iter()
 							.enumerate()
 							.map(|(i, chunk)| {
-								let global_chunk_idx = page_num * 1000 + i;
-								let snippet = if chunk.chars().count() > 200 {
-									let truncated: String = chunk.chars().take(200).collect();
-									Some(format!("{}...", truncated))
-								} else {
-									Some(chunk.clone())
-								};
+								let global_chunk_idx = total_chunks_so_far + i;
 								DocumentMetadata {
 									doc_id: String::new(),
 									file_path: path.clone(),
 									file_type: file_type.clone(),
 									chunk_index: global_chunk_idx,
-									snippet,
+									snippet: Some(make_snippet(&chunk.text, snippet_length)),
+									byte_start: chunk.byte_start,
+									byte_end: chunk.byte_end,
+									title: info.title.clone(),
+									author: info.author.clone(),
+									created_at: info.created_at,
 								}
 							})
 							.collect();
 
 						// Batch insert all page embeddings at once
-						match self.store.add_embeddings_batch(embeddings, metadata_batch).await {
+						match self.store_embeddings(&path, embeddings, metadata_batch, false).await {
 							Ok(doc_ids) => {
 								embeddings_stored += doc_ids.len();
 								
@@ -455,11 +1441,11 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 										.zip(chunks.iter())
 										.enumerate()
 										.map(|(i, (doc_id, chunk))| {
-											let global_chunk_idx = page_num * 1000 + i;
+											let global_chunk_idx = total_chunks_so_far + i;
 											LexicalDoc {
 												doc_id: doc_id.clone(),
 												file_path: path.to_string_lossy().to_string(),
-												content: chunk.clone(),
+												content: chunk.text.clone(),
 												chunk_index: global_chunk_idx,
 											}
 										})
@@ -470,18 +1456,27 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 								}
 								
 								// Report progress
-								for (i, doc_id) in doc_ids.iter().enumerate() {
-									let global_chunk_idx = page_num * 1000 + i;
-									cb(IndexEvent::ChunkEmbedded(path.clone(), global_chunk_idx, doc_id.clone()));
+								for ((i, doc_id), chunk) in doc_ids.iter().enumerate().zip(chunks.iter()) {
+									let global_chunk_idx = total_chunks_so_far + i;
+									if verbose_events {
+										cb(IndexEvent::ChunkEmbedded(path.clone(), global_chunk_idx, doc_id.clone()));
+									}
+									cb(IndexEvent::ChunkIndexed {
+										path: path.clone(),
+										chunk_index: global_chunk_idx,
+										byte_start: chunk.byte_start,
+										byte_end: chunk.byte_end,
+									});
 								}
 
 								// Checkpoint: mark this page as indexed
 								if let Some(ref state) = self.state {
-									if let Err(e) = state.mark_page_indexed(&path, mtime, page_num, total_pages, &doc_ids) {
-										eprintln!("  warning: failed to checkpoint page {} of {}: {}", 
-											page_num, path.display(), e);
+									if let Err(e) = state.mark_page_indexed(&path, mtime, page_num, total_pages, total_chunks_so_far, &doc_ids) {
+										eprintln!("  warning [{}]: failed to checkpoint page {} of {}: {}",
+											session_id, page_num, path.display(), e);
 									}
 								}
+								total_chunks_so_far += doc_ids.len();
 							}
 							Err(e) => {
 								let err_str = format!("Failed to store page {} embeddings: {}", page_num, e);
@@ -501,167 +1496,852 @@ impl<E: SyncTextExtractor + PagedExtractor, M: Embedder, S: VectorStore> Indexer
 				cb(IndexEvent::PageProcessed(path.clone(), page_num, total_pages));
 			}
 
+			if change_detection.uses_hash() && !dry_run {
+				if let Some(ref state) = self.state {
+					record_content_hash(state, &path, session_id);
+				}
+			}
 			files_indexed += 1;
+			bytes_processed += file_size(&path);
 			cb(IndexEvent::FileIndexed(path));
 		}
 
-		// Persist the store
-		self.store.save().await?;
-		
-		// Commit the lexical index if configured
-		if let Some(ref lexical) = self.lexical {
-			lexical.commit()?;
-		}
+		// Phase 4: Files inside archives, addressed as `<archive>!<inner>`
+		for path in archive_files {
+			if is_cancelled() {
+				break;
+			}
+			if paused || over_chunk_budget(max_total_chunks, chunks_indexed, 0) {
+				paused = true;
+				break;
+			}
 
-		cb(IndexEvent::Done);
-		Ok(IndexResult {
-			files_indexed,
-			files_skipped: files_skipped.load(Ordering::Relaxed),
-			files_unchanged: files_unchanged.load(Ordering::Relaxed),
-			chunks_indexed,
-			embeddings_stored,
-			errors,
-		})
-	}
-}
+			let mtime = match std::fs::metadata(&path).and_then(|m| m.modified()) {
+				Ok(t) => t,
+				Err(_) => {
+					errors.push((path.clone(), "Failed to get file mtime".to_string()));
+					continue;
+				}
+			};
 
-/// Recursively discover supported files in a directory.
-fn discover_files(root: &PathBuf, skip_extensions: &[String], skip_files: &[String]) -> Result<Vec<PathBuf>> {
-	let mut files = Vec::new();
-	
-	// Text-based extensions (code, config, docs)
-	let text_extensions: std::collections::HashSet<&str> = [
-		// Documents
-		"txt", "md", "markdown", "rst", "org", "tex", "rtf",
-		// Programming languages
-		"py", "rs", "js", "ts", "jsx", "tsx", "cpp", "c", "h", "hpp", "cc", "cxx",
-		"go", "java", "kt", "kts", "scala", "rb", "php", "swift", "m", "mm",
-		"cs", "fs", "vb", "r", "lua", "pl", "pm", "tcl", "zig", "nim", "d",
-		"hs", "ml", "mli", "ex", "exs", "erl", "hrl", "clj", "cljs", "lisp", "el",
-		"v", "sv", "vhd", "vhdl", "asm", "s",
-		// Shell/scripts
-		"sh", "bash", "zsh", "fish", "ps1", "psm1", "bat", "cmd",
-		// Config/data
-		"json", "yaml", "yml", "toml", "xml", "ini", "cfg", "conf", "config",
-		"env", "properties", "plist",
-		// Web
-		"html", "htm", "css", "scss", "sass", "less", "svg",
-		// Database/query
-		"sql", "graphql", "gql",
-		// Build/CI
-		"cmake", "make", "gradle", "sbt", "cabal",
-		// Other
-		"csv", "tsv", "log", "diff", "patch",
-		// Binary/Office formats with text extraction
-		"pdf", "png", "jpg", "jpeg",
-		"docx", "xlsx", "pptx",  // Microsoft Office
-		"odt", "odp",            // OpenDocument (no ods support yet)
-	].into_iter().collect();
-	
-	// Known text filenames (no extension)
-	let text_filenames: std::collections::HashSet<&str> = [
-		"Makefile", "makefile", "GNUmakefile",
-		"Dockerfile", "dockerfile", "Containerfile",
-		"Vagrantfile", "Gemfile", "Rakefile",
-		"LICENSE", "LICENCE", "COPYING",
-		"README", "CHANGELOG", "HISTORY", "AUTHORS", "CONTRIBUTORS",
-		"TODO", "NOTES", "INSTALL", "NEWS",
-	].into_iter().collect();
-	
-	for entry in walkdir::WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-		let path = entry.path();
-		if path.is_file() {
-			// Skip if filename matches any skip pattern
-			if let Some(filename) = path.file_name().and_then(OsStr::to_str) {
-				if skip_files.iter().any(|pattern| filename.contains(pattern)) {
+			if let Some(state) = self.state.as_ref().filter(|_| !replace_existing) {
+				if let Ok(false) = file_needs_indexing(state, &path, change_detection) {
+					files_unchanged.fetch_add(1, Ordering::Relaxed);
 					continue;
 				}
-				
-				// Check for known text filenames (no extension)
-				if text_filenames.contains(filename) {
-					files.push(path.to_path_buf());
+			}
+
+			let entries = match archive::extract_entries(
+				&path,
+				self.extractor.as_ref(),
+				self.options.archive_depth,
+				max_file_size,
+			) {
+				Ok(e) => e,
+				Err(e) => {
+					let err_str = format!("Failed to read archive: {}", e);
+					cb(IndexEvent::FileError(path.clone(), err_str.clone()));
+					errors.push((path.clone(), err_str));
+					continue;
+				}
+			};
+
+			let mut entry_chunks = Vec::with_capacity(entries.len());
+			for entry in entries {
+				let strategy = chunk_strategy_for(&entry.virtual_path, chunk_strategy);
+				let chunks = chunk_with_strategy(&entry.text, chunk_size, chunk_overlap, strategy, &self.embedder);
+				if chunks.is_empty() {
+					continue;
+				}
+				if chunks.len() > max_chunks {
+					files_skipped.fetch_add(1, Ordering::Relaxed);
 					continue;
 				}
+				entry_chunks.push((entry.virtual_path, chunks));
 			}
-			
-			if let Some(ext) = path.extension().and_then(OsStr::to_str) {
-				let ext_lower = ext.to_lowercase();
-				// Skip if extension in skip list
-				if skip_extensions.iter().any(|s| s.to_lowercase() == ext_lower) {
+			// Checked before anything is replaced, so an archive left for the next run keeps its embeddings
+			let new_chunks = entry_chunks.iter().map(|(_, chunks)| chunks.len()).sum();
+			if over_chunk_budget(max_total_chunks, chunks_indexed, new_chunks) {
+				paused = true;
+				break;
+			}
+
+			cb(IndexEvent::FileStarted(path.clone()));
+
+			// All doc_ids are tracked against the archive itself so GC and
+			// change detection work on the real file. Replacing removes them
+			// all up front, since entries are stored under their virtual paths.
+			if replace_existing && !dry_run {
+				if let Err(e) = delete_indexed_file(self.store.as_ref(), self.state.as_deref(), self.lexical.as_deref(), &path).await {
+					let err_str = format!("Failed to remove previous embeddings: {}", e);
+					cb(IndexEvent::FileError(path.clone(), err_str.clone()));
+					errors.push((path.clone(), err_str));
 					continue;
 				}
-				if text_extensions.contains(ext_lower.as_str()) {
-					files.push(path.to_path_buf());
+			}
+			let mut archive_doc_ids: Vec<String> = Vec::new();
+			for (entry_path, chunks) in entry_chunks {
+				if dry_run {
+					for i in 0..chunks.len() {
+						cb(IndexEvent::ChunkProcessed(entry_path.clone(), i));
+					}
+					chunks_indexed += chunks.len();
+					files_indexed += 1;
+					continue;
+				}
+
+				let file_type = entry_path.extension()
+					.and_then(|e| e.to_str())
+					.unwrap_or("unknown")
+					.to_string();
+				let chunk_refs: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+
+				let embeddings = match self.embed_chunks(session_id, &entry_path, &chunk_refs, &mut cb).await {
+					Ok(e) => e,
+					Err(e) => {
+						let err_str = format!("Embedding failed: {}", e);
+						cb(IndexEvent::FileError(entry_path.clone(), err_str.clone()));
+						errors.push((entry_path, err_str));
+						continue;
+					}
+				};
+				chunks_indexed += chunks.len();
+
+				let metadata_batch: Vec<DocumentMetadata> = chunks.iter()
+					.enumerate()
+					.map(|(i, chunk)| {
+						DocumentMetadata {
+							doc_id: String::new(),
+							file_path: entry_path.clone(),
+							file_type: file_type.clone(),
+							chunk_index: i,
+							snippet: Some(make_snippet(&chunk.text, snippet_length)),
+							byte_start: chunk.byte_start,
+							byte_end: chunk.byte_end,
+							..Default::default()
+						}
+					})
+					.collect();
+
+				match self.store_embeddings(&entry_path, embeddings, metadata_batch, false).await {
+					Ok(doc_ids) => {
+						embeddings_stored += doc_ids.len();
+
+						if let Some(ref lexical) = self.lexical {
+							let lexical_docs: Vec<LexicalDoc> = doc_ids.iter()
+								.zip(chunks.iter())
+								.enumerate()
+								.map(|(i, (doc_id, chunk))| LexicalDoc {
+									doc_id: doc_id.clone(),
+									file_path: entry_path.to_string_lossy().to_string(),
+									content: chunk.text.clone(),
+									chunk_index: i,
+								})
+								.collect();
+							if let Err(e) = lexical.add_documents(lexical_docs) {
+								cb(IndexEvent::FileError(entry_path.clone(), format!("Lexical index error: {}", e)));
+							}
+						}
+
+						for ((i, doc_id), chunk) in doc_ids.iter().enumerate().zip(chunks.iter()) {
+							if verbose_events {
+								cb(IndexEvent::ChunkEmbedded(entry_path.clone(), i, doc_id.clone()));
+							}
+							cb(IndexEvent::ChunkIndexed {
+								path: entry_path.clone(),
+								chunk_index: i,
+								byte_start: chunk.byte_start,
+								byte_end: chunk.byte_end,
+							});
+						}
+						archive_doc_ids.extend(doc_ids);
+						files_indexed += 1;
+					}
+					Err(e) => {
+						let err_str = format!("Failed to store embeddings: {}", e);
+						cb(IndexEvent::FileError(entry_path.clone(), err_str.clone()));
+						errors.push((entry_path, err_str));
+					}
+				}
+			}
+
+			if let Some(state) = self.state.as_ref().filter(|_| !dry_run) {
+				if let Err(e) = state.mark_indexed(&path, mtime, &archive_doc_ids) {
+					eprintln!("  warning [{}]: failed to update state for {}: {}", session_id, path.display(), e);
+				} else if change_detection.uses_hash() {
+					record_content_hash(state, &path, session_id);
 				}
 			}
+			bytes_processed += file_size(&path);
+			cb(IndexEvent::FileIndexed(path));
+		}
+
+		if is_cancelled() {
+			cb(IndexEvent::IndexingPaused("cancelled".to_string()));
+		} else if paused {
+			cb(IndexEvent::IndexingPaused("max_total_chunks reached".to_string()));
+		}
+
+		if !dry_run {
+			// Persist the store
+			self.store.save().await?;
+			
+			// Commit the lexical index if configured
+			if let Some(ref lexical) = self.lexical {
+				lexical.commit()?;
+			}
+		}
+
+		let elapsed = started.elapsed();
+		cb(IndexEvent::Done);
+		Ok(IndexResult {
+			session_id,
+			files_indexed,
+			files_skipped: files_skipped.load(Ordering::Relaxed),
+			files_unchanged: files_unchanged.load(Ordering::Relaxed),
+			chunks_indexed,
+			embeddings_stored,
+			errors,
+			elapsed,
+			bytes_processed,
+		})
+	}
+}
+
+/// Message prefix used to group an error: file-specific text removed, first 60 chars kept.
+fn error_prefix(path: &Path, message: &str) -> String {
+	let mut message = message.to_string();
+	let path_str = path.to_string_lossy();
+	let file_name = path.file_name().map(|n| n.to_string_lossy());
+	for detail in [Some(path_str), file_name].into_iter().flatten() {
+		if !detail.is_empty() {
+			message = message.replace(detail.as_ref(), "");
+		}
+	}
+	let prefix: String = message.chars().take(ERROR_SUMMARY_PREFIX_LEN).collect();
+	prefix.trim().to_string()
+}
+
+/// Whether starting a file with `next` chunks would go past `max_total_chunks`.
+/// The first file of a run is always let through, so one oversized file can't stall indexing.
+fn over_chunk_budget(max_total_chunks: Option<usize>, indexed: usize, next: usize) -> bool {
+	match max_total_chunks {
+		Some(max) => indexed >= max || (indexed > 0 && indexed + next > max),
+		None => false,
+	}
+}
+
+/// Check whether a path is a type the extractor supports (ignoring skip lists).
+pub(crate) fn is_supported_file(path: &Path) -> bool {
+	PlainTextExtractor::is_supported(path, &[], &[])
+}
+
+/// Check a file against the skip lists and supported types.
+/// Archives (tarballs and ZIP files) are accepted only when `include_archives` is set.
+fn should_index(path: &Path, skip_extensions: &[String], skip_files: &[String], include_archives: bool) -> bool {
+	if include_archives && (archive::is_archive(path) || PlainTextExtractor::is_zip_archive(path)) {
+		return !PlainTextExtractor::is_skipped(path, skip_extensions, skip_files);
+	}
+	PlainTextExtractor::is_supported(path, skip_extensions, skip_files)
+}
+
+/// Recursively discover supported files under `options.root`.
+/// Hidden paths and those matched by ignore files are left out as set by `exclude_hidden`,
+/// `respect_gitignore` and `use_nexusignore`, archives are included only with `follow_archive_contents`,
+/// and files marked generated in `.gitattributes` are left out with `auto_exclude_generated`.
+fn discover_files(options: &IndexOptions) -> Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	let mut generated = gitattributes::GeneratedFilter::default();
+	let exclude_generated = options.auto_exclude_generated;
+	
+	let mut walker = ignore::WalkBuilder::new(&options.root);
+	walker
+		.max_depth(options.max_depth)
+		.hidden(options.exclude_hidden)
+		.ignore(false)
+		.git_ignore(options.respect_gitignore)
+		.git_global(options.respect_gitignore)
+		.git_exclude(options.respect_gitignore)
+		.require_git(false)
+		.overrides(skip_overrides(options)?);
+	if options.use_nexusignore {
+		walker.add_custom_ignore_filename(".nexusignore");
+	}
+	
+	// The walk yields a directory before its contents, so its rules are loaded in time
+	for entry in walker.build().filter_map(|e| e.ok()) {
+		let path = entry.path();
+		if exclude_generated && entry.file_type().is_some_and(|t| t.is_dir()) {
+			if let Err(e) = generated.load_dir(path) {
+				tracing::warn!(dir = %path.display(), "failed to read .gitattributes: {}", e);
+			}
+			continue;
+		}
+		if path.is_file()
+			&& should_index(path, &options.skip_extensions, &options.skip_files, options.follow_archive_contents)
+			&& !(exclude_generated && generated.is_excluded(path))
+		{
+			files.push(path.to_path_buf());
 		}
 	}
 	Ok(files)
 }
 
+/// Ignore globs for `skip_extensions` and `skip_files`, so matching directories are
+/// not walked at all. A directory only matches a `skip_files` entry as a whole path
+/// component ("target" prunes `target/` but not `marketing-targets/`); `should_index`
+/// still checks each file name against the same lists.
+fn skip_overrides(options: &IndexOptions) -> Result<ignore::overrides::Override> {
+	let mut overrides = ignore::overrides::OverrideBuilder::new(&options.root);
+	for ext in &options.skip_extensions {
+		overrides.add(&format!("!*.{}", escape_glob(ext)))?;
+	}
+	for pattern in options.skip_files.iter().filter(|p| !p.is_empty()) {
+		overrides.add(&format!("!**/{}/", escape_glob(pattern)))?;
+	}
+	if options.respect_gitignore {
+		overrides.add("!.git/")?;
+	}
+	Ok(overrides.build()?)
+}
+
+/// Escape glob metacharacters so `text` only matches itself.
+fn escape_glob(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'*' | '?' | '[' | ']' | '{' | '}' => {
+				escaped.push('[');
+				escaped.push(c);
+				escaped.push(']');
+			}
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// List existing files under `root` that changed between two git refs.
+fn git_changed_files(root: &Path, from_ref: &str, to_ref: &str) -> Result<Vec<PathBuf>> {
+	let run_git = |args: &[&str]| -> Result<String> {
+		let output = match std::process::Command::new("git").arg("-C").arg(root).args(args).output() {
+			Ok(o) => o,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+				anyhow::bail!("git is not installed or not on PATH")
+			}
+			Err(e) => return Err(e.into()),
+		};
+		if !output.status.success() {
+			anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+		}
+		Ok(String::from_utf8_lossy(&output.stdout).to_string())
+	};
+
+	// `git diff --name-only` prints paths relative to the repository top level
+	let toplevel = PathBuf::from(run_git(&["rev-parse", "--show-toplevel"])?.trim()).canonicalize()?;
+	let range = format!("{}..{}", from_ref, to_ref);
+	let changed = run_git(&["diff", "--name-only", &range])?;
+
+	let root = root.canonicalize()?;
+	Ok(changed
+		.lines()
+		.filter(|line| !line.is_empty())
+		.map(|line| toplevel.join(line))
+		.filter(|path| path.starts_with(&root) && path.is_file())
+		.collect())
+}
+
+/// Properties `path` records about itself, or none if the extractor couldn't read them.
+/// They only label results, so failing to read them never stops indexing.
+fn document_info_or_default(path: &Path, info: Result<DocumentInfo>) -> DocumentInfo {
+	info.unwrap_or_else(|e| {
+		tracing::debug!(path = %path.display(), "failed to read document properties: {}", e);
+		DocumentInfo::default()
+	})
+}
+
+/// Build a preview snippet of at most `max_len` chars, ending in "..." when truncated.
+/// Below 3 chars there is no room for the ellipsis, so the text is just cut.
+fn make_snippet(chunk: &str, max_len: usize) -> String {
+	if chunk.chars().count() <= max_len {
+		return chunk.to_string();
+	}
+	if max_len < 3 {
+		return chunk.chars().take(max_len).collect();
+	}
+	let truncated: String = chunk.chars().take(max_len.saturating_sub(3)).collect();
+	format!("{}...", truncated)
+}
+
+/// Size of `path` in bytes, or 0 if it can't be read.
+fn file_size(path: &Path) -> u64 {
+	std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Whether a file needs (re)indexing under the given change detection.
+fn file_needs_indexing(state: &StateManager, path: &Path, change_detection: ChangeDetection) -> Result<bool> {
+	match change_detection {
+		ChangeDetection::Mtime => state.needs_indexing(path),
+		ChangeDetection::Hash => state.needs_indexing_by_hash(path),
+		ChangeDetection::MtimeThenHash => {
+			Ok(state.needs_indexing(path)? && state.needs_indexing_by_hash(path)?)
+		}
+	}
+}
+
+/// Store the content hash of a freshly indexed file; failures only cost a re-index later.
+fn record_content_hash(state: &StateManager, path: &Path, session_id: Uuid) {
+	if let Ok(hash) = store::content_hash(path) {
+		if let Err(e) = state.set_content_hash(path, &hash) {
+			eprintln!("  warning [{}]: failed to store content hash for {}: {}", session_id, path.display(), e);
+		}
+	}
+}
+
+/// Pick the chunking strategy for a file, forcing `Fixed` for data files and
+/// using `Markdown` or `Code` for those files when the default is `WordBoundary`.
+fn chunk_strategy_for(path: &Path, default: ChunkStrategy) -> ChunkStrategy {
+	let ext = path.extension()
+		.and_then(OsStr::to_str)
+		.map(str::to_lowercase)
+		.unwrap_or_default();
+	if BINARY_LIKE_EXTENSIONS.contains(&ext.as_str()) {
+		return ChunkStrategy::Fixed;
+	}
+	if default == ChunkStrategy::WordBoundary && MARKDOWN_EXTENSIONS.contains(&ext.as_str()) {
+		return ChunkStrategy::Markdown;
+	}
+	let language = CODE_EXTENSIONS.iter()
+		.find(|(code_ext, _)| *code_ext == ext)
+		.map(|&(_, language)| language);
+	match (default, language) {
+		(ChunkStrategy::WordBoundary, Some(language)) => ChunkStrategy::Code(language),
+		_ => default,
+	}
+}
+
+/// A piece of extracted text and where it came from in that text, so results
+/// can be mapped back to the source (see `DocumentMetadata::byte_start`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Chunk {
+	text: String,
+	/// Byte offset of the chunk start in the extracted text.
+	byte_start: usize,
+	/// Byte offset just past the chunk end. The text may differ from
+	/// `source[byte_start..byte_end]`, e.g. merged paragraphs or Markdown heading paths.
+	byte_end: usize,
+}
+
+impl Chunk {
+	fn new(text: impl Into<String>, byte_start: usize, byte_end: usize) -> Self {
+		Self { text: text.into(), byte_start, byte_end }
+	}
+}
+
+/// A page number and the chunks of that page's text.
+type PageChunks = (usize, Vec<Chunk>);
+
+/// Split text into chunks using the given strategy.
+/// `embedder` supplies the tokenizer and token limit for `ChunkStrategy::Tokens`.
+fn chunk_with_strategy<M: Embedder + ?Sized>(text: &str, max_len: usize, overlap: usize, strategy: ChunkStrategy, embedder: &M) -> Vec<Chunk> {
+	match strategy {
+		ChunkStrategy::WordBoundary => chunk_text(text, max_len, overlap),
+		ChunkStrategy::Fixed => chunk_fixed(text, max_len),
+		ChunkStrategy::Code(language) => chunk_code(text, language, max_len, overlap),
+		ChunkStrategy::Markdown => chunk_by_markdown(text, max_len, overlap),
+		ChunkStrategy::Tokens(max_tokens) => {
+			// Leave room for the [CLS]/[SEP] tokens the model adds
+			let max_tokens = match embedder.max_input_tokens() {
+				Some(limit) => max_tokens.min(limit.saturating_sub(2)),
+				None => max_tokens,
+			};
+			match embedder.token_spans(text) {
+				Some(spans) => chunk_by_tokens(text, &spans, max_tokens),
+				None => chunk_text(text, max_tokens * CHARS_PER_TOKEN, 0),
+			}
+		}
+	}
+}
+
+/// Token counts of the `texts` longer than the embedder's `max_input_tokens`.
+fn over_token_limit<M: Embedder>(embedder: &M, texts: &[&str]) -> Vec<usize> {
+	let Some(limit) = embedder.max_input_tokens() else {
+		return Vec::new();
+	};
+	// The model adds [CLS]/[SEP] to every input
+	let limit = limit.saturating_sub(2);
+	texts.iter()
+		.map(|text| embedder.token_spans(text).map_or(text.len() / CHARS_PER_TOKEN, |spans| spans.len()))
+		.filter(|&tokens| tokens > limit)
+		.collect()
+}
+
+/// Group tokens into chunks of at most `max_tokens`, ending each chunk before a
+/// token that starts a new word when one is in the second half of the window.
+/// `spans` are the tokens' byte ranges in `text`, in order.
+fn chunk_by_tokens(text: &str, spans: &[(usize, usize)], max_tokens: usize) -> Vec<Chunk> {
+	let max_tokens = max_tokens.max(1);
+	// A token starts a word if there is a gap (whitespace) before it
+	let starts_word = |i: usize| i == 0 || spans[i].0 > spans[i - 1].1;
+	let mut chunks = Vec::new();
+	let mut start = 0;
+	
+	while start < spans.len() {
+		let mut end = (start + max_tokens).min(spans.len());
+		if end < spans.len() {
+			if let Some(word_start) = (start + max_tokens / 2 + 1..=end).rev().find(|&i| starts_word(i)) {
+				end = word_start;
+			}
+		}
+		
+		let (byte_start, byte_end) = (spans[start].0, spans[end - 1].1);
+		let chunk = text[byte_start..byte_end].trim();
+		if !chunk.is_empty() {
+			let byte_start = byte_start + (text[byte_start..byte_end].len() - text[byte_start..byte_end].trim_start().len());
+			chunks.push(Chunk::new(chunk, byte_start, byte_start + chunk.len()));
+		}
+		start = end;
+	}
+	
+	chunks
+}
+
+/// Split text every `max_len` characters, ignoring word boundaries.
+/// Whitespace-only chunks are dropped; others are kept verbatim.
+fn chunk_fixed(text: &str, max_len: usize) -> Vec<Chunk> {
+	let max_len = max_len.max(1);
+	let boundaries: Vec<usize> = text.char_indices()
+		.map(|(i, _)| i)
+		.step_by(max_len)
+		.chain(std::iter::once(text.len()))
+		.collect();
+	
+	boundaries.windows(2)
+		.filter(|w| !text[w[0]..w[1]].trim().is_empty())
+		.map(|w| Chunk::new(&text[w[0]..w[1]], w[0], w[1]))
+		.collect()
+}
+
 /// Split text into chunks of roughly `max_len` characters.
 /// Uses a smarter strategy:
 /// 1. First try to split by paragraphs (double newlines)
 /// 2. For content with many short lines, group them more aggressively
 /// 3. Never break mid-word if possible
-fn chunk_text(text: &str, max_len: usize) -> Vec<String> {
+///
+/// Each chunk carries the byte range that locates it in `text`.
+/// Merged paragraphs span from the first to the last one.
+/// Each chunk starts up to `overlap` characters (at most `max_len / 2`) before
+/// the end of the previous one, at a word start.
+fn chunk_text(text: &str, max_len: usize, overlap: usize) -> Vec<Chunk> {
+	let overlap = overlap.min(max_len / 2);
+
 	// First, try paragraph-based chunking (split on double newlines)
-	let paragraphs: Vec<&str> = text.split("\n\n").collect();
+	let mut paragraphs: Vec<(&str, usize)> = Vec::new();
+	let mut offset = 0;
+	for para in text.split("\n\n") {
+		paragraphs.push((para, offset));
+		offset += para.len() + 2;
+	}
 	
 	// If we have reasonable paragraphs, use them
 	if paragraphs.len() > 1 && paragraphs.len() < text.len() / 100 {
-		return chunk_by_paragraphs(&paragraphs, max_len);
+		return chunk_by_paragraphs(&paragraphs, max_len, overlap);
 	}
 	
 	// Otherwise, use character-based chunking (better for short-line content)
-	chunk_by_chars(text, max_len)
+	chunk_by_chars(text, max_len, overlap)
+}
+
+/// Split source code before its top-level definitions. Neighbouring definitions
+/// share a chunk while they fit in `max_len`; one longer than that is split with
+/// `chunk_by_chars`. Comments and attributes directly above a definition stay
+/// with it. Text with no definitions falls back to `chunk_text`.
+fn chunk_code(text: &str, language: CodeLanguage, max_len: usize, overlap: usize) -> Vec<Chunk> {
+	let starts = definition_starts(text, language);
+	if starts.is_empty() {
+		return chunk_text(text, max_len, overlap);
+	}
+	
+	let mut chunks = Vec::new();
+	// Byte range of the definitions gathered for the next chunk
+	let mut current: Option<(usize, usize)> = None;
+	let bounds: Vec<usize> = std::iter::once(0).chain(starts).chain(std::iter::once(text.len())).collect();
+	for window in bounds.windows(2) {
+		let section = &text[window[0]..window[1]];
+		let trimmed = section.trim();
+		if trimmed.is_empty() {
+			continue;
+		}
+		let start = window[0] + (section.len() - section.trim_start().len());
+		let end = start + trimmed.len();
+		
+		if let Some((current_start, current_end)) = current {
+			if end - current_start <= max_len {
+				current = Some((current_start, end));
+				continue;
+			}
+			chunks.push(Chunk::new(&text[current_start..current_end], current_start, current_end));
+			current = None;
+		}
+		if trimmed.len() > max_len {
+			chunks.extend(
+				chunk_by_chars(trimmed, max_len, overlap)
+					.into_iter()
+					.map(|chunk| Chunk::new(chunk.text, start + chunk.byte_start, start + chunk.byte_end))
+			);
+		} else {
+			current = Some((start, end));
+		}
+	}
+	if let Some((start, end)) = current {
+		chunks.push(Chunk::new(&text[start..end], start, end));
+	}
+	chunks
+}
+
+/// Byte offsets of the lines that start top-level definitions, moved up over the
+/// annotation lines directly above them. Offset 0 is never included.
+fn definition_starts(text: &str, language: CodeLanguage) -> Vec<usize> {
+	let mut starts = Vec::new();
+	// Where the run of annotation lines above the current line began
+	let mut annotations_start = None;
+	let mut offset = 0;
+	for line in text.split_inclusive('\n') {
+		if language.starts_definition(line) {
+			let start = annotations_start.take().unwrap_or(offset);
+			if start > 0 {
+				starts.push(start);
+			}
+		} else if language.is_annotation(line) {
+			annotations_start.get_or_insert(offset);
+		} else {
+			annotations_start = None;
+		}
+		offset += line.len();
+	}
+	starts
+}
+
+/// A Markdown section, from its heading line up to the next heading.
+struct MarkdownSection {
+	/// Heading lines from the top-level ancestor down to this section's own.
+	path: Vec<String>,
+	/// Byte offset of the heading line.
+	start: usize,
+	/// Byte offset just past the heading line.
+	body_start: usize,
+	/// Byte offset of the next heading, or the end of the text.
+	end: usize,
+}
+
+/// Level of an ATX heading line (`## Install` is 2), or `None` for other lines.
+fn heading_level(line: &str) -> Option<usize> {
+	let level = line.bytes().take_while(|&b| b == b'#').count();
+	let rest = &line[level..];
+	let is_heading = (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(char::is_whitespace));
+	is_heading.then_some(level)
+}
+
+/// The sections of a Markdown document, in order. Lines inside fenced code
+/// blocks are never headings, so shell comments in examples don't split sections.
+fn markdown_sections(text: &str) -> Vec<MarkdownSection> {
+	let mut sections: Vec<MarkdownSection> = Vec::new();
+	// Open headings, outermost first, with their levels
+	let mut open: Vec<(usize, String)> = Vec::new();
+	let mut in_fence = false;
+	let mut offset = 0;
+	for line in text.split_inclusive('\n') {
+		let line_start = offset;
+		offset += line.len();
+		let line = line.trim_end();
+		if line.starts_with("```") || line.starts_with("~~~") {
+			in_fence = !in_fence;
+			continue;
+		}
+		let Some(level) = heading_level(line).filter(|_| !in_fence) else {
+			continue;
+		};
+		
+		if let Some(previous) = sections.last_mut() {
+			previous.end = line_start;
+		}
+		while open.last().is_some_and(|(open_level, _)| *open_level >= level) {
+			open.pop();
+		}
+		open.push((level, line.to_string()));
+		sections.push(MarkdownSection {
+			path: open.iter().map(|(_, heading)| heading.clone()).collect(),
+			start: line_start,
+			body_start: offset,
+			end: text.len(),
+		});
+	}
+	sections
+}
+
+/// The heading path joined as `# Guide > ## Install`. When that takes more than
+/// half of `max_len`, only the section's own heading is used, cut to fit.
+fn heading_prefix(path: &[String], max_len: usize) -> String {
+	let prefix = path.join(" > ");
+	if prefix.len() <= max_len / 2 {
+		return prefix;
+	}
+	let own = path.last().map(String::as_str).unwrap_or_default();
+	own.chars().take(max_len / 2).collect()
+}
+
+/// Split Markdown at its ATX headings. Each section's text follows its heading path
+/// (see `heading_prefix`) on the first line; a section too long for `max_len` is
+/// split with `chunk_text`, repeating the path on every piece, and neighbouring
+/// pieces share a chunk while they fit. A heading with nothing under it but
+/// subsections gets no piece of its own. Text before the first heading is chunked
+/// as plain text, as is a document without headings.
+///
+/// Byte ranges locate the section text (from the heading line) in `text`; the
+/// chunk text itself carries the heading path instead of the heading line.
+fn chunk_by_markdown(text: &str, max_len: usize, overlap: usize) -> Vec<Chunk> {
+	let sections = markdown_sections(text);
+	let Some(first) = sections.first() else {
+		return chunk_text(text, max_len, overlap);
+	};
+	
+	let mut pieces = chunk_text(&text[..first.start], max_len, overlap);
+	for (i, section) in sections.iter().enumerate() {
+		let body = &text[section.body_start..section.end];
+		let trimmed = body.trim();
+		let has_subsection = sections.get(i + 1).is_some_and(|next| next.path.len() > section.path.len());
+		if trimmed.is_empty() && has_subsection {
+			continue;
+		}
+		
+		let prefix = heading_prefix(&section.path, max_len);
+		let body_start = section.body_start + (body.len() - body.trim_start().len());
+		if prefix.len() + 1 + trimmed.len() <= max_len {
+			let chunk = format!("{}\n{}", prefix, trimmed);
+			pieces.push(Chunk::new(chunk.trim_end(), section.start, body_start + trimmed.len()));
+		} else {
+			for piece in chunk_text(trimmed, max_len.saturating_sub(prefix.len() + 1).max(1), overlap) {
+				let text = format!("{}\n{}", prefix, piece.text);
+				pieces.push(Chunk::new(text, body_start + piece.byte_start, body_start + piece.byte_end));
+			}
+		}
+	}
+	
+	let mut chunks: Vec<Chunk> = Vec::new();
+	for piece in pieces {
+		if let Some(last) = chunks.last_mut() {
+			if last.text.len() + 2 + piece.text.len() <= max_len {
+				last.text.push_str("\n\n");
+				last.text.push_str(&piece.text);
+				last.byte_end = piece.byte_end;
+				continue;
+			}
+		}
+		chunks.push(piece);
+	}
+	chunks
+}
+
+/// Byte offset in `text` where its last `overlap` characters begin, moved
+/// forward to the next word start so overlaps never begin mid-word.
+/// Returns `text.len()` when there is no word start in that range.
+fn overlap_start(text: &str, overlap: usize) -> usize {
+	if overlap == 0 {
+		return text.len();
+	}
+	let start = text.char_indices().rev().nth(overlap - 1).map_or(0, |(i, _)| i);
+	let rest = &text[start..];
+	let word_start = if start == 0 || text[..start].ends_with(char::is_whitespace) {
+		Some(start)
+	} else {
+		rest.find(char::is_whitespace).map(|ws| start + ws)
+	};
+	match word_start {
+		Some(i) => {
+			let tail = &text[i..];
+			i + (tail.len() - tail.trim_start().len())
+		}
+		None => text.len(),
+	}
 }
 
 /// Chunk by paragraphs, merging small ones and splitting large ones.
-fn chunk_by_paragraphs(paragraphs: &[&str], max_len: usize) -> Vec<String> {
+/// Each paragraph is paired with its byte offset in the source text.
+/// A chunk that follows a merged chunk starts with up to `overlap` characters
+/// from the end of that chunk's last paragraph, if they still fit.
+fn chunk_by_paragraphs(paragraphs: &[(&str, usize)], max_len: usize, overlap: usize) -> Vec<Chunk> {
 	let mut chunks = Vec::new();
 	let mut current = String::new();
+	let mut current_start = 0;
+	let mut current_end = 0;
+	// Last paragraph added to `current`, with its byte offset
+	let mut last_para: (&str, usize) = ("", 0);
 	
-	for para in paragraphs {
+	for &(para, para_offset) in paragraphs {
+		let start = para_offset + (para.len() - para.trim_start().len());
 		let para = para.trim();
 		if para.is_empty() {
 			continue;
 		}
+		let end = start + para.len();
 		
 		// If adding this paragraph would exceed limit
 		if !current.is_empty() && current.len() + para.len() + 2 > max_len {
-			chunks.push(current.clone());
+			chunks.push(Chunk::new(current.clone(), current_start, current_end));
 			current.clear();
+			
+			// Carry the previous paragraph's tail over when it fits alongside this one
+			let (last, last_start) = last_para;
+			let tail_start = overlap_start(last, overlap);
+			let tail = &last[tail_start..];
+			if !tail.is_empty() && tail.len() + para.len() + 2 <= max_len {
+				current.push_str(tail);
+				current_start = last_start + tail_start;
+			}
 		}
 		
 		// If single paragraph is too long, split it
 		if para.len() > max_len {
 			if !current.is_empty() {
-				chunks.push(current.clone());
+				chunks.push(Chunk::new(current.clone(), current_start, current_end));
 				current.clear();
 			}
-			chunks.extend(chunk_by_chars(para, max_len));
+			chunks.extend(
+				chunk_by_chars(para, max_len, overlap)
+					.into_iter()
+					.map(|chunk| Chunk::new(chunk.text, start + chunk.byte_start, start + chunk.byte_end))
+			);
 			continue;
 		}
 		
-		if !current.is_empty() {
+		if current.is_empty() {
+			current_start = start;
+		} else {
 			current.push_str("\n\n");
 		}
 		current.push_str(para);
+		current_end = end;
+		last_para = (para, start);
 	}
 	
 	if !current.is_empty() {
-		chunks.push(current);
+		chunks.push(Chunk::new(current, current_start, current_end));
 	}
 	chunks
 }
 
 /// Character-based chunking that respects word boundaries.
 /// Much better for short-line content (poetry, lyrics, code).
-fn chunk_by_chars(text: &str, max_len: usize) -> Vec<String> {
+/// Each chunk after the first repeats up to `overlap` characters of the previous one.
+fn chunk_by_chars(text: &str, max_len: usize, overlap: usize) -> Vec<Chunk> {
 	let mut chunks = Vec::new();
 	let mut start = 0;
 	let chars: Vec<char> = text.chars().collect();
+	// Byte offset of each char, plus one past the end
+	let byte_offsets: Vec<usize> = text.char_indices()
+		.map(|(i, _)| i)
+		.chain(std::iter::once(text.len()))
+		.collect();
 	let len = chars.len();
 	
 	while start < len {
@@ -680,12 +2360,22 @@ fn chunk_by_chars(text: &str, max_len: usize) -> Vec<String> {
 			}
 		}
 		
-		let chunk: String = chars[start..end].iter().collect();
-		let trimmed = chunk.trim();
+		let slice = &text[byte_offsets[start]..byte_offsets[end]];
+		let trimmed = slice.trim();
 		if !trimmed.is_empty() {
-			chunks.push(trimmed.to_string());
+			let byte_start = byte_offsets[start] + (slice.len() - slice.trim_start().len());
+			chunks.push(Chunk::new(trimmed, byte_start, byte_start + trimmed.len()));
 		}
-		start = end;
+		if end == len {
+			break;
+		}
+		
+		// Step back into this chunk for the overlap, always moving forward overall
+		let next_byte = byte_offsets[start] + overlap_start(slice, overlap);
+		start = match byte_offsets.binary_search(&next_byte) {
+			Ok(next) if next > start => next,
+			_ => end,
+		};
 		
 		// Skip leading whitespace for next chunk
 		while start < len && chars[start].is_whitespace() {
@@ -715,5 +2405,461 @@ pub trait Embedder: Send + Sync {
 	async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
 	/// Return the embedding dimension.
 	fn dimension(&self) -> usize;
+	/// Most tokens the model reads from one input; the rest is silently truncated.
+	/// `None` if the limit is unknown.
+	fn max_input_tokens(&self) -> Option<usize> {
+		None
+	}
+	/// Byte ranges of the tokens `text` is split into, without special tokens.
+	/// `None` if the embedder has no tokenizer; `ChunkStrategy::Tokens` then
+	/// assumes ~4 characters per token.
+	fn token_spans(&self, _text: &str) -> Option<Vec<(usize, usize)>> {
+		None
+	}
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_chunk_by_chars_byte_offsets() {
+		let text = "  héllo wörld, this is ünïcode text that spans several chunks  ";
+		for chunk in chunk_by_chars(text, 16, 0) {
+			assert_eq!(&text[chunk.byte_start..chunk.byte_end], chunk.text);
+		}
+	}
+
+	#[test]
+	fn test_chunk_fixed_splits_at_exact_length() {
+		let text = "aGVsbG8gd29ybGQ=".repeat(10); // no whitespace to break on
+		let chunks = chunk_fixed(&text, 64);
+		assert_eq!(chunks.len(), 3);
+		assert!(chunks[..2].iter().all(|c| c.text.chars().count() == 64));
+		for chunk in &chunks {
+			assert_eq!(&text[chunk.byte_start..chunk.byte_end], chunk.text);
+		}
+	}
+
+	#[test]
+	fn test_make_snippet_length() {
+		assert_eq!(make_snippet("short", 10), "short");
+		assert_eq!(make_snippet("héllo wörld", 8), "héllo...");
+		for max_len in 0..3 {
+			assert_eq!(make_snippet("héllo", max_len), "héllo".chars().take(max_len).collect::<String>());
+		}
+	}
+
+	#[test]
+	fn test_data_files_use_fixed_strategy() {
+		assert_eq!(chunk_strategy_for(Path::new("rows.CSV"), ChunkStrategy::WordBoundary), ChunkStrategy::Fixed);
+		assert_eq!(chunk_strategy_for(Path::new("events.jsonl"), ChunkStrategy::WordBoundary), ChunkStrategy::Fixed);
+		assert_eq!(chunk_strategy_for(Path::new("notes.txt"), ChunkStrategy::WordBoundary), ChunkStrategy::WordBoundary);
+		assert_eq!(chunk_strategy_for(Path::new("notes.md"), ChunkStrategy::WordBoundary), ChunkStrategy::Markdown);
+	}
+
+	#[test]
+	fn test_chunk_by_markdown_keeps_heading_paths() {
+		let mut text = String::from("# Handbook\n\n");
+		for i in 1..=10 {
+			text.push_str(&format!("## Section {}\n\n", i));
+			let sentences = if i % 3 == 0 { 12 } else { 2 };
+			for j in 0..sentences {
+				text.push_str(&format!("Sentence {} of section {} explains one more detail.\n\n", j, i));
+			}
+			if i == 4 {
+				text.push_str("### Details\n\n```sh\n# not a heading\nmake install\n```\n\n");
+			}
+		}
+		
+		let max_len = 300;
+		let chunks = chunk_by_markdown(&text, max_len, 0);
+		assert!(chunks.len() > 5);
+		for chunk in &chunks {
+			assert!(chunk.text.starts_with("# Handbook > ## Section "), "{:?}", chunk.text);
+			assert!(chunk.text.len() <= max_len, "{} bytes: {:?}", chunk.text.len(), chunk.text);
+			assert!(chunk.byte_start < chunk.byte_end && chunk.byte_end <= text.len());
+		}
+		
+		// Long sections repeat their path on every piece; the fence comment is no heading
+		let section_6: Vec<_> = chunks.iter().filter(|c| c.text.starts_with("# Handbook > ## Section 6\n")).collect();
+		assert!(section_6.len() > 1);
+		assert!(chunks.iter().any(|c| c.text.contains("# Handbook > ## Section 4 > ### Details\n```sh\n# not a heading")));
+		
+		// No headings: plain word-boundary chunks
+		assert_eq!(chunk_by_markdown("just text\n", 100, 0), chunk_text("just text\n", 100, 0));
+	}
+
+	#[test]
+	fn test_code_files_use_code_strategy() {
+		assert_eq!(chunk_strategy_for(Path::new("src/lib.rs"), ChunkStrategy::WordBoundary), ChunkStrategy::Code(CodeLanguage::Rust));
+		assert_eq!(chunk_strategy_for(Path::new("app.TSX"), ChunkStrategy::WordBoundary), ChunkStrategy::Code(CodeLanguage::JavaScript));
+		// Explicit strategies are left alone
+		assert_eq!(chunk_strategy_for(Path::new("main.py"), ChunkStrategy::Tokens(128)), ChunkStrategy::Tokens(128));
+	}
+
+	/// Chunk `text` as `language` and check each chunk matches its byte range.
+	fn code_chunks(text: &str, language: CodeLanguage, max_len: usize) -> Vec<String> {
+		let chunks = chunk_code(text, language, max_len, 0);
+		for chunk in &chunks {
+			assert_eq!(&text[chunk.byte_start..chunk.byte_end], chunk.text);
+		}
+		chunks.into_iter().map(|chunk| chunk.text).collect()
+	}
+
+	#[test]
+	fn test_chunk_code_rust() {
+		let text = "use std::fmt;\n\n/// Adds one.\n#[inline]\npub fn add_one(x: i32) -> i32 {\n    let y = x + 1;\n\n    y\n}\n\nstruct Point {\n    x: i32,\n}\n\nimpl Point {\n    fn norm(&self) -> i32 {\n        self.x.abs()\n    }\n}\n";
+		let chunks = code_chunks(text, CodeLanguage::Rust, 90);
+		assert_eq!(chunks, vec![
+			"use std::fmt;",
+			"/// Adds one.\n#[inline]\npub fn add_one(x: i32) -> i32 {\n    let y = x + 1;\n\n    y\n}",
+			"struct Point {\n    x: i32,\n}",
+			"impl Point {\n    fn norm(&self) -> i32 {\n        self.x.abs()\n    }\n}",
+		]);
+		
+		// With room to spare, neighbouring definitions share a chunk
+		let chunks = code_chunks(text, CodeLanguage::Rust, 200);
+		assert_eq!(chunks.len(), 1);
+		assert_eq!(chunks[0], text.trim());
+	}
+
+	#[test]
+	fn test_chunk_code_python() {
+		let text = "import os\n\n@cache\ndef load(path):\n    data = open(path).read()\n\n    return data\n\nclass Store:\n    def get(self, key):\n        return self.items[key]\n";
+		let chunks = code_chunks(text, CodeLanguage::Python, 70);
+		assert_eq!(chunks, vec![
+			"import os",
+			"@cache\ndef load(path):\n    data = open(path).read()\n\n    return data",
+			"class Store:\n    def get(self, key):\n        return self.items[key]",
+		]);
+	}
+
+	#[test]
+	fn test_chunk_code_javascript() {
+		let text = "const x = 1;\n/**\n * Greets.\n */\nexport function greet(name) {\n  return `hi ${name}`;\n}\n\nexport default class Widget {\n  render() {}\n}\n";
+		let chunks = code_chunks(text, CodeLanguage::JavaScript, 80);
+		assert_eq!(chunks, vec![
+			"const x = 1;",
+			"/**\n * Greets.\n */\nexport function greet(name) {\n  return `hi ${name}`;\n}",
+			"export default class Widget {\n  render() {}\n}",
+		]);
+	}
+
+	#[test]
+	fn test_chunk_code_long_definition_and_fallback() {
+		// A definition longer than max_len is split on its own
+		let body: String = (0..40).map(|i| format!("    let v{} = {};\n", i, i)).collect();
+		let text = format!("fn small() {{}}\n\nfn big() {{\n{}}}\n", body);
+		let chunks = code_chunks(&text, CodeLanguage::Rust, 200);
+		assert_eq!(chunks[0], "fn small() {}");
+		assert!(chunks[1].starts_with("fn big() {"));
+		assert!(chunks.len() > 3);
+		
+		// No definitions: the same chunks as the word-boundary strategy
+		let script = "x = 1\n\nprint(x)\n";
+		assert_eq!(chunk_code(script, CodeLanguage::Python, 100, 0), chunk_text(script, 100, 0));
+	}
+
+	/// One token per space-separated word, with an optional input limit.
+	struct WordEmbedder(Option<usize>);
+	#[async_trait]
+	impl Embedder for WordEmbedder {
+		async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+			Ok(vec![0.0])
+		}
+		async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+			Ok(texts.iter().map(|_| vec![0.0]).collect())
+		}
+		fn dimension(&self) -> usize {
+			1
+		}
+		fn max_input_tokens(&self) -> Option<usize> {
+			self.0
+		}
+		fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+			Some(text.split(' ').map(|w| (0, w.len())).collect())
+		}
+	}
+
+	#[test]
+	fn test_over_token_limit() {
+		let long = "word ".repeat(2000);
+		let texts = ["short text", long.as_str(), "eight words fit in a ten token limit"];
+		assert_eq!(over_token_limit(&WordEmbedder(Some(10)), &texts), vec![2001]);
+		assert!(over_token_limit(&WordEmbedder(None), &texts).is_empty());
+	}
+
+	#[test]
+	fn test_chunk_by_tokens_breaks_between_words() {
+		// "unbelievable" is three sub-word tokens
+		let text = "unbelievable things ok";
+		let spans = [(0, 2), (2, 8), (8, 12), (13, 19), (20, 22)];
+		let chunks = chunk_by_tokens(text, &spans, 3);
+		assert_eq!(chunks, vec![
+			Chunk::new("unbelievable", 0, 12),
+			Chunk::new("things ok", 13, 22),
+		]);
+		// With no word start in reach, the window is split mid-word
+		assert_eq!(chunk_by_tokens(text, &spans, 2)[0].text, "unbeliev");
+	}
+
+	#[test]
+	fn test_chunk_by_chars_overlap() {
+		let text = "alpha bravo charlie delta echo foxtrot golf hotel india juliet kilo lima mike november";
+		let chunks = chunk_by_chars(text, 30, 12);
+		assert!(chunks.len() > 2);
+		for pair in chunks.windows(2) {
+			let (prev, next) = (&pair[0], &pair[1]);
+			// The next chunk starts inside the previous one, at a word start
+			assert!(next.byte_start > prev.byte_start && next.byte_start < prev.byte_end);
+			let shared = &text[next.byte_start..prev.byte_end];
+			assert!(prev.text.ends_with(shared) && next.text.starts_with(shared));
+			assert!(shared.chars().count() <= 12);
+			assert!(!shared.starts_with(' '));
+		}
+		for chunk in &chunks {
+			assert_eq!(&text[chunk.byte_start..chunk.byte_end], chunk.text);
+		}
+		// Overlap is capped at half the chunk size
+		assert_eq!(chunk_text(text, 30, 1000), chunk_text(text, 30, 15));
+	}
+
+	#[test]
+	fn test_chunk_by_paragraphs_overlap() {
+		let text = "the first paragraph ends here\n\nsecond one\n\nthird paragraph text";
+		let mut with_offsets = Vec::new();
+		let mut offset = 0;
+		for para in text.split("\n\n") {
+			with_offsets.push((para, offset));
+			offset += para.len() + 2;
+		}
+
+		let chunks = chunk_by_paragraphs(&with_offsets, 45, 10);
+		assert_eq!(chunks.len(), 2);
+		// The second chunk begins with the tail of the first one's last paragraph
+		assert!(chunks[0].text.ends_with("second one"));
+		assert_eq!(chunks[1].text, "second one\n\nthird paragraph text");
+		assert_eq!(&text[chunks[1].byte_start..chunks[1].byte_end], chunks[1].text);
+	}
+
+	#[test]
+	fn test_chunk_by_paragraphs_byte_offsets() {
+		let paragraphs = ["first paragraph", "second één", "third paragraph"];
+		let text = paragraphs.join("\n\n");
+		let mut with_offsets = Vec::new();
+		let mut offset = 0;
+		for para in text.split("\n\n") {
+			with_offsets.push((para, offset));
+			offset += para.len() + 2;
+		}
+
+		let chunks = chunk_by_paragraphs(&with_offsets, 30, 0);
+		assert_eq!(chunks.len(), 2);
+		// Merged paragraphs span from the first to the last one
+		assert_eq!(&text[chunks[0].byte_start..chunks[0].byte_end], chunks[0].text);
+		assert_eq!(&text[chunks[1].byte_start..chunks[1].byte_end], "third paragraph");
+	}
+
+	#[test]
+	fn test_discover_files_skips_generated() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::create_dir(dir.path().join("src")).unwrap();
+		std::fs::write(dir.path().join(".gitattributes"), "src/generated.rs linguist-generated=true\n").unwrap();
+		std::fs::write(dir.path().join("src/generated.rs"), "// @generated").unwrap();
+		std::fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+		let root = dir.path().to_path_buf();
+		let mut options = IndexOptions {
+			root: root.clone(),
+			auto_exclude_generated: true,
+			..Default::default()
+		};
+
+		let found = discover_files(&options).unwrap();
+		assert!(found.contains(&root.join("src/main.rs")));
+		assert!(!found.contains(&root.join("src/generated.rs")));
+
+		options.auto_exclude_generated = false;
+		let found = discover_files(&options).unwrap();
+		assert!(found.contains(&root.join("src/generated.rs")));
+	}
+
+	#[test]
+	fn test_discover_files_prunes_whole_directory_names() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		for sub in ["target/debug", "marketing-targets", "docs/target"] {
+			std::fs::create_dir_all(root.join(sub)).unwrap();
+		}
+		for file in ["target/debug/out.txt", "marketing-targets/plan.txt", "docs/target/notes.txt", "docs/readme.txt", "docs/target-list.txt"] {
+			std::fs::write(root.join(file), "text").unwrap();
+		}
+		let options = IndexOptions {
+			root: root.clone(),
+			skip_files: vec!["target".to_string()],
+			..Default::default()
+		};
+
+		let mut found: Vec<PathBuf> = discover_files(&options).unwrap()
+			.into_iter()
+			.map(|p| p.strip_prefix(&root).unwrap().to_path_buf())
+			.collect();
+		found.sort();
+		// File names still match by substring
+		assert_eq!(found, vec![PathBuf::from("docs/readme.txt"), PathBuf::from("marketing-targets/plan.txt")]);
+	}
+
+	#[test]
+	fn test_discover_files_respects_ignore_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		for sub in ["logs", "node_modules/lib", "drafts"] {
+			std::fs::create_dir_all(root.join(sub)).unwrap();
+		}
+		std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+		std::fs::write(root.join(".nexusignore"), "drafts/\n").unwrap();
+		for file in ["notes.txt", "build.log", "logs/today.log", "node_modules/lib/index.js", "drafts/idea.md"] {
+			std::fs::write(root.join(file), "text").unwrap();
+		}
+		let mut options = IndexOptions {
+			root: root.clone(),
+			skip_files: vec!["node_modules".to_string()],
+			..Default::default()
+		};
+
+		let found = discover_files(&options).unwrap();
+		assert!(found.contains(&root.join("notes.txt")));
+		assert!(!found.iter().any(|p| p.extension().is_some_and(|e| e == "log")));
+		assert!(!found.contains(&root.join("node_modules/lib/index.js")));
+		assert!(!found.contains(&root.join("drafts/idea.md")));
+
+		options.respect_gitignore = false;
+		options.use_nexusignore = false;
+		let found = discover_files(&options).unwrap();
+		assert!(found.contains(&root.join("logs/today.log")));
+		assert!(found.contains(&root.join("drafts/idea.md")));
+		assert!(!found.contains(&root.join("node_modules/lib/index.js")));
+	}
+
+	#[test]
+	fn test_discover_files_excludes_hidden() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		std::fs::create_dir(root.join(".notes")).unwrap();
+		for file in ["visible.txt", ".hidden_file.txt", ".notes/secret.txt"] {
+			std::fs::write(root.join(file), "text").unwrap();
+		}
+		let mut options = IndexOptions {
+			root: root.clone(),
+			..Default::default()
+		};
+		assert!(options.exclude_hidden);
+
+		let found = discover_files(&options).unwrap();
+		assert_eq!(found, vec![root.join("visible.txt")]);
+
+		options.exclude_hidden = false;
+		let found = discover_files(&options).unwrap();
+		assert_eq!(found.len(), 3);
+	}
+
+	#[test]
+	fn test_discover_files_max_depth() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		// level1.txt sits directly in the root, level5.txt four directories down
+		let mut sub = root.clone();
+		for level in 1..=5 {
+			std::fs::create_dir_all(&sub).unwrap();
+			std::fs::write(sub.join(format!("level{}.txt", level)), "text").unwrap();
+			sub.push(format!("d{}", level));
+		}
+		let mut options = IndexOptions {
+			root: root.clone(),
+			max_depth: Some(2),
+			..Default::default()
+		};
+
+		let mut found = discover_files(&options).unwrap();
+		found.sort();
+		assert_eq!(found, vec![root.join("d1/level2.txt"), root.join("level1.txt")]);
+
+		options.max_depth = None;
+		assert_eq!(discover_files(&options).unwrap().len(), 5);
+	}
+
+	#[test]
+	fn test_error_summary_groups_by_prefix() {
+		let pdf_error = |name: &str| (
+			PathBuf::from(format!("/docs/{}", name)),
+			format!("Failed to extract pages: Failed to open PDF {}: libpoppler missing", name),
+		);
+		let result = IndexResult {
+			session_id: Uuid::nil(),
+			files_indexed: 0,
+			files_skipped: 0,
+			files_unchanged: 0,
+			chunks_indexed: 0,
+			embeddings_stored: 0,
+			elapsed: Duration::ZERO,
+			bytes_processed: 0,
+			errors: vec![
+				pdf_error("a.pdf"),
+				(PathBuf::from("/docs/notes.txt"), "Embedding failed: model not loaded".to_string()),
+				pdf_error("b.pdf"),
+				pdf_error("c.pdf"),
+			],
+		};
+		let summary = result.error_summary();
+		assert_eq!(summary.len(), 2);
+		assert_eq!(summary[0].1, 3);
+		assert!(summary[0].0.starts_with("Failed to extract pages: Failed to open PDF"));
+		assert!(summary[0].0.chars().count() <= ERROR_SUMMARY_PREFIX_LEN);
+		assert_eq!(summary[1], ("Embedding failed: model not loaded".to_string(), 1));
+	}
+
+	#[test]
+	fn test_index_event_serde_round_trip() {
+		let path = PathBuf::from("/docs/report.pdf");
+		let events = vec![
+			IndexEvent::IndexingStarted { session_id: Uuid::new_v4() },
+			IndexEvent::Started { total_files: 12 },
+			IndexEvent::FileStarted(path.clone()),
+			IndexEvent::FileIndexed(path.clone()),
+			IndexEvent::FileError(path.clone(), "bad xref".to_string()),
+			IndexEvent::FileSkipped(path.clone(), "too large".to_string()),
+			IndexEvent::FileUnchanged(path.clone()),
+			IndexEvent::MemoryPressure(4096, 2048),
+			IndexEvent::PageProcessed(path.clone(), 2, 10),
+			IndexEvent::ChunkProcessed(path.clone(), 3),
+			IndexEvent::ChunkEmbedded(path.clone(), 3, "doc-3".to_string()),
+			IndexEvent::BatchEmbedded { path: path.clone(), chunk_count: 8, elapsed_ms: 120, tokens_estimated: 900 },
+			IndexEvent::ChunkIndexed { path: path.clone(), chunk_index: 3, byte_start: 10, byte_end: 90 },
+			IndexEvent::IndexingPaused("max_total_chunks reached".to_string()),
+			IndexEvent::WatchPathAdded(PathBuf::from("/docs/new")),
+			IndexEvent::Done,
+			IndexEvent::Finished(IndexResult {
+				session_id: Uuid::new_v4(),
+				files_indexed: 10,
+				files_skipped: 1,
+				files_unchanged: 0,
+				chunks_indexed: 40,
+				embeddings_stored: 40,
+				errors: vec![(path.clone(), "bad xref".to_string())],
+				elapsed: Duration::from_millis(1500),
+				bytes_processed: 524_288,
+			}),
+			IndexEvent::Failed("Root path does not exist".to_string()),
+		];
+		for event in events {
+			let json = serde_json::to_string(&event).unwrap();
+			let back: IndexEvent = serde_json::from_str(&json).unwrap();
+			assert_eq!(back, event, "{}", json);
+		}
+
+		let value = serde_json::to_value(IndexEvent::FileSkipped(path, "too large".to_string())).unwrap();
+		assert_eq!(value, serde_json::json!({ "type": "file-skipped", "path": "/docs/report.pdf", "reason": "too large" }));
+		assert_eq!(serde_json::to_value(IndexEvent::Done).unwrap(), serde_json::json!({ "type": "done" }));
+	}
+}
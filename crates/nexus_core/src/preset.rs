@@ -0,0 +1,153 @@
+//! Named tuning presets for indexing.
+//!
+//! A preset only sets the options it lists; everything else keeps its current
+//! value. Apply the preset first and explicit settings afterwards so they win.
+//!
+//! | preset     | chunk_size | threads | embed_batch_size | max_image_dimension | max_chunks_per_file | skip_extensions |
+//! |------------|------------|---------|------------------|---------------------|---------------------|-----------------|
+//! | `cpu`      | 1000       | 1       | 8                | 1000                | -                   | image formats   |
+//! | `balanced` | -          | -       | -                | -                   | -                   | -               |
+//! | `quality`  | 3000       | -       | -                | 4000                | 2000                | -               |
+
+use anyhow::Result;
+
+use crate::IndexOptions;
+
+/// Image extensions skipped by presets that avoid OCR.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp", "tiff", "tif"];
+
+/// A named set of `IndexOptions` overrides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexPreset {
+    pub name: &'static str,
+    pub chunk_size: Option<usize>,
+    pub threads: Option<usize>,
+    pub embed_batch_size: Option<usize>,
+    pub max_image_dimension: Option<u32>,
+    pub max_chunks_per_file: Option<usize>,
+    /// Extensions added to `skip_extensions`.
+    pub skip_extensions: &'static [&'static str],
+}
+
+impl IndexPreset {
+    /// Low resource usage: one extraction thread, small embedding batches, no OCR.
+    pub const CPU: IndexPreset = IndexPreset {
+        name: "cpu",
+        chunk_size: Some(1000),
+        threads: Some(1),
+        embed_batch_size: Some(8),
+        max_image_dimension: Some(1000),
+        max_chunks_per_file: None,
+        skip_extensions: IMAGE_EXTENSIONS,
+    };
+
+    /// The defaults.
+    pub const BALANCED: IndexPreset = IndexPreset {
+        name: "balanced",
+        chunk_size: None,
+        threads: None,
+        embed_batch_size: None,
+        max_image_dimension: None,
+        max_chunks_per_file: None,
+        skip_extensions: &[],
+    };
+
+    /// Full coverage: larger chunks, high-resolution OCR, and a higher chunk limit per file.
+    pub const QUALITY: IndexPreset = IndexPreset {
+        name: "quality",
+        chunk_size: Some(3000),
+        threads: None,
+        embed_batch_size: None,
+        max_image_dimension: Some(4000),
+        max_chunks_per_file: Some(2000),
+        skip_extensions: &[],
+    };
+
+    /// All available presets.
+    pub const ALL: &'static [IndexPreset] = &[Self::CPU, Self::BALANCED, Self::QUALITY];
+
+    /// Look up a preset by name (case-insensitive).
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|p| p.name).collect();
+                anyhow::anyhow!("Unknown preset '{}' (expected one of: {})", name, names.join(", "))
+            })
+    }
+
+    /// Overwrite the options this preset sets.
+    pub fn apply_to_options(&self, options: &mut IndexOptions) {
+        if let Some(v) = self.chunk_size {
+            options.chunk_size = v;
+        }
+        if let Some(v) = self.threads {
+            options.threads = v;
+        }
+        if let Some(v) = self.embed_batch_size {
+            options.embed_batch_size = v;
+        }
+        if let Some(v) = self.max_image_dimension {
+            options.extractor.max_image_dimension = v;
+        }
+        if let Some(v) = self.max_chunks_per_file {
+            options.max_chunks_per_file = v;
+        }
+        for ext in self.skip_extensions {
+            if !options.skip_extensions.iter().any(|s| s.eq_ignore_ascii_case(ext)) {
+                options.skip_extensions.push(ext.to_string());
+            }
+        }
+    }
+}
+
+impl Default for IndexPreset {
+    fn default() -> Self {
+        Self::BALANCED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_preset() {
+        let mut options = IndexOptions::default();
+        IndexPreset::from_name("cpu").unwrap().apply_to_options(&mut options);
+        assert!(options.extractor.max_image_dimension < IndexOptions::default().extractor.max_image_dimension);
+        assert_eq!(options.threads, 1);
+        for ext in ["png", "jpg", "jpeg"] {
+            assert!(options.skip_extensions.contains(&ext.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_balanced_preset_keeps_defaults() {
+        let mut options = IndexOptions::default();
+        IndexPreset::default().apply_to_options(&mut options);
+        let defaults = IndexOptions::default();
+        assert_eq!(options.chunk_size, defaults.chunk_size);
+        assert_eq!(options.extractor.max_image_dimension, defaults.extractor.max_image_dimension);
+        assert!(options.skip_extensions.is_empty());
+    }
+
+    #[test]
+    fn test_skip_extensions_not_duplicated() {
+        let mut options = IndexOptions {
+            skip_extensions: vec!["PNG".to_string()],
+            ..Default::default()
+        };
+        IndexPreset::CPU.apply_to_options(&mut options);
+        assert_eq!(options.skip_extensions.iter().filter(|e| e.eq_ignore_ascii_case("png")).count(), 1);
+        assert!(options.skip_extensions.contains(&"tiff".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_preset() {
+        assert!(IndexPreset::from_name("turbo").is_err());
+        assert_eq!(IndexPreset::from_name("Quality").unwrap(), IndexPreset::QUALITY);
+    }
+}
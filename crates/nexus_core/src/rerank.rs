@@ -0,0 +1,139 @@
+//! Re-ranking of search results.
+//!
+//! Hybrid search often returns several chunks of the same file that are all
+//! relevant but say the same thing. Maximal Marginal Relevance (MMR) trades a
+//! little relevance for variety: each pick maximises
+//! `lambda * relevance - (1 - lambda) * max_similarity_to_selected`.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A search result as seen by the re-rankers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredResult {
+    pub doc_id: String,
+    /// Relevance, higher is better. Only the order and spread matter.
+    pub score: f32,
+}
+
+/// How search results are re-ranked after retrieval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RerankStrategy {
+    /// Maximal Marginal Relevance, see [`mmr_rerank`].
+    Mmr,
+}
+
+impl std::str::FromStr for RerankStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "mmr" => Ok(RerankStrategy::Mmr),
+            _ => anyhow::bail!("Unknown rerank strategy '{}' (expected mmr)", s),
+        }
+    }
+}
+
+/// Pick up to `top_k` of `results` with Maximal Marginal Relevance.
+///
+/// `embeddings[i]` is the vector of `results[i]`; a missing or empty vector
+/// counts as unlike every other result. Scores are min-max normalised first so
+/// `lambda` means the same for any scoring scheme: 1.0 keeps the relevance
+/// order, 0.0 only looks at diversity. The returned results keep their scores.
+pub fn mmr_rerank(results: Vec<ScoredResult>, embeddings: &[Vec<f32>], lambda: f32, top_k: usize) -> Vec<ScoredResult> {
+    let lambda = lambda.clamp(0.0, 1.0);
+    let (min, max) = results
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), r| (lo.min(r.score), hi.max(r.score)));
+    let relevance: Vec<f32> = results
+        .iter()
+        .map(|r| if max > min { (r.score - min) / (max - min) } else { 1.0 })
+        .collect();
+    let vector = |i: usize| embeddings.get(i).filter(|v| !v.is_empty());
+
+    let mut remaining: Vec<usize> = (0..results.len()).collect();
+    let mut selected: Vec<usize> = Vec::new();
+    while selected.len() < top_k && !remaining.is_empty() {
+        let mut best = 0;
+        let mut best_value = f32::NEG_INFINITY;
+        for (pos, &i) in remaining.iter().enumerate() {
+            let redundancy = match vector(i) {
+                Some(v) => selected
+                    .iter()
+                    .filter_map(|&j| vector(j))
+                    .map(|w| cosine_similarity(v, w))
+                    .fold(0.0, f32::max),
+                None => 0.0,
+            };
+            let value = lambda * relevance[i] - (1.0 - lambda) * redundancy;
+            // Strictly greater, so ties keep the original order
+            if value > best_value {
+                best = pos;
+                best_value = value;
+            }
+        }
+        selected.push(remaining.remove(best));
+    }
+
+    let mut results: Vec<Option<ScoredResult>> = results.into_iter().map(Some).collect();
+    selected.into_iter().filter_map(|i| results[i].take()).collect()
+}
+
+/// Cosine similarity of two vectors, 0 if either is all zeros.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(scores: &[f32]) -> Vec<ScoredResult> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| ScoredResult { doc_id: format!("doc{}", i), score })
+            .collect()
+    }
+
+    fn ids(results: &[ScoredResult]) -> Vec<&str> {
+        results.iter().map(|r| r.doc_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lambda_one_keeps_relevance_order() {
+        let embeddings = vec![vec![1.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]];
+        let reranked = mmr_rerank(results(&[0.9, 0.8, 0.1]), &embeddings, 1.0, 3);
+        assert_eq!(ids(&reranked), vec!["doc0", "doc1", "doc2"]);
+        assert_eq!(reranked[1].score, 0.8);
+    }
+
+    #[test]
+    fn test_near_duplicates_pushed_down() {
+        // doc1 repeats doc0, doc2 is less relevant but says something else
+        let embeddings = vec![vec![1.0, 0.0], vec![0.99, 0.01], vec![0.0, 1.0]];
+        let reranked = mmr_rerank(results(&[0.9, 0.85, 0.5]), &embeddings, 0.5, 2);
+        assert_eq!(ids(&reranked), vec!["doc0", "doc2"]);
+    }
+
+    #[test]
+    fn test_missing_embeddings_and_top_k() {
+        let reranked = mmr_rerank(results(&[0.2, 0.7, 0.4]), &[], 0.3, 5);
+        assert_eq!(ids(&reranked), vec!["doc1", "doc2", "doc0"]);
+        assert!(mmr_rerank(results(&[0.2]), &[], 0.5, 0).is_empty());
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!("MMR".parse::<RerankStrategy>().unwrap(), RerankStrategy::Mmr);
+        assert!("cross-encoder".parse::<RerankStrategy>().is_err());
+    }
+}
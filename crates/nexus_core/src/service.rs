@@ -5,11 +5,92 @@
 //! - macOS: launchd plist
 //! - Windows: Startup folder shortcut (via PowerShell)
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::env;
 use anyhow::Result;
 
+/// Structured service state, gathered without shelling out where possible.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServiceStatus {
+    /// Service definition (unit file, plist, startup script) is present.
+    pub installed: bool,
+    /// A watcher process is currently running.
+    pub running: bool,
+    /// PID of the running watcher, if known.
+    pub pid: Option<u32>,
+    /// Service is set to start automatically.
+    pub enabled: bool,
+}
+
+impl fmt::Display for ServiceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        writeln!(f, "installed: {}", yes_no(self.installed))?;
+        writeln!(f, "enabled: {}", yes_no(self.enabled))?;
+        match self.pid {
+            Some(pid) => write!(f, "running: yes (pid {})", pid),
+            None => write!(f, "running: {}", yes_no(self.running)),
+        }
+    }
+}
+
+/// Parse the `key=value` entries of one `[section]` of an INI-style unit file.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_unit_section(content: &str, section: &str) -> HashMap<String, String> {
+    let header = format!("[{}]", section);
+    let mut in_section = false;
+    let mut entries = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == header;
+            continue;
+        }
+        if !in_section || line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            entries.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    entries
+}
+
+/// Keep the last `lines` log lines, dropping blanks and journalctl markers like `-- No entries --`.
+#[cfg_attr(not(any(target_os = "linux", target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn parse_log_lines(output: &str, lines: usize) -> Vec<String> {
+    let entries: Vec<&str> = output
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("-- "))
+        .collect();
+    let skip = entries.len().saturating_sub(lines);
+    entries[skip..].iter().map(|l| l.to_string()).collect()
+}
+
+/// Find a running `<binary> watch` process other than this one.
+fn find_watch_process(binary: &Path) -> Option<u32> {
+    use sysinfo::{ProcessesToUpdate, System};
+
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    let own_pid = std::process::id();
+    let binary_name = binary.file_name()?;
+
+    sys.processes().iter()
+        .filter(|(pid, _)| pid.as_u32() != own_pid)
+        .find(|(_, process)| {
+            let exe_matches = process.exe()
+                .map(|exe| exe == binary || exe.file_name() == Some(binary_name))
+                .unwrap_or(false);
+            exe_matches && process.cmd().iter().any(|arg| arg == "watch")
+        })
+        .map(|(pid, _)| pid.as_u32())
+}
+
 /// Service manager for the current platform.
 pub struct ServiceManager {
     binary_path: PathBuf,
@@ -58,7 +139,7 @@ impl ServiceManager {
     }
 
     /// Get the service status.
-    pub fn status(&self) -> Result<String> {
+    pub fn status(&self) -> Result<ServiceStatus> {
         #[cfg(target_os = "linux")]
         return self.status_linux();
 
@@ -72,6 +153,22 @@ impl ServiceManager {
         anyhow::bail!("Service status not supported on this platform");
     }
 
+    /// Get the last `lines` lines of the service log, oldest first.
+    /// Returns an empty list if the service has not logged anything yet.
+    pub fn logs(&self, lines: usize) -> Result<Vec<String>> {
+        #[cfg(target_os = "linux")]
+        return self.logs_linux(lines);
+
+        #[cfg(target_os = "macos")]
+        return self.logs_macos(lines);
+
+        #[cfg(target_os = "windows")]
+        return self.logs_windows(lines);
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+        anyhow::bail!("Service logs not supported on this platform");
+    }
+
     // ========== Linux (systemd) ==========
 
     #[cfg(target_os = "linux")]
@@ -159,12 +256,67 @@ To view logs:
     }
 
     #[cfg(target_os = "linux")]
-    fn status_linux(&self) -> Result<String> {
-        let output = std::process::Command::new("systemctl")
-            .args(["--user", "status", "nexus"])
-            .output()?;
+    fn status_linux(&self) -> Result<ServiceStatus> {
+        let service_path = self.systemd_service_path();
+        let content = match fs::read_to_string(&service_path) {
+            Ok(c) => c,
+            Err(_) => return Ok(ServiceStatus::default()),
+        };
+
+        // Match running processes against the binary the unit actually starts
+        let service = parse_unit_section(&content, "Service");
+        let binary = service.get("ExecStart")
+            .and_then(|cmd| cmd.split_whitespace().next())
+            .map(PathBuf::from);
+        let installed = binary.is_some();
+
+        let enabled = service_path.parent()
+            .map(|dir| dir.join("default.target.wants/nexus.service"))
+            .map(|link| link.symlink_metadata().is_ok())
+            .unwrap_or(false);
+
+        let pid = Self::systemd_cgroup_pid()
+            .or_else(|| find_watch_process(binary.as_deref().unwrap_or(&self.binary_path)));
+
+        Ok(ServiceStatus {
+            installed,
+            running: pid.is_some(),
+            pid,
+            enabled,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn logs_linux(&self, lines: usize) -> Result<Vec<String>> {
+        let output = std::process::Command::new("journalctl")
+            .args(["--user", "-u", "nexus", "-n", &lines.to_string(), "--no-pager", "-o", "short"])
+            .output()
+            .map_err(|e| anyhow::anyhow!("Failed to run journalctl: {}", e))?;
+        if !output.status.success() {
+            anyhow::bail!("journalctl failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(parse_log_lines(&String::from_utf8_lossy(&output.stdout), lines))
+    }
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    /// Read the main PID from the unit's cgroup, if systemd placed it in one.
+    #[cfg(target_os = "linux")]
+    fn systemd_cgroup_pid() -> Option<u32> {
+        let status = fs::read_to_string("/proc/self/status").ok()?;
+        let uid = status.lines()
+            .find_map(|l| l.strip_prefix("Uid:"))?
+            .split_whitespace()
+            .next()?;
+
+        let user_service = PathBuf::from(format!(
+            "/sys/fs/cgroup/user.slice/user-{uid}.slice/user@{uid}.service"
+        ));
+        [
+            user_service.join("app.slice/nexus.service/cgroup.procs"),
+            user_service.join("nexus.service/cgroup.procs"),
+        ]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|procs| procs.lines().next().and_then(|l| l.trim().parse().ok()))
     }
 
     // ========== macOS (launchd) ==========
@@ -253,18 +405,41 @@ To view logs:
     }
 
     #[cfg(target_os = "macos")]
-    fn status_macos(&self) -> Result<String> {
+    fn status_macos(&self) -> Result<ServiceStatus> {
+        let installed = self.launchd_plist_path().exists();
+
+        // `launchctl list` prints "PID\tStatus\tLabel", with "-" for stopped jobs
         let output = std::process::Command::new("launchctl")
             .args(["list"])
             .output()?;
-
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let nexus_line = stdout
+        let job = stdout
             .lines()
-            .find(|l| l.contains("nexus"))
-            .unwrap_or("Nexus service not loaded");
+            .map(|l| l.split_whitespace().collect::<Vec<_>>())
+            .find(|cols| cols.len() == 3 && cols[2] == "com.nexus.watch");
+
+        let pid = job.as_ref()
+            .and_then(|cols| cols[0].parse().ok())
+            .or_else(|| find_watch_process(&self.binary_path));
+        Ok(ServiceStatus {
+            installed,
+            running: pid.is_some(),
+            pid,
+            enabled: job.is_some(),
+        })
+    }
 
-        Ok(nexus_line.to_string())
+    #[cfg(target_os = "macos")]
+    fn logs_macos(&self, lines: usize) -> Result<Vec<String>> {
+        let log_path = Path::new("/tmp/nexus.log");
+        if !log_path.exists() {
+            return Ok(Vec::new());
+        }
+        let output = std::process::Command::new("tail")
+            .args(["-n", &lines.to_string()])
+            .arg(log_path)
+            .output()?;
+        Ok(parse_log_lines(&String::from_utf8_lossy(&output.stdout), lines))
     }
 
     // ========== Windows (Startup folder) ==========
@@ -280,12 +455,18 @@ To view logs:
     fn generate_startup_batch(&self) -> String {
         format!(
             r#"@echo off
-start /min "" "{}" watch
+start /min "" cmd /c ""{}" watch >> "{}" 2>&1"
 "#,
-            self.binary_path.display()
+            self.binary_path.display(),
+            Self::windows_log_path().display()
         )
     }
 
+    #[cfg(target_os = "windows")]
+    fn windows_log_path() -> PathBuf {
+        env::temp_dir().join("nexus.log")
+    }
+
     #[cfg(target_os = "windows")]
     fn install_windows(&self) -> Result<String> {
         let shortcut_path = self.startup_shortcut_path();
@@ -323,13 +504,75 @@ To start now, run:
     }
 
     #[cfg(target_os = "windows")]
-    fn status_windows(&self) -> Result<String> {
-        let shortcut_path = self.startup_shortcut_path();
+    fn status_windows(&self) -> Result<ServiceStatus> {
+        let script_installed = self.startup_shortcut_path().exists();
+
+        // Also honour a per-user Run key, which some setups use instead of the Startup folder
+        let run_key = winreg::RegKey::predef(winreg::enums::HKEY_CURRENT_USER)
+            .open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Run")
+            .and_then(|key| key.get_value::<String, _>("nexus"))
+            .is_ok();
+
+        let pid = find_watch_process(&self.binary_path);
+        Ok(ServiceStatus {
+            installed: script_installed || run_key,
+            running: pid.is_some(),
+            pid,
+            enabled: script_installed || run_key,
+        })
+    }
 
-        if shortcut_path.exists() {
-            Ok(format!("Nexus startup script installed at: {}", shortcut_path.display()))
-        } else {
-            Ok("Nexus startup script not installed".to_string())
+    #[cfg(target_os = "windows")]
+    fn logs_windows(&self, lines: usize) -> Result<Vec<String>> {
+        match fs::read(Self::windows_log_path()) {
+            Ok(bytes) => Ok(parse_log_lines(&String::from_utf8_lossy(&bytes), lines)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unit_section() {
+        let unit = "[Unit]\nDescription=Nexus\n\n[Service]\nType=simple\nExecStart=/usr/bin/nexus watch\n# comment\n\n[Install]\nWantedBy=default.target\n";
+        let service = parse_unit_section(unit, "Service");
+        assert_eq!(service.get("ExecStart").map(String::as_str), Some("/usr/bin/nexus watch"));
+        assert_eq!(service.get("Type").map(String::as_str), Some("simple"));
+        assert!(!service.contains_key("Description"));
+        assert!(!service.contains_key("WantedBy"));
+    }
+
+    #[test]
+    fn test_status_display() {
+        let status = ServiceStatus { installed: true, running: true, pid: Some(42), enabled: false };
+        let text = status.to_string();
+        assert!(text.contains("installed: yes"));
+        assert!(text.contains("enabled: no"));
+        assert!(text.contains("pid 42"));
+    }
+
+    #[test]
+    fn test_parse_log_lines() {
+        let output = "-- Logs begin at Mon 2024-05-06 --\nMay 06 10:00:01 host nexus[1]: watching\n\nMay 06 10:00:02 host nexus[1]: indexed a.txt\nMay 06 10:00:03 host nexus[1]: indexed b.txt\n";
+        assert_eq!(parse_log_lines(output, 2), vec![
+            "May 06 10:00:02 host nexus[1]: indexed a.txt",
+            "May 06 10:00:03 host nexus[1]: indexed b.txt",
+        ]);
+        assert_eq!(parse_log_lines(output, 10).len(), 3);
+        assert!(parse_log_lines("-- No entries --\n", 10).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_logs_without_service() {
+        // journalctl may be missing or have no user journal; either way this must not panic
+        let manager = ServiceManager::with_binary(PathBuf::from("/nonexistent/nexus"));
+        if let Ok(lines) = manager.logs(5) {
+            assert!(lines.len() <= 5);
         }
     }
 }
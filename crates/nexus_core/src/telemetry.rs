@@ -0,0 +1,43 @@
+//! Tracing spans for indexing, and an optional subscriber to print them.
+//!
+//! The indexer always emits `tracing` spans (`run_with_progress`, `extract_text_sync`,
+//! `embed_batch`, `add_embeddings_batch`). They cost next to nothing unless a
+//! subscriber is installed. With the `tracing-subscriber` feature, `init_tracing`
+//! installs one that prints span timings to stderr when `NEXUS_LOG` is set, using
+//! `EnvFilter` syntax, e.g. `NEXUS_LOG=nexus_core=trace`.
+
+/// Environment variable holding the `EnvFilter` directives.
+pub const LOG_ENV: &str = "NEXUS_LOG";
+
+#[cfg(feature = "tracing-subscriber")]
+use tracing_subscriber::fmt::{format::FmtSpan, MakeWriter};
+
+/// Print span timings to stderr if `NEXUS_LOG` is set.
+/// Returns whether a subscriber was installed.
+#[cfg(feature = "tracing-subscriber")]
+pub fn init_tracing() -> anyhow::Result<bool> {
+    init_tracing_with_writer(std::io::stderr)
+}
+
+/// Like `init_tracing`, but writes to `make_writer` instead of stderr.
+#[cfg(feature = "tracing-subscriber")]
+pub fn init_tracing_with_writer<W>(make_writer: W) -> anyhow::Result<bool>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let directives = match std::env::var(LOG_ENV) {
+        Ok(d) if !d.trim().is_empty() => d,
+        _ => return Ok(false),
+    };
+    let filter = tracing_subscriber::EnvFilter::try_new(&directives)
+        .map_err(|e| anyhow::anyhow!("Invalid {}='{}': {}", LOG_ENV, directives, e))?;
+
+    // Fails only if another global subscriber is already installed
+    let installed = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(make_writer)
+        .try_init()
+        .is_ok();
+    Ok(installed)
+}
@@ -2,21 +2,23 @@
 //!
 //! Uses the `notify` crate to watch directories for file system events.
 //! Changes are debounced to avoid re-indexing on every keystroke.
+//! Events arrive over a Tokio channel, so waiting for them doesn't tie up a thread.
 
 use std::path::PathBuf;
-use std::sync::mpsc::{channel, Receiver};
 use std::time::Duration;
 use std::collections::HashSet;
 
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use anyhow::Result;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
 use crate::config::WatchConfig;
+use crate::IndexEvent;
 
 /// File watcher that monitors directories for changes.
 pub struct FileWatcher {
     watcher: RecommendedWatcher,
-    receiver: Receiver<Result<Event, notify::Error>>,
+    receiver: UnboundedReceiver<Result<Event, notify::Error>>,
     config: WatchConfig,
     watched_roots: Vec<PathBuf>,
 }
@@ -28,12 +30,14 @@ pub struct ChangeBatch {
     pub modified: Vec<PathBuf>,
     /// Files that were deleted.
     pub deleted: Vec<PathBuf>,
+    /// Watcher events raised while collecting the batch, e.g. `WatchPathAdded`.
+    pub events: Vec<IndexEvent>,
 }
 
 impl FileWatcher {
     /// Create a new file watcher with the given configuration.
     pub fn new(config: WatchConfig) -> Result<Self> {
-        let (tx, rx) = channel();
+        let (tx, rx) = unbounded_channel();
         
         let watcher = RecommendedWatcher::new(
             move |res| {
@@ -66,27 +70,29 @@ impl FileWatcher {
     }
 
     /// Wait for file changes and return a debounced batch.
-    /// Blocks until changes are detected, then waits for `debounce_secs` of quiet.
-    pub fn wait_for_changes(&self) -> Result<ChangeBatch> {
+    /// Resolves once changes are detected and `debounce_secs` of quiet have followed.
+    pub async fn wait_for_changes(&mut self) -> Result<ChangeBatch> {
         let mut modified = HashSet::new();
         let mut deleted = HashSet::new();
+        let mut events = Vec::new();
         
         // Wait for first event
-        let first_event = self.receiver.recv()?;
-        self.process_event(first_event, &mut modified, &mut deleted);
+        let first_event = self.receiver.recv().await
+            .ok_or_else(|| anyhow::anyhow!("Watcher channel disconnected"))?;
+        self.process_event(first_event, &mut modified, &mut deleted, &mut events);
         
         // Debounce: collect all events within the debounce window
         let debounce = Duration::from_secs(self.config.debounce_secs);
         loop {
-            match self.receiver.recv_timeout(debounce) {
-                Ok(event) => {
-                    self.process_event(event, &mut modified, &mut deleted);
+            match tokio::time::timeout(debounce, self.receiver.recv()).await {
+                Ok(Some(event)) => {
+                    self.process_event(event, &mut modified, &mut deleted, &mut events);
                 }
-                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                Err(_) => {
                     // Debounce period elapsed, return the batch
                     break;
                 }
-                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                Ok(None) => {
                     anyhow::bail!("Watcher channel disconnected");
                 }
             }
@@ -100,15 +106,17 @@ impl FileWatcher {
         Ok(ChangeBatch {
             modified: modified.into_iter().collect(),
             deleted: deleted.into_iter().collect(),
+            events,
         })
     }
 
     /// Process a single event into modified/deleted sets.
     fn process_event(
-        &self,
+        &mut self,
         event: Result<Event, notify::Error>,
         modified: &mut HashSet<PathBuf>,
         deleted: &mut HashSet<PathBuf>,
+        events: &mut Vec<IndexEvent>,
     ) {
         let event = match event {
             Ok(e) => e,
@@ -118,9 +126,16 @@ impl FileWatcher {
             }
         };
         
+        use notify::EventKind;
+        use notify::event::CreateKind;
+
         for path in event.paths {
-            // Skip directories
+            // Skip directories, but start watching new ones
             if path.is_dir() {
+                let created = matches!(event.kind, EventKind::Create(CreateKind::Folder | CreateKind::Any));
+                if created && self.config.auto_watch_new_dirs && !self.should_ignore(&path) {
+                    self.watch_new_dir(&path, modified, events);
+                }
                 continue;
             }
             
@@ -129,7 +144,6 @@ impl FileWatcher {
                 continue;
             }
             
-            use notify::EventKind;
             match event.kind {
                 EventKind::Create(_) | EventKind::Modify(_) => {
                     modified.insert(path);
@@ -142,6 +156,21 @@ impl FileWatcher {
         }
     }
 
+    /// Watch a directory created after startup.
+    /// Files written to it before the watch was registered are added to `modified`.
+    fn watch_new_dir(&mut self, dir: &PathBuf, modified: &mut HashSet<PathBuf>, events: &mut Vec<IndexEvent>) {
+        if let Err(e) = self.watcher.watch(dir, RecursiveMode::Recursive) {
+            eprintln!("  watch error: {}: {:?}", dir.display(), e);
+            return;
+        }
+        for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() && !self.should_ignore(&entry.path().to_path_buf()) {
+                modified.insert(entry.into_path());
+            }
+        }
+        events.push(IndexEvent::WatchPathAdded(dir.clone()));
+    }
+
     /// Check if a path matches any ignore pattern.
     fn should_ignore(&self, path: &PathBuf) -> bool {
         let filename = path.file_name()
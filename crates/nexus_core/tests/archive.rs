@@ -0,0 +1,146 @@
+use nexus_core::{IndexOptions, Indexer, LexicalIndex, SearchMode, InMemoryVectorStore, StateManager, VectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+fn write_tar_gz(path: &PathBuf, name: &str, contents: &[u8]) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, contents)?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tar_gz_contents_are_searchable() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let archive_path = docs.path().join("notes.tar.gz");
+    write_tar_gz(&archive_path, "inner.txt", b"the quick zebrafish swims")?;
+
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        follow_archive_contents: true,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()))
+        .with_lexical(lexical.clone());
+    let result = indexer.run().await?;
+    assert!(result.errors.is_empty());
+    assert_eq!(result.files_indexed, 1);
+
+    let hits = lexical.search("zebrafish", 10, SearchMode::Auto)?;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].file_path, format!("{}!inner.txt", archive_path.display()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archives_ignored_by_default() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    write_tar_gz(&docs.path().join("notes.tar.gz"), "inner.txt", b"the quick zebrafish swims")?;
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(docs.path().join("notes.zip"))?);
+    zip.start_file("inner.txt", zip::write::SimpleFileOptions::default())?;
+    std::io::Write::write_all(&mut zip, b"the quick zebrafish swims")?;
+    zip.finish()?;
+
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()))
+        .with_lexical(lexical.clone());
+    let result = indexer.run().await?;
+    assert_eq!(result.files_indexed, 0);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(lexical.search("zebrafish", 10, SearchMode::Auto)?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_garbage_collect_removes_archive_entries() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let edited = docs.path().join("edited.tar.gz");
+    let removed = docs.path().join("removed.tar.gz");
+    write_tar_gz(&edited, "inner.txt", b"the quick zebrafish swims")?;
+    write_tar_gz(&removed, "inner.txt", b"a lazy axolotl naps")?;
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let state = Arc::new(StateManager::new(data.path())?);
+    let lexical = Arc::new(LexicalIndex::new(data.path().join("lexical"))?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        follow_archive_contents: true,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone())
+        .with_lexical(lexical.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 2);
+    assert_eq!(store.count().await, 2);
+
+    write_tar_gz(&edited, "inner.txt", b"the quick zebrafish dives")?;
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    std::fs::File::options().write(true).open(&edited)?.set_modified(later)?;
+    std::fs::remove_file(&removed)?;
+
+    let gc = indexer.garbage_collect().await?;
+    assert_eq!(gc.modified_files, 1);
+    assert_eq!(gc.deleted_files, 1);
+    assert_eq!(gc.embeddings_removed, 2);
+    assert_eq!(store.count().await, 0);
+    assert!(lexical.search("zebrafish", 10, SearchMode::Auto)?.is_empty());
+    assert!(lexical.search("axolotl", 10, SearchMode::Auto)?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_existing_reindexes_modified_archive() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let archive_path = docs.path().join("notes.tar.gz");
+    write_tar_gz(&archive_path, "inner.txt", b"the quick zebrafish swims")?;
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let state = Arc::new(StateManager::new(data.path())?);
+    let lexical = Arc::new(LexicalIndex::new(data.path().join("lexical"))?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        follow_archive_contents: true,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options.clone(), TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone())
+        .with_lexical(lexical.clone());
+    indexer.run().await?;
+
+    write_tar_gz(&archive_path, "inner.txt", b"a lazy axolotl naps")?;
+    let options = IndexOptions { replace_existing: true, ..options };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone())
+        .with_lexical(lexical.clone());
+    let result = indexer.run().await?;
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    // Only the new entry is left, and it is tracked against the archive itself
+    assert_eq!(store.count().await, 1);
+    assert!(lexical.search("zebrafish", 10, SearchMode::Auto)?.is_empty());
+    let hits = lexical.search("axolotl", 10, SearchMode::Auto)?;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(state.get_doc_ids(&archive_path)?, vec![hits[0].doc_id.clone()]);
+    let entry_path = PathBuf::from(format!("{}!inner.txt", archive_path.display()));
+    assert!(state.get_doc_ids(&entry_path)?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use nexus_core::{IndexOptions, Indexer, VectorStore, StateManager};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder, InstrumentedStore};
+
+/// Index four one-chunk notes, delete three and garbage-collect them.
+async fn index_then_delete(threshold: Option<usize>) -> Result<usize> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    for i in 0..4 {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        auto_compact_threshold: threshold,
+        ..Default::default()
+    };
+    let store = Arc::new(InstrumentedStore::default());
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(Arc::new(StateManager::new(data.path())?));
+    assert_eq!(indexer.run().await?.files_indexed, 4);
+    assert_eq!(store.compactions.load(Ordering::SeqCst), 0);
+
+    for i in 0..3 {
+        std::fs::remove_file(docs.path().join(format!("note{}.txt", i)))?;
+    }
+    assert_eq!(indexer.garbage_collect().await?.embeddings_removed, 3);
+    indexer.run().await?;
+    // The count starts over after a compaction
+    indexer.run().await?;
+    assert_eq!(store.count().await, 1);
+    Ok(store.compactions.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn test_compacts_after_threshold_deletions() -> Result<()> {
+    assert_eq!(index_then_delete(Some(2)).await?, 1);
+    assert_eq!(index_then_delete(Some(3)).await?, 0);
+    assert_eq!(index_then_delete(None).await?, 0);
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use nexus_core::{ChunkStrategy, IndexEvent, IndexOptions, Indexer, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Index a single five-chunk file and collect every event.
+async fn collect_events(verbose_events: bool) -> Result<Vec<IndexEvent>> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("notes.txt"), "abcde")?;
+
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 1,
+        chunk_strategy: ChunkStrategy::Fixed,
+        embed_batch_size: 2,
+        verbose_events,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()));
+    let mut events = Vec::new();
+    indexer.run_with_progress(|e| events.push(e)).await?;
+    Ok(events)
+}
+
+#[tokio::test]
+async fn test_batch_embedded_once_per_batch() -> Result<()> {
+    let events = collect_events(false).await?;
+
+    let batches: Vec<(usize, usize)> = events.iter()
+        .filter_map(|e| match e {
+            IndexEvent::BatchEmbedded { chunk_count, tokens_estimated, .. } => Some((*chunk_count, *tokens_estimated)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(batches.iter().map(|b| b.0).collect::<Vec<_>>(), vec![2, 2, 1]);
+    assert!(batches.iter().all(|b| b.1 == 0), "single-char chunks estimate to zero tokens");
+    assert!(!events.iter().any(|e| matches!(e, IndexEvent::ChunkEmbedded(..))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verbose_events_include_chunk_embedded() -> Result<()> {
+    let events = collect_events(true).await?;
+
+    let chunk_events = events.iter().filter(|e| matches!(e, IndexEvent::ChunkEmbedded(..))).count();
+    let batch_events = events.iter().filter(|e| matches!(e, IndexEvent::BatchEmbedded { .. })).count();
+    assert_eq!(chunk_events, 5);
+    assert_eq!(batch_events, 3);
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use nexus_core::{IndexOptions, Indexer, IndexEvent, CancellationToken, InMemoryVectorStore};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, SlowEmbedder};
+
+#[tokio::test]
+async fn test_cancel_stops_run_early() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let total_files = 200;
+    for i in 0..total_files {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let token = CancellationToken::new();
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, TextExtractor, SlowEmbedder, store)
+        .with_cancellation(token.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+    });
+
+    let mut events = Vec::new();
+    let result = indexer.run_with_progress(|event| events.push(event)).await?;
+    assert!(result.files_indexed < total_files);
+    assert!(events.iter().any(|e| matches!(e, IndexEvent::IndexingPaused(reason) if reason == "cancelled")));
+    assert!(matches!(events.last(), Some(IndexEvent::Done)));
+    Ok(())
+}
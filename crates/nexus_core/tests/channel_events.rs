@@ -0,0 +1,68 @@
+use nexus_core::{IndexEvent, IndexOptions, Indexer, InMemoryVectorStore};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_run_with_channel_delivers_done() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    for i in 0..3 {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(256);
+
+    let task = tokio::spawn(async move {
+        let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store);
+        indexer.run_with_channel(tx).await
+    });
+
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    let result = task.await??;
+
+    assert_eq!(result.files_indexed, 3);
+    assert_eq!(events.iter().filter(|e| matches!(e, IndexEvent::FileIndexed(_))).count(), 3);
+    assert!(matches!(events.last(), Some(IndexEvent::Done)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_with_channel_waits_to_send_done() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    for i in 0..3 {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    // Fills up with the first event while nobody is reading
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+
+    let task = tokio::spawn(async move {
+        let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store);
+        indexer.run_with_channel(tx).await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    task.await??;
+
+    assert!(events.len() < 5, "progress events should have been dropped");
+    assert!(matches!(events.last(), Some(IndexEvent::Done)));
+    Ok(())
+}
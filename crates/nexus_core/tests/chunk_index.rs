@@ -0,0 +1,52 @@
+use nexus_core::{ChunkStrategy, IndexOptions, Indexer, SyncTextExtractor, ExtractedPage, PagedExtractor, InMemoryVectorStore};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Extractor that treats every `.pdf` as a two page document whose first page
+/// produces more than 1000 single-character chunks.
+struct BigPageExtractor;
+impl SyncTextExtractor for BigPageExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for BigPageExtractor {
+    fn extract_pages(&self, _path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok(vec![
+            ExtractedPage { page_num: 0, total_pages: 2, text: "a".repeat(1001) },
+            ExtractedPage { page_num: 1, total_pages: 2, text: "bb".to_string() },
+        ])
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf").unwrap_or(false)
+    }
+}
+
+#[tokio::test]
+async fn test_page_chunk_indices_do_not_collide() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("big.pdf"), "placeholder")?;
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 1,
+        chunk_strategy: ChunkStrategy::Fixed,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, BigPageExtractor, DummyEmbedder, store.clone());
+    let result = indexer.run().await?;
+    assert!(result.errors.is_empty());
+
+    let metadata = store.all_metadata().await;
+    assert_eq!(metadata.len(), 1003);
+    let indices: HashSet<usize> = metadata.iter().map(|m| m.chunk_index).collect();
+    assert_eq!(indices.len(), metadata.len(), "duplicate chunk_index values");
+    assert_eq!(indices.iter().max(), Some(&1002));
+    Ok(())
+}
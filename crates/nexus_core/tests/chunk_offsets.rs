@@ -0,0 +1,42 @@
+use nexus_core::{IndexOptions, Indexer, IndexEvent, InMemoryVectorStore, VectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_chunk_indexed_byte_offsets_match_source() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let path = docs.path().join("notes.txt");
+    let contents = "Ünïcode words repeated for chunking. ".repeat(40);
+    std::fs::write(&path, &contents)?;
+
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 100,
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone());
+    let mut ranges = Vec::new();
+    indexer.run_with_progress(|e| {
+        if let IndexEvent::ChunkIndexed { byte_start, byte_end, .. } = e {
+            ranges.push((byte_start, byte_end));
+        }
+    }).await?;
+
+    assert!(ranges.len() > 1);
+    for &(start, end) in &ranges {
+        let chunk = &contents[start..end];
+        assert!(!chunk.is_empty());
+        assert_eq!(chunk, chunk.trim());
+    }
+
+    // The stored rows carry the same ranges, so results can point back into the file
+    let mut stored = store.get_by_file_path(&path).await?;
+    stored.sort_by_key(|m| m.chunk_index);
+    let stored: Vec<_> = stored.iter().map(|m| (m.byte_start, m.byte_end)).collect();
+    assert_eq!(stored, ranges);
+    Ok(())
+}
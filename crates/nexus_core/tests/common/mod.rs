@@ -0,0 +1,98 @@
+//! Fixtures shared by the indexer integration tests. Each test binary uses only
+//! some of them.
+#![allow(dead_code)]
+
+use nexus_core::{DocumentMetadata, Embedder, ExtractedPage, InMemoryVectorStore, PagedExtractor, SearchOptions, SearchResult, SyncTextExtractor, VectorStore};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Reads every file as UTF-8 text; nothing is paged.
+pub struct TextExtractor;
+impl SyncTextExtractor for TextExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for TextExtractor {
+    fn extract_pages(&self, _path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok(vec![])
+    }
+    fn is_paged(&self, _path: &PathBuf) -> bool {
+        false
+    }
+}
+
+/// Embeds every text as the same three-dimensional vector.
+pub struct DummyEmbedder;
+#[async_trait]
+impl Embedder for DummyEmbedder {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.0, 1.0, 2.0])
+    }
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![0.0, 1.0, 2.0]).collect())
+    }
+    fn dimension(&self) -> usize { 3 }
+}
+
+/// `DummyEmbedder` taking 10ms per call, so a run over many files can be stopped partway.
+pub struct SlowEmbedder;
+#[async_trait]
+impl Embedder for SlowEmbedder {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(vec![0.0, 1.0, 2.0])
+    }
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(texts.iter().map(|_| vec![0.0, 1.0, 2.0]).collect())
+    }
+    fn dimension(&self) -> usize { 3 }
+}
+
+/// In-memory store that counts `compact` calls and, with `fail_saves`, fails every `save`.
+#[derive(Default)]
+pub struct InstrumentedStore {
+    pub inner: InMemoryVectorStore,
+    pub compactions: AtomicUsize,
+    pub fail_saves: bool,
+}
+#[async_trait]
+impl VectorStore for InstrumentedStore {
+    async fn add_embedding(&self, embedding: Vec<f32>, metadata: DocumentMetadata) -> Result<String> {
+        self.inner.add_embedding(embedding, metadata).await
+    }
+    async fn search(&self, query: Vec<f32>, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        self.inner.search(query, options).await
+    }
+    async fn get_metadata(&self, doc_id: &str) -> Result<Option<DocumentMetadata>> {
+        self.inner.get_metadata(doc_id).await
+    }
+    async fn get_by_file_path(&self, path: &Path) -> Result<Vec<DocumentMetadata>> {
+        self.inner.get_by_file_path(path).await
+    }
+    async fn delete_by_doc_ids(&self, doc_ids: &[String]) -> Result<usize> {
+        self.inner.delete_by_doc_ids(doc_ids).await
+    }
+    async fn delete_by_file_path(&self, path: &Path) -> Result<usize> {
+        self.inner.delete_by_file_path(path).await
+    }
+    async fn deduplicate(&self, similarity_threshold: f32) -> Result<Vec<String>> {
+        self.inner.deduplicate(similarity_threshold).await
+    }
+    async fn reindex_file(&self, file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        self.inner.reindex_file(file_path, new_embeddings, metadata).await
+    }
+    async fn save(&self) -> Result<()> {
+        anyhow::ensure!(!self.fail_saves, "disk full");
+        Ok(())
+    }
+    async fn count(&self) -> usize { self.inner.count().await }
+    async fn compact(&self) -> Result<()> {
+        self.compactions.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
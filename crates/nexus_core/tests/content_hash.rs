@@ -0,0 +1,88 @@
+use nexus_core::{ChangeDetection, IndexOptions, Indexer, StateManager, InMemoryVectorStore};
+use std::path::Path;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Index `docs` twice, bumping the file's mtime in between without changing its content.
+async fn reindex_after_touch(docs: &Path, data: &Path, change_detection: ChangeDetection) -> Result<usize> {
+    reindex_after(docs, data, change_detection, |file| {
+        let later = std::fs::metadata(file)?.modified()? + std::time::Duration::from_secs(60);
+        std::fs::File::options().write(true).open(file)?.set_modified(later)?;
+        Ok(())
+    }).await
+}
+
+/// Index `docs` twice, editing the file in between but restoring its mtime.
+async fn reindex_after_silent_edit(docs: &Path, data: &Path, change_detection: ChangeDetection) -> Result<usize> {
+    reindex_after(docs, data, change_detection, |file| {
+        let mtime = std::fs::metadata(file)?.modified()?;
+        std::fs::write(file, "content that quietly changed")?;
+        std::fs::File::options().write(true).open(file)?.set_modified(mtime)?;
+        Ok(())
+    }).await
+}
+
+/// Index `docs` twice with `change` applied to its only file in between;
+/// returns the number of files the second run indexed.
+async fn reindex_after(docs: &Path, data: &Path, change_detection: ChangeDetection, change: impl Fn(&Path) -> Result<()>) -> Result<usize> {
+    let file = docs.join("notes.txt");
+    std::fs::write(&file, "content that does not change")?;
+
+    let state = Arc::new(StateManager::new(data)?);
+    let options = IndexOptions {
+        root: docs.to_path_buf(),
+        change_detection,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options.clone(), TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()))
+        .with_state(state.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 1);
+
+    change(&file)?;
+
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()))
+        .with_state(state);
+    Ok(indexer.run().await?.files_indexed)
+}
+
+#[tokio::test]
+async fn test_touched_file_unchanged_with_content_hash() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    assert_eq!(reindex_after_touch(docs.path(), data.path(), ChangeDetection::Hash).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_touched_file_reindexed_without_content_hash() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    assert_eq!(reindex_after_touch(docs.path(), data.path(), ChangeDetection::Mtime).await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_touched_file_unchanged_with_mtime_then_hash() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    assert_eq!(reindex_after_touch(docs.path(), data.path(), ChangeDetection::MtimeThenHash).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_silent_edit_detected_by_hash() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    assert_eq!(reindex_after_silent_edit(docs.path(), data.path(), ChangeDetection::Hash).await?, 1);
+
+    // Neither mode that trusts an unchanged mtime notices the edit
+    for change_detection in [ChangeDetection::Mtime, ChangeDetection::MtimeThenHash] {
+        let docs = tempfile::tempdir()?;
+        let data = tempfile::tempdir()?;
+        assert_eq!(reindex_after_silent_edit(docs.path(), data.path(), change_detection).await?, 0);
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use nexus_core::{ChunkStrategy, IndexOptions, Indexer, LexicalIndex, SearchMode, SearchOptions, populate_context_chunks, SearchResult, VectorStore, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_context_window_returns_neighbouring_chunks() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("notes.txt"), "alpha bravo delta gamma omega")?;
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 6,
+        chunk_strategy: ChunkStrategy::Fixed,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_lexical(lexical.clone());
+    indexer.run().await?;
+
+    let hit = lexical.search("delta", 1, SearchMode::Auto)?.remove(0);
+    let metadata = store.get_metadata(&hit.doc_id).await?.unwrap();
+    assert_eq!(metadata.chunk_index, 2);
+    let mut results = vec![SearchResult {
+        doc_id: hit.doc_id,
+        score: hit.score,
+        snippet: metadata.snippet.clone(),
+        metadata,
+        context_chunks: Vec::new(),
+    }];
+
+    populate_context_chunks(&mut results, store.as_ref(), &lexical, &SearchOptions::default()).await?;
+    assert!(results[0].context_chunks.is_empty());
+
+    populate_context_chunks(&mut results, store.as_ref(), &lexical, &SearchOptions::default().with_context_window(1)).await?;
+    assert_eq!(results[0].context_chunks, vec!["bravo ".to_string(), "gamma ".to_string()]);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use nexus_core::{deduplicate_index, IndexOptions, Indexer, StateManager, LexicalIndex, SearchMode, VectorStore, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_delete_file_removes_everything_for_that_file() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let removed = docs.path().join("removed.txt");
+    std::fs::write(&removed, "platypus notes")?;
+    std::fs::write(docs.path().join("kept.txt"), "wombat notes")?;
+
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions { root: docs.path().to_path_buf(), ..Default::default() };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone())
+        .with_lexical(lexical.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 2);
+
+    let result = indexer.delete_file(&removed).await?;
+    assert_eq!(result.files_removed, 1);
+    assert_eq!(result.embeddings_removed, 1);
+    assert_eq!(store.count().await, 1);
+    assert_eq!(state.file_count()?, 1);
+    assert!(state.get_doc_ids(&removed)?.is_empty());
+    assert!(lexical.search("platypus", 10, SearchMode::Auto)?.is_empty());
+    assert_eq!(lexical.search("wombat", 10, SearchMode::Auto)?.len(), 1);
+
+    // Deleting again finds nothing to remove
+    let result = indexer.delete_file(&removed).await?;
+    assert_eq!(result.files_removed, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deduplicate_index_removes_everything_for_the_duplicates() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("one.txt"), "platypus notes")?;
+    std::fs::write(docs.path().join("two.txt"), "platypus notes, again")?;
+
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions { root: docs.path().to_path_buf(), ..Default::default() };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone())
+        .with_lexical(lexical.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 2);
+
+    // The dummy embedder gives both files the same vector despite their different text
+    assert_eq!(deduplicate_index(store.as_ref(), Some(&state), Some(&lexical), 0.98).await?, 1);
+    lexical.commit()?;
+    assert_eq!(store.count().await, 1);
+    let tracked: usize = ["one.txt", "two.txt"].iter()
+        .map(|name| state.get_doc_ids(&docs.path().join(name)).map(|ids| ids.len()))
+        .sum::<Result<usize>>()?;
+    assert_eq!(tracked, 1);
+    assert_eq!(lexical.search("platypus", 10, SearchMode::Auto)?.len(), 1);
+    Ok(())
+}
@@ -1,6 +1,6 @@
 extern crate nexus_core;
-use nexus_core::{IndexOptions, Indexer, TextExtractor, Embedder, IndexEvent, VectorStore, DocumentMetadata, SearchResult};
-use std::path::PathBuf;
+use nexus_core::{IndexOptions, Indexer, TextExtractor, Embedder, IndexEvent, VectorStore, DocumentMetadata, SearchOptions, SearchResult};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -33,12 +33,21 @@ impl VectorStore for DummyStore {
         metadata.doc_id = id.clone();
         Ok(id)
     }
-    async fn search(&self, _query: Vec<f32>, _top_k: usize) -> Result<Vec<SearchResult>> {
+    async fn search(&self, _query: Vec<f32>, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
         Ok(vec![])
     }
     async fn get_metadata(&self, _doc_id: &str) -> Result<Option<DocumentMetadata>> {
         Ok(None)
     }
+    async fn get_by_file_path(&self, _path: &Path) -> Result<Vec<DocumentMetadata>> {
+        Ok(vec![])
+    }
+    async fn delete_by_file_path(&self, _path: &Path) -> Result<usize> {
+        Ok(0)
+    }
+    async fn reindex_file(&self, _file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        self.add_embeddings_batch(new_embeddings, metadata).await
+    }
     async fn save(&self) -> Result<()> { Ok(()) }
     async fn count(&self) -> usize { 0 }
 }
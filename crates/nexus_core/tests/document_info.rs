@@ -0,0 +1,61 @@
+use nexus_core::{IndexOptions, Indexer, SyncTextExtractor, DocumentInfo, ExtractedPage, PagedExtractor, VectorStore, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Extractor that reports a title for `.pdf` files (which go through the paged
+/// path) and fails to read the properties of everything else.
+struct TitledExtractor;
+impl SyncTextExtractor for TitledExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for TitledExtractor {
+    fn extract_pages(&self, path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok(vec![ExtractedPage { page_num: 0, total_pages: 1, text: std::fs::read_to_string(path)? }])
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf").unwrap_or(false)
+    }
+    fn extract_info(&self, path: &PathBuf) -> Result<DocumentInfo> {
+        anyhow::ensure!(self.is_paged(path), "no document properties");
+        Ok(DocumentInfo {
+            title: Some("Quarterly Report".to_string()),
+            author: Some("Finance".to_string()),
+            created_at: Some(1_700_000_000),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_document_info_stored_with_chunks() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("report.pdf"), "revenue grew")?;
+    std::fs::write(docs.path().join("notes.txt"), "call the bank")?;
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, TitledExtractor, DummyEmbedder, store.clone());
+    let result = indexer.run().await?;
+    assert_eq!(result.files_indexed, 2);
+    assert!(result.errors.is_empty());
+
+    let report = store.get_by_file_path(&docs.path().join("report.pdf")).await?;
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].title.as_deref(), Some("Quarterly Report"));
+    assert_eq!(report[0].author.as_deref(), Some("Finance"));
+    assert_eq!(report[0].created_at, Some(1_700_000_000));
+
+    // Unreadable properties leave the fields empty without failing the file
+    let notes = store.get_by_file_path(&docs.path().join("notes.txt")).await?;
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].title, None);
+    Ok(())
+}
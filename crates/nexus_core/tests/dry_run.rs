@@ -0,0 +1,54 @@
+use nexus_core::{IndexEvent, IndexOptions, Indexer, Embedder, StateManager, VectorStore, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+
+mod common;
+use common::TextExtractor;
+
+/// Fails the test if anything tries to embed during a dry run.
+struct PanickingEmbedder;
+#[async_trait]
+impl Embedder for PanickingEmbedder {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        panic!("dry run must not embed");
+    }
+    async fn embed_batch(&self, _texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        panic!("dry run must not embed");
+    }
+    fn dimension(&self) -> usize { 3 }
+}
+
+#[tokio::test]
+async fn test_dry_run_stores_nothing() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("short.txt"), "a short note")?;
+    std::fs::write(docs.path().join("long.txt"), "word ".repeat(1000))?;
+    std::fs::write(docs.path().join("skipped.log"), "not wanted")?;
+
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        skip_extensions: vec!["log".to_string()],
+        dry_run: true,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, PanickingEmbedder, store.clone())
+        .with_state(state.clone());
+    let mut chunk_events = 0;
+    let result = indexer.run_with_progress(|e| {
+        if matches!(e, IndexEvent::ChunkProcessed(_, _)) {
+            chunk_events += 1;
+        }
+    }).await?;
+
+    assert_eq!(result.files_indexed, 2);
+    assert!(result.chunks_indexed > 2);
+    assert_eq!(chunk_events, result.chunks_indexed);
+    assert_eq!(result.embeddings_stored, 0);
+    assert_eq!(store.count().await, 0);
+    assert_eq!(state.file_count()?, 0);
+    Ok(())
+}
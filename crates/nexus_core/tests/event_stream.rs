@@ -0,0 +1,91 @@
+use nexus_core::{IndexOptions, Indexer, IndexEvent, InMemoryVectorStore, VectorStore};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use futures::StreamExt;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder, SlowEmbedder, InstrumentedStore};
+
+#[tokio::test]
+async fn test_stream_yields_events_then_result() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        std::fs::write(docs.path().join(name), format!("contents of {}", name))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store);
+
+    let events: Vec<IndexEvent> = indexer.stream().collect().await;
+    assert!(matches!(events.first(), Some(IndexEvent::IndexingStarted { .. })));
+    // `Done` closes the progress events and the result follows it
+    let [.., IndexEvent::Done, IndexEvent::Finished(result)] = events.as_slice() else {
+        panic!("stream did not end with Done and Finished: {:?}", events);
+    };
+    assert_eq!(result.files_indexed, 3);
+    assert!(result.errors.is_empty());
+
+    // Each file's events come between its start and the next file's start
+    let files: Vec<(usize, usize)> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| match e {
+            IndexEvent::FileStarted(path) => Some((i, events.iter().position(|e| e == &IndexEvent::FileIndexed(path.clone())).unwrap())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(files.len(), 3);
+    for pair in files.windows(2) {
+        assert!(pair[0].0 < pair[0].1 && pair[0].1 < pair[1].0);
+    }
+    let batch = events.iter().position(|e| matches!(e, IndexEvent::BatchEmbedded { .. })).unwrap();
+    assert!(files[0].0 < batch && batch < files[0].1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_reports_failure() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("a.txt"), "contents of a.txt")?;
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let store = Arc::new(InstrumentedStore { fail_saves: true, ..Default::default() });
+    let indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store);
+
+    let events: Vec<IndexEvent> = indexer.stream().collect().await;
+    assert_eq!(events.last(), Some(&IndexEvent::Failed("disk full".to_string())), "{:?}", events);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dropping_stream_cancels_run() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let total_files = 200;
+    for i in 0..total_files {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let indexer = Indexer::new(options, TextExtractor, SlowEmbedder, store.clone());
+
+    let mut stream = Box::pin(indexer.stream());
+    while !matches!(stream.next().await, Some(IndexEvent::FileIndexed(_))) {}
+    drop(stream);
+
+    // The run stops after the file in progress instead of embedding the rest
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let stored = store.count().await;
+    assert!(stored < total_files, "{} of {} files stored", stored, total_files);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(store.count().await, stored);
+    Ok(())
+}
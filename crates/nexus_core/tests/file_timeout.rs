@@ -0,0 +1,63 @@
+use nexus_core::{IndexOptions, Indexer, SyncTextExtractor, ExtractedPage, PagedExtractor, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Extractor that hangs on files named `slow.*`, as on a malformed document.
+/// `.pdf` files go through the paged path.
+struct HangingExtractor;
+impl HangingExtractor {
+    fn read(path: &PathBuf) -> Result<String> {
+        if path.file_stem().is_some_and(|stem| stem == "slow") {
+            std::thread::sleep(Duration::from_secs(2));
+        }
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl SyncTextExtractor for HangingExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Self::read(path)
+    }
+}
+impl PagedExtractor for HangingExtractor {
+    fn extract_pages(&self, path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok(vec![ExtractedPage { page_num: 0, total_pages: 1, text: Self::read(path)? }])
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf").unwrap_or(false)
+    }
+}
+
+#[tokio::test]
+async fn test_hung_extraction_times_out() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    for name in ["slow.txt", "slow.pdf", "fast.txt", "fast.pdf"] {
+        std::fs::write(docs.path().join(name), format!("contents of {}", name))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        file_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, HangingExtractor, DummyEmbedder, store);
+    let started = std::time::Instant::now();
+    let result = indexer.run().await?;
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    assert_eq!(result.files_indexed, 2);
+    let mut errors: Vec<_> = result.errors.iter()
+        .map(|(path, error)| (path.file_name().unwrap().to_string_lossy().into_owned(), error.clone()))
+        .collect();
+    errors.sort();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].0, "slow.pdf");
+    assert!(errors[0].1.contains("timeout after 100ms"), "{}", errors[0].1);
+    assert_eq!(errors[1], ("slow.txt".to_string(), "timeout after 100ms".to_string()));
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use nexus_core::{IndexOptions, Indexer, LexicalIndex, SearchMode, InMemoryVectorStore};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git").arg("-C").arg(dir).args(args).status()?;
+    anyhow::ensure!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_index_git_diff_only_indexes_changed_files() -> Result<()> {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("git not available, skipping");
+        return Ok(());
+    }
+
+    let repo = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    git(repo.path(), &["init", "-q"])?;
+    git(repo.path(), &["config", "user.email", "test@example.com"])?;
+    git(repo.path(), &["config", "user.name", "Test"])?;
+
+    std::fs::write(repo.path().join("stable.txt"), "unchanged aardvark content")?;
+    std::fs::write(repo.path().join("changing.txt"), "first draft")?;
+    git(repo.path(), &["add", "."])?;
+    git(repo.path(), &["commit", "-q", "-m", "initial"])?;
+
+    std::fs::write(repo.path().join("changing.txt"), "second draft about pangolins")?;
+    git(repo.path(), &["commit", "-q", "-am", "update"])?;
+
+    let lexical = Arc::new(LexicalIndex::new(data.path().to_path_buf())?);
+    let options = IndexOptions {
+        root: repo.path().to_path_buf(),
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()))
+        .with_lexical(lexical.clone());
+    let result = indexer.index_git_diff("HEAD~1", "HEAD").await?;
+
+    assert_eq!(result.files_indexed, 1);
+    assert_eq!(lexical.search("pangolins", 10, SearchMode::Auto)?.len(), 1);
+    assert!(lexical.search("aardvark", 10, SearchMode::Auto)?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use nexus_core::{ExtractedPage, IndexEvent, IndexOptions, Indexer, PagedExtractor, StateManager, SyncTextExtractor, VectorStore, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Treats every `.pdf` as a three page document with one chunk per page.
+struct ThreePageExtractor;
+impl SyncTextExtractor for ThreePageExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for ThreePageExtractor {
+    fn extract_pages(&self, _path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok((0..3)
+            .map(|page_num| ExtractedPage { page_num, total_pages: 3, text: format!("page {}", page_num) })
+            .collect())
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().is_some_and(|e| e == "pdf")
+    }
+}
+
+#[tokio::test]
+async fn test_max_total_chunks_caps_run() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    // 20 single-chunk files
+    for i in 0..20 {
+        std::fs::write(docs.path().join(format!("note{:02}.txt", i)), format!("note number {}", i))?;
+    }
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        max_total_chunks: Some(5),
+        ..Default::default()
+    };
+
+    let mut indexer = Indexer::new(options.clone(), TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    let mut events = Vec::new();
+    let result = indexer.run_with_progress(|e| events.push(e)).await?;
+    assert_eq!(result.chunks_indexed, 5);
+    assert_eq!(store.count().await, 5);
+    assert!(events.iter().any(|e| matches!(e, IndexEvent::IndexingPaused(_))));
+    assert!(matches!(events.last(), Some(IndexEvent::Done)));
+
+    // The next run skips what is already indexed and continues with the rest
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state);
+    let result = indexer.run().await?;
+    assert_eq!(result.files_unchanged, 5);
+    assert_eq!(result.chunks_indexed, 5);
+    assert_eq!(store.count().await, 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_total_chunks_counts_every_page() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("note.txt"), "a short note")?;
+    std::fs::write(docs.path().join("report.pdf"), "placeholder")?;
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        max_total_chunks: Some(2),
+        ..Default::default()
+    };
+
+    // The three pages don't fit next to the note, so the PDF waits for the next run
+    let mut indexer = Indexer::new(options.clone(), ThreePageExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    let result = indexer.run().await?;
+    assert_eq!(result.chunks_indexed, 1);
+    assert_eq!(store.count().await, 1);
+
+    let mut indexer = Indexer::new(options, ThreePageExtractor, DummyEmbedder, store.clone())
+        .with_state(state);
+    let result = indexer.run().await?;
+    assert_eq!(result.chunks_indexed, 3);
+    assert_eq!(store.count().await, 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_total_chunks_counts_every_archive_member() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("note.txt"), "a short note")?;
+    let file = std::fs::File::create(docs.path().join("notes.tar.gz"))?;
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+    for i in 0..3 {
+        let contents = format!("archived note {}", i);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, format!("note{}.txt", i), contents.as_bytes())?;
+    }
+    builder.into_inner()?.finish()?;
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        follow_archive_contents: true,
+        max_total_chunks: Some(2),
+        ..Default::default()
+    };
+
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone());
+    let mut events = Vec::new();
+    let result = indexer.run_with_progress(|e| events.push(e)).await?;
+    assert_eq!(result.chunks_indexed, 1);
+    assert_eq!(store.count().await, 1);
+    assert!(events.iter().any(|e| matches!(e, IndexEvent::IndexingPaused(_))));
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use nexus_core::{DocumentInfo, IndexEvent, IndexOptions, Indexer, SyncTextExtractor, ExtractedPage, PagedExtractor, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Extractor that treats every `.pdf` and `.csv` as a ten page document with one blank page,
+/// counting the calls to `extract_pages_parallel`.
+#[derive(Default)]
+struct TenPageExtractor {
+    parallel_reads: Arc<AtomicUsize>,
+}
+impl SyncTextExtractor for TenPageExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for TenPageExtractor {
+    fn extract_pages(&self, _path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok((0..10)
+            .map(|page_num| ExtractedPage {
+                page_num,
+                total_pages: 10,
+                text: if page_num == 4 { "   ".to_string() } else { format!("page {} ", page_num).repeat(page_num + 1) },
+            })
+            .collect())
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf" || e == "csv").unwrap_or(false)
+    }
+    fn extract_pages_parallel(&self, path: &PathBuf) -> Result<(Vec<ExtractedPage>, Result<DocumentInfo>)> {
+        self.parallel_reads.fetch_add(1, Ordering::SeqCst);
+        self.extract_pages_with_info(path)
+    }
+}
+
+/// Index a single ten page file, returning the stored (chunk_index, snippet, byte range)
+/// records, the pages reported as processed and how many times the pages were read in parallel.
+async fn index_paged(file_name: &str, parallel_pages: bool) -> Result<(Vec<(usize, Option<String>, usize, usize)>, Vec<usize>, usize)> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join(file_name), "placeholder")?;
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 16,
+        parallel_pages,
+        // Keep the memory gate from forcing the sequential path
+        max_memory_bytes: u64::MAX,
+        ..Default::default()
+    };
+
+    let extractor = TenPageExtractor::default();
+    let parallel_reads = extractor.parallel_reads.clone();
+    let mut indexer = Indexer::new(options, extractor, DummyEmbedder, store.clone());
+    let mut pages = Vec::new();
+    let result = indexer.run_with_progress(|e| {
+        if let IndexEvent::PageProcessed(_, page, _) = e {
+            pages.push(page);
+        }
+    }).await?;
+    assert_eq!(result.files_indexed, 1);
+
+    let records = store.all_metadata().await
+        .iter()
+        .map(|m| (m.chunk_index, m.snippet.clone(), m.byte_start, m.byte_end))
+        .collect();
+    Ok((records, pages, parallel_reads.load(Ordering::SeqCst)))
+}
+
+#[tokio::test]
+async fn test_parallel_pages_match_sequential() -> Result<()> {
+    let (sequential, sequential_pages, sequential_reads) = index_paged("report.pdf", false).await?;
+    let (parallel, parallel_pages, parallel_reads) = index_paged("report.pdf", true).await?;
+    assert_eq!((sequential_reads, parallel_reads), (0, 1));
+
+    assert!(sequential.len() > 10);
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel_pages, (0..10).collect::<Vec<_>>());
+    assert_eq!(parallel_pages, sequential_pages);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pages_are_chunked_by_file_type() -> Result<()> {
+    for parallel_pages in [false, true] {
+        // Data files are cut at fixed offsets, other pages at word boundaries
+        let (rows, _, _) = index_paged("rows.csv", parallel_pages).await?;
+        assert!(rows.iter().all(|&(_, _, start, end)| start % 16 == 0 && end - start <= 16));
+        let (report, _, _) = index_paged("report.pdf", parallel_pages).await?;
+        assert!(report.iter().any(|&(_, _, start, _)| start % 16 != 0));
+    }
+    Ok(())
+}
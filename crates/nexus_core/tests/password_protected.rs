@@ -0,0 +1,65 @@
+use nexus_core::{IndexEvent, IndexOptions, Indexer, SyncTextExtractor, ExtractedPage, PagedExtractor, PasswordProtected, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Extractor that can't open files whose name starts with "locked";
+/// `.pdf` files go through the paged path.
+struct LockingExtractor;
+fn is_locked(path: &PathBuf) -> bool {
+    path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("locked"))
+}
+impl SyncTextExtractor for LockingExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        if is_locked(path) {
+            return Err(PasswordProtected.into());
+        }
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for LockingExtractor {
+    fn extract_pages(&self, path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        if is_locked(path) {
+            return Err(PasswordProtected.into());
+        }
+        Ok(vec![ExtractedPage { page_num: 0, total_pages: 1, text: std::fs::read_to_string(path)? }])
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf").unwrap_or(false)
+    }
+}
+
+#[tokio::test]
+async fn test_password_protected_files_are_skipped() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("locked.txt"), "secret")?;
+    std::fs::write(docs.path().join("locked.pdf"), "secret")?;
+    std::fs::write(docs.path().join("open.txt"), "hello")?;
+    std::fs::write(docs.path().join("open.pdf"), "hello")?;
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, LockingExtractor, DummyEmbedder, store.clone());
+    let mut skipped = Vec::new();
+    let result = indexer.run_with_progress(|e| {
+        if let IndexEvent::FileSkipped(path, reason) = e {
+            skipped.push((path.file_name().unwrap().to_string_lossy().to_string(), reason));
+        }
+    }).await?;
+
+    skipped.sort();
+    assert_eq!(skipped, vec![
+        ("locked.pdf".to_string(), "password protected".to_string()),
+        ("locked.txt".to_string(), "password protected".to_string()),
+    ]);
+    assert_eq!(result.files_skipped, 2);
+    assert_eq!(result.files_indexed, 2);
+    assert!(result.errors.is_empty());
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use nexus_core::{IndexOptions, Indexer, SyncTextExtractor, VectorStore, DocumentMetadata, SearchOptions, SearchResult, StateManager, ExtractedPage, PagedExtractor, InMemoryVectorStore};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use async_trait::async_trait;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Reads `.pdf` files as pages separated by form feeds.
+struct PagedTextExtractor;
+impl SyncTextExtractor for PagedTextExtractor {
+    fn extract_text_sync(&self, path: &PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+impl PagedExtractor for PagedTextExtractor {
+    fn extract_pages(&self, path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        let text = std::fs::read_to_string(path)?;
+        let pages: Vec<&str> = text.split('\x0c').collect();
+        Ok(pages.iter().enumerate()
+            .map(|(page_num, page)| ExtractedPage { page_num, total_pages: pages.len(), text: page.to_string() })
+            .collect())
+    }
+    fn is_paged(&self, path: &PathBuf) -> bool {
+        path.extension().map(|e| e == "pdf").unwrap_or(false)
+    }
+}
+
+/// Records which files went through `add_embeddings_batch` and which through `reindex_file`.
+#[derive(Default)]
+struct RecordingStore {
+    added: Mutex<Vec<PathBuf>>,
+    reindexed: Mutex<Vec<PathBuf>>,
+}
+#[async_trait]
+impl VectorStore for RecordingStore {
+    async fn add_embedding(&self, _embedding: Vec<f32>, metadata: DocumentMetadata) -> Result<String> {
+        self.added.lock().unwrap().push(metadata.file_path);
+        Ok(String::new())
+    }
+    async fn add_embeddings_batch(&self, _embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        self.added.lock().unwrap().extend(metadata.iter().map(|m| m.file_path.clone()));
+        Ok(metadata.iter().map(|m| format!("{}#{}", m.file_path.display(), m.chunk_index)).collect())
+    }
+    async fn search(&self, _query: Vec<f32>, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        Ok(vec![])
+    }
+    async fn get_metadata(&self, _doc_id: &str) -> Result<Option<DocumentMetadata>> {
+        Ok(None)
+    }
+    async fn get_by_file_path(&self, _path: &Path) -> Result<Vec<DocumentMetadata>> {
+        Ok(vec![])
+    }
+    async fn delete_by_doc_ids(&self, _doc_ids: &[String]) -> Result<usize> {
+        Ok(0)
+    }
+    async fn delete_by_file_path(&self, _path: &Path) -> Result<usize> {
+        Ok(0)
+    }
+    async fn deduplicate(&self, _similarity_threshold: f32) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+    async fn reindex_file(&self, file_path: &Path, _new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        self.reindexed.lock().unwrap().push(file_path.to_path_buf());
+        Ok(metadata.iter().map(|m| format!("{}#{}", m.file_path.display(), m.chunk_index)).collect())
+    }
+    async fn save(&self) -> Result<()> { Ok(()) }
+    async fn count(&self) -> usize { 0 }
+}
+
+#[tokio::test]
+async fn test_replace_existing_reindexes_unchanged_files() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    for i in 0..3 {
+        std::fs::write(docs.path().join(format!("note{}.txt", i)), format!("note number {}", i))?;
+    }
+    let state = Arc::new(StateManager::new(data.path())?);
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let store = Arc::new(RecordingStore::default());
+    let mut indexer = Indexer::new(options.clone(), TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 3);
+    assert_eq!(store.added.lock().unwrap().len(), 3);
+    assert!(store.reindexed.lock().unwrap().is_empty());
+
+    // Nothing changed on disk, but every file is re-embedded in place
+    let store = Arc::new(RecordingStore::default());
+    let options = IndexOptions { replace_existing: true, ..options };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state);
+    let result = indexer.run().await?;
+    assert_eq!(result.files_unchanged, 0);
+    assert_eq!(result.files_indexed, 3);
+    assert!(store.added.lock().unwrap().is_empty());
+    let mut reindexed = store.reindexed.lock().unwrap().clone();
+    reindexed.sort();
+    assert_eq!(reindexed, (0..3).map(|i| docs.path().join(format!("note{}.txt", i))).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_existing_replaces_every_page() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let path = docs.path().join("report.pdf");
+    std::fs::write(&path, "first page\x0csecond page\x0cthird page")?;
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options.clone(), PagedTextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    indexer.run().await?;
+    assert_eq!(store.count().await, 3);
+
+    let options = IndexOptions { replace_existing: true, ..options };
+    let mut indexer = Indexer::new(options, PagedTextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 1);
+    assert_eq!(store.count().await, 3);
+    let mut tracked = state.get_doc_ids(&path)?;
+    tracked.sort();
+    let mut stored: Vec<String> = store.get_by_file_path(&path).await?.into_iter().map(|m| m.doc_id).collect();
+    stored.sort();
+    assert_eq!(tracked, stored);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use nexus_core::{IndexOptions, Indexer, SyncTextExtractor, ExtractedPage, PagedExtractor, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+
+mod common;
+use common::DummyEmbedder;
+
+/// Returns the same text for every file, whatever its size.
+struct DummyExtractor;
+impl SyncTextExtractor for DummyExtractor {
+    fn extract_text_sync(&self, _path: &PathBuf) -> Result<String> {
+        Ok("dummy text".to_string())
+    }
+}
+impl PagedExtractor for DummyExtractor {
+    fn extract_pages(&self, _path: &PathBuf) -> Result<Vec<ExtractedPage>> {
+        Ok(vec![])
+    }
+    fn is_paged(&self, _path: &PathBuf) -> bool {
+        false
+    }
+}
+
+#[tokio::test]
+async fn test_elapsed_and_bytes_processed() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let sizes = [("a.txt", 10), ("b.md", 2_000), ("c.txt", 35_000)];
+    for (name, size) in sizes {
+        std::fs::write(docs.path().join(name), "x".repeat(size))?;
+    }
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, DummyExtractor, DummyEmbedder, store);
+    let result = indexer.run().await?;
+    assert_eq!(result.files_indexed, 3);
+    assert!(result.elapsed > Duration::ZERO);
+    assert_eq!(result.bytes_processed, sizes.iter().map(|(_, size)| *size as u64).sum::<u64>());
+    Ok(())
+}
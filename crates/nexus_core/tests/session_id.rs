@@ -0,0 +1,55 @@
+use nexus_core::{IndexEvent, IndexResult, Uuid, IndexOptions, Indexer, InMemoryVectorStore};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Index `dir` on its own task, collecting its events through a channel.
+async fn index_in_background(dir: PathBuf) -> Result<(IndexResult, Vec<IndexEvent>)> {
+    let options = IndexOptions { root: dir, ..Default::default() };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(256);
+    let task = tokio::spawn(async move {
+        let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store);
+        indexer.run_with_channel(tx).await
+    });
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    Ok((task.await??, events))
+}
+
+fn started_sessions(events: &[IndexEvent]) -> Vec<Uuid> {
+    events.iter()
+        .filter_map(|e| match e {
+            IndexEvent::IndexingStarted { session_id } => Some(*session_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_runs_have_own_session_ids() -> Result<()> {
+    let first = tempfile::tempdir()?;
+    let second = tempfile::tempdir()?;
+    for i in 0..3 {
+        std::fs::write(first.path().join(format!("a{}.txt", i)), format!("first run {}", i))?;
+        std::fs::write(second.path().join(format!("b{}.txt", i)), format!("second run {}", i))?;
+    }
+
+    let (a, b) = tokio::join!(
+        index_in_background(first.path().to_path_buf()),
+        index_in_background(second.path().to_path_buf()),
+    );
+    let (result_a, events_a) = a?;
+    let (result_b, events_b) = b?;
+
+    assert_ne!(result_a.session_id, result_b.session_id);
+    assert!(matches!(events_a.first(), Some(IndexEvent::IndexingStarted { .. })));
+    assert_eq!(started_sessions(&events_a), vec![result_a.session_id]);
+    assert_eq!(started_sessions(&events_b), vec![result_b.session_id]);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use nexus_core::{IndexOptions, Indexer, StateManager, VectorStore, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_index_single_file_replaces_old_embeddings() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    let edited = docs.path().join("edited.txt");
+    let other = docs.path().join("other.txt");
+    std::fs::write(&edited, "first draft")?;
+    std::fs::write(&other, "left alone")?;
+
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions { root: docs.path().to_path_buf(), ..Default::default() };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone())
+        .with_state(state.clone());
+    assert_eq!(indexer.run().await?.files_indexed, 2);
+    let old_ids = state.get_doc_ids(&edited)?;
+    let other_ids = state.get_doc_ids(&other)?;
+
+    std::fs::write(&edited, "second draft")?;
+    let later = std::fs::metadata(&edited)?.modified()? + std::time::Duration::from_secs(60);
+    std::fs::File::options().write(true).open(&edited)?.set_modified(later)?;
+
+    let result = indexer.index_single_file(&edited, |_| {}).await?;
+    assert_eq!(result.files_indexed, 1);
+
+    for id in &old_ids {
+        assert!(store.get_metadata(id).await?.is_none());
+    }
+    let new_ids = state.get_doc_ids(&edited)?;
+    assert!(!new_ids.is_empty());
+    assert_ne!(new_ids, old_ids);
+    let metadata = store.get_metadata(&new_ids[0]).await?.unwrap();
+    assert_eq!(metadata.snippet.as_deref(), Some("second draft"));
+    assert_eq!(state.get_doc_ids(&other)?, other_ids);
+    assert_eq!(store.count().await, 2);
+
+    // Unchanged since the last call: nothing is removed or re-embedded
+    let result = indexer.index_single_file(&edited, |_| {}).await?;
+    assert_eq!(result.files_unchanged, 1);
+    assert_eq!(state.get_doc_ids(&edited)?, new_ids);
+    Ok(())
+}
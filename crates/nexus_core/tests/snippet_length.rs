@@ -0,0 +1,31 @@
+use nexus_core::{IndexOptions, Indexer, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+#[tokio::test]
+async fn test_snippets_respect_snippet_length() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("long.txt"), "A fairly long sentence for previews. ".repeat(30))?;
+    std::fs::write(docs.path().join("short.txt"), "tiny")?;
+
+    let store = Arc::new(InMemoryVectorStore::new());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        snippet_length: 50,
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, store.clone());
+    indexer.run().await?;
+
+    let metadata = store.all_metadata().await;
+    assert!(!metadata.is_empty());
+    for m in metadata.iter() {
+        let snippet = m.snippet.as_deref().unwrap_or_default();
+        assert!(snippet.chars().count() <= 50, "snippet too long: {:?}", snippet);
+    }
+    assert!(metadata.iter().any(|m| m.snippet.as_deref() == Some("tiny")));
+    Ok(())
+}
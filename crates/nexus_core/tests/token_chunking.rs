@@ -0,0 +1,58 @@
+use nexus_core::{ChunkStrategy, IndexOptions, Indexer, Embedder, VectorStore, InMemoryVectorStore};
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+
+mod common;
+use common::TextExtractor;
+
+/// Treats every whitespace-separated word as one token, with a 256-token limit.
+struct WordTokenEmbedder;
+#[async_trait]
+impl Embedder for WordTokenEmbedder {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.0, 1.0, 2.0])
+    }
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![0.0, 1.0, 2.0]).collect())
+    }
+    fn dimension(&self) -> usize { 3 }
+    fn max_input_tokens(&self) -> Option<usize> { Some(256) }
+    fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        for word in text.split(' ') {
+            if !word.is_empty() {
+                spans.push((offset, offset + word.len()));
+            }
+            offset += word.len() + 1;
+        }
+        Some(spans)
+    }
+}
+
+async fn chunks_stored(strategy: ChunkStrategy) -> Result<usize> {
+    let docs = tempfile::tempdir()?;
+    // ~2000 characters, ~180 words
+    std::fs::write(docs.path().join("repetitive.txt"), "repetitive ".repeat(182))?;
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_strategy: strategy,
+        ..Default::default()
+    };
+    let store = Arc::new(InMemoryVectorStore::new());
+    let mut indexer = Indexer::new(options, TextExtractor, WordTokenEmbedder, store.clone());
+    indexer.run().await?;
+    Ok(store.count().await)
+}
+
+#[tokio::test]
+async fn test_token_chunking_fits_model_limit() -> Result<()> {
+    // 1500-character chunks split the file in two
+    assert_eq!(chunks_stored(ChunkStrategy::WordBoundary).await?, 2);
+    // ~180 tokens fit in one 256-token chunk
+    assert_eq!(chunks_stored(ChunkStrategy::Tokens(256)).await?, 1);
+    // A smaller token budget splits it again
+    assert_eq!(chunks_stored(ChunkStrategy::Tokens(100)).await?, 2);
+    Ok(())
+}
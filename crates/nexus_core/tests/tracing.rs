@@ -0,0 +1,49 @@
+//! Requires `--features tracing-subscriber`.
+#![cfg(feature = "tracing-subscriber")]
+
+use nexus_core::{IndexOptions, Indexer, InMemoryVectorStore};
+use nexus_core::telemetry::{init_tracing_with_writer, LOG_ENV};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+
+mod common;
+use common::{TextExtractor, DummyEmbedder};
+
+/// Shared in-memory log sink.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_nexus_log_emits_span_timings() -> Result<()> {
+    let capture = Capture::default();
+    std::env::set_var(LOG_ENV, "nexus_core=trace");
+    let sink = capture.clone();
+    assert!(init_tracing_with_writer(move || sink.clone())?);
+
+    let docs = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("notes.txt"), "tracing test")?;
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        ..Default::default()
+    };
+    let mut indexer = Indexer::new(options, TextExtractor, DummyEmbedder, Arc::new(InMemoryVectorStore::new()));
+    indexer.run().await?;
+
+    let output = String::from_utf8(capture.0.lock().unwrap().clone())?;
+    assert!(!output.is_empty());
+    for span in ["run_with_progress", "extract_text_sync", "embed_batch", "add_embeddings_batch"] {
+        assert!(output.contains(span), "missing span {} in:\n{}", span, output);
+    }
+    Ok(())
+}
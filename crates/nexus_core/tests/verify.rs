@@ -0,0 +1,153 @@
+use nexus_core::{IndexOptions, Indexer, Embedder, VectorStore, DocumentMetadata, SearchOptions, SearchResult, StateManager, ChunkStrategy};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use async_trait::async_trait;
+
+mod common;
+use common::TextExtractor;
+
+/// Letter histogram, so different texts land on different vectors.
+struct LetterEmbedder;
+#[async_trait]
+impl Embedder for LetterEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut v = vec![0.0; 26];
+        for c in text.to_ascii_lowercase().bytes().filter(u8::is_ascii_lowercase) {
+            v[(c - b'a') as usize] += 1.0;
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(1.0);
+        Ok(v.into_iter().map(|x| x / norm).collect())
+    }
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut out = Vec::new();
+        for text in texts {
+            out.push(self.embed(text).await?);
+        }
+        Ok(out)
+    }
+    fn dimension(&self) -> usize { 26 }
+}
+
+/// In-memory store with brute-force dot-product search. Doc ids count down, so
+/// they sort in the reverse of the order chunks were stored in.
+#[derive(Default)]
+struct MemoryStore(Mutex<Vec<(Vec<f32>, DocumentMetadata)>>);
+impl MemoryStore {
+    fn corrupt(&self, doc_id: &str) {
+        let mut rows = self.0.lock().unwrap();
+        let row = rows.iter_mut().find(|(_, m)| m.doc_id == doc_id).unwrap();
+        row.0 = vec![0.0; 26];
+    }
+}
+#[async_trait]
+impl VectorStore for MemoryStore {
+    async fn add_embedding(&self, embedding: Vec<f32>, metadata: DocumentMetadata) -> Result<String> {
+        Ok(self.add_embeddings_batch(vec![embedding], vec![metadata]).await?.remove(0))
+    }
+    async fn add_embeddings_batch(&self, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        let mut rows = self.0.lock().unwrap();
+        let mut ids = Vec::new();
+        for (embedding, mut meta) in embeddings.into_iter().zip(metadata) {
+            meta.doc_id = format!("doc-{}", 9999 - rows.len());
+            ids.push(meta.doc_id.clone());
+            rows.push((embedding, meta));
+        }
+        Ok(ids)
+    }
+    async fn search(&self, query: Vec<f32>, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        let rows = self.0.lock().unwrap();
+        let mut scored: Vec<SearchResult> = rows.iter()
+            .filter(|(_, m)| options.filter_path.as_ref().is_none_or(|p| p.matches_path(&m.file_path)))
+            .map(|(v, m)| SearchResult {
+                doc_id: m.doc_id.clone(),
+                score: v.iter().zip(&query).map(|(a, b)| a * b).sum(),
+                snippet: m.snippet.clone(),
+                metadata: m.clone(),
+                context_chunks: Vec::new(),
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(options.top_k);
+        Ok(scored)
+    }
+    async fn get_metadata(&self, doc_id: &str) -> Result<Option<DocumentMetadata>> {
+        Ok(self.0.lock().unwrap().iter().find(|(_, m)| m.doc_id == doc_id).map(|(_, m)| m.clone()))
+    }
+    async fn get_by_file_path(&self, path: &Path) -> Result<Vec<DocumentMetadata>> {
+        let mut chunks: Vec<DocumentMetadata> = self.0.lock().unwrap().iter()
+            .filter(|(_, m)| m.file_path == path)
+            .map(|(_, m)| m.clone())
+            .collect();
+        chunks.sort_by_key(|m| m.chunk_index);
+        Ok(chunks)
+    }
+    async fn delete_by_doc_ids(&self, _doc_ids: &[String]) -> Result<usize> {
+        Ok(0)
+    }
+    async fn delete_by_file_path(&self, _path: &Path) -> Result<usize> {
+        Ok(0)
+    }
+    async fn deduplicate(&self, _similarity_threshold: f32) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+    async fn reindex_file(&self, _file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        self.add_embeddings_batch(new_embeddings, metadata).await
+    }
+    async fn save(&self) -> Result<()> { Ok(()) }
+    async fn count(&self) -> usize { self.0.lock().unwrap().len() }
+}
+
+#[tokio::test]
+async fn test_verify_reports_corrupt_embeddings() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("a.txt"), "aaaa apple banana")?;
+    std::fs::write(docs.path().join("b.txt"), "zzz quiz jazz fizz")?;
+    std::fs::write(docs.path().join("c.txt"), "moon noon mummy")?;
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(MemoryStore::default());
+    let options = IndexOptions { root: docs.path().to_path_buf(), ..Default::default() };
+
+    let mut indexer = Indexer::new(options, TextExtractor, LetterEmbedder, store.clone())
+        .with_state(state.clone());
+    let result = indexer.run().await?;
+    assert_eq!(result.files_indexed, 3);
+
+    let verification = indexer.verify().await?;
+    assert_eq!(verification.verified, 3);
+    assert!(verification.mismatches.is_empty());
+
+    // Zero out one file's stored vector: it no longer comes back as its own top hit
+    let corrupted = docs.path().join("b.txt");
+    store.corrupt(&state.get_doc_ids(&corrupted)?[0]);
+    let verification = indexer.verify().await?;
+    assert_eq!(verification.verified, 2);
+    assert_eq!(verification.mismatches, vec![corrupted]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_checks_first_chunk_of_multi_chunk_files() -> Result<()> {
+    let docs = tempfile::tempdir()?;
+    let data = tempfile::tempdir()?;
+    std::fs::write(docs.path().join("long.txt"), "aaaa apple banana zzzz quiz jazz moon noon mummy")?;
+    let state = Arc::new(StateManager::new(data.path())?);
+    let store = Arc::new(MemoryStore::default());
+    let options = IndexOptions {
+        root: docs.path().to_path_buf(),
+        chunk_size: 18,
+        chunk_strategy: ChunkStrategy::Fixed,
+        ..Default::default()
+    };
+
+    let mut indexer = Indexer::new(options, TextExtractor, LetterEmbedder, store.clone())
+        .with_state(state.clone());
+    indexer.run().await?;
+    assert!(state.get_doc_ids(&docs.path().join("long.txt"))?.len() > 1);
+
+    let verification = indexer.verify().await?;
+    assert_eq!(verification.verified, 1);
+    assert!(verification.mismatches.is_empty());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use nexus_core::{FileWatcher, IndexEvent};
+use nexus_core::config::WatchConfig;
+use std::time::Duration;
+use anyhow::Result;
+
+#[tokio::test]
+async fn test_new_directory_is_watched() -> Result<()> {
+    let root = tempfile::tempdir()?;
+    let config = WatchConfig {
+        debounce_secs: 1,
+        ..Default::default()
+    };
+    let mut watcher = FileWatcher::new(config)?;
+    watcher.watch(&root.path().to_path_buf())?;
+
+    let new_dir = root.path().join("projects");
+    let new_file = new_dir.join("notes.txt");
+    let writer = {
+        let new_dir = new_dir.clone();
+        let new_file = new_file.clone();
+        std::thread::spawn(move || -> std::io::Result<()> {
+            std::thread::sleep(Duration::from_millis(200));
+            std::fs::create_dir(&new_dir)?;
+            std::thread::sleep(Duration::from_millis(200));
+            std::fs::write(&new_file, "created after the watcher started")
+        })
+    };
+
+    // The directory and the file may land in separate batches
+    let mut seen_file = false;
+    let mut seen_dir = false;
+    for _ in 0..3 {
+        let batch = tokio::time::timeout(Duration::from_secs(5), watcher.wait_for_changes())
+            .await
+            .expect("no change batch within 5 seconds")?;
+        seen_file |= batch.modified.iter().any(|p| p.ends_with("projects/notes.txt"));
+        seen_dir |= batch.events.iter().any(|e| matches!(e, IndexEvent::WatchPathAdded(p) if p.ends_with("projects")));
+        if seen_file && seen_dir {
+            break;
+        }
+    }
+    writer.join().unwrap()?;
+    assert!(seen_dir, "new directory was not watched");
+    assert!(seen_file, "file in new directory was not reported");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_created_file_is_reported() -> Result<()> {
+    let root = tempfile::tempdir()?;
+    let config = WatchConfig {
+        debounce_secs: 1,
+        ..Default::default()
+    };
+    let mut watcher = FileWatcher::new(config)?;
+    watcher.watch(&root.path().to_path_buf())?;
+
+    let file = root.path().join("todo.txt");
+    tokio::fs::write(&file, "water the plants").await?;
+
+    let batch = tokio::time::timeout(Duration::from_secs(5), watcher.wait_for_changes())
+        .await
+        .expect("no change batch within 5 seconds")?;
+    assert!(batch.modified.iter().any(|p| p.ends_with("todo.txt")));
+    assert!(batch.deleted.is_empty());
+    Ok(())
+}
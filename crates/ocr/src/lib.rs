@@ -4,22 +4,84 @@
 //! Images are automatically resized before OCR to limit memory usage.
 //! PDFs are processed page-by-page to reduce memory footprint.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
 use async_trait::async_trait;
 use anyhow::Result;
 
-use leptess::LepTess;
+use leptess::{LepTess, Variable};
 use poppler::PopplerDocument;
+use glib::error::ErrorDomain;
 use image::GenericImageView;
 use tempfile::NamedTempFile;
 use dotext::{MsDoc, Docx, Xlsx, Pptx, Odt, Odp};
 use dotext::doc::OpenOfficeDoc;
+use epub::doc::EpubDoc;
 use std::io::Read;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use mail_parser::{Address, MessageParser, MimeHeaders, PartType};
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader as XmlReader;
+use rayon::prelude::*;
 
 /// Maximum dimension (width or height) for images before OCR.
 /// Larger images are downscaled to fit within this limit.
-const MAX_IMAGE_DIMENSION: u32 = 2000;
+pub const MAX_IMAGE_DIMENSION: u32 = 2000;
+
+/// Tesseract language used when none is configured.
+pub const DEFAULT_OCR_LANGUAGE: &str = "eng";
+
+/// Tesseract settings for OCR of images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcrOptions {
+    /// Tesseract language codes joined with `+`, e.g. "eng" or "eng+fra".
+    /// Each needs its traineddata file installed.
+    pub language: String,
+    /// Page segmentation mode, as in `tesseract --psm` (3 = fully automatic).
+    pub psm: u8,
+}
+
+impl Default for OcrOptions {
+    fn default() -> Self {
+        Self {
+            language: DEFAULT_OCR_LANGUAGE.to_string(),
+            psm: 3,
+        }
+    }
+}
+
+impl OcrOptions {
+    /// Options for `language`: one Tesseract code, or several separated by `+` or `,`.
+    /// Fails if a code is empty or contains anything but letters, digits and `_`.
+    pub fn new(language: &str) -> Result<Self> {
+        let codes: Vec<&str> = language.split(['+', ',']).map(str::trim).collect();
+        for code in &codes {
+            anyhow::ensure!(
+                !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "Invalid OCR language '{}' (expected Tesseract codes like eng or eng+fra)", language
+            );
+        }
+        Ok(Self { language: codes.join("+"), ..Default::default() })
+    }
+    
+    /// Set the page segmentation mode.
+    pub fn with_psm(mut self, psm: u8) -> Self {
+        self.psm = psm;
+        self
+    }
+    
+    /// Start Tesseract with these settings.
+    fn tesseract(&self) -> Result<LepTess> {
+        // Checked again here since the fields are public; a NUL byte would panic in leptess
+        let options = Self::new(&self.language)?;
+        let mut lt = LepTess::new(None, &options.language).map_err(|e| {
+            anyhow::anyhow!("Failed to load OCR language '{}' (is its traineddata installed?): {}", self.language, e)
+        })?;
+        lt.set_variable(Variable::TesseditPagesegMode, &self.psm.to_string())
+            .map_err(|_| anyhow::anyhow!("Invalid OCR page segmentation mode {}", self.psm))?;
+        Ok(lt)
+    }
+}
 
 /// Trait for OCR text extraction from images or PDFs.
 #[async_trait]
@@ -32,8 +94,66 @@ pub trait SyncOcrEngine: Send + Sync {
     fn extract_text_sync(&self, path: &PathBuf) -> Result<String>;
 }
 
+/// Returned when a PDF is encrypted and none of the configured passwords open it.
+/// Callers can detect it with `anyhow::Error::is::<PasswordProtected>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordProtected;
+
+impl std::fmt::Display for PasswordProtected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "password protected")
+    }
+}
+
+impl std::error::Error for PasswordProtected {}
+
+/// `POPPLER_ERROR_ENCRYPTED` from poppler-glib's error domain, which the poppler
+/// crate doesn't wrap. Poppler reports both a missing and a wrong password with it.
+#[derive(Debug, Clone, Copy)]
+struct PopplerEncrypted;
+
+impl ErrorDomain for PopplerEncrypted {
+    fn domain() -> glib::Quark {
+        glib::Quark::from_str("poppler-quark")
+    }
+    
+    fn code(self) -> i32 {
+        1
+    }
+    
+    fn from(code: i32) -> Option<Self> {
+        (code == 1).then_some(Self)
+    }
+}
+
+/// What to do with a text file that is not valid UTF-8.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EncodingFallback {
+    /// Fail extraction, so the file is skipped.
+    #[default]
+    Skip,
+    /// Guess the encoding with [`detect_and_decode`].
+    Detect,
+    /// Decode with this encoding, given as a label like "latin1" or "shift_jis".
+    ForceEncoding(String),
+}
+
+impl std::str::FromStr for EncodingFallback {
+    type Err = anyhow::Error;
+
+    /// `skip`, `detect` or the label of the encoding to force.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(EncodingFallback::Skip),
+            "detect" => Ok(EncodingFallback::Detect),
+            _ if Encoding::for_label(s.trim().as_bytes()).is_some() => Ok(EncodingFallback::ForceEncoding(s.trim().to_string())),
+            _ => anyhow::bail!("Unknown encoding '{}' (expected skip, detect or an encoding such as latin1)", s),
+        }
+    }
+}
+
 /// A single page extracted from a document.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExtractedPage {
     /// Page number (0-indexed)
     pub page_num: usize,
@@ -43,6 +163,15 @@ pub struct ExtractedPage {
     pub text: String,
 }
 
+/// Properties a document records about itself, such as a PDF's information dictionary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// Creation time in Unix seconds.
+    pub created_at: Option<i64>,
+}
+
 /// Trait for page-by-page extraction (for PDFs and multi-page documents).
 pub trait PagedExtractor: Send + Sync {
     /// Extract pages one at a time. Returns iterator of pages.
@@ -51,25 +180,56 @@ pub trait PagedExtractor: Send + Sync {
     
     /// Check if this file type supports paged extraction.
     fn is_paged(&self, path: &PathBuf) -> bool;
+    
+    /// Title, author and creation date of the document, where its format records them.
+    /// The default knows of none.
+    fn extract_info(&self, _path: &PathBuf) -> Result<DocumentInfo> {
+        Ok(DocumentInfo::default())
+    }
+    
+    /// `extract_pages` and `extract_info` together, for formats that read both from
+    /// one parse. The inner result fails when only the properties couldn't be read.
+    /// The default calls the two in turn.
+    fn extract_pages_with_info(&self, path: &PathBuf) -> Result<(Vec<ExtractedPage>, Result<DocumentInfo>)> {
+        let pages = self.extract_pages(path)?;
+        Ok((pages, self.extract_info(path)))
+    }
+    
+    /// `extract_pages_with_info`, reading the pages on several threads for formats
+    /// that allow it, at the cost of more memory. The default reads them in order.
+    fn extract_pages_parallel(&self, path: &PathBuf) -> Result<(Vec<ExtractedPage>, Result<DocumentInfo>)> {
+        self.extract_pages_with_info(path)
+    }
+}
+
+/// Title, author and creation date from a PDF's information dictionary.
+/// Blank entries count as missing.
+fn pdf_info(doc: &PopplerDocument) -> DocumentInfo {
+    let text = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    DocumentInfo {
+        title: text(doc.get_title()),
+        author: text(doc.get_author()),
+        created_at: doc.get_creation_date().filter(|&at| at > 0),
+    }
 }
 
 /// Preprocesses an image: loads it, resizes if needed, saves to temp file.
 /// Returns the path to use for OCR (either original or temp file).
-fn preprocess_image(path: &PathBuf) -> Result<(PathBuf, Option<NamedTempFile>)> {
+fn preprocess_image(path: &PathBuf, max_dimension: u32) -> Result<(PathBuf, Option<NamedTempFile>)> {
     let img = image::open(path)?;
     let (width, height) = img.dimensions();
     
     // Check if resizing is needed
-    if width <= MAX_IMAGE_DIMENSION && height <= MAX_IMAGE_DIMENSION {
+    if width <= max_dimension && height <= max_dimension {
         // Image is small enough, use original
         return Ok((path.clone(), None));
     }
     
     // Calculate new dimensions preserving aspect ratio
     let scale = if width > height {
-        MAX_IMAGE_DIMENSION as f64 / width as f64
+        max_dimension as f64 / width as f64
     } else {
-        MAX_IMAGE_DIMENSION as f64 / height as f64
+        max_dimension as f64 / height as f64
     };
     
     let new_width = (width as f64 * scale) as u32;
@@ -100,7 +260,7 @@ const TEXT_EXTENSIONS: &[&str] = &[
     // Shell/scripts
     "sh", "bash", "zsh", "fish", "ps1", "psm1", "bat", "cmd",
     // Config/data
-    "json", "yaml", "yml", "toml", "xml", "ini", "cfg", "conf", "config",
+    "json", "jsonl", "yaml", "yml", "toml", "xml", "ini", "cfg", "conf", "config",
     "env", "properties", "plist",
     // Web
     "html", "htm", "css", "scss", "sass", "less", "svg",
@@ -112,6 +272,19 @@ const TEXT_EXTENSIONS: &[&str] = &[
     "csv", "tsv", "log", "diff", "patch",
 ];
 
+/// Formats `do_extract` parses rather than reads as-is (OCR, PDF, office documents,
+/// ebooks, Jupyter notebooks and emails). ZIP archives are extracted too, but are
+/// left out here since callers opt into archives separately (see `is_zip_archive`).
+const EXTRACTED_EXTENSIONS: &[&str] = &[
+    "pdf",
+    "png", "jpg", "jpeg", "webp", "bmp", "tiff", "tif",
+    "docx", "xlsx", "pptx",
+    "odt", "odp",
+    "epub",
+    "ipynb",
+    "eml",
+];
+
 /// Known no-extension filenames that are text
 const TEXT_FILENAMES: &[&str] = &[
     "Makefile", "makefile", "GNUmakefile",
@@ -128,8 +301,177 @@ const TEXT_FILENAMES: &[&str] = &[
     "requirements.txt", "Pipfile", "Cargo.toml", "go.mod", "package.json",
 ];
 
+/// Plain text of each EPUB chapter, in spine (reading) order. Chapters with no
+/// text, such as a cover image page, are left out.
+fn epub_chapters(path: &Path) -> Result<Vec<String>> {
+    let mut doc = EpubDoc::new(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open EPUB {}: {}", path.display(), e))?;
+    let mut chapters = Vec::with_capacity(doc.get_num_pages());
+    loop {
+        if let Some((xhtml, _mime)) = doc.get_current_str() {
+            let text = html2text::from_read(xhtml.as_bytes(), 100)?;
+            if !text.trim().is_empty() {
+                chapters.push(text);
+            }
+        }
+        if !doc.go_next() {
+            break;
+        }
+    }
+    Ok(chapters)
+}
+
+/// Text of each slide in a PPTX presentation, in slide order, read straight from the
+/// `ppt/slides/slideN.xml` entries. Paragraphs end with a newline; slides with no
+/// text are kept as empty pages so page numbers match slide numbers.
+fn pptx_slides(path: &Path) -> Result<Vec<String>> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut slides: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let num = name.strip_prefix("ppt/slides/slide")?.strip_suffix(".xml")?.parse().ok()?;
+            Some((num, name.to_string()))
+        })
+        .collect();
+    slides.sort();
+    anyhow::ensure!(!slides.is_empty(), "No slides found in {}", path.display());
+    
+    let mut texts = Vec::with_capacity(slides.len());
+    for (_, name) in slides {
+        let mut xml = String::new();
+        archive.by_name(&name)?.read_to_string(&mut xml)?;
+        texts.push(slide_text(&xml)?);
+    }
+    Ok(texts)
+}
+
+/// Text of the `a:t` runs in one slide's XML, one line per `a:p` paragraph.
+fn slide_text(xml: &str) -> Result<String> {
+    let mut reader = XmlReader::from_str(xml);
+    let mut text = String::new();
+    let mut in_run = false;
+    loop {
+        match reader.read_event()? {
+            XmlEvent::Start(e) if e.name().as_ref() == b"a:t" => in_run = true,
+            XmlEvent::End(e) if e.name().as_ref() == b"a:t" => in_run = false,
+            XmlEvent::End(e) if e.name().as_ref() == b"a:p" && !text.is_empty() && !text.ends_with('\n') => {
+                text.push('\n');
+            }
+            XmlEvent::Text(t) if in_run => text.push_str(&t.unescape()?),
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text.trim_end().to_string())
+}
+
+/// Text of a Jupyter notebook: markdown cells verbatim and code cells fenced with the
+/// notebook's language, separated by blank lines. With `include_outputs`, the text of
+/// `stream` outputs (what the cell printed) follows its code cell; rich outputs such
+/// as images, HTML and tracebacks are always left out.
+fn notebook_text(json: &str, include_outputs: bool) -> Result<String> {
+    let notebook: serde_json::Value = serde_json::from_str(json)?;
+    let language = notebook["metadata"]["language_info"]["name"].as_str()
+        .or_else(|| notebook["metadata"]["kernelspec"]["language"].as_str())
+        .unwrap_or("python");
+    
+    let mut parts = Vec::new();
+    for cell in notebook["cells"].as_array().map(Vec::as_slice).unwrap_or_default() {
+        let source = notebook_source(&cell["source"]);
+        if source.trim().is_empty() {
+            continue;
+        }
+        match cell["cell_type"].as_str() {
+            Some("markdown") => parts.push(source),
+            Some("code") => {
+                parts.push(format!("```{}\n{}\n```", language, source.trim_end_matches('\n')));
+                if !include_outputs {
+                    continue;
+                }
+                for output in cell["outputs"].as_array().map(Vec::as_slice).unwrap_or_default() {
+                    if output["output_type"] == "stream" {
+                        parts.push(notebook_source(&output["text"]));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(parts.join("\n\n"))
+}
+
+/// Notebook text fields hold either one string or a list of lines.
+fn notebook_source(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(lines) => lines.iter().filter_map(|line| line.as_str()).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Text of an email: a `Subject`/`From`/`To`/`Date` header block, then the body
+/// (the plain text part, or the HTML part without its tags if there is none), then
+/// each text attachment headed by its file name. Other attachments are left out.
+fn eml_text(data: &[u8]) -> Result<String> {
+    let message = MessageParser::default()
+        .parse(data)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse email"))?;
+    
+    let mut headers = Vec::new();
+    if let Some(subject) = message.subject() {
+        headers.push(format!("Subject: {}", subject));
+    }
+    if let Some(from) = message.from() {
+        headers.push(format!("From: {}", format_addresses(from)));
+    }
+    if let Some(to) = message.to() {
+        headers.push(format!("To: {}", format_addresses(to)));
+    }
+    if let Some(date) = message.date() {
+        headers.push(format!("Date: {}", date.to_rfc822()));
+    }
+    let mut sections = Vec::new();
+    if !headers.is_empty() {
+        sections.push(headers.join("\n"));
+    }
+    
+    let plain = message.text_body.iter().filter_map(|&id| message.part(id)).find_map(|part| match &part.body {
+        PartType::Text(text) => Some(text.to_string()),
+        _ => None,
+    });
+    let body = plain.or_else(|| {
+        message.html_body.iter().filter_map(|&id| message.part(id)).find_map(|part| match &part.body {
+            PartType::Html(html) => html2text::from_read(html.as_bytes(), 100).ok(),
+            _ => None,
+        })
+    });
+    sections.extend(body);
+    
+    for attachment in message.attachments() {
+        if let PartType::Text(text) = &attachment.body {
+            let name = attachment.attachment_name().unwrap_or("attachment");
+            sections.push(format!("## {}\n{}", name, text));
+        }
+    }
+    Ok(sections.join("\n\n"))
+}
+
+/// `Name <address>` for each mailbox in an address header, comma-separated.
+fn format_addresses(address: &Address) -> String {
+    address
+        .iter()
+        .map(|addr| match (addr.name(), addr.address()) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Check if a file is likely text by trying to read it as UTF-8
-fn is_valid_utf8_file(path: &PathBuf, max_bytes: usize) -> bool {
+fn is_valid_utf8_file(path: &Path, max_bytes: usize) -> bool {
     if let Ok(file) = fs::File::open(path) {
         use std::io::Read;
         let mut reader = std::io::BufReader::new(file);
@@ -143,12 +485,451 @@ fn is_valid_utf8_file(path: &PathBuf, max_bytes: usize) -> bool {
     false
 }
 
+/// Guess the encoding of a text file's bytes. A byte order mark decides it,
+/// then UTF-16 is recognised by its zero bytes and UTF-8 by being valid. Anything
+/// else is taken as Windows-1252, which covers Latin-1 and is what most legacy
+/// Western text files use.
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if let Some(encoding) = detect_utf16(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    WINDOWS_1252
+}
+
+/// UTF-16 without a byte order mark, told apart by where the zero high bytes of
+/// ASCII characters fall: odd offsets for little-endian, even for big-endian.
+/// Only the first few KB are sampled.
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(4096) & !1];
+    let units = sample.len() / 2;
+    let zeros_at = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    match (zeros_at(0), zeros_at(1)) {
+        (0, odd) if units > 0 && odd * 2 > units => Some(UTF_16LE),
+        (even, 0) if units > 0 && even * 2 > units => Some(UTF_16BE),
+        _ => None,
+    }
+}
+
+/// Transcode the contents of the text file at `path` to UTF-8 from whatever
+/// encoding they appear to use. `path` is only used in the notice printed when
+/// the file is not UTF-8.
+pub fn detect_and_decode(path: &Path, bytes: &[u8]) -> String {
+    let encoding = detect_encoding(bytes);
+    if encoding != UTF_8 {
+        eprintln!("  {}: not UTF-8, decoding as {}", path.display(), encoding.name());
+    }
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+/// Data rows per page when extracting annotated CSV, so each chunk keeps its column names.
+pub const CSV_ROWS_PER_CHUNK: usize = 10;
+
+/// Parse CSV text and format every data row as `column: value, column: value`.
+///
+/// Returns one string per `CSV_ROWS_PER_CHUNK` rows, each row on its own line. Quoting and
+/// newlines inside quoted fields are handled by the parser; such newlines are flattened to
+/// spaces. Rows with more fields than the header name the extras `column N`.
+pub fn annotate_csv(content: &str) -> Result<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+
+    let mut groups = Vec::new();
+    let mut group = String::new();
+    let mut rows = 0;
+    for record in reader.records() {
+        let record = record?;
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let fields: Vec<String> = record
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let value = value.trim().replace(['\r', '\n'], " ");
+                match headers.get(i).filter(|h| !h.is_empty()) {
+                    Some(name) => format!("{}: {}", name, value),
+                    None => format!("column {}: {}", i + 1, value),
+                }
+            })
+            .collect();
+        group.push_str(&fields.join(", "));
+        group.push('\n');
+        rows += 1;
+        if rows % CSV_ROWS_PER_CHUNK == 0 {
+            groups.push(std::mem::take(&mut group));
+        }
+    }
+    if !group.is_empty() {
+        groups.push(group);
+    }
+    Ok(groups)
+}
+
+/// Default number of lines per page when paging plain text files.
+const DEFAULT_LINES_PER_PAGE: usize = 200;
+/// Default line count above which plain text files are paged.
+const DEFAULT_MIN_LINES_FOR_PAGING: usize = 1000;
+/// Default limit on the total decompressed size of a ZIP archive's text entries.
+const DEFAULT_MAX_ARCHIVE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// How documents are read, shared by every entry point that builds a
+/// `PlainTextExtractor` (see `PlainTextExtractor::from_options`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractorOptions {
+    /// Images larger than this (width or height) are downscaled before OCR.
+    pub max_image_dimension: u32,
+    /// Extract CSV rows as `column: value` pairs, `CSV_ROWS_PER_CHUNK` rows per page.
+    pub csv_annotate_columns: bool,
+    /// Passwords tried, in order, on encrypted PDFs; files none of them open are skipped.
+    pub pdf_passwords: Vec<String>,
+    /// Include the printed (`stream`) output of Jupyter notebook code cells.
+    pub notebook_outputs: bool,
+    /// How text files that are not valid UTF-8 are read (skipped by default).
+    pub encoding_fallback: EncodingFallback,
+    /// Tesseract language and page segmentation for images.
+    pub ocr: OcrOptions,
+}
+
+impl Default for ExtractorOptions {
+    fn default() -> Self {
+        Self {
+            max_image_dimension: MAX_IMAGE_DIMENSION,
+            csv_annotate_columns: true,
+            pdf_passwords: Vec::new(),
+            notebook_outputs: false,
+            encoding_fallback: EncodingFallback::default(),
+            ocr: OcrOptions::default(),
+        }
+    }
+}
+
 /// Implementation for extracting text from various file types.
-pub struct PlainTextExtractor;
+#[derive(Debug, Clone)]
+pub struct PlainTextExtractor {
+    /// Lines per page when splitting long text files into pages.
+    pub lines_per_page: usize,
+    /// Text files with more lines than this are processed page-by-page.
+    pub min_lines_for_paging: usize,
+    /// Images larger than this (width or height) are downscaled before OCR.
+    pub max_image_dimension: u32,
+    /// Extract CSV rows as `column: value` pairs, `CSV_ROWS_PER_CHUNK` rows per page.
+    pub csv_annotate_columns: bool,
+    /// Passwords tried, in order, on encrypted PDFs.
+    pub pdf_passwords: Vec<String>,
+    /// ZIP archives whose text entries decompress to more than this are rejected.
+    pub max_archive_bytes: u64,
+    /// Include the printed (`stream`) output of Jupyter notebook code cells.
+    pub notebook_outputs: bool,
+    /// How text files that are not valid UTF-8 are read.
+    pub encoding_fallback: EncodingFallback,
+    /// Tesseract language and page segmentation for images.
+    pub ocr: OcrOptions,
+}
+
+impl Default for PlainTextExtractor {
+    fn default() -> Self {
+        Self::from_options(&ExtractorOptions::default())
+    }
+}
 
 impl PlainTextExtractor {
+    /// Create an extractor with default paging settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create an extractor that reads documents as `options` says, with default
+    /// paging settings and archive size limit.
+    pub fn from_options(options: &ExtractorOptions) -> Self {
+        let options = options.clone();
+        Self {
+            lines_per_page: DEFAULT_LINES_PER_PAGE,
+            min_lines_for_paging: DEFAULT_MIN_LINES_FOR_PAGING,
+            max_image_dimension: options.max_image_dimension.max(1),
+            csv_annotate_columns: options.csv_annotate_columns,
+            pdf_passwords: options.pdf_passwords,
+            max_archive_bytes: DEFAULT_MAX_ARCHIVE_BYTES,
+            notebook_outputs: options.notebook_outputs,
+            encoding_fallback: options.encoding_fallback,
+            ocr: options.ocr,
+        }
+    }
+    
+    /// Set the number of lines per page for long text files.
+    pub fn with_lines_per_page(mut self, lines_per_page: usize) -> Self {
+        self.lines_per_page = lines_per_page.max(1);
+        self
+    }
+    
+    /// Set the line count above which text files are paged.
+    pub fn with_min_lines_for_paging(mut self, min_lines: usize) -> Self {
+        self.min_lines_for_paging = min_lines;
+        self
+    }
+    
+    /// Set the maximum image dimension used before OCR.
+    pub fn with_max_image_dimension(mut self, max_dimension: u32) -> Self {
+        self.max_image_dimension = max_dimension.max(1);
+        self
+    }
+    
+    /// Set whether CSV files are extracted with column-header annotation.
+    pub fn with_csv_annotate_columns(mut self, annotate: bool) -> Self {
+        self.csv_annotate_columns = annotate;
+        self
+    }
+    
+    /// Set the passwords tried on encrypted PDFs.
+    pub fn with_pdf_passwords(mut self, passwords: Vec<String>) -> Self {
+        self.pdf_passwords = passwords;
+        self
+    }
+    
+    /// Set the limit on the total decompressed size of a ZIP archive.
+    pub fn with_max_archive_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_archive_bytes = max_bytes;
+        self
+    }
+    
+    /// Set whether notebook code cells are followed by their printed output.
+    pub fn with_notebook_outputs(mut self, include: bool) -> Self {
+        self.notebook_outputs = include;
+        self
+    }
+    
+    /// Set how text files that are not valid UTF-8 are read.
+    pub fn with_encoding_fallback(mut self, fallback: EncodingFallback) -> Self {
+        self.encoding_fallback = fallback;
+        self
+    }
+    
+    /// Set the Tesseract language and page segmentation used for images.
+    pub fn with_ocr_options(mut self, options: OcrOptions) -> Self {
+        self.ocr = options;
+        self
+    }
+    
+    /// Read a text file, falling back to `encoding_fallback` if it is not valid UTF-8.
+    fn read_text(&self, path: &Path) -> Result<String> {
+        let bytes = fs::read(path)?;
+        let err = match String::from_utf8(bytes) {
+            Ok(text) => return Ok(text),
+            Err(e) => e,
+        };
+        match &self.encoding_fallback {
+            EncodingFallback::Skip => {
+                Err(anyhow::Error::new(err.utf8_error()).context(format!("{} is not valid UTF-8", path.display())))
+            }
+            EncodingFallback::Detect => Ok(detect_and_decode(path, err.as_bytes())),
+            EncodingFallback::ForceEncoding(label) => {
+                let encoding = Encoding::for_label(label.as_bytes())
+                    .ok_or_else(|| anyhow::anyhow!("Unknown encoding '{}'", label))?;
+                eprintln!("  {}: not UTF-8, decoding as {}", path.display(), encoding.name());
+                let (text, _, _) = encoding.decode(err.as_bytes());
+                Ok(text.into_owned())
+            }
+        }
+    }
+    
+    /// Text of each text-file entry in a ZIP archive, as `(entry name, text)` pairs.
+    /// Fails once the entries read so far decompress to more than `max_archive_bytes`,
+    /// so a zip bomb can't fill the disk or memory.
+    fn zip_entries(&self, path: &Path) -> Result<Vec<(String, String)>> {
+        let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+        let mut remaining = self.max_archive_bytes;
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            let ext = Path::new(&name).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+            if !TEXT_EXTENSIONS.contains(&ext.as_str()) {
+                continue;
+            }
+            
+            // Unpacked under the entry's extension so the usual text handling applies
+            let mut temp = NamedTempFile::with_suffix(format!(".{}", ext))?;
+            let written = std::io::copy(&mut Read::take(&mut entry, remaining.saturating_add(1)), &mut temp)?;
+            if written > remaining {
+                anyhow::bail!("ZIP archive {} decompresses to more than {} bytes", path.display(), self.max_archive_bytes);
+            }
+            remaining -= written;
+            let text = self.do_extract(&temp.path().to_path_buf())?;
+            entries.push((name, text));
+        }
+        Ok(entries)
+    }
+    
+    /// Open a PDF, trying each of `pdf_passwords` if it is encrypted.
+    /// Fails with `PasswordProtected` if none of them work.
+    fn open_pdf(&self, data: &mut [u8]) -> Result<PopplerDocument> {
+        match PopplerDocument::new_from_data(data, None) {
+            Ok(doc) => return Ok(doc),
+            Err(e) if e.matches(PopplerEncrypted) => {}
+            Err(e) => anyhow::bail!("Failed to open PDF: {}", e),
+        }
+        for password in &self.pdf_passwords {
+            if let Ok(doc) = PopplerDocument::new_from_data(data, Some(password.as_str())) {
+                return Ok(doc);
+            }
+        }
+        Err(PasswordProtected.into())
+    }
+    
+    /// Whether a file has the `.pdf` extension.
+    fn is_pdf(path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+    }
+    
+    /// A PDF's pages and its properties, from a single parse.
+    fn pdf_pages(&self, path: &Path) -> Result<(Vec<ExtractedPage>, DocumentInfo)> {
+        // Memory-mapped file reading would be ideal here, but poppler needs the data
+        // For now, we still read the file but process pages individually
+        let mut data = fs::read(path)?;
+        let doc = self.open_pdf(&mut data)?;
+        
+        let pages: Vec<_> = doc.pages().collect();
+        let total_pages = pages.len();
+        
+        let mut result = Vec::with_capacity(total_pages);
+        for (page_num, page) in pages.into_iter().enumerate() {
+            let text = page.get_text().unwrap_or_default().to_string();
+            result.push(ExtractedPage {
+                page_num,
+                total_pages,
+                text,
+            });
+        }
+        Ok((result, pdf_info(&doc)))
+    }
+    
+    /// `pdf_pages` with the pages split into one run per Rayon thread. Poppler
+    /// documents can't be shared between threads, so each run parses its own copy.
+    fn pdf_pages_parallel(&self, path: &Path) -> Result<(Vec<ExtractedPage>, DocumentInfo)> {
+        let data = fs::read(path)?;
+        let (total_pages, info) = {
+            let mut copy = data.clone();
+            let doc = self.open_pdf(&mut copy)?;
+            (doc.get_n_pages(), pdf_info(&doc))
+        };
+        
+        let per_run = total_pages.div_ceil(rayon::current_num_threads()).max(1);
+        let runs: Vec<Vec<String>> = (0..total_pages)
+            .step_by(per_run)
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|start| {
+                let mut copy = data.clone();
+                let doc = self.open_pdf(&mut copy)?;
+                Ok((start..(start + per_run).min(total_pages))
+                    .map(|i| doc.get_page(i).and_then(|page| page.get_text().map(str::to_string)).unwrap_or_default())
+                    .collect())
+            })
+            .collect::<Result<_>>()?;
+        
+        let pages = runs.into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(page_num, text)| ExtractedPage { page_num, total_pages, text })
+            .collect();
+        Ok((pages, info))
+    }
+    
+    /// Whether a file is a CSV that should be extracted with column annotation.
+    fn is_annotated_csv(&self, path: &Path) -> bool {
+        self.csv_annotate_columns
+            && path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+    }
+    
+    /// Split text into pages of `lines_per_page` lines each.
+    pub fn extract_as_pages(&self, text: &str) -> Vec<ExtractedPage> {
+        let lines: Vec<&str> = text.lines().collect();
+        let groups: Vec<&[&str]> = lines.chunks(self.lines_per_page.max(1)).collect();
+        let total_pages = groups.len();
+        groups
+            .into_iter()
+            .enumerate()
+            .map(|(page_num, group)| ExtractedPage {
+                page_num,
+                total_pages,
+                text: group.join("\n"),
+            })
+            .collect()
+    }
+    
+    /// Check whether a file has more than `min_lines` lines, reading only as far as needed.
+    fn exceeds_line_count(path: &PathBuf, min_lines: usize) -> bool {
+        let file = match fs::File::open(path) {
+            Ok(f) => f,
+            Err(_) => return false,
+        };
+        let mut reader = std::io::BufReader::new(file);
+        let mut buffer = [0u8; 8192];
+        let mut lines = 0;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => return false,
+                Ok(n) => {
+                    lines += buffer[..n].iter().filter(|&&b| b == b'\n').count();
+                    if lines > min_lines {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Check if a file is plain text that should be read directly (not OCR'd or parsed).
+    fn is_plain_text(path: &PathBuf) -> bool {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        Self::is_text_file(path) && !matches!(ext.as_str(), "pdf" | "png" | "jpg" | "jpeg" | "webp" | "bmp" | "tiff" | "tif" | "docx" | "xlsx" | "pptx" | "odt" | "odp")
+    }
+    
+    /// Check whether a file should be extracted: it must pass the skip lists and be
+    /// either a binary format handled by `do_extract` or a text file.
+    ///
+    /// `skip_extensions` match case-insensitively; `skip_files` are substrings of the file name.
+    /// This is the single definition of "supported" used by file discovery.
+    pub fn is_supported(path: &Path, skip_extensions: &[String], skip_files: &[String]) -> bool {
+        if Self::is_skipped(path, skip_extensions, skip_files) {
+            return false;
+        }
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        EXTRACTED_EXTENSIONS.contains(&ext.as_str()) || Self::is_text_file(path)
+    }
+    
+    /// Check if a file is a ZIP archive, whose text entries `do_extract` returns as pages.
+    /// Not covered by `is_supported`, so archives are only read when asked for.
+    pub fn is_zip_archive(path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    }
+    
+    /// Check a path against the skip lists only.
+    pub fn is_skipped(path: &Path, skip_extensions: &[String], skip_files: &[String]) -> bool {
+        if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
+            if skip_files.iter().any(|pattern| filename.contains(pattern.as_str())) {
+                return true;
+            }
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => skip_extensions.iter().any(|s| s.eq_ignore_ascii_case(ext)),
+            None => false,
+        }
+    }
+    
     /// Check if file is a supported text file
-    pub fn is_text_file(path: &PathBuf) -> bool {
+    pub fn is_text_file(path: &Path) -> bool {
         // Check extension
         if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
             let ext_lower = ext.to_lowercase();
@@ -175,10 +956,14 @@ impl PlainTextExtractor {
     fn do_extract(&self, path: &PathBuf) -> Result<String> {
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
         
+        if self.is_annotated_csv(path) {
+            let groups = annotate_csv(&self.read_text(path)?)?;
+            return Ok(groups.join("\n"));
+        }
+        
         // Check for text files first (including code, config, no-extension)
-        if Self::is_text_file(path) && !matches!(ext.as_str(), "pdf" | "png" | "jpg" | "jpeg" | "webp" | "bmp" | "tiff" | "tif" | "docx" | "xlsx" | "pptx" | "odt" | "odp") {
-            let text = fs::read_to_string(path)?;
-            return Ok(text);
+        if Self::is_plain_text(path) {
+            return self.read_text(path);
         }
         
         match ext.as_str() {
@@ -220,12 +1005,22 @@ impl PlainTextExtractor {
                 let text = html2text::from_read(html_content.as_bytes(), 100)?;
                 Ok(text)
             }
+            "epub" => Ok(epub_chapters(path)?.join("\n\n")),
+            "ipynb" => notebook_text(&fs::read_to_string(path)?, self.notebook_outputs),
+            "eml" => eml_text(&fs::read(path)?),
+            "zip" => {
+                let entries: Vec<String> = self.zip_entries(path)?
+                    .into_iter()
+                    .map(|(name, text)| format!("## {}\n{}", name, text))
+                    .collect();
+                Ok(entries.join("\n\n"))
+            }
             // Images
             "png" | "jpg" | "jpeg" | "webp" | "bmp" | "tiff" | "tif" => {
                 // Preprocess image (resize if needed)
-                let (ocr_path, _temp_file) = preprocess_image(path)?;
+                let (ocr_path, _temp_file) = preprocess_image(path, self.max_image_dimension)?;
                 
-                let mut lt = LepTess::new(None, "eng")?;
+                let mut lt = self.ocr.tesseract()?;
                 lt.set_image(&ocr_path)?;
                 let text = lt.get_utf8_text()?;
                 
@@ -234,8 +1029,7 @@ impl PlainTextExtractor {
             }
             "pdf" => {
                 let mut data = fs::read(path)?;
-                let doc = PopplerDocument::new_from_data(&mut data, None)
-                    .map_err(|e| anyhow::anyhow!("Failed to open PDF: {:?}", e))?;
+                let doc = self.open_pdf(&mut data)?;
                 
                 let mut text = String::new();
                 for page in doc.pages() {
@@ -268,27 +1062,60 @@ impl PagedExtractor for PlainTextExtractor {
     fn extract_pages(&self, path: &PathBuf) -> Result<Vec<ExtractedPage>> {
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
         
+        if self.is_annotated_csv(path) {
+            // One page per group of rows, so no chunk mixes groups
+            let groups = annotate_csv(&self.read_text(path)?)?;
+            let total_pages = groups.len();
+            return Ok(groups
+                .into_iter()
+                .enumerate()
+                .map(|(page_num, text)| ExtractedPage { page_num, total_pages, text })
+                .collect());
+        }
+        
         match ext.as_str() {
-            "pdf" => {
-                // Memory-mapped file reading would be ideal here, but poppler needs the data
-                // For now, we still read the file but process pages individually
-                let mut data = fs::read(path)?;
-                let doc = PopplerDocument::new_from_data(&mut data, None)
-                    .map_err(|e| anyhow::anyhow!("Failed to open PDF: {:?}", e))?;
-                
-                let pages: Vec<_> = doc.pages().collect();
-                let total_pages = pages.len();
-                
-                let mut result = Vec::with_capacity(total_pages);
-                for (page_num, page) in pages.into_iter().enumerate() {
-                    let text = page.get_text().unwrap_or_default().to_string();
-                    result.push(ExtractedPage {
+            "pdf" => Ok(self.pdf_pages(path)?.0),
+            "epub" => {
+                // One page per chapter, so long books are chunked a chapter at a time
+                let chapters = epub_chapters(path)?;
+                let total_pages = chapters.len();
+                Ok(chapters
+                    .into_iter()
+                    .enumerate()
+                    .map(|(page_num, text)| ExtractedPage { page_num, total_pages, text })
+                    .collect())
+            }
+            "pptx" => {
+                // One page per slide; whole-deck extraction if the slides can't be read
+                let slides = match pptx_slides(path) {
+                    Ok(slides) => slides,
+                    Err(_) => vec![self.do_extract(path)?],
+                };
+                let total_pages = slides.len();
+                Ok(slides
+                    .into_iter()
+                    .enumerate()
+                    .map(|(page_num, text)| ExtractedPage { page_num, total_pages, text })
+                    .collect())
+            }
+            "zip" => {
+                // One page per entry, headed by its name inside the archive
+                let entries = self.zip_entries(path)?;
+                let total_pages = entries.len();
+                Ok(entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(page_num, (name, text))| ExtractedPage {
                         page_num,
                         total_pages,
-                        text,
-                    });
-                }
-                Ok(result)
+                        text: format!("## {}\n{}", name, text),
+                    })
+                    .collect())
+            }
+            _ if Self::is_plain_text(path) => {
+                // Long text files: fixed line-count pages for checkpointing
+                let text = self.read_text(path)?;
+                Ok(self.extract_as_pages(&text))
             }
             _ => {
                 // Non-paged documents: return single page with all content
@@ -304,7 +1131,35 @@ impl PagedExtractor for PlainTextExtractor {
     
     fn is_paged(&self, path: &PathBuf) -> bool {
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
-        ext == "pdf"
+        if matches!(ext.as_str(), "pdf" | "epub" | "pptx" | "zip") || self.is_annotated_csv(path) {
+            return true;
+        }
+        Self::is_plain_text(path) && Self::exceeds_line_count(path, self.min_lines_for_paging)
+    }
+    
+    fn extract_info(&self, path: &PathBuf) -> Result<DocumentInfo> {
+        if !Self::is_pdf(path) {
+            return Ok(DocumentInfo::default());
+        }
+        let mut data = fs::read(path)?;
+        Ok(pdf_info(&self.open_pdf(&mut data)?))
+    }
+    
+    fn extract_pages_with_info(&self, path: &PathBuf) -> Result<(Vec<ExtractedPage>, Result<DocumentInfo>)> {
+        if Self::is_pdf(path) {
+            let (pages, info) = self.pdf_pages(path)?;
+            return Ok((pages, Ok(info)));
+        }
+        // Only PDFs record properties
+        Ok((self.extract_pages(path)?, Ok(DocumentInfo::default())))
+    }
+    
+    fn extract_pages_parallel(&self, path: &PathBuf) -> Result<(Vec<ExtractedPage>, Result<DocumentInfo>)> {
+        if Self::is_pdf(path) {
+            let (pages, info) = self.pdf_pages_parallel(path)?;
+            return Ok((pages, Ok(info)));
+        }
+        self.extract_pages_with_info(path)
     }
 }
 
@@ -330,10 +1185,390 @@ mod tests {
 
     #[tokio::test]
     async fn test_plain_text_extraction() {
-        let extractor = PlainTextExtractor;
+        let extractor = PlainTextExtractor::default();
         let path = PathBuf::from("src/lib.rs");
         let result = extractor.extract_text(&path).await;
         assert!(result.is_ok());
         assert!(result.unwrap().contains("OcrEngine"));
     }
+
+    #[test]
+    fn test_text_file_paging() {
+        let file = NamedTempFile::with_suffix(".txt").unwrap();
+        let content: Vec<String> = (0..600).map(|i| format!("line {}", i)).collect();
+        fs::write(file.path(), content.join("\n")).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let extractor = PlainTextExtractor::default().with_lines_per_page(200);
+        let pages = extractor.extract_pages(&path).unwrap();
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|p| p.total_pages == 3));
+        assert!(pages[1].text.starts_with("line 200"));
+        
+        // 600 lines is below the default paging threshold
+        assert!(!extractor.is_paged(&path));
+        assert!(extractor.with_min_lines_for_paging(500).is_paged(&path));
+    }
+
+    #[test]
+    fn test_csv_column_annotation() {
+        let mut file = NamedTempFile::with_suffix(".csv").unwrap();
+        let mut content = String::from("name,city,score\n");
+        for i in 0..20 {
+            content.push_str(&format!("person{},\"Springfield, IL\",{}\n", i, i * 10));
+        }
+        std::io::Write::write_all(&mut file, content.as_bytes()).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let extractor = PlainTextExtractor::default();
+        assert!(extractor.is_paged(&path));
+        let pages = extractor.extract_pages(&path).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].text.lines().count(), CSV_ROWS_PER_CHUNK);
+        assert!(pages[0].text.starts_with("name: person0, city: Springfield, IL, score: 0\n"));
+        assert!(pages[1].text.contains("name: person19, city: Springfield, IL, score: 190"));
+        
+        // Disabled: the file is read as plain text
+        let raw = PlainTextExtractor::default().with_csv_annotate_columns(false);
+        assert!(!raw.is_paged(&path));
+        assert_eq!(raw.extract_text_sync(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_annotate_csv_quoting() {
+        let groups = annotate_csv("title,notes\n\"A \"\"quoted\"\" title\",\"line one\nline two\"\nplain,x,extra\n").unwrap();
+        assert_eq!(groups, vec![
+            "title: A \"quoted\" title, notes: line one line two\ntitle: plain, notes: x, column 3: extra\n".to_string(),
+        ]);
+    }
+
+    /// A two-chapter EPUB, zipped in memory.
+    fn test_epub() -> Vec<u8> {
+        use std::io::Write;
+        let container = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+        let opf = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="id">test-book</dc:identifier>
+    <dc:title>Test Book</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+        let chapter = |title: &str, body: &str| format!(
+            "<html xmlns=\"http://www.w3.org/1999/xhtml\"><body><h1>{}</h1><p>{}</p></body></html>", title, body
+        );
+        let entries = [
+            ("mimetype", "application/epub+zip".to_string()),
+            ("META-INF/container.xml", container.to_string()),
+            ("OEBPS/content.opf", opf.to_string()),
+            ("OEBPS/ch1.xhtml", chapter("Chapter One", "The lighthouse keeper woke early.")),
+            ("OEBPS/ch2.xhtml", chapter("Chapter Two", "A storm rolled in from the west.")),
+        ];
+        
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in entries {
+            zip.start_file(name, stored).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_epub_chapters_as_pages() {
+        let file = NamedTempFile::with_suffix(".epub").unwrap();
+        fs::write(file.path(), test_epub()).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let extractor = PlainTextExtractor::default();
+        assert!(extractor.is_paged(&path));
+        let pages = extractor.extract_pages(&path).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|p| p.total_pages == 2));
+        assert!(pages[0].text.contains("lighthouse keeper"));
+        assert!(pages[1].text.contains("storm rolled in"));
+        
+        let text = extractor.extract_text_sync(&path).unwrap();
+        assert!(text.contains("lighthouse keeper"));
+        assert!(text.contains("\n\n"));
+        assert!(text.find("lighthouse").unwrap() < text.find("storm").unwrap());
+    }
+
+    /// A three-slide PPTX built in memory, holding only the slide parts.
+    fn test_pptx() -> Vec<u8> {
+        use std::io::Write;
+        let slide = |paragraphs: &[&str]| {
+            let body: String = paragraphs
+                .iter()
+                .map(|p| format!("<a:p><a:r><a:rPr lang=\"en-US\"/><a:t>{}</a:t></a:r></a:p>", p))
+                .collect();
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?><p:sld xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+                 xmlns:p=\"http://schemas.openxmlformats.org/presentationml/2006/main\"><p:cSld><p:spTree><p:sp><p:txBody>{}</p:txBody></p:sp></p:spTree></p:cSld></p:sld>",
+                body
+            )
+        };
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        // Stored out of order, as nothing guarantees entry order in the archive
+        for (name, content) in [
+            ("ppt/slides/slide2.xml", slide(&["Revenue", "Up 12% year over year"])),
+            ("ppt/slides/slide1.xml", slide(&["Q3 Results"])),
+            ("ppt/slides/slide3.xml", slide(&["Questions &amp; answers"])),
+            ("ppt/slides/_rels/slide1.xml.rels", String::new()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_pptx_slides_as_pages() {
+        let file = NamedTempFile::with_suffix(".pptx").unwrap();
+        fs::write(file.path(), test_pptx()).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let extractor = PlainTextExtractor::default();
+        assert!(extractor.is_paged(&path));
+        let pages = extractor.extract_pages(&path).unwrap();
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|p| p.total_pages == 3));
+        assert_eq!(pages[0].text, "Q3 Results");
+        assert_eq!(pages[1].text, "Revenue\nUp 12% year over year");
+        assert_eq!(pages[2].text, "Questions & answers");
+    }
+
+    /// A ZIP of two notes and an image, built in memory.
+    fn test_zip() -> Vec<u8> {
+        use std::io::Write;
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.add_directory("vault/", options).unwrap();
+        for (name, content) in [
+            ("vault/groceries.md", "# Groceries\neggs, flour, butter".as_bytes()),
+            ("vault/photo.png", &[0x89, b'P', b'N', b'G'][..]),
+            ("vault/todo.txt", "call the plumber".as_bytes()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_zip_entries_as_pages() {
+        let file = NamedTempFile::with_suffix(".zip").unwrap();
+        fs::write(file.path(), test_zip()).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let extractor = PlainTextExtractor::default();
+        assert!(extractor.is_paged(&path));
+        let pages = extractor.extract_pages(&path).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].text, "## vault/groceries.md\n# Groceries\neggs, flour, butter");
+        assert_eq!(pages[1].text, "## vault/todo.txt\ncall the plumber");
+        
+        let text = extractor.extract_text_sync(&path).unwrap();
+        assert_eq!(text, format!("{}\n\n{}", pages[0].text, pages[1].text));
+    }
+
+    #[test]
+    fn test_zip_decompressed_size_limit() {
+        let file = NamedTempFile::with_suffix(".zip").unwrap();
+        fs::write(file.path(), test_zip()).unwrap();
+        let path = file.path().to_path_buf();
+        
+        // The two text entries hold 47 bytes between them
+        assert!(PlainTextExtractor::default().with_max_archive_bytes(47).extract_pages(&path).is_ok());
+        let err = PlainTextExtractor::default().with_max_archive_bytes(46).extract_pages(&path).unwrap_err();
+        assert!(err.to_string().contains("decompresses to more than 46 bytes"));
+    }
+
+    const TEST_NOTEBOOK: &str = r##"{
+        "metadata": {"language_info": {"name": "python"}},
+        "nbformat": 4,
+        "cells": [
+            {"cell_type": "markdown", "metadata": {}, "source": ["# Loading data\n", "Read the survey results."]},
+            {"cell_type": "code", "metadata": {}, "execution_count": 1, "source": "import pandas as pd\ndf = pd.read_csv('survey.csv')\nprint(len(df))\n",
+             "outputs": [
+                {"output_type": "stream", "name": "stdout", "text": ["1204\n"]},
+                {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo="}, "metadata": {}}
+             ]},
+            {"cell_type": "code", "metadata": {}, "execution_count": null, "source": [], "outputs": []}
+        ]
+    }"##;
+
+    #[test]
+    fn test_notebook_cells() {
+        let text = notebook_text(TEST_NOTEBOOK, false).unwrap();
+        assert_eq!(text, "# Loading data\nRead the survey results.\n\n```python\nimport pandas as pd\ndf = pd.read_csv('survey.csv')\nprint(len(df))\n```");
+        
+        // Only the stream output is kept, never the image
+        let with_outputs = notebook_text(TEST_NOTEBOOK, true).unwrap();
+        assert!(with_outputs.ends_with("print(len(df))\n```\n\n1204\n"));
+        assert!(!with_outputs.contains("iVBORw0KGgo"));
+    }
+
+    #[test]
+    fn test_notebook_file_extraction() {
+        let file = NamedTempFile::with_suffix(".ipynb").unwrap();
+        fs::write(file.path(), TEST_NOTEBOOK).unwrap();
+        let path = file.path().to_path_buf();
+        
+        let text = PlainTextExtractor::default().extract_text_sync(&path).unwrap();
+        assert!(text.starts_with("# Loading data"));
+        assert!(!text.contains("1204"));
+        let text = PlainTextExtractor::default().with_notebook_outputs(true).extract_text_sync(&path).unwrap();
+        assert!(text.contains("1204"));
+    }
+
+    #[test]
+    fn test_latin1_encoding_fallback() {
+        let file = NamedTempFile::with_suffix(".txt").unwrap();
+        // "café, über, mañana" in Latin-1
+        fs::write(file.path(), b"caf\xe9, \xfcber, ma\xf1ana").unwrap();
+        let path = file.path().to_path_buf();
+        
+        assert!(PlainTextExtractor::default().extract_text_sync(&path).is_err());
+        assert_eq!(detect_and_decode(&path, &fs::read(&path).unwrap()), "café, über, mañana");
+        let detect = PlainTextExtractor::default().with_encoding_fallback(EncodingFallback::Detect);
+        assert_eq!(detect.extract_text_sync(&path).unwrap(), "café, über, mañana");
+        assert_eq!(detect.extract_pages(&path).unwrap()[0].text, "café, über, mañana");
+        let forced = PlainTextExtractor::default().with_encoding_fallback("latin1".parse().unwrap());
+        assert_eq!(forced.extract_text_sync(&path).unwrap(), "café, über, mañana");
+        let from_options = PlainTextExtractor::from_options(&ExtractorOptions {
+            encoding_fallback: EncodingFallback::Detect,
+            ..Default::default()
+        });
+        assert_eq!(from_options.extract_text_sync(&path).unwrap(), "café, über, mañana");
+        
+        // UTF-8 files are read as they are
+        fs::write(file.path(), "café, über, mañana").unwrap();
+        assert_eq!(forced.extract_text_sync(&path).unwrap(), "café, über, mañana");
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding("café".as_bytes()), UTF_8);
+        assert_eq!(detect_encoding(b"caf\xe9"), WINDOWS_1252);
+        assert_eq!(detect_encoding(b"\xff\xfec\0a\0"), UTF_16LE);
+        let le: Vec<u8> = "plain notes".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(detect_encoding(&le), UTF_16LE);
+        let be: Vec<u8> = "plain notes".encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(detect_encoding(&be), UTF_16BE);
+        assert_eq!(detect_and_decode(Path::new("notes.txt"), &be), "plain notes");
+    }
+
+    #[test]
+    fn test_parse_encoding_fallback() {
+        assert_eq!("Detect".parse::<EncodingFallback>().unwrap(), EncodingFallback::Detect);
+        assert_eq!("skip".parse::<EncodingFallback>().unwrap(), EncodingFallback::Skip);
+        assert_eq!("windows-1252".parse::<EncodingFallback>().unwrap(), EncodingFallback::ForceEncoding("windows-1252".to_string()));
+        assert!("klingon".parse::<EncodingFallback>().is_err());
+    }
+
+    #[test]
+    fn test_ocr_language() {
+        assert_eq!(OcrOptions::new("eng, fra").unwrap().language, "eng+fra");
+        assert_eq!(OcrOptions::new("chi_sim+eng").unwrap().language, "chi_sim+eng");
+        assert!(OcrOptions::new("").is_err());
+        assert!(OcrOptions::new("eng+").is_err());
+        assert!(OcrOptions::new("../eng").is_err());
+        
+        // A language without traineddata fails the extraction instead of panicking
+        let file = NamedTempFile::with_suffix(".png").unwrap();
+        image::RgbImage::from_pixel(20, 20, image::Rgb([255, 255, 255])).save(file.path()).unwrap();
+        let options = OcrOptions::new("zzz_missing").unwrap();
+        let extractor = PlainTextExtractor::default().with_ocr_options(options);
+        let err = extractor.extract_text_sync(&file.path().to_path_buf()).unwrap_err();
+        assert!(err.to_string().contains("zzz_missing"));
+        
+        let nul = PlainTextExtractor::default().with_ocr_options(OcrOptions { language: "eng\0".to_string(), psm: 3 });
+        assert!(nul.extract_text_sync(&file.path().to_path_buf()).is_err());
+    }
+
+    const TEST_EML: &str = "From: Alice Smith <alice@example.com>\r
+To: Bob <bob@example.com>\r
+Subject: Lease renewal\r
+Date: Tue, 14 May 2024 09:30:00 +0000\r
+MIME-Version: 1.0\r
+Content-Type: multipart/mixed; boundary=\"sep\"\r
+\r
+--sep\r
+Content-Type: text/plain; charset=utf-8\r
+\r
+The landlord agreed to keep the rent unchanged.\r
+--sep\r
+Content-Type: text/plain; charset=utf-8\r
+Content-Disposition: attachment; filename=\"terms.txt\"\r
+\r
+Security deposit: two months.\r
+--sep\r
+Content-Type: application/octet-stream\r
+Content-Disposition: attachment; filename=\"scan.bin\"\r
+Content-Transfer-Encoding: base64\r
+\r
+AAECAw==\r
+--sep--\r
+";
+
+    #[test]
+    fn test_eml_extraction() {
+        let file = NamedTempFile::with_suffix(".eml").unwrap();
+        fs::write(file.path(), TEST_EML).unwrap();
+        let text = PlainTextExtractor::default().extract_text_sync(&file.path().to_path_buf()).unwrap();
+        
+        assert!(text.contains("Subject: Lease renewal"));
+        assert!(text.contains("From: Alice Smith <alice@example.com>"));
+        assert!(text.contains("To: Bob <bob@example.com>"));
+        assert!(text.contains("Date: "));
+        assert!(text.contains("The landlord agreed to keep the rent unchanged."));
+        assert!(text.contains("## terms.txt\nSecurity deposit: two months."));
+        assert!(!text.contains("scan.bin"));
+        assert!(text.find("Subject:").unwrap() < text.find("landlord").unwrap());
+        assert!(text.find("landlord").unwrap() < text.find("Security deposit").unwrap());
+    }
+
+    #[test]
+    fn test_eml_html_only_body() {
+        let eml = "Subject: Newsletter\r\nContent-Type: text/html\r\n\r\n<html><body><p>Spring <b>sale</b> starts Monday</p></body></html>\r\n";
+        let text = eml_text(eml.as_bytes()).unwrap();
+        assert!(text.starts_with("Subject: Newsletter\n\n"));
+        assert!(text.contains("Spring") && text.contains("starts Monday"));
+        assert!(!text.contains("<p>"));
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let none: Vec<String> = vec![];
+        for ext in TEXT_EXTENSIONS.iter().chain(EXTRACTED_EXTENSIONS) {
+            let path = PathBuf::from(format!("file.{}", ext));
+            assert!(PlainTextExtractor::is_supported(&path, &none, &none), "{} not supported", ext);
+        }
+        assert!(PlainTextExtractor::is_supported(Path::new("Makefile"), &none, &none));
+        assert!(PlainTextExtractor::is_supported(Path::new("notes.MD"), &none, &none));
+        assert!(!PlainTextExtractor::is_supported(Path::new("setup.exe"), &none, &none));
+        assert!(!PlainTextExtractor::is_supported(Path::new("archive.7z"), &none, &none));
+        assert!(!PlainTextExtractor::is_supported(Path::new("archive.zip"), &none, &none));
+        assert!(PlainTextExtractor::is_zip_archive(Path::new("archive.ZIP")));
+        
+        // Skip lists
+        let skip_ext = vec!["PNG".to_string()];
+        let skip_files = vec!["secret".to_string()];
+        assert!(!PlainTextExtractor::is_supported(Path::new("photo.png"), &skip_ext, &none));
+        assert!(!PlainTextExtractor::is_supported(Path::new("my_secret.txt"), &none, &skip_files));
+        assert!(PlainTextExtractor::is_supported(Path::new("public.txt"), &skip_ext, &skip_files));
+    }
 }
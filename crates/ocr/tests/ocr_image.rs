@@ -10,7 +10,7 @@ async fn test_image_ocr() -> Result<()> {
         eprintln!("ocr_test.png not found, skipping image OCR test");
         return Ok(());
     }
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&path).await?;
     let expected = "HOW TO COMBINE\nTEXT AND IMAGE\nIN ELEARNING DESIGN";
     println!("Expected OCR text:\n{}\nExtracted OCR text:\n{}", expected, text);
@@ -1,4 +1,4 @@
-use ocr::{OcrEngine, PlainTextExtractor};
+use ocr::{OcrEngine, PagedExtractor, PasswordProtected, PlainTextExtractor};
 use std::path::PathBuf;
 use anyhow::Result;
 
@@ -10,9 +10,50 @@ async fn test_pdf_text_extraction() -> Result<()> {
         eprintln!("brainfuck.pdf not found, skipping PDF extraction test");
         return Ok(());
     }
-    let extractor = PlainTextExtractor;
+    let extractor = PlainTextExtractor::default();
     let text = extractor.extract_text(&path).await?;
     println!("Extracted PDF text:\n{}", text);
     assert!(!text.trim().is_empty(), "PDF extraction should return some text");
     Ok(())
 }
+
+#[tokio::test]
+async fn test_password_protected_pdf() -> Result<()> {
+    // RC4-encrypted with the user password "hunter2"
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/protected.pdf");
+
+    let err = PlainTextExtractor::default().extract_text(&path).await.unwrap_err();
+    assert!(err.is::<PasswordProtected>());
+
+    let extractor = PlainTextExtractor::default()
+        .with_pdf_passwords(vec!["wrong".to_string(), "hunter2".to_string()]);
+    let text = extractor.extract_text(&path).await?;
+    assert!(text.contains("Encrypted fixture text"));
+    Ok(())
+}
+
+#[test]
+fn test_pdf_document_info() -> Result<()> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/brainfuck.pdf");
+    let info = PlainTextExtractor::default().extract_info(&path)?;
+    // The fixture has a title but no author; either way reading it must not fail
+    if let Some(title) = &info.title {
+        assert!(title.contains("Brainfuck"), "unexpected title {:?}", title);
+    }
+    assert!(info.author.as_deref().is_none_or(|a| !a.trim().is_empty()));
+
+    // Extracting the pages reads the same properties from the same parse
+    let (pages, with_pages) = PlainTextExtractor::default().extract_pages_with_info(&path)?;
+    assert!(!pages.is_empty());
+    assert_eq!(with_pages?, info);
+    
+    // Reading the pages on several threads gives the same result
+    let (parallel_pages, parallel_info) = PlainTextExtractor::default().extract_pages_parallel(&path)?;
+    assert_eq!(parallel_pages, pages);
+    assert_eq!(parallel_info?, info);
+
+    // Formats without document properties report none
+    let text = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs");
+    assert_eq!(PlainTextExtractor::default().extract_info(&text)?, Default::default());
+    Ok(())
+}
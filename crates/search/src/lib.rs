@@ -3,24 +3,170 @@
 // Provides a trait for hybrid (vector + lexical) search and ranking.
 
 use async_trait::async_trait;
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use store::{DocumentMetadata, InMemoryVectorStore, SearchOptions, SearchResult, VectorStore};
 
 /// Query for hybrid search (text, embedding, options).
 pub struct HybridSearchQuery {
 	pub text: String,
 	pub embedding: Option<Vec<f32>>,
 	pub top_k: usize,
+	/// Only return chunks of files whose path matches this glob.
+	pub filter_path: Option<String>,
 	// TODO: Add filters, etc.
 }
 
 /// Result of a hybrid search.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct HybridSearchResult {
+	pub doc_id: String,
+	#[serde(serialize_with = "serialize_path_lossy")]
 	pub file_path: PathBuf,
+	pub chunk_index: usize,
 	pub score: f32,
 	pub snippet: Option<String>,
 	pub metadata: Option<String>, // TODO: Replace with richer metadata
+	/// Which search produced the result: "semantic", "lexical" or "hybrid".
+	pub source: String,
+	/// 0-based rank in the semantic results, if the result appeared there.
+	pub semantic_rank: Option<usize>,
+	/// 0-based rank in the lexical results, if the result appeared there.
+	pub lexical_rank: Option<usize>,
+	/// Snippets of neighbouring chunks (empty unless context was requested).
+	pub context_chunks: Vec<String>,
+}
+
+/// Every field, so new fields reach JSON consumers without further changes.
+impl From<HybridSearchResult> for serde_json::Value {
+	fn from(result: HybridSearchResult) -> Self {
+		// Paths are written lossily and non-finite scores become null, so this cannot fail
+		serde_json::to_value(result).unwrap_or(serde_json::Value::Null)
+	}
+}
+
+/// The chunk as a store search result, for helpers such as [`group_by_file`].
+impl From<HybridSearchResult> for SearchResult {
+	fn from(result: HybridSearchResult) -> Self {
+		let file_type = result.file_path
+			.extension()
+			.map(|e| e.to_string_lossy().to_lowercase())
+			.unwrap_or_default();
+		SearchResult {
+			doc_id: result.doc_id.clone(),
+			score: result.score,
+			snippet: result.snippet.clone(),
+			metadata: DocumentMetadata {
+				doc_id: result.doc_id,
+				file_path: result.file_path,
+				file_type,
+				chunk_index: result.chunk_index,
+				snippet: result.snippet,
+				byte_start: 0,
+				byte_end: 0,
+				..Default::default()
+			},
+			context_chunks: result.context_chunks,
+		}
+	}
+}
+
+/// Serialize a path as a string, replacing invalid UTF-8 instead of failing.
+fn serialize_path_lossy<S: Serializer>(path: &Path, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+	serializer.serialize_str(&path.to_string_lossy())
+}
+
+/// Parameters of Reciprocal Rank Fusion: every result adds
+/// `weight / (k + rank + 1)` to its document's score, with `rank` 0-based.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RrfParams {
+	/// Smoothing constant. Lower values widen the gap between top and lower ranks,
+	/// higher values flatten it.
+	pub k: f64,
+	/// Multiplier for the semantic results' scores; 0 leaves them out.
+	pub semantic_weight: f64,
+	/// Multiplier for the lexical results' scores; 0 leaves them out.
+	pub lexical_weight: f64,
+}
+
+impl Default for RrfParams {
+	fn default() -> Self {
+		Self { k: 60.0, semantic_weight: 1.0, lexical_weight: 1.0 }
+	}
+}
+
+/// A document's fused score and its rank in each ranking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedRank {
+	pub doc_id: String,
+	pub score: f32,
+	/// 0-based rank in the semantic results, if the document appeared there.
+	pub semantic_rank: Option<usize>,
+	/// 0-based rank in the lexical results, if the document appeared there.
+	pub lexical_rank: Option<usize>,
+}
+
+/// Fuse semantic and lexical rankings of doc IDs (best first) with Reciprocal Rank
+/// Fusion, returning documents best first. A ranking whose weight is 0 is ignored,
+/// so its documents don't appear at all. Equal scores keep semantic order, then lexical.
+pub fn reciprocal_rank_fusion(semantic: &[&str], lexical: &[&str], params: &RrfParams) -> Vec<FusedRank> {
+	let mut fused: Vec<FusedRank> = Vec::new();
+	let mut positions: HashMap<&str, usize> = HashMap::new();
+	for (ranking, weight, is_semantic) in [(semantic, params.semantic_weight, true), (lexical, params.lexical_weight, false)] {
+		if weight == 0.0 {
+			continue;
+		}
+		for (rank, &doc_id) in ranking.iter().enumerate() {
+			let i = *positions.entry(doc_id).or_insert_with(|| {
+				fused.push(FusedRank { doc_id: doc_id.to_string(), score: 0.0, semantic_rank: None, lexical_rank: None });
+				fused.len() - 1
+			});
+			fused[i].score += (weight / (params.k + rank as f64 + 1.0)) as f32;
+			if is_semantic {
+				fused[i].semantic_rank = Some(rank);
+			} else {
+				fused[i].lexical_rank = Some(rank);
+			}
+		}
+	}
+	fused.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+	fused
+}
+
+/// The search results from one file.
+#[derive(Debug, Clone)]
+pub struct FileSearchResult {
+	pub file_path: PathBuf,
+	/// Best score among the file's results.
+	pub score: f32,
+	/// The file's best results, best first.
+	pub chunks: Vec<SearchResult>,
+}
+
+/// Merge results from the same file, keeping at most `max_chunks_per_file` of
+/// each file's best chunks (0 keeps them all). Files are ordered by their best
+/// score; ties keep the order in which the files first appeared.
+pub fn group_by_file(results: Vec<SearchResult>, max_chunks_per_file: usize) -> Vec<FileSearchResult> {
+	let mut files: Vec<FileSearchResult> = Vec::new();
+	let mut positions: HashMap<PathBuf, usize> = HashMap::new();
+	for result in results {
+		let i = *positions.entry(result.metadata.file_path.clone()).or_insert_with(|| {
+			files.push(FileSearchResult { file_path: result.metadata.file_path.clone(), score: f32::NEG_INFINITY, chunks: Vec::new() });
+			files.len() - 1
+		});
+		files[i].score = files[i].score.max(result.score);
+		files[i].chunks.push(result);
+	}
+	for file in &mut files {
+		file.chunks.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+		if max_chunks_per_file > 0 {
+			file.chunks.truncate(max_chunks_per_file);
+		}
+	}
+	files.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+	files
 }
 
 /// Trait for hybrid search and ranking.
@@ -30,11 +176,146 @@ pub trait HybridSearch: Send + Sync {
 }
 
 // Example stub implementation (to be replaced with real hybrid search backend)
-pub struct DummyHybridSearch;
+/// Semantic-only search over an in-memory store; queries without an embedding return nothing.
+#[derive(Clone, Default)]
+pub struct DummyHybridSearch {
+	pub store: InMemoryVectorStore,
+}
+
+impl DummyHybridSearch {
+	pub fn new(store: InMemoryVectorStore) -> Self {
+		Self { store }
+	}
+}
 
 #[async_trait]
 impl HybridSearch for DummyHybridSearch {
-	async fn search(&self, _query: HybridSearchQuery) -> Result<Vec<HybridSearchResult>> {
-		Ok(vec![])
+	async fn search(&self, query: HybridSearchQuery) -> Result<Vec<HybridSearchResult>> {
+		let Some(embedding) = query.embedding else {
+			return Ok(vec![]);
+		};
+		let filter_path = query.filter_path
+			.map(|p| glob::Pattern::new(&p).with_context(|| format!("Invalid path filter '{}'", p)))
+			.transpose()?;
+		let options = SearchOptions::new(query.top_k).with_filter_path(filter_path);
+		let results = self.store.search(embedding, &options).await?;
+		Ok(results.into_iter().enumerate().map(|(rank, r)| HybridSearchResult {
+			doc_id: r.doc_id,
+			file_path: r.metadata.file_path,
+			chunk_index: r.metadata.chunk_index,
+			score: r.score,
+			snippet: r.snippet,
+			source: "semantic".to_string(),
+			semantic_rank: Some(rank),
+			..Default::default()
+		}).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_result_to_json_has_all_fields() {
+		let result = HybridSearchResult {
+			doc_id: "abc".to_string(),
+			file_path: PathBuf::from("/docs/notes.md"),
+			chunk_index: 3,
+			score: 0.5,
+			snippet: Some("hello".to_string()),
+			source: "hybrid".to_string(),
+			semantic_rank: Some(0),
+			lexical_rank: None,
+			context_chunks: vec!["before".to_string()],
+			..Default::default()
+		};
+		let value = serde_json::Value::from(result);
+		assert_eq!(value["doc_id"], "abc");
+		assert_eq!(value["file_path"], "/docs/notes.md");
+		assert_eq!(value["chunk_index"], 3);
+		assert_eq!(value["score"], 0.5);
+		assert_eq!(value["source"], "hybrid");
+		assert_eq!(value["semantic_rank"], 0);
+		assert!(value["lexical_rank"].is_null());
+		assert_eq!(value["context_chunks"], serde_json::json!(["before"]));
+	}
+
+	fn result(path: &str, score: f32) -> SearchResult {
+		SearchResult::from(HybridSearchResult {
+			doc_id: format!("{}#{}", path, score),
+			file_path: PathBuf::from(path),
+			score,
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn test_group_by_file() {
+		let results = vec![
+			result("/docs/a.md", 0.9),
+			result("/docs/b.md", 0.95),
+			result("/docs/a.md", 0.7),
+			result("/docs/b.md", 0.4),
+			result("/docs/a.md", 0.8),
+		];
+		let grouped = group_by_file(results, 2);
+		assert_eq!(grouped.len(), 2);
+		assert_eq!(grouped[0].file_path, PathBuf::from("/docs/b.md"));
+		assert_eq!(grouped[0].score, 0.95);
+		assert_eq!(grouped[1].score, 0.9);
+		let scores: Vec<f32> = grouped[1].chunks.iter().map(|c| c.score).collect();
+		assert_eq!(scores, [0.9, 0.8]);
+		assert_eq!(grouped[1].chunks[0].metadata.file_type, "md");
+
+		// 0 keeps every chunk
+		let grouped = group_by_file(vec![result("/x.txt", 0.1), result("/x.txt", 0.2)], 0);
+		assert_eq!(grouped[0].chunks.len(), 2);
+		assert!(group_by_file(Vec::new(), 3).is_empty());
+	}
+
+	#[test]
+	fn test_rrf_rewards_agreement() {
+		let fused = reciprocal_rank_fusion(&["a", "b", "c"], &["c", "d"], &RrfParams::default());
+		let ids: Vec<&str> = fused.iter().map(|f| f.doc_id.as_str()).collect();
+		assert_eq!(ids, ["c", "a", "b", "d"]);
+		assert_eq!(fused[0].semantic_rank, Some(2));
+		assert_eq!(fused[0].lexical_rank, Some(0));
+		assert!((fused[0].score - (1.0 / 63.0 + 1.0 / 61.0) as f32).abs() < 1e-6);
+		// "b" and "d" are both second in their ranking; semantic order comes first
+		assert_eq!(fused[2].score, fused[3].score);
+	}
+
+	#[test]
+	fn test_rrf_zero_lexical_weight_matches_semantic() {
+		let semantic = ["s1", "shared", "s2", "s3"];
+		let lexical = ["shared", "l1", "s3", "l2"];
+		let params = RrfParams { lexical_weight: 0.0, ..Default::default() };
+		let fused = reciprocal_rank_fusion(&semantic, &lexical, &params);
+
+		let ids: Vec<&str> = fused.iter().map(|f| f.doc_id.as_str()).collect();
+		assert_eq!(ids, semantic);
+		for (rank, result) in fused.iter().enumerate() {
+			assert_eq!(result.semantic_rank, Some(rank));
+			assert_eq!(result.lexical_rank, None);
+		}
+	}
+
+	#[test]
+	fn test_rrf_weights_and_k() {
+		let semantic = ["a", "b"];
+		let lexical = ["b", "a"];
+		// Equal weights tie; a heavier lexical weight lifts its top result
+		let params = RrfParams { lexical_weight: 2.0, ..Default::default() };
+		assert_eq!(reciprocal_rank_fusion(&semantic, &lexical, &params)[0].doc_id, "b");
+		let params = RrfParams { semantic_weight: 2.0, ..Default::default() };
+		assert_eq!(reciprocal_rank_fusion(&semantic, &lexical, &params)[0].doc_id, "a");
+
+		// A smaller k widens the gap between ranks 0 and 1
+		let gap = |k: f64| {
+			let fused = reciprocal_rank_fusion(&["x", "y"], &[], &RrfParams { k, ..Default::default() });
+			fused[0].score - fused[1].score
+		};
+		assert!(gap(1.0) > gap(60.0));
 	}
 }
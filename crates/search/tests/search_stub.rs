@@ -1,16 +1,60 @@
 use search::{HybridSearch, DummyHybridSearch, HybridSearchQuery};
+use store::{DocumentMetadata, InMemoryVectorStore, VectorStore};
+use std::path::PathBuf;
 use anyhow::Result;
-use async_trait::async_trait;
 
 #[tokio::test]
 async fn test_dummy_hybrid_search() -> Result<()> {
-    let searcher = DummyHybridSearch;
+    let searcher = DummyHybridSearch::default();
     let query = HybridSearchQuery {
         text: "test query".to_string(),
         embedding: Some(vec![1.0, 2.0, 3.0]),
         top_k: 5,
+        filter_path: None,
     };
     let results = searcher.search(query).await?;
-    assert!(results.is_empty()); // DummyHybridSearch always returns empty
+    assert!(results.is_empty()); // Nothing stored yet
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dummy_hybrid_search_returns_matches() -> Result<()> {
+    let store = InMemoryVectorStore::new();
+    for (i, vector) in [vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.9, 0.1]].into_iter().enumerate() {
+        let dir = if i < 2 { "docs" } else { "archive" };
+        store.add_embedding(vector, DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(format!("/{}/{}.txt", dir, i)),
+            file_type: "txt".to_string(),
+            chunk_index: 0,
+            snippet: Some(format!("document {}", i)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        }).await?;
+    }
+    let searcher = DummyHybridSearch::new(store);
+    let results = searcher.search(HybridSearchQuery {
+        text: "second".to_string(),
+        embedding: Some(vec![0.1, 0.9, 0.0]),
+        top_k: 1,
+        filter_path: None,
+    }).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].file_path, PathBuf::from("/docs/1.txt"));
+    assert_eq!(results[0].semantic_rank, Some(0));
+    
+    // Only the matching directory is searched
+    let query = |filter_path: &str| HybridSearchQuery {
+        text: "second".to_string(),
+        embedding: Some(vec![0.1, 0.9, 0.0]),
+        top_k: 5,
+        filter_path: Some(filter_path.to_string()),
+    };
+    let results = searcher.search(query("/archive/**")).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].file_path, PathBuf::from("/archive/2.txt"));
+    assert_eq!(searcher.search(query("/docs/*")).await?.len(), 2);
+    assert!(searcher.search(query("/docs/[")).await.is_err());
     Ok(())
 }
@@ -3,15 +3,36 @@
 //! Provides BM25-based keyword search to complement vector similarity search.
 
 use anyhow::{Result, Context};
-use std::path::PathBuf;
+use crate::{LanceVectorStore, SearchOptions};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
 use tantivy::{
     schema::{Schema, STRING, STORED, Field, TextOptions, TextFieldIndexing, IndexRecordOption, Value},
     Index, IndexWriter, IndexReader, TantivyDocument,
-    query::QueryParser,
-    collector::TopDocs,
+    query::{QueryParser, BooleanQuery, ConstScoreQuery, Occur, PhraseQuery, RegexQuery, TermQuery, Query},
+    tokenizer::TokenStream,
+    collector::{Count, TopDocs},
+    snippet::SnippetGenerator,
+    DocSet, Term, TERMINATED,
 };
 
+/// Name of the lock file Tantivy creates while an index writer is open.
+const WRITER_LOCK_FILE: &str = ".tantivy-writer.lock";
+/// Writer locks older than this are assumed to be left over from a crash.
+const STALE_LOCK_SECS: u64 = 60;
+/// When sorting by date, the best `top_k * DATE_SORT_CANDIDATES` matches by score are re-sorted.
+const DATE_SORT_CANDIDATES: usize = 10;
+/// With date or type filters, the best `top_k * FILTER_CANDIDATES` matches are filtered.
+const FILTER_CANDIDATES: usize = 10;
+/// Characters in the excerpt returned by `search_with_highlights`.
+const SEARCH_SNIPPET_CHARS: usize = 200;
+/// Documents added per batch when rebuilding from the vector store.
+const REBUILD_BATCH_SIZE: usize = 1000;
+
 /// A document stored in the lexical index.
 #[derive(Debug, Clone)]
 pub struct LexicalDoc {
@@ -28,11 +49,119 @@ pub struct LexicalSearchResult {
     pub file_path: String,
     pub chunk_index: usize,
     pub score: f32,
+    /// When the document was added (unix seconds, 0 if the index predates this field).
+    pub indexed_at: i64,
+    /// Excerpt around the best match; only set by `search_with_highlights` when content is stored.
+    pub snippet: Option<String>,
+    /// Byte ranges of matched query terms within `snippet`.
+    pub highlights: Vec<Range<usize>>,
+}
+
+/// Chunk text with the byte ranges of matched query terms, for previews.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HighlightedSnippet {
+    pub text: String,
+    /// Byte ranges into `text` to highlight.
+    pub highlights: Vec<Range<usize>>,
+}
+
+/// Ordering of lexical search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortField {
+    /// Best BM25 match first.
+    #[default]
+    Score,
+    /// Most recently indexed first.
+    Date,
+}
+
+impl std::str::FromStr for SortField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "score" | "relevance" => Ok(SortField::Score),
+            "date" | "indexed_at" => Ok(SortField::Date),
+            _ => anyhow::bail!("Unknown sort field '{}' (expected score or date)", s),
+        }
+    }
+}
+
+/// How a lexical query string is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Match any of the words; quotes are ignored.
+    FuzzyTerms,
+    /// Match the whole query as one phrase, words adjacent and in order.
+    ExactPhrase,
+    /// Double-quoted parts must match as phrases, the other words rank the results.
+    #[default]
+    Auto,
+}
+
+impl std::str::FromStr for SearchMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "terms" | "fuzzy" => Ok(SearchMode::FuzzyTerms),
+            "phrase" | "exact" => Ok(SearchMode::ExactPhrase),
+            "auto" => Ok(SearchMode::Auto),
+            _ => anyhow::bail!("Unknown search mode '{}' (expected terms, phrase or auto)", s),
+        }
+    }
+}
+
+/// Split `query` into its double-quoted phrases and the remaining text.
+/// An unmatched quote is dropped.
+fn split_phrases(query: &str) -> (Vec<&str>, String) {
+    let parts: Vec<&str> = query.split('"').collect();
+    // With an odd number of quotes the last part follows the unmatched one
+    let closed = if parts.len().is_multiple_of(2) { parts.len() - 1 } else { parts.len() };
+    let mut phrases = Vec::new();
+    let mut rest = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i % 2 == 1 && i < closed {
+            phrases.push(*part);
+        } else {
+            rest.push_str(part);
+            rest.push(' ');
+        }
+    }
+    (phrases, rest)
+}
+
+/// Statistics about the on-disk lexical index.
+#[derive(Debug, Clone, Default)]
+pub struct LexicalStats {
+    /// Number of live (non-deleted) documents.
+    pub num_docs: usize,
+    /// Number of searchable segments.
+    pub num_segments: usize,
+    /// Total size of the index directory in bytes.
+    pub index_size_bytes: u64,
+    /// Documents marked as deleted but not yet purged by a merge.
+    pub deleted_docs: usize,
+}
+
+/// Tuning options for the lexical index.
+#[derive(Debug, Clone)]
+pub struct LexicalIndexOptions {
+    /// Merge all segments after a commit that leaves more than this many (0 = never).
+    pub optimization_threshold: usize,
+}
+
+impl Default for LexicalIndexOptions {
+    fn default() -> Self {
+        Self { optimization_threshold: 10 }
+    }
 }
 
 /// Tantivy-based lexical (BM25) search index.
 pub struct LexicalIndex {
     index: Index,
+    index_path: PathBuf,
+    options: LexicalIndexOptions,
     writer: RwLock<IndexWriter>,
     reader: RwLock<IndexReader>,
     // Schema fields
@@ -40,11 +169,20 @@ pub struct LexicalIndex {
     file_path_field: Field,
     content_field: Field,
     chunk_index_field: Field,
+    /// Missing in indexes created before timestamps were recorded.
+    indexed_at_field: Option<Field>,
 }
 
 impl LexicalIndex {
-    /// Create or open a lexical index at the given directory.
+    /// Create or open a lexical index at the given directory, storing chunk text for highlighting.
     pub fn new(data_dir: PathBuf) -> Result<Self> {
+        Self::new_with_options(data_dir, true)
+    }
+    
+    /// Create or open a lexical index at the given directory. With `store_content` false
+    /// a new index keeps only the search terms, which saves disk but disables highlighting.
+    /// An existing index keeps the schema it was created with.
+    pub fn new_with_options(data_dir: PathBuf, store_content: bool) -> Result<Self> {
         let index_path = data_dir.join("tantivy_index");
         std::fs::create_dir_all(&index_path)?;
         
@@ -57,19 +195,25 @@ impl LexicalIndex {
         // file_path: stored for retrieval
         let file_path_field = schema_builder.add_text_field("file_path", STRING | STORED);
         
-        // content: full-text indexed with positions for phrase queries
-        // NOT stored - we use LanceDB snippets for display, this is just for BM25 scoring
-        let text_options = TextOptions::default()
+        // content: full-text indexed with positions for phrase queries, and optionally
+        // stored so previews can highlight matches
+        let mut text_options = TextOptions::default()
             .set_indexing_options(
                 TextFieldIndexing::default()
                     .set_tokenizer("default")
                     .set_index_option(IndexRecordOption::WithFreqsAndPositions)
             );
+        if store_content {
+            text_options = text_options.set_stored();
+        }
         let content_field = schema_builder.add_text_field("content", text_options);
         
         // chunk_index: stored as text (Tantivy doesn't have native i32 in older versions)
         let chunk_index_field = schema_builder.add_text_field("chunk_index", STRING | STORED);
         
+        // indexed_at: unix seconds as decimal text, for date sorting
+        schema_builder.add_text_field("indexed_at", STRING | STORED);
+        
         let schema = schema_builder.build();
         
         // Open or create index
@@ -88,29 +232,88 @@ impl LexicalIndex {
         let reader = index.reader()
             .context("Failed to create index reader")?;
         
+        // Tantivy can't add fields to an existing index; older ones go without
+        // timestamps until the lexical index is rebuilt
+        let indexed_at_field = index.schema().get_field("indexed_at").ok();
+        
         Ok(Self {
             index,
+            index_path,
+            options: LexicalIndexOptions::default(),
             writer: RwLock::new(writer),
             reader: RwLock::new(reader),
             doc_id_field,
             file_path_field,
             content_field,
             chunk_index_field,
+            indexed_at_field,
         })
     }
     
+    /// Set tuning options.
+    pub fn with_options(mut self, options: LexicalIndexOptions) -> Self {
+        self.options = options;
+        self
+    }
+    
+    /// Open the index after a crash that may have left the writer lock behind.
+    /// A lock file older than 60 seconds is removed before opening.
+    pub fn reopen_after_crash(data_dir: PathBuf) -> Result<Self> {
+        Self::remove_stale_writer_lock(&data_dir)?;
+        Self::new(data_dir)
+    }
+    
+    /// Rebuild the index from scratch using the chunk snippets stored in LanceDB.
+    /// Any existing index under `data_dir` is discarded first.
+    pub async fn create_from_lance_snapshot(lance_store: &LanceVectorStore, data_dir: PathBuf) -> Result<Self> {
+        let index_path = data_dir.join("tantivy_index");
+        if index_path.exists() {
+            std::fs::remove_dir_all(&index_path)
+                .with_context(|| format!("Failed to remove {}", index_path.display()))?;
+        }
+        let index = Self::new(data_dir)?;
+        
+        let rows = lance_store.scan_all().await?;
+        for batch in rows.chunks(REBUILD_BATCH_SIZE) {
+            let docs = batch
+                .iter()
+                .map(|m| LexicalDoc {
+                    doc_id: m.doc_id.clone(),
+                    file_path: m.file_path.to_string_lossy().to_string(),
+                    content: m.snippet.clone().unwrap_or_default(),
+                    chunk_index: m.chunk_index,
+                })
+                .collect();
+            index.add_documents(docs)?;
+        }
+        index.commit()?;
+        Ok(index)
+    }
+    
+    /// Remove the writer lock file if it is stale. Returns whether a lock was removed.
+    pub fn remove_stale_writer_lock(data_dir: &Path) -> Result<bool> {
+        let lock_path = data_dir.join("tantivy_index").join(WRITER_LOCK_FILE);
+        let modified = match std::fs::metadata(&lock_path).and_then(|m| m.modified()) {
+            Ok(t) => t,
+            Err(_) => return Ok(false), // No lock file
+        };
+        
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        if age < Duration::from_secs(STALE_LOCK_SECS) {
+            return Ok(false);
+        }
+        
+        std::fs::remove_file(&lock_path)
+            .with_context(|| format!("Failed to remove stale lock {}", lock_path.display()))?;
+        Ok(true)
+    }
+    
     /// Add a document to the lexical index.
     pub fn add_document(&self, doc: LexicalDoc) -> Result<()> {
         let writer = self.writer.write()
             .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))?;
         
-        let mut tantivy_doc = TantivyDocument::default();
-        tantivy_doc.add_text(self.doc_id_field, &doc.doc_id);
-        tantivy_doc.add_text(self.file_path_field, &doc.file_path);
-        tantivy_doc.add_text(self.content_field, &doc.content);
-        tantivy_doc.add_text(self.chunk_index_field, &doc.chunk_index.to_string());
-        
-        writer.add_document(tantivy_doc)?;
+        writer.add_document(self.to_tantivy_doc(&doc, unix_now()))?;
         Ok(())
     }
     
@@ -119,83 +322,305 @@ impl LexicalIndex {
         let writer = self.writer.write()
             .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))?;
         
+        let indexed_at = unix_now();
         for doc in docs {
-            let mut tantivy_doc = TantivyDocument::default();
-            tantivy_doc.add_text(self.doc_id_field, &doc.doc_id);
-            tantivy_doc.add_text(self.file_path_field, &doc.file_path);
-            tantivy_doc.add_text(self.content_field, &doc.content);
-            tantivy_doc.add_text(self.chunk_index_field, &doc.chunk_index.to_string());
-            
-            writer.add_document(tantivy_doc)?;
+            writer.add_document(self.to_tantivy_doc(&doc, indexed_at))?;
         }
         Ok(())
     }
     
+    /// Build the Tantivy document for `doc`.
+    fn to_tantivy_doc(&self, doc: &LexicalDoc, indexed_at: i64) -> TantivyDocument {
+        let mut tantivy_doc = TantivyDocument::default();
+        tantivy_doc.add_text(self.doc_id_field, &doc.doc_id);
+        tantivy_doc.add_text(self.file_path_field, &doc.file_path);
+        tantivy_doc.add_text(self.content_field, &doc.content);
+        tantivy_doc.add_text(self.chunk_index_field, &doc.chunk_index.to_string());
+        if let Some(field) = self.indexed_at_field {
+            tantivy_doc.add_text(field, &indexed_at.to_string());
+        }
+        tantivy_doc
+    }
+    
     /// Commit pending changes to the index.
+    /// Segments are merged afterwards if there are more than `optimization_threshold`.
     pub fn commit(&self) -> Result<()> {
+        {
+            let mut writer = self.writer.write()
+                .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))?;
+            writer.commit()?;
+        }
+        self.reload_reader()?;
+        
+        let threshold = self.options.optimization_threshold;
+        if threshold > 0 && self.index.searchable_segment_ids()?.len() > threshold {
+            self.optimize()?;
+        }
+        Ok(())
+    }
+    
+    /// Merge all segments into one. Each commit creates a segment, and many small
+    /// segments slow down queries; merging also purges deleted documents.
+    pub fn optimize(&self) -> Result<()> {
         let mut writer = self.writer.write()
             .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))?;
-        writer.commit()?;
         
-        // Reload reader to see new documents
+        // Tantivy's background merges may retire segments between listing and merging,
+        // so retry once with a fresh list.
+        let mut attempts = 0;
+        loop {
+            let segment_ids = self.index.searchable_segment_ids()?;
+            if segment_ids.len() <= 1 {
+                break;
+            }
+            match writer.merge(&segment_ids).wait() {
+                Ok(_) => break,
+                Err(_) if attempts == 0 => attempts += 1,
+                Err(e) => return Err(e).context("Failed to merge lexical index segments"),
+            }
+        }
+        
+        // Old segment files are no longer referenced after the merge
+        let _ = writer.garbage_collect_files().wait();
+        drop(writer);
+        
+        self.reload_reader()
+    }
+    
+    /// Reload the reader so searches see the latest commit.
+    fn reload_reader(&self) -> Result<()> {
         let reader = self.reader.write()
             .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
         reader.reload()?;
-        
         Ok(())
     }
     
-    /// Search for documents matching the query.
-    pub fn search(&self, query_str: &str, top_k: usize) -> Result<Vec<LexicalSearchResult>> {
+    /// Search for documents matching the query, best match first.
+    pub fn search(&self, query_str: &str, top_k: usize, mode: SearchMode) -> Result<Vec<LexicalSearchResult>> {
+        self.search_sorted(query_str, top_k, mode, SortField::Score, &SearchOptions::default())
+    }
+    
+    /// Search for documents matching the query in the given order, only keeping
+    /// chunks that pass the date, path and type filters of `filter`. The file
+    /// type is the path's extension; `filter.top_k` is not used.
+    ///
+    /// `SortField::Date` re-sorts the best `top_k * 10` matches by score, so very
+    /// weak matches never crowd out relevant ones just for being recent. The path
+    /// filter is part of the query; Tantivy doesn't index dates or file types, so
+    /// those filters are applied to the best `top_k * 10` matches and fewer than
+    /// `top_k` results may come back.
+    pub fn search_sorted(&self, query_str: &str, top_k: usize, mode: SearchMode, sort_by: SortField, filter: &SearchOptions) -> Result<Vec<LexicalSearchResult>> {
+        self.search_inner(query_str, top_k, mode, sort_by, filter, false)
+    }
+    
+    /// Like `search_sorted`, but each result carries an excerpt around its best match
+    /// with the matched terms marked. Results have no snippet if content isn't stored.
+    pub fn search_with_highlights(&self, query_str: &str, top_k: usize, mode: SearchMode, sort_by: SortField, filter: &SearchOptions) -> Result<Vec<LexicalSearchResult>> {
+        self.search_inner(query_str, top_k, mode, sort_by, filter, true)
+    }
+    
+    fn search_inner(&self, query_str: &str, top_k: usize, mode: SearchMode, sort_by: SortField, filter: &SearchOptions, highlight: bool) -> Result<Vec<LexicalSearchResult>> {
         let reader = self.reader.read()
             .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
         
         let searcher = reader.searcher();
-        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
-        
-        // Parse query, fall back to match-all if empty
-        let query = if query_str.trim().is_empty() {
+        let Some(mut query) = self.build_query(query_str, mode)? else {
             return Ok(vec![]);
-        } else {
-            query_parser.parse_query(query_str)
-                .unwrap_or_else(|_| {
-                    // If query parsing fails, try as a simple term query
-                    Box::new(tantivy::query::AllQuery)
-                })
         };
+        if let Some(pattern) = &filter.filter_path {
+            // Scores nothing, so results rank as without the filter
+            let paths = RegexQuery::from_pattern(&glob_to_regex(pattern.as_str()), self.file_path_field)?;
+            query = Box::new(BooleanQuery::new(vec![
+                (Occur::Must, query),
+                (Occur::Must, Box::new(ConstScoreQuery::new(Box::new(paths), 0.0))),
+            ]));
+        }
         
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(top_k))?;
+        let mut limit = match sort_by {
+            SortField::Score => top_k,
+            SortField::Date => top_k.saturating_mul(DATE_SORT_CANDIDATES),
+        };
+        if filter.after.is_some() || filter.before.is_some() || filter.filter_type.is_some() {
+            limit = limit.saturating_mul(FILTER_CANDIDATES);
+        }
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit.max(1)))?;
+        let generator = if highlight {
+            let mut generator = SnippetGenerator::create(&searcher, &*query, self.content_field)?;
+            generator.set_max_num_chars(SEARCH_SNIPPET_CHARS);
+            Some(generator)
+        } else {
+            None
+        };
         
         let mut results = Vec::with_capacity(top_docs.len());
         for (score, doc_address) in top_docs {
             let doc: TantivyDocument = searcher.doc(doc_address)?;
-            
-            let doc_id = doc.get_first(self.doc_id_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            
-            let file_path = doc.get_first(self.file_path_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            
-            let chunk_index: usize = doc.get_first(self.chunk_index_field)
-                .and_then(|v| v.as_str())
-                .and_then(|s: &str| s.parse().ok())
-                .unwrap_or(0);
-            
-            results.push(LexicalSearchResult {
-                doc_id,
-                file_path,
-                chunk_index,
-                score,
-            });
+            let mut result = self.to_result(&doc, score);
+            let path = Path::new(&result.file_path);
+            let file_type = path.extension().and_then(|e| e.to_str()).unwrap_or("unknown");
+            if !filter.matches(path, file_type, result.indexed_at) {
+                continue;
+            }
+            if let Some(generator) = &generator {
+                if doc.get_first(self.content_field).is_some() {
+                    let snippet = generator.snippet_from_doc(&doc);
+                    result.snippet = Some(snippet.fragment().to_string());
+                    result.highlights = snippet.highlighted().to_vec();
+                }
+            }
+            results.push(result);
+        }
+        
+        if sort_by == SortField::Date {
+            // Stable sort keeps score order among documents indexed in the same second
+            results.sort_by(|a, b| b.indexed_at.cmp(&a.indexed_at));
+        }
+        results.truncate(top_k);
+        Ok(results)
+    }
+    
+    /// Build the query for `query_str`, or `None` if it has nothing to search for.
+    fn build_query(&self, query_str: &str, mode: SearchMode) -> Result<Option<Box<dyn Query>>> {
+        let (phrases, rest) = match mode {
+            SearchMode::FuzzyTerms => (Vec::new(), query_str.replace('"', " ")),
+            SearchMode::ExactPhrase => (vec![query_str.trim_matches('"')], String::new()),
+            SearchMode::Auto => split_phrases(query_str),
+        };
+        
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for phrase in phrases {
+            if let Some(query) = self.phrase_query(phrase)? {
+                clauses.push((Occur::Must, query));
+            }
+        }
+        if !rest.trim().is_empty() {
+            let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
+            // If query parsing fails, fall back to match-all
+            let terms = query_parser.parse_query(&rest)
+                .unwrap_or_else(|_| Box::new(tantivy::query::AllQuery));
+            clauses.push((Occur::Should, terms));
+        }
+        
+        Ok(match clauses.len() {
+            0 => None,
+            1 => clauses.pop().map(|(_, query)| query),
+            _ => Some(Box::new(BooleanQuery::new(clauses))),
+        })
+    }
+    
+    /// Query matching the words of `phrase` adjacent and in order, tokenized like the content field.
+    fn phrase_query(&self, phrase: &str) -> Result<Option<Box<dyn Query>>> {
+        let mut tokenizer = self.index.tokenizer_for_field(self.content_field)?;
+        let mut stream = tokenizer.token_stream(phrase);
+        let mut terms = Vec::new();
+        while let Some(token) = stream.next() {
+            terms.push(Term::from_field_text(self.content_field, &token.text));
+        }
+        Ok(match terms.len() {
+            0 => None,
+            1 => Some(Box::new(TermQuery::new(terms.remove(0), IndexRecordOption::WithFreqs))),
+            _ => Some(Box::new(PhraseQuery::new(terms))),
+        })
+    }
+    
+    /// Find the chunks of `file_path` within `window` positions of `chunk_index`,
+    /// excluding the chunk itself, ordered by chunk index.
+    pub fn search_near_chunk(&self, file_path: &str, chunk_index: usize, window: usize) -> Result<Vec<LexicalSearchResult>> {
+        if window == 0 {
+            return Ok(vec![]);
         }
+        let reader = self.reader.read()
+            .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
+        let searcher = reader.searcher();
+        
+        let neighbours: Vec<(Occur, Box<dyn Query>)> = (chunk_index.saturating_sub(window)..=chunk_index + window)
+            .filter(|&i| i != chunk_index)
+            .map(|i| {
+                let term = Term::from_field_text(self.chunk_index_field, &i.to_string());
+                (Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>)
+            })
+            .collect();
+        let file_term = Term::from_field_text(self.file_path_field, file_path);
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(TermQuery::new(file_term, IndexRecordOption::Basic)) as Box<dyn Query>),
+            (Occur::Must, Box::new(BooleanQuery::new(neighbours))),
+        ]);
         
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(2 * window))?;
+        let mut results = Vec::with_capacity(top_docs.len());
+        for (score, doc_address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            results.push(self.to_result(&doc, score));
+        }
+        results.sort_by_key(|r| r.chunk_index);
         Ok(results)
     }
     
+    /// Return the chunk `doc_id` with the terms of `query` highlighted, in a
+    /// fragment of up to `context_chars` characters around the best match.
+    /// Returns `None` if the document doesn't exist or its content isn't stored.
+    pub fn highlight_in_document(&self, doc_id: &str, query: &str, context_chars: usize) -> Result<Option<HighlightedSnippet>> {
+        let reader = self.reader.read()
+            .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
+        let searcher = reader.searcher();
+        
+        let term = Term::from_field_text(self.doc_id_field, doc_id);
+        let top_docs = searcher.search(&TermQuery::new(term, IndexRecordOption::Basic), &TopDocs::with_limit(1))?;
+        let Some((_, doc_address)) = top_docs.into_iter().next() else {
+            return Ok(None);
+        };
+        let doc: TantivyDocument = searcher.doc(doc_address)?;
+        if doc.get_first(self.content_field).is_none() {
+            return Ok(None);
+        }
+        
+        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
+        let query = query_parser.parse_query(query)
+            .unwrap_or_else(|_| Box::new(tantivy::query::EmptyQuery));
+        let mut generator = SnippetGenerator::create(&searcher, &*query, self.content_field)?;
+        generator.set_max_num_chars(context_chars.max(1));
+        let snippet = generator.snippet_from_doc(&doc);
+        
+        Ok(Some(HighlightedSnippet {
+            text: snippet.fragment().to_string(),
+            highlights: snippet.highlighted().to_vec(),
+        }))
+    }
+    
+    /// Convert a stored document into a search result.
+    fn to_result(&self, doc: &TantivyDocument, score: f32) -> LexicalSearchResult {
+        let doc_id = doc.get_first(self.doc_id_field)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        
+        let file_path = doc.get_first(self.file_path_field)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        
+        let chunk_index: usize = doc.get_first(self.chunk_index_field)
+            .and_then(|v| v.as_str())
+            .and_then(|s: &str| s.parse().ok())
+            .unwrap_or(0);
+        
+        let indexed_at: i64 = self.indexed_at_field
+            .and_then(|field| doc.get_first(field))
+            .and_then(|v| v.as_str())
+            .and_then(|s: &str| s.parse().ok())
+            .unwrap_or(0);
+        
+        LexicalSearchResult {
+            doc_id,
+            file_path,
+            chunk_index,
+            score,
+            indexed_at,
+            snippet: None,
+            highlights: Vec::new(),
+        }
+    }
+    
     /// Delete documents by their doc_ids.
     pub fn delete_by_doc_ids(&self, doc_ids: &[String]) -> Result<usize> {
         if doc_ids.is_empty() {
@@ -215,6 +640,25 @@ impl LexicalIndex {
         Ok(deleted)
     }
     
+    /// Delete every document indexed for `file_path`, whatever its doc_id.
+    /// Returns how many committed documents matched; the deletion itself is
+    /// applied on the next `commit`.
+    pub fn delete_by_file_path(&self, file_path: &str) -> Result<usize> {
+        let term = Term::from_field_text(self.file_path_field, file_path);
+        let matched = {
+            let reader = self.reader.read()
+                .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
+            let query = TermQuery::new(term.clone(), IndexRecordOption::Basic);
+            reader.searcher().search(&query, &Count)?
+        };
+        
+        let writer = self.writer.write()
+            .map_err(|e| anyhow::anyhow!("Writer lock poisoned: {}", e))?;
+        writer.delete_term(term);
+        
+        Ok(matched)
+    }
+    
     /// Get the number of documents in the index.
     pub fn count(&self) -> Result<usize> {
         let reader = self.reader.read()
@@ -222,6 +666,123 @@ impl LexicalIndex {
         let searcher = reader.searcher();
         Ok(searcher.num_docs() as usize)
     }
+    
+    /// Number of documents (chunks) per file path.
+    ///
+    /// Walks the `file_path` term dictionary of each segment, so the cost grows
+    /// with the number of files and chunks but no stored documents are loaded.
+    /// `StateManager::count_by_file` answers the same question from SQLite more
+    /// cheaply, but counts what the state database recorded rather than what is
+    /// actually searchable here.
+    pub fn count_by_file(&self) -> Result<HashMap<String, usize>> {
+        let reader = self.reader.read()
+            .map_err(|e| anyhow::anyhow!("Reader lock poisoned: {}", e))?;
+        let searcher = reader.searcher();
+        
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.file_path_field)?;
+            let alive_bitset = segment_reader.alive_bitset();
+            let mut terms = inverted_index.terms().stream()?;
+            while terms.advance() {
+                let term_info = terms.value();
+                // doc_freq still includes deleted documents, so walk the postings if there are any
+                let count = match alive_bitset {
+                    None => term_info.doc_freq as usize,
+                    Some(alive) => {
+                        let mut postings = inverted_index.read_postings_from_terminfo(term_info, IndexRecordOption::Basic)?;
+                        let mut alive_docs = 0;
+                        while postings.doc() != TERMINATED {
+                            if alive.is_alive(postings.doc()) {
+                                alive_docs += 1;
+                            }
+                            postings.advance();
+                        }
+                        alive_docs
+                    }
+                };
+                if count > 0 {
+                    let path = String::from_utf8_lossy(terms.key()).into_owned();
+                    *counts.entry(path).or_default() += count;
+                }
+            }
+        }
+        Ok(counts)
+    }
+    
+    /// Get statistics about the index (document, segment and disk usage counts).
+    pub fn stats(&self) -> Result<LexicalStats> {
+        let segment_metas = self.index.searchable_segment_metas()
+            .context("Failed to read segment metadata")?;
+        
+        let deleted_docs: usize = segment_metas.iter()
+            .map(|meta| meta.num_deleted_docs() as usize)
+            .sum();
+        let num_docs: usize = segment_metas.iter()
+            .map(|meta| meta.num_docs() as usize)
+            .sum();
+        
+        // Tantivy keeps all segment files flat in the index directory
+        let mut index_size_bytes = 0u64;
+        for entry in std::fs::read_dir(&self.index_path)? {
+            let entry = entry?;
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    index_size_bytes += metadata.len();
+                }
+            }
+        }
+        
+        Ok(LexicalStats {
+            num_docs,
+            num_segments: segment_metas.len(),
+            index_size_bytes,
+            deleted_docs,
+        })
+    }
+}
+
+/// A regex matching at least the paths `glob` matches: `*` and `**` become
+/// `.*`, anything else matching one character becomes `.`. The glob itself is
+/// matched afterwards to drop the extra documents.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("(?s)");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                regex.push_str(".*");
+            }
+            '?' => regex.push('.'),
+            '[' => {
+                // A character class matches one character; skip to its end
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+                regex.push('.');
+            }
+            c => {
+                if "\\.+()|{}^$]".contains(c) {
+                    regex.push('\\');
+                }
+                regex.push(c);
+            }
+        }
+    }
+    regex
+}
+
+/// Current time in unix seconds.
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
 }
 
 #[cfg(test)]
@@ -245,15 +806,107 @@ mod tests {
         index.commit().unwrap();
         
         // Search for "fox"
-        let results = index.search("fox", 10).unwrap();
+        let results = index.search("fox", 10, SearchMode::Auto).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].doc_id, "doc1");
         
         // Search for "cat" (not in document)
-        let results = index.search("cat", 10).unwrap();
+        let results = index.search("cat", 10, SearchMode::Auto).unwrap();
         assert_eq!(results.len(), 0);
     }
     
+    #[test]
+    fn test_phrase_search() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "lease".to_string(),
+            file_path: "/test/lease.txt".to_string(),
+            content: "Please sign the security deposit agreement by Friday".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        
+        assert_eq!(index.search("\"security deposit\"", 10, SearchMode::Auto).unwrap().len(), 1);
+        assert!(index.search("\"deposit security\"", 10, SearchMode::Auto).unwrap().is_empty());
+        // A phrase must match, the other words only rank
+        assert_eq!(index.search("\"deposit agreement\" refund", 10, SearchMode::Auto).unwrap().len(), 1);
+        assert!(index.search("\"deposit refund\" agreement", 10, SearchMode::Auto).unwrap().is_empty());
+        
+        assert!(index.search("deposit security", 10, SearchMode::ExactPhrase).unwrap().is_empty());
+        assert_eq!(index.search("\"deposit security\"", 10, SearchMode::FuzzyTerms).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_split_phrases() {
+        let (phrases, rest) = split_phrases("lease \"security deposit\" refund \"unclosed");
+        assert_eq!(phrases, vec!["security deposit"]);
+        assert_eq!(rest.split_whitespace().collect::<Vec<_>>(), vec!["lease", "refund", "unclosed"]);
+    }
+    
+    #[test]
+    fn test_highlight_in_document() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "doc1".to_string(),
+            file_path: "/test/file.txt".to_string(),
+            content: "the quick brown fox".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        
+        let snippet = index.highlight_in_document("doc1", "fox", 200).unwrap().unwrap();
+        assert_eq!(snippet.text, "the quick brown fox");
+        assert_eq!(snippet.highlights.len(), 1);
+        assert_eq!(&snippet.text[snippet.highlights[0].clone()], "fox");
+        
+        assert!(index.highlight_in_document("missing", "fox", 200).unwrap().is_none());
+    }
+    
+    #[test]
+    fn test_search_with_highlights() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "doc1".to_string(),
+            file_path: "/test/file.txt".to_string(),
+            content: "Tenants pay rent monthly. Late Rent incurs a fee.".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        
+        let results = index.search_with_highlights("rent", 10, SearchMode::Auto, SortField::Score, &SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        let snippet = results[0].snippet.as_deref().unwrap();
+        assert_eq!(results[0].highlights.len(), 2);
+        for range in &results[0].highlights {
+            assert_eq!(snippet[range.clone()].to_lowercase(), "rent");
+        }
+        
+        // Plain search leaves the excerpt out
+        assert!(index.search("rent", 10, SearchMode::Auto).unwrap()[0].snippet.is_none());
+    }
+    
+    #[test]
+    fn test_unstored_content_has_no_highlights() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new_with_options(dir.path().to_path_buf(), false).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "doc1".to_string(),
+            file_path: "/test/file.txt".to_string(),
+            content: "the quick brown fox".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        
+        let results = index.search_with_highlights("fox", 10, SearchMode::Auto, SortField::Score, &SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].snippet.is_none());
+        assert!(results[0].highlights.is_empty());
+        assert!(index.highlight_in_document("doc1", "fox", 200).unwrap().is_none());
+    }
+    
     #[test]
     fn test_lexical_index_multiple_docs() {
         let dir = tempdir().unwrap();
@@ -283,12 +936,332 @@ mod tests {
         index.commit().unwrap();
         
         // Search for "programming" - should match doc1 and doc2
-        let results = index.search("programming", 10).unwrap();
+        let results = index.search("programming", 10, SearchMode::Auto).unwrap();
         assert_eq!(results.len(), 2);
         
         // Search for "Rust" - should match doc1
-        let results = index.search("Rust", 10).unwrap();
+        let results = index.search("Rust", 10, SearchMode::Auto).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].doc_id, "doc1");
     }
+    
+    #[test]
+    fn test_search_filtered_by_path() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        let docs = (0..6).map(|i| LexicalDoc {
+            doc_id: format!("doc{}", i),
+            file_path: format!("/{}/notes{}.md", if i % 2 == 0 { "work" } else { "home" }, i),
+            content: "quarterly budget review".to_string(),
+            chunk_index: 0,
+        }).collect();
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let work = SearchOptions::default().with_filter_path(Some(glob::Pattern::new("/work/**").unwrap()));
+        let results = index.search_sorted("budget", 2, SearchMode::Auto, SortField::Score, &work).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.file_path.starts_with("/work/")));
+        let results = index.search_with_highlights("budget", 10, SearchMode::Auto, SortField::Score, &work).unwrap();
+        assert_eq!(results.len(), 3);
+    }
+    
+    #[test]
+    fn test_path_filter_beyond_candidates() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        // Thirty better matches elsewhere outrank the only one in /work
+        let mut docs: Vec<LexicalDoc> = (0..30).map(|i| LexicalDoc {
+            doc_id: format!("home{}", i),
+            file_path: format!("/home/budget{}.md", i),
+            content: "budget budget budget".to_string(),
+            chunk_index: 0,
+        }).collect();
+        docs.push(LexicalDoc {
+            doc_id: "work".to_string(),
+            file_path: "/work/q3 [draft].md".to_string(),
+            content: "the budget is one line in a long report about many other things".to_string(),
+            chunk_index: 0,
+        });
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let work = SearchOptions::default().with_filter_path(Some(glob::Pattern::new("/work/*.md").unwrap()));
+        let results = index.search_sorted("budget", 1, SearchMode::Auto, SortField::Score, &work).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, "work");
+        let unfiltered = index.search_sorted("budget", 40, SearchMode::Auto, SortField::Score, &SearchOptions::default()).unwrap();
+        let score = unfiltered.iter().find(|r| r.doc_id == "work").unwrap().score;
+        assert_eq!(results[0].score, score);
+    }
+    
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("/docs/**/*.md"), "(?s)/docs/.*/.*\\.md");
+        assert_eq!(glob_to_regex("/docs/report-?.[tp]df"), "(?s)/docs/report-.\\..df");
+        assert_eq!(glob_to_regex("/a+b/(1)"), "(?s)/a\\+b/\\(1\\)");
+    }
+    
+    #[test]
+    fn test_search_filtered_by_type() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        let docs = ["txt", "pdf", "txt", "md"].iter().enumerate().map(|(i, ext)| LexicalDoc {
+            doc_id: format!("doc{}", i),
+            file_path: format!("/docs/lease{}.{}", i, ext),
+            content: "security deposit terms".to_string(),
+            chunk_index: 0,
+        }).collect();
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let txt = SearchOptions::default().with_filter_type(Some(vec!["txt".to_string()]));
+        let results = index.search_sorted("deposit", 10, SearchMode::Auto, SortField::Score, &txt).unwrap();
+        let mut ids: Vec<&str> = results.iter().map(|r| r.doc_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["doc0", "doc2"]);
+        let pdf_or_md = SearchOptions::default().with_filter_type(Some(vec!["pdf".to_string(), "md".to_string()]));
+        assert_eq!(index.search_sorted("deposit", 10, SearchMode::Auto, SortField::Score, &pdf_or_md).unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn test_search_sorted_by_date() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        
+        let doc = |doc_id: &str, content: &str| LexicalDoc {
+            doc_id: doc_id.to_string(),
+            file_path: format!("/{}.txt", doc_id),
+            content: content.to_string(),
+            chunk_index: 0,
+        };
+        {
+            // Explicit timestamps, so the test doesn't need to wait between adds
+            let writer = index.writer.write().unwrap();
+            writer.add_document(index.to_tantivy_doc(&doc("old", "rust rust rust"), 1_000)).unwrap();
+            writer.add_document(index.to_tantivy_doc(&doc("new", "rust and some other words"), 2_000)).unwrap();
+        }
+        index.commit().unwrap();
+        
+        let by_score = index.search("rust", 10, SearchMode::Auto).unwrap();
+        assert_eq!(by_score[0].doc_id, "old");
+        
+        let by_date = index.search_sorted("rust", 10, SearchMode::Auto, SortField::Date, &SearchOptions::default()).unwrap();
+        assert_eq!(by_date.iter().map(|r| r.doc_id.as_str()).collect::<Vec<_>>(), vec!["new", "old"]);
+        assert_eq!(by_date[0].indexed_at, 2_000);
+        
+        assert_eq!("date".parse::<SortField>().unwrap(), SortField::Date);
+        assert!("size".parse::<SortField>().is_err());
+    }
+    
+    #[test]
+    fn test_indexed_at_recorded() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "doc1".to_string(),
+            file_path: "/a.txt".to_string(),
+            content: "timestamped".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        
+        let results = index.search("timestamped", 1, SearchMode::Auto).unwrap();
+        assert!((results[0].indexed_at - unix_now()).abs() < 60);
+    }
+    
+    #[test]
+    fn test_search_near_chunk() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        
+        let mut docs: Vec<LexicalDoc> = (0..5)
+            .map(|i| LexicalDoc {
+                doc_id: format!("a{}", i),
+                file_path: "/a.txt".to_string(),
+                content: format!("chunk {}", i),
+                chunk_index: i,
+            })
+            .collect();
+        docs.push(LexicalDoc {
+            doc_id: "b1".to_string(),
+            file_path: "/b.txt".to_string(),
+            content: "other file".to_string(),
+            chunk_index: 1,
+        });
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let near: Vec<String> = index.search_near_chunk("/a.txt", 2, 1).unwrap()
+            .into_iter().map(|r| r.doc_id).collect();
+        assert_eq!(near, vec!["a1", "a3"]);
+        
+        let near: Vec<String> = index.search_near_chunk("/a.txt", 0, 2).unwrap()
+            .into_iter().map(|r| r.doc_id).collect();
+        assert_eq!(near, vec!["a1", "a2"]);
+    }
+    
+    #[test]
+    fn test_delete_by_file_path() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        
+        let docs = [("a0", "/a.txt"), ("a1", "/a.txt"), ("b0", "/b.txt")]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (doc_id, path))| LexicalDoc {
+                doc_id: doc_id.to_string(),
+                file_path: path.to_string(),
+                content: "shared searchable words".to_string(),
+                chunk_index: i,
+            })
+            .collect();
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        assert_eq!(index.delete_by_file_path("/a.txt").unwrap(), 2);
+        index.commit().unwrap();
+        
+        let results = index.search("searchable", 10, SearchMode::Auto).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, "/b.txt");
+        assert_eq!(index.delete_by_file_path("/a.txt").unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_count_by_file() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        
+        let mut docs = Vec::new();
+        for (path, chunks) in [("/a.txt", 2), ("/b.txt", 4), ("/c.txt", 6)] {
+            for i in 0..chunks {
+                docs.push(LexicalDoc {
+                    doc_id: format!("{}-{}", path, i),
+                    file_path: path.to_string(),
+                    content: format!("chunk {}", i),
+                    chunk_index: i,
+                });
+            }
+        }
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let counts = index.count_by_file().unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["/a.txt"], 2);
+        assert_eq!(counts["/b.txt"], 4);
+        assert_eq!(counts["/c.txt"], 6);
+        
+        // Deleted documents are not counted
+        index.delete_by_doc_ids(&["/c.txt-0".to_string(), "/a.txt-0".to_string(), "/a.txt-1".to_string()]).unwrap();
+        index.commit().unwrap();
+        let counts = index.count_by_file().unwrap();
+        assert_eq!(counts.get("/a.txt"), None);
+        assert_eq!(counts["/c.txt"], 5);
+    }
+    
+    #[test]
+    fn test_lexical_index_stats() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+        
+        let docs: Vec<LexicalDoc> = (0..100)
+            .map(|i| LexicalDoc {
+                doc_id: format!("doc{}", i),
+                file_path: format!("/file{}.txt", i),
+                content: format!("document number {}", i),
+                chunk_index: 0,
+            })
+            .collect();
+        index.add_documents(docs).unwrap();
+        index.commit().unwrap();
+        
+        let to_delete: Vec<String> = (0..50).map(|i| format!("doc{}", i)).collect();
+        index.delete_by_doc_ids(&to_delete).unwrap();
+        index.commit().unwrap();
+        
+        let stats = index.stats().unwrap();
+        assert_eq!(stats.num_docs, 50);
+        assert_eq!(stats.deleted_docs, 50);
+        assert!(stats.num_segments >= 1);
+        assert!(stats.index_size_bytes > 0);
+    }
+
+    #[test]
+    fn test_optimize_merges_segments() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap()
+            .with_options(LexicalIndexOptions { optimization_threshold: 0 });
+        
+        for i in 0..20 {
+            index.add_document(LexicalDoc {
+                doc_id: format!("doc{}", i),
+                file_path: format!("/file{}.txt", i),
+                content: format!("commit number {}", i),
+                chunk_index: 0,
+            }).unwrap();
+            index.commit().unwrap();
+        }
+        
+        index.optimize().unwrap();
+        let stats = index.stats().unwrap();
+        assert_eq!(stats.num_segments, 1);
+        assert_eq!(stats.num_docs, 20);
+        assert_eq!(index.search("commit", 50, SearchMode::Auto).unwrap().len(), 20);
+    }
+    
+    #[test]
+    fn test_commit_optimizes_above_threshold() {
+        let dir = tempdir().unwrap();
+        let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap()
+            .with_options(LexicalIndexOptions { optimization_threshold: 3 });
+        
+        for i in 0..20 {
+            index.add_document(LexicalDoc {
+                doc_id: format!("doc{}", i),
+                file_path: format!("/file{}.txt", i),
+                content: format!("commit number {}", i),
+                chunk_index: 0,
+            }).unwrap();
+            index.commit().unwrap();
+            assert!(index.stats().unwrap().num_segments <= 3);
+        }
+    }
+    
+    #[test]
+    fn test_reopen_after_crash_removes_stale_lock() {
+        let dir = tempdir().unwrap();
+        {
+            let index = LexicalIndex::new(dir.path().to_path_buf()).unwrap();
+            index.commit().unwrap();
+        }
+        
+        // Simulate a lock file left behind by a crashed writer
+        let lock_path = dir.path().join("tantivy_index").join(WRITER_LOCK_FILE);
+        let lock = std::fs::File::create(&lock_path).unwrap();
+        lock.set_modified(SystemTime::now() - Duration::from_secs(STALE_LOCK_SECS * 2)).unwrap();
+        drop(lock);
+        
+        let index = LexicalIndex::reopen_after_crash(dir.path().to_path_buf()).unwrap();
+        index.add_document(LexicalDoc {
+            doc_id: "doc1".to_string(),
+            file_path: "/test/file.txt".to_string(),
+            content: "recovered after crash".to_string(),
+            chunk_index: 0,
+        }).unwrap();
+        index.commit().unwrap();
+        assert_eq!(index.search("recovered", 10, SearchMode::Auto).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_fresh_lock_is_kept() {
+        let dir = tempdir().unwrap();
+        let index_dir = dir.path().join("tantivy_index");
+        std::fs::create_dir_all(&index_dir).unwrap();
+        std::fs::write(index_dir.join(WRITER_LOCK_FILE), "").unwrap();
+        
+        assert!(!LexicalIndex::remove_stale_writer_lock(dir.path()).unwrap());
+        assert!(index_dir.join(WRITER_LOCK_FILE).exists());
+    }
 }
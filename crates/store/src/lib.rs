@@ -7,21 +7,24 @@
 mod state;
 mod lexical;
 
-pub use state::{StateManager, FileState, FileInfo};
-pub use lexical::{LexicalIndex, LexicalDoc, LexicalSearchResult};
+pub use state::{StateManager, FileState, FileInfo, IndexStats, IntegrityReport, ListFilter, ListSort, VacuumResult, content_hash};
+pub use lexical::{LexicalIndex, LexicalIndexOptions, LexicalDoc, HighlightedSnippet, LexicalSearchResult, LexicalStats, SearchMode, SortField};
 
 use async_trait::async_trait;
 use anyhow::{Result, Context};
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
 use lancedb::connect;
-use lancedb::query::{QueryBase, ExecutableQuery};
+use lancedb::index::{Index, vector::IvfPqIndexBuilder};
+use lancedb::query::{QueryBase, ExecutableQuery, Select};
+use lancedb::table::{ColumnAlteration, CompactionOptions, NewColumnTransform, OptimizeAction};
 use arrow_array::{
-    RecordBatch, RecordBatchIterator, StringArray, Float32Array, Int32Array,
-    ArrayRef, Array,
+    RecordBatch, RecordBatchIterator, StringArray, Float32Array, Int32Array, Int64Array,
+    FixedSizeListArray, ArrayRef, Array,
 };
 use arrow_array::builder::{FixedSizeListBuilder, Float32Builder};
 use arrow_schema::{Schema, Field, DataType};
@@ -29,13 +32,28 @@ use futures::TryStreamExt;
 use tokio::sync::RwLock;
 
 /// Metadata associated with a document or chunk.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DocumentMetadata {
     pub doc_id: String,
     pub file_path: PathBuf,
     pub file_type: String,
     pub chunk_index: usize,
     pub snippet: Option<String>,
+    /// Byte offset of the chunk start in the extracted text.
+    #[serde(default)]
+    pub byte_start: usize,
+    /// Byte offset just past the chunk end in the extracted text.
+    #[serde(default)]
+    pub byte_end: usize,
+    /// Title from the document's own properties (e.g. a PDF's info dictionary).
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Author from the document's own properties.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// When the document says it was created (Unix seconds).
+    #[serde(default)]
+    pub created_at: Option<i64>,
 }
 
 /// Result of a search query.
@@ -45,6 +63,73 @@ pub struct SearchResult {
     pub score: f32,
     pub snippet: Option<String>,
     pub metadata: DocumentMetadata,
+    /// Snippets of the neighbouring chunks, in chunk order (empty unless requested).
+    pub context_chunks: Vec<String>,
+}
+
+/// Which chunks a search considers, how many it returns and what is attached to them.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub top_k: usize,
+    /// Only chunks indexed at or after this time (Unix seconds).
+    pub after: Option<i64>,
+    /// Only chunks indexed before this time (Unix seconds).
+    pub before: Option<i64>,
+    /// Only chunks of files whose path matches this glob.
+    pub filter_path: Option<glob::Pattern>,
+    /// Only chunks whose `file_type` (the file extension) is one of these,
+    /// compared case-insensitively.
+    pub filter_type: Option<Vec<String>>,
+    /// Number of neighbouring chunks on each side to attach as context (0 = none).
+    /// Applied after retrieval, so stores ignore it.
+    pub context_window: usize,
+}
+
+impl SearchOptions {
+    pub fn new(top_k: usize) -> Self {
+        Self { top_k, ..Default::default() }
+    }
+
+    /// Restrict the search to chunks indexed in `[after, before)`; `None` leaves that side open.
+    pub fn with_date_range(mut self, after: Option<i64>, before: Option<i64>) -> Self {
+        self.after = after;
+        self.before = before;
+        self
+    }
+
+    /// Restrict the search to files whose path matches `pattern`.
+    pub fn with_filter_path(mut self, pattern: Option<glob::Pattern>) -> Self {
+        self.filter_path = pattern;
+        self
+    }
+
+    /// Restrict the search to chunks whose `file_type` is one of `types`.
+    /// Types are lowercased and may be given with a leading dot (".pdf").
+    pub fn with_filter_type(mut self, types: Option<Vec<String>>) -> Self {
+        self.filter_type = types.map(|types| {
+            types.iter().map(|t| t.trim().trim_start_matches('.').to_lowercase()).collect()
+        });
+        self
+    }
+
+    /// Attach `window` neighbouring chunks on each side of every result.
+    pub fn with_context_window(mut self, window: usize) -> Self {
+        self.context_window = window;
+        self
+    }
+
+    /// Whether any filter is set.
+    pub fn has_filters(&self) -> bool {
+        self.after.is_some() || self.before.is_some() || self.filter_path.is_some() || self.filter_type.is_some()
+    }
+
+    /// Whether a chunk of `path` with `file_type`, indexed at `indexed_at`, passes every filter.
+    pub fn matches(&self, path: &Path, file_type: &str, indexed_at: i64) -> bool {
+        self.after.is_none_or(|a| indexed_at >= a)
+            && self.before.is_none_or(|b| indexed_at < b)
+            && self.filter_path.as_ref().is_none_or(|p| p.matches_path(path))
+            && self.filter_type.as_ref().is_none_or(|types| types.iter().any(|t| t.eq_ignore_ascii_case(file_type)))
+    }
 }
 
 /// Trait for a vector + metadata store.
@@ -52,29 +137,318 @@ pub struct SearchResult {
 pub trait VectorStore: Send + Sync {
     async fn add_embedding(&self, embedding: Vec<f32>, metadata: DocumentMetadata) -> Result<String>;
     /// Add multiple embeddings in a single batch operation (much faster than individual inserts).
-    async fn add_embeddings_batch(&self, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>>;
-    async fn search(&self, query: Vec<f32>, top_k: usize) -> Result<Vec<SearchResult>>;
+    /// The default inserts them one at a time; stores with a bulk path should override it.
+    async fn add_embeddings_batch(&self, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        anyhow::ensure!(
+            embeddings.len() == metadata.len(),
+            "{} embeddings but {} metadata rows", embeddings.len(), metadata.len()
+        );
+        let mut doc_ids = Vec::with_capacity(embeddings.len());
+        for (embedding, metadata) in embeddings.into_iter().zip(metadata) {
+            doc_ids.push(self.add_embedding(embedding, metadata).await?);
+        }
+        Ok(doc_ids)
+    }
+    /// The `options.top_k` chunks closest to `query` among those passing the
+    /// filters in `options`, best first.
+    async fn search(&self, query: Vec<f32>, options: &SearchOptions) -> Result<Vec<SearchResult>>;
     async fn get_metadata(&self, doc_id: &str) -> Result<Option<DocumentMetadata>>;
+    /// Metadata of the chunks with exactly these doc_ids, keyed by doc_id; unknown ids
+    /// are left out. The default calls `get_metadata` once per id; stores that can
+    /// look them up together should override it.
+    async fn get_metadata_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, DocumentMetadata>> {
+        let mut found = HashMap::with_capacity(doc_ids.len());
+        for doc_id in doc_ids {
+            if let Some(metadata) = self.get_metadata(doc_id).await?.filter(|m| &m.doc_id == doc_id) {
+                found.insert(doc_id.clone(), metadata);
+            }
+        }
+        Ok(found)
+    }
+    /// The stored vector of the chunk with exactly this `doc_id`, if there is one.
+    /// The default returns `None`, for stores that don't keep their vectors.
+    async fn get_embedding_by_doc_id(&self, _doc_id: &str) -> Result<Option<Vec<f32>>> {
+        Ok(None)
+    }
+    /// The stored vectors of the chunks with exactly these doc_ids, keyed by doc_id;
+    /// unknown ids are left out. The default calls `get_embedding_by_doc_id` once per id.
+    async fn get_embeddings_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        let mut found = HashMap::with_capacity(doc_ids.len());
+        for doc_id in doc_ids {
+            if let Some(embedding) = self.get_embedding_by_doc_id(doc_id).await? {
+                found.insert(doc_id.clone(), embedding);
+            }
+        }
+        Ok(found)
+    }
+    /// Metadata of every chunk stored for `path`, ordered by chunk index.
+    async fn get_by_file_path(&self, path: &Path) -> Result<Vec<DocumentMetadata>>;
     async fn delete_by_doc_ids(&self, doc_ids: &[String]) -> Result<usize>;
+    /// Remove every embedding stored for `path`, returning how many rows were deleted.
+    async fn delete_by_file_path(&self, path: &Path) -> Result<usize>;
+    /// Remove near-duplicate embeddings, returning the doc_ids deleted so callers can
+    /// drop them from the state and lexical index too (see `nexus_core::deduplicate_index`).
+    /// Chunks of the same file type whose vectors have cosine similarity above
+    /// `similarity_threshold` are collapsed into the most recently indexed one.
+    async fn deduplicate(&self, similarity_threshold: f32) -> Result<Vec<String>>;
+    /// Replace every embedding stored for `file_path` with `new_embeddings`, returning
+    /// the new doc_ids. Used to regenerate a file's embeddings, e.g. after a model change.
+    async fn reindex_file(&self, file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>>;
     async fn save(&self) -> Result<()>;
     async fn count(&self) -> usize;
+    /// Reclaim the space left by deleted rows. Stores that delete in place need
+    /// nothing, so the default does nothing.
+    async fn compact(&self) -> Result<()> {
+        Ok(())
+    }
+    /// Build an approximate nearest-neighbour index so searches stop scanning every
+    /// vector. `None` leaves a parameter to the store. Stores that always search
+    /// exhaustively need no index, so the default does nothing.
+    async fn create_vector_index(&self, _num_partitions: Option<u32>, _num_sub_vectors: Option<u32>) -> Result<()> {
+        Ok(())
+    }
 }
 
 const TABLE_NAME: &str = "embeddings";
-const EMBEDDING_DIM: i32 = 384; // all-MiniLM-L6-v2
+/// Vector width of the default embedding model (all-MiniLM-L6-v2).
+pub const DEFAULT_EMBEDDING_DIM: usize = 384;
+/// Columns read when loading chunk metadata without vectors.
+const METADATA_COLUMNS: &[&str] = &["doc_id", "file_path", "file_type", "chunk_index", "snippet", "byte_start", "byte_end", "title", "author", "created_at"];
+/// Fewest vectors an IVF-PQ index can be trained on: product quantization
+/// fits 256 centroids per sub-vector.
+pub const MIN_VECTOR_INDEX_ROWS: usize = 256;
+/// Doc IDs per delete statement when removing duplicates.
+const DEDUP_DELETE_BATCH: usize = 500;
+/// Vector comparisons between pauses when looking for duplicates.
+const DEDUP_COMPARISONS_PER_PAUSE: usize = 1_000_000;
+/// How long duplicate detection sleeps after each `DEDUP_COMPARISONS_PER_PAUSE`
+/// comparisons, so a large store doesn't keep a core busy for the whole scan.
+const DEDUP_PAUSE: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// Distance used to rank vector search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceMetric {
+    /// Euclidean distance, scored as `1 / (1 + d)`.
+    #[default]
+    L2,
+    /// Cosine distance, scored as `1 - d` (the cosine similarity).
+    Cosine,
+}
+
+impl DistanceMetric {
+    fn distance_type(self) -> lancedb::DistanceType {
+        match self {
+            DistanceMetric::L2 => lancedb::DistanceType::L2,
+            DistanceMetric::Cosine => lancedb::DistanceType::Cosine,
+        }
+    }
+
+    /// Convert a raw distance into a similarity score, higher is better.
+    fn score(self, distance: f32) -> f32 {
+        match self {
+            DistanceMetric::L2 => 1.0 / (1.0 + distance),
+            DistanceMetric::Cosine => 1.0 - distance,
+        }
+    }
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "l2" | "euclidean" => Ok(DistanceMetric::L2),
+            "cosine" => Ok(DistanceMetric::Cosine),
+            _ => anyhow::bail!("Unknown distance metric '{}' (expected l2 or cosine)", s),
+        }
+    }
+}
+
+/// Lance filter matching the rows with exactly these doc_ids: `doc_id IN ('id1', ...)`.
+fn doc_id_filter(doc_ids: &[String]) -> String {
+    let quoted: Vec<String> = doc_ids.iter().map(|id| format!("'{}'", id.replace('\'', "''"))).collect();
+    format!("doc_id IN ({})", quoted.join(", "))
+}
+
+/// Row loaded for duplicate detection.
+struct DedupCandidate {
+    doc_id: String,
+    file_type: String,
+    vector: Vec<f32>,
+    /// When the chunk's file was indexed (Unix seconds), to keep the newest copy.
+    indexed_at: i64,
+}
+
+/// Doc IDs of the candidates that have a newer copy: one of the same file type
+/// whose vector is within `similarity_threshold` cosine similarity. Candidates are
+/// compared against the copies kept so far, newest first, which is O(n²) per file
+/// type; the calling thread sleeps for `DEDUP_PAUSE` every `DEDUP_COMPARISONS_PER_PAUSE`
+/// comparisons.
+fn find_duplicates(mut candidates: Vec<DedupCandidate>, similarity_threshold: f32) -> Vec<String> {
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.indexed_at));
+    let mut kept: HashMap<&str, Vec<&[f32]>> = HashMap::new();
+    let mut duplicates = Vec::new();
+    let mut comparisons = 0;
+    for candidate in &candidates {
+        let group = kept.entry(candidate.file_type.as_str()).or_default();
+        comparisons += group.len();
+        if comparisons >= DEDUP_COMPARISONS_PER_PAUSE {
+            comparisons = 0;
+            std::thread::sleep(DEDUP_PAUSE);
+        }
+        if group.iter().any(|k| cosine_similarity(k, &candidate.vector) > similarity_threshold) {
+            duplicates.push(candidate.doc_id.clone());
+        } else {
+            group.push(&candidate.vector);
+        }
+    }
+    duplicates
+}
+
+/// Cosine similarity of two vectors (0.0 if either is zero).
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Current time in Unix seconds.
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// String at row `i` of a nullable column; `None` if it is null or the table predates the column.
+fn optional_str(batch: &RecordBatch, column: &str, i: usize) -> Option<String> {
+    let values = batch.column_by_name(column)?.as_any().downcast_ref::<StringArray>()?;
+    (!values.is_null(i)).then(|| values.value(i).to_string())
+}
+
+/// Integer at row `i` of a nullable column, like `optional_str`.
+fn optional_i64(batch: &RecordBatch, column: &str, i: usize) -> Option<i64> {
+    let values = batch.column_by_name(column)?.as_any().downcast_ref::<Int64Array>()?;
+    (!values.is_null(i)).then(|| values.value(i))
+}
+
+/// Byte offset at row `i` of `column`; 0 if it is null or the table predates the column.
+fn offset(batch: &RecordBatch, column: &str, i: usize) -> usize {
+    optional_i64(batch, column, i).map_or(0, |v| v.max(0) as usize)
+}
+
+/// LanceDB SQL filter for `after <= indexed_at < before`, or `None` if both are open.
+fn search_filter(options: &SearchOptions) -> Option<String> {
+    let mut clauses = Vec::new();
+    if let Some(after) = options.after {
+        clauses.push(format!("indexed_at >= {}", after));
+    }
+    if let Some(before) = options.before {
+        clauses.push(format!("indexed_at < {}", before));
+    }
+    if let Some(pattern) = &options.filter_path {
+        clauses.push(format!("file_path LIKE '{}'", glob_to_like(pattern.as_str()).replace('\'', "''")));
+    }
+    if let Some(types) = &options.filter_type {
+        let quoted: Vec<String> = types.iter().map(|t| format!("'{}'", t.replace('\'', "''"))).collect();
+        clauses.push(format!("lower(file_type) IN ({})", quoted.join(", ")));
+    }
+    if clauses.is_empty() {
+        None
+    } else {
+        Some(clauses.join(" AND "))
+    }
+}
+
+/// A `LIKE` pattern matching at least the paths `glob` matches: `*` and `**`
+/// become `%`, anything else matching one character becomes `_`. Literal `%`
+/// and `_` also become `_`, so no escaping is needed; callers match the glob
+/// itself afterwards to drop the extra rows.
+fn glob_to_like(glob: &str) -> String {
+    let mut like = String::with_capacity(glob.len());
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                like.push('%');
+            }
+            '?' | '%' | '_' => like.push('_'),
+            '[' => {
+                // A character class matches one character; skip to its end
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+                like.push('_');
+            }
+            c => like.push(c),
+        }
+    }
+    like
+}
 
 /// LanceDB-backed vector store.
 /// Data is stored on disk with efficient ANN search.
 pub struct LanceVectorStore {
     db: Arc<lancedb::Connection>,
     table: RwLock<Option<lancedb::Table>>,
+    /// Length of every stored vector.
+    dim: usize,
+    metric: DistanceMetric,
     #[allow(dead_code)]
     data_dir: PathBuf,
+    state: Option<Arc<StateManager>>,
 }
 
 impl LanceVectorStore {
-    /// Create or open a LanceDB store at the given directory.
-    pub async fn new(data_dir: PathBuf) -> Result<Self> {
+    /// Create or open a LanceDB store at the given directory for `dim`-length vectors.
+    /// Fails if an existing table was created with a different dimension.
+    pub async fn new(data_dir: PathBuf, dim: usize) -> Result<Self> {
+        Self::new_with_metric(data_dir, dim, DistanceMetric::default()).await
+    }
+
+    /// Like `new`, ranking search results by `metric` instead of L2 distance.
+    pub async fn new_with_metric(data_dir: PathBuf, dim: usize, metric: DistanceMetric) -> Result<Self> {
+        Self::open(data_dir, Some(dim), metric).await
+    }
+
+    /// Open the store at `data_dir` with whatever dimension its table was created
+    /// with, for commands that read or delete embeddings but never add any.
+    /// An empty store gets `DEFAULT_EMBEDDING_DIM`.
+    pub async fn open_existing(data_dir: PathBuf) -> Result<Self> {
+        Self::open_existing_with_metric(data_dir, DistanceMetric::default()).await
+    }
+
+    /// Like `open_existing`, ranking search results by `metric` instead of L2 distance.
+    pub async fn open_existing_with_metric(data_dir: PathBuf, metric: DistanceMetric) -> Result<Self> {
+        Self::open(data_dir, None, metric).await
+    }
+
+    /// Drop every stored embedding, and the vector index built over them, and
+    /// open the store empty for `dim`-length vectors. For switching to a model
+    /// whose embeddings can't be compared with the stored ones.
+    pub async fn recreate(data_dir: PathBuf, dim: usize) -> Result<Self> {
+        std::fs::create_dir_all(&data_dir)?;
+        let db = connect(&data_dir.to_string_lossy()).execute().await
+            .context("Failed to connect to LanceDB")?;
+        if db.table_names().execute().await?.iter().any(|name| name == TABLE_NAME) {
+            db.drop_table(TABLE_NAME, &[]).await
+                .context("Failed to drop the embeddings table")?;
+        }
+        Self::new(data_dir, dim).await
+    }
+
+    /// Connect to the store, checking an existing table against `dim` if given.
+    async fn open(data_dir: PathBuf, dim: Option<usize>, metric: DistanceMetric) -> Result<Self> {
         std::fs::create_dir_all(&data_dir)?;
         let db_path = data_dir.to_string_lossy().to_string();
         let db = connect(&db_path).execute().await
@@ -85,27 +459,250 @@ impl LanceVectorStore {
             Ok(t) => Some(t),
             Err(_) => None, // Table doesn't exist yet
         };
+        let dim = match (&table, dim) {
+            (Some(table), dim) => {
+                let stored_dim = Self::stored_dim(table).await?;
+                if let Some(dim) = dim {
+                    anyhow::ensure!(
+                        stored_dim == dim,
+                        "store at {:?} holds {}-dimensional embeddings, expected {}; re-index with the matching model",
+                        data_dir, stored_dim, dim
+                    );
+                }
+                Self::migrate(table).await?;
+                stored_dim
+            }
+            (None, dim) => dim.unwrap_or(DEFAULT_EMBEDDING_DIM),
+        };
         
         Ok(Self {
             db: Arc::new(db),
             table: RwLock::new(table),
+            dim,
+            metric,
             data_dir,
+            state: None,
         })
     }
 
+    /// Set the state manager, used to prefer the most recently indexed copy when deduplicating.
+    pub fn with_state(mut self, state: Arc<StateManager>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Count rows matching a LanceDB SQL filter, e.g. `file_type = 'pdf'`.
+    pub async fn count_by_query(&self, filter: &str) -> Result<usize> {
+        let table_guard = self.table.read().await;
+        match &*table_guard {
+            Some(table) => Ok(table.count_rows(Some(filter.to_string())).await?),
+            None => Ok(0),
+        }
+    }
+
+    /// Count chunks of the given file type (extension without the dot).
+    pub async fn count_by_file_type(&self, file_type: &str) -> Result<usize> {
+        self.count_by_query(&format!("file_type = '{}'", file_type.replace('\'', "''"))).await
+    }
+
+    /// Count chunks stored for a single file.
+    pub async fn count_by_file_path(&self, file_path: &str) -> Result<usize> {
+        self.count_by_query(&format!("file_path = '{}'", file_path.replace('\'', "''"))).await
+    }
+
+    /// List the distinct file types present in the store, sorted.
+    pub async fn file_types(&self) -> Result<Vec<String>> {
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(Vec::new()),
+            };
+            table
+                .query()
+                .select(Select::columns(&["file_type"]))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        let mut types = BTreeSet::new();
+        for batch in batches {
+            if let Some(file_types) = batch.column_by_name("file_type")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+            {
+                for i in 0..batch.num_rows() {
+                    types.insert(file_types.value(i).to_string());
+                }
+            }
+        }
+        Ok(types.into_iter().collect())
+    }
+
+    /// Read the vector dimension an existing table was created with.
+    async fn stored_dim(table: &lancedb::Table) -> Result<usize> {
+        let schema = table.schema().await?;
+        match schema.field_with_name("vector")?.data_type() {
+            DataType::FixedSizeList(_, dim) => Ok(*dim as usize),
+            other => anyhow::bail!("unexpected vector column type {:?}", other),
+        }
+    }
+
+    /// Add columns missing from tables created by older versions, and widen
+    /// columns whose type changed since.
+    async fn migrate(table: &lancedb::Table) -> Result<()> {
+        let schema = table.schema().await?;
+        if schema.field_with_name("indexed_at").is_err() {
+            // Existing rows have no known index time, treat them as indexed at the epoch
+            table.add_columns(
+                NewColumnTransform::SqlExpressions(vec![
+                    ("indexed_at".to_string(), "CAST(0 AS BIGINT)".to_string()),
+                ]),
+                None,
+            ).await.context("Failed to add indexed_at column")?;
+        }
+        match schema.field_with_name("byte_start").map(|f| f.data_type().clone()) {
+            // Rows stored before offsets were recorded have none
+            Err(_) => {
+                table.add_columns(
+                    NewColumnTransform::SqlExpressions(vec![
+                        ("byte_start".to_string(), "CAST(NULL AS BIGINT)".to_string()),
+                        ("byte_end".to_string(), "CAST(NULL AS BIGINT)".to_string()),
+                    ]),
+                    None,
+                ).await.context("Failed to add byte offset columns")?;
+            }
+            // Early versions stored them as non-nullable 32-bit integers
+            Ok(data_type) if data_type != DataType::Int64 => {
+                let widen = |column: &str| ColumnAlteration::new(column.to_string())
+                    .cast_to(DataType::Int64)
+                    .set_nullable(true);
+                table.alter_columns(&[widen("byte_start"), widen("byte_end")])
+                    .await.context("Failed to widen byte offset columns")?;
+            }
+            Ok(_) => {}
+        }
+        if schema.field_with_name("title").is_err() {
+            table.add_columns(
+                NewColumnTransform::SqlExpressions(vec![
+                    ("title".to_string(), "CAST(NULL AS STRING)".to_string()),
+                    ("author".to_string(), "CAST(NULL AS STRING)".to_string()),
+                    ("created_at".to_string(), "CAST(NULL AS BIGINT)".to_string()),
+                ]),
+                None,
+            ).await.context("Failed to add document property columns")?;
+        }
+        Ok(())
+    }
+
+    /// List the distinct file paths present in the store, sorted.
+    /// Only the `file_path` column is read, so vectors never leave disk.
+    pub async fn get_all_file_paths(&self) -> Result<Vec<PathBuf>> {
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(Vec::new()),
+            };
+            table
+                .query()
+                .select(Select::columns(&["file_path"]))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        let mut paths = BTreeSet::new();
+        for batch in batches {
+            if let Some(file_paths) = batch.column_by_name("file_path")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+            {
+                for i in 0..batch.num_rows() {
+                    paths.insert(PathBuf::from(file_paths.value(i)));
+                }
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Load the metadata of every row in the store, without the vectors.
+    pub async fn scan_all(&self) -> Result<Vec<DocumentMetadata>> {
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(Vec::new()),
+            };
+            table
+                .query()
+                .select(Select::columns(METADATA_COLUMNS))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        Ok(Self::metadata_rows(&batches))
+    }
+
+    /// Decode metadata rows from record batches holding `METADATA_COLUMNS`.
+    fn metadata_rows(batches: &[RecordBatch]) -> Vec<DocumentMetadata> {
+        let mut rows = Vec::new();
+        for batch in batches {
+            let doc_ids = batch.column_by_name("doc_id")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let file_paths = batch.column_by_name("file_path")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let file_types = batch.column_by_name("file_type")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let chunk_indices = batch.column_by_name("chunk_index")
+                .and_then(|c| c.as_any().downcast_ref::<Int32Array>());
+            let snippets = batch.column_by_name("snippet")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+
+            if let (Some(doc_ids), Some(file_paths), Some(file_types), Some(chunk_indices), Some(snippets))
+                = (doc_ids, file_paths, file_types, chunk_indices, snippets)
+            {
+                for i in 0..batch.num_rows() {
+                    rows.push(DocumentMetadata {
+                        doc_id: doc_ids.value(i).to_string(),
+                        file_path: PathBuf::from(file_paths.value(i)),
+                        file_type: file_types.value(i).to_string(),
+                        chunk_index: chunk_indices.value(i) as usize,
+                        snippet: if snippets.is_null(i) { None } else { Some(snippets.value(i).to_string()) },
+                        byte_start: offset(batch, "byte_start", i),
+                        byte_end: offset(batch, "byte_end", i),
+                        title: optional_str(batch, "title", i),
+                        author: optional_str(batch, "author", i),
+                        created_at: optional_i64(batch, "created_at", i),
+                    });
+                }
+            }
+        }
+        rows
+    }
+
     /// Get the Arrow schema for the embeddings table.
-    fn schema() -> Arc<Schema> {
+    fn schema(&self) -> Arc<Schema> {
         Arc::new(Schema::new(vec![
             Field::new("doc_id", DataType::Utf8, false),
             Field::new("file_path", DataType::Utf8, false),
             Field::new("file_type", DataType::Utf8, false),
             Field::new("chunk_index", DataType::Int32, false),
             Field::new("snippet", DataType::Utf8, true),
+            Field::new("byte_start", DataType::Int64, true),
+            Field::new("byte_end", DataType::Int64, true),
+            Field::new("title", DataType::Utf8, true),
+            Field::new("author", DataType::Utf8, true),
+            Field::new("created_at", DataType::Int64, true),
+            Field::new("indexed_at", DataType::Int64, false),
             Field::new(
                 "vector",
                 DataType::FixedSizeList(
                     Arc::new(Field::new("item", DataType::Float32, true)),
-                    EMBEDDING_DIM,
+                    self.dim as i32,
                 ),
                 false,
             ),
@@ -113,17 +710,27 @@ impl LanceVectorStore {
     }
 
     /// Create a RecordBatch from a single embedding + metadata.
-    fn create_batch(embedding: Vec<f32>, metadata: &DocumentMetadata) -> Result<RecordBatch> {
-        let schema = Self::schema();
+    fn create_batch(&self, embedding: Vec<f32>, metadata: &DocumentMetadata, indexed_at: i64) -> Result<RecordBatch> {
+        anyhow::ensure!(
+            embedding.len() == self.dim,
+            "embedding has {} dimensions, store expects {}", embedding.len(), self.dim
+        );
+        let schema = self.schema();
         
         let doc_id = StringArray::from(vec![metadata.doc_id.as_str()]);
         let file_path = StringArray::from(vec![metadata.file_path.to_string_lossy().to_string()]);
         let file_type = StringArray::from(vec![metadata.file_type.as_str()]);
         let chunk_index = Int32Array::from(vec![metadata.chunk_index as i32]);
         let snippet = StringArray::from(vec![metadata.snippet.as_deref()]);
+        let byte_start = Int64Array::from(vec![metadata.byte_start as i64]);
+        let byte_end = Int64Array::from(vec![metadata.byte_end as i64]);
+        let title = StringArray::from(vec![metadata.title.as_deref()]);
+        let author = StringArray::from(vec![metadata.author.as_deref()]);
+        let created_at = Int64Array::from(vec![metadata.created_at]);
+        let indexed_at = Int64Array::from(vec![indexed_at]);
         
         // Create FixedSizeList for the embedding vector using builder
-        let mut list_builder = FixedSizeListBuilder::new(Float32Builder::new(), EMBEDDING_DIM);
+        let mut list_builder = FixedSizeListBuilder::new(Float32Builder::new(), self.dim as i32);
         let values_builder = list_builder.values();
         for v in &embedding {
             values_builder.append_value(*v);
@@ -139,6 +746,12 @@ impl LanceVectorStore {
                 Arc::new(file_type) as ArrayRef,
                 Arc::new(chunk_index) as ArrayRef,
                 Arc::new(snippet) as ArrayRef,
+                Arc::new(byte_start) as ArrayRef,
+                Arc::new(byte_end) as ArrayRef,
+                Arc::new(title) as ArrayRef,
+                Arc::new(author) as ArrayRef,
+                Arc::new(created_at) as ArrayRef,
+                Arc::new(indexed_at) as ArrayRef,
                 Arc::new(vector) as ArrayRef,
             ],
         )?;
@@ -147,8 +760,11 @@ impl LanceVectorStore {
     }
 
     /// Create a RecordBatch from multiple embeddings + metadata (batch insert).
-    fn create_batch_multi(embeddings: &[Vec<f32>], metadata: &[DocumentMetadata]) -> Result<RecordBatch> {
-        let schema = Self::schema();
+    fn create_batch_multi(&self, embeddings: &[Vec<f32>], metadata: &[DocumentMetadata], indexed_at: i64) -> Result<RecordBatch> {
+        if let Some(bad) = embeddings.iter().find(|e| e.len() != self.dim) {
+            anyhow::bail!("embedding has {} dimensions, store expects {}", bad.len(), self.dim);
+        }
+        let schema = self.schema();
         let n = embeddings.len();
         
         let doc_ids: Vec<&str> = metadata.iter().map(|m| m.doc_id.as_str()).collect();
@@ -156,15 +772,26 @@ impl LanceVectorStore {
         let file_types: Vec<&str> = metadata.iter().map(|m| m.file_type.as_str()).collect();
         let chunk_indices: Vec<i32> = metadata.iter().map(|m| m.chunk_index as i32).collect();
         let snippets: Vec<Option<&str>> = metadata.iter().map(|m| m.snippet.as_deref()).collect();
+        let byte_starts: Vec<i64> = metadata.iter().map(|m| m.byte_start as i64).collect();
+        let byte_ends: Vec<i64> = metadata.iter().map(|m| m.byte_end as i64).collect();
+        let titles: Vec<Option<&str>> = metadata.iter().map(|m| m.title.as_deref()).collect();
+        let authors: Vec<Option<&str>> = metadata.iter().map(|m| m.author.as_deref()).collect();
+        let created_ats: Vec<Option<i64>> = metadata.iter().map(|m| m.created_at).collect();
         
         let doc_id_array = StringArray::from(doc_ids);
         let file_path_array = StringArray::from(file_paths.iter().map(|s| s.as_str()).collect::<Vec<_>>());
         let file_type_array = StringArray::from(file_types);
         let chunk_index_array = Int32Array::from(chunk_indices);
         let snippet_array = StringArray::from(snippets);
+        let byte_start_array = Int64Array::from(byte_starts);
+        let byte_end_array = Int64Array::from(byte_ends);
+        let title_array = StringArray::from(titles);
+        let author_array = StringArray::from(authors);
+        let created_at_array = Int64Array::from(created_ats);
+        let indexed_at_array = Int64Array::from(vec![indexed_at; n]);
         
         // Create FixedSizeList for all embedding vectors
-        let mut list_builder = FixedSizeListBuilder::new(Float32Builder::new(), EMBEDDING_DIM);
+        let mut list_builder = FixedSizeListBuilder::new(Float32Builder::new(), self.dim as i32);
         for embedding in embeddings {
             let values_builder = list_builder.values();
             for v in embedding {
@@ -182,6 +809,12 @@ impl LanceVectorStore {
                 Arc::new(file_type_array) as ArrayRef,
                 Arc::new(chunk_index_array) as ArrayRef,
                 Arc::new(snippet_array) as ArrayRef,
+                Arc::new(byte_start_array) as ArrayRef,
+                Arc::new(byte_end_array) as ArrayRef,
+                Arc::new(title_array) as ArrayRef,
+                Arc::new(author_array) as ArrayRef,
+                Arc::new(created_at_array) as ArrayRef,
+                Arc::new(indexed_at_array) as ArrayRef,
                 Arc::new(vector_array) as ArrayRef,
             ],
         )?;
@@ -189,6 +822,40 @@ impl LanceVectorStore {
         debug_assert_eq!(batch.num_rows(), n);
         Ok(batch)
     }
+
+    /// Assign doc_ids to rows without one and build a single record batch.
+    fn prepare_batch(&self, embeddings: &[Vec<f32>], metadata: Vec<DocumentMetadata>) -> Result<(Vec<String>, RecordBatch)> {
+        let metadata_with_ids: Vec<DocumentMetadata> = metadata
+            .into_iter()
+            .map(|m| {
+                if m.doc_id.is_empty() {
+                    DocumentMetadata { doc_id: Uuid::new_v4().to_string(), ..m }
+                } else {
+                    m
+                }
+            })
+            .collect();
+        
+        let doc_ids: Vec<String> = metadata_with_ids.iter().map(|m| m.doc_id.clone()).collect();
+        let batch = self.create_batch_multi(embeddings, &metadata_with_ids, unix_now())?;
+        Ok((doc_ids, batch))
+    }
+
+    /// Append `batch` to the table, creating the table if this is the first write.
+    async fn append_batch(&self, table: &mut Option<lancedb::Table>, batch: RecordBatch) -> Result<()> {
+        if let Some(ref table) = *table {
+            table.add(
+                RecordBatchIterator::new(vec![Ok(batch)], self.schema())
+            ).execute().await?;
+        } else {
+            let new_table = self.db.create_table(
+                TABLE_NAME,
+                RecordBatchIterator::new(vec![Ok(batch)], self.schema()),
+            ).execute().await?;
+            *table = Some(new_table);
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -201,20 +868,20 @@ impl VectorStore for LanceVectorStore {
         };
         
         let metadata = DocumentMetadata { doc_id: doc_id.clone(), ..metadata };
-        let batch = Self::create_batch(embedding, &metadata)?;
+        let batch = self.create_batch(embedding, &metadata, unix_now())?;
         
         let mut table_guard = self.table.write().await;
         
         if let Some(ref table) = *table_guard {
             // Add to existing table
             table.add(
-                RecordBatchIterator::new(vec![Ok(batch)], Self::schema())
+                RecordBatchIterator::new(vec![Ok(batch)], self.schema())
             ).execute().await?;
         } else {
             // Create new table
             let new_table = self.db.create_table(
                 TABLE_NAME,
-                RecordBatchIterator::new(vec![Ok(batch)], Self::schema()),
+                RecordBatchIterator::new(vec![Ok(batch)], self.schema()),
             ).execute().await?;
             *table_guard = Some(new_table);
         }
@@ -227,41 +894,18 @@ impl VectorStore for LanceVectorStore {
             return Ok(vec![]);
         }
         
-        // Generate doc_ids for any missing ones
-        let metadata_with_ids: Vec<DocumentMetadata> = metadata
-            .into_iter()
-            .map(|m| {
-                if m.doc_id.is_empty() {
-                    DocumentMetadata { doc_id: Uuid::new_v4().to_string(), ..m }
-                } else {
-                    m
-                }
-            })
-            .collect();
-        
-        let doc_ids: Vec<String> = metadata_with_ids.iter().map(|m| m.doc_id.clone()).collect();
-        
-        // Create single batch with all embeddings
-        let batch = Self::create_batch_multi(&embeddings, &metadata_with_ids)?;
-        
+        let (doc_ids, batch) = self.prepare_batch(&embeddings, metadata)?;
         let mut table_guard = self.table.write().await;
-        
-        if let Some(ref table) = *table_guard {
-            table.add(
-                RecordBatchIterator::new(vec![Ok(batch)], Self::schema())
-            ).execute().await?;
-        } else {
-            let new_table = self.db.create_table(
-                TABLE_NAME,
-                RecordBatchIterator::new(vec![Ok(batch)], Self::schema()),
-            ).execute().await?;
-            *table_guard = Some(new_table);
-        }
+        self.append_batch(&mut table_guard, batch).await?;
         
         Ok(doc_ids)
     }
 
-    async fn search(&self, query: Vec<f32>, top_k: usize) -> Result<Vec<SearchResult>> {
+    async fn search(&self, query: Vec<f32>, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        // `IN ()` is not valid SQL, and no type matches an empty list anyway
+        if options.filter_type.as_ref().is_some_and(|types| types.is_empty()) {
+            return Ok(vec![]);
+        }
         let table_guard = self.table.read().await;
         
         let table = match &*table_guard {
@@ -269,9 +913,13 @@ impl VectorStore for LanceVectorStore {
             None => return Ok(vec![]), // No table means no results
         };
         
-        let results = table
-            .vector_search(query)?
-            .limit(top_k)
+        let mut vector_query = table.vector_search(query)?
+            .distance_type(self.metric.distance_type())
+            .limit(options.top_k);
+        if let Some(filter) = search_filter(options) {
+            vector_query = vector_query.only_if(filter);
+        }
+        let results = vector_query
             .execute()
             .await?
             .try_collect::<Vec<_>>()
@@ -309,9 +957,10 @@ impl VectorStore for LanceVectorStore {
                     let chunk_index = chunk_indices.value(i) as usize;
                     let snippet = if snippets.is_null(i) { None } else { Some(snippets.value(i).to_string()) };
                     let distance = distances.value(i);
+                    let byte_start = offset(&batch, "byte_start", i);
+                    let byte_end = offset(&batch, "byte_end", i);
                     
-                    // Convert L2 distance to similarity score (1 / (1 + distance))
-                    let score = 1.0 / (1.0 + distance);
+                    let score = self.metric.score(distance);
                     
                     search_results.push(SearchResult {
                         doc_id: doc_id.clone(),
@@ -323,15 +972,93 @@ impl VectorStore for LanceVectorStore {
                             file_type,
                             chunk_index,
                             snippet,
+                            byte_start,
+                            byte_end,
+                            title: optional_str(&batch, "title", i),
+                            author: optional_str(&batch, "author", i),
+                            created_at: optional_i64(&batch, "created_at", i),
                         },
+                        context_chunks: Vec::new(),
                     });
                 }
             }
         }
         
+        // LIKE matched a superset of the glob
+        if let Some(pattern) = &options.filter_path {
+            search_results.retain(|r| pattern.matches_path(&r.metadata.file_path));
+        }
+        
         Ok(search_results)
     }
 
+    async fn get_embedding_by_doc_id(&self, doc_id: &str) -> Result<Option<Vec<f32>>> {
+        let table_guard = self.table.read().await;
+        let table = match &*table_guard {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        let filter = format!("doc_id = '{}'", doc_id.replace('\'', "''"));
+        let results = table
+            .query()
+            .only_if(filter)
+            .select(Select::columns(&["vector"]))
+            .limit(1)
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        for batch in results {
+            let vectors = batch.column_by_name("vector")
+                .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>());
+            if let Some(vectors) = vectors.filter(|v| !v.is_empty()) {
+                let vector = vectors.value(0);
+                return Ok(vector.as_any().downcast_ref::<Float32Array>().map(|v| v.values().to_vec()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn get_embeddings_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        if doc_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(HashMap::new()),
+            };
+            table
+                .query()
+                .only_if(doc_id_filter(doc_ids))
+                .select(Select::columns(&["doc_id", "vector"]))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        let mut found = HashMap::with_capacity(doc_ids.len());
+        for batch in batches {
+            let ids = batch.column_by_name("doc_id")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let vectors = batch.column_by_name("vector")
+                .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>());
+            if let (Some(ids), Some(vectors)) = (ids, vectors) {
+                for i in 0..batch.num_rows() {
+                    let vector = vectors.value(i);
+                    if let Some(v) = vector.as_any().downcast_ref::<Float32Array>() {
+                        found.insert(ids.value(i).to_string(), v.values().to_vec());
+                    }
+                }
+            }
+        }
+        Ok(found)
+    }
+
     async fn get_metadata(&self, doc_id: &str) -> Result<Option<DocumentMetadata>> {
         let table_guard = self.table.read().await;
         
@@ -376,6 +1103,11 @@ impl VectorStore for LanceVectorStore {
                     file_type: file_types.value(0).to_string(),
                     chunk_index: chunk_indices.value(0) as usize,
                     snippet: if snippets.is_null(0) { None } else { Some(snippets.value(0).to_string()) },
+                    byte_start: offset(&batch, "byte_start", 0),
+                    byte_end: offset(&batch, "byte_end", 0),
+                    title: optional_str(&batch, "title", 0),
+                    author: optional_str(&batch, "author", 0),
+                    created_at: optional_i64(&batch, "created_at", 0),
                 }));
             }
         }
@@ -383,11 +1115,147 @@ impl VectorStore for LanceVectorStore {
         Ok(None)
     }
 
-    async fn save(&self) -> Result<()> {
-        // LanceDB automatically persists to disk, no explicit save needed
-        Ok(())
-    }
-
+    async fn get_metadata_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, DocumentMetadata>> {
+        if doc_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(HashMap::new()),
+            };
+            table
+                .query()
+                .only_if(doc_id_filter(doc_ids))
+                .select(Select::columns(METADATA_COLUMNS))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+        Ok(Self::metadata_rows(&batches).into_iter().map(|m| (m.doc_id.clone(), m)).collect())
+    }
+
+    async fn get_by_file_path(&self, path: &Path) -> Result<Vec<DocumentMetadata>> {
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(Vec::new()),
+            };
+            table
+                .query()
+                .only_if(format!("file_path = '{}'", path.to_string_lossy().replace('\'', "''")))
+                .select(Select::columns(METADATA_COLUMNS))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        let mut rows = Self::metadata_rows(&batches);
+        rows.sort_by_key(|m| m.chunk_index);
+        Ok(rows)
+    }
+
+    async fn deduplicate(&self, similarity_threshold: f32) -> Result<Vec<String>> {
+        let batches = {
+            let table_guard = self.table.read().await;
+            let table = match &*table_guard {
+                Some(t) => t,
+                None => return Ok(Vec::new()),
+            };
+            table
+                .query()
+                .select(Select::columns(&["doc_id", "file_path", "file_type", "vector"]))
+                .execute()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+        };
+
+        let mut indexed_at_cache: HashMap<String, i64> = HashMap::new();
+        let mut candidates = Vec::new();
+        for batch in batches {
+            let doc_ids = batch.column_by_name("doc_id")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let file_paths = batch.column_by_name("file_path")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let file_types = batch.column_by_name("file_type")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let vectors = batch.column_by_name("vector")
+                .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>());
+
+            if let (Some(doc_ids), Some(file_paths), Some(file_types), Some(vectors))
+                = (doc_ids, file_paths, file_types, vectors)
+            {
+                for i in 0..batch.num_rows() {
+                    let vector = vectors.value(i);
+                    let vector = match vector.as_any().downcast_ref::<Float32Array>() {
+                        Some(v) => v.values().to_vec(),
+                        None => continue,
+                    };
+                    let file_path = file_paths.value(i);
+                    let indexed_at = match self.state {
+                        Some(ref state) => *indexed_at_cache
+                            .entry(file_path.to_string())
+                            .or_insert_with(|| state.indexed_at(Path::new(file_path)).ok().flatten().unwrap_or(0)),
+                        None => 0,
+                    };
+                    candidates.push(DedupCandidate {
+                        doc_id: doc_ids.value(i).to_string(),
+                        file_type: file_types.value(i).to_string(),
+                        vector,
+                        indexed_at,
+                    });
+                }
+            }
+        }
+
+        // The comparison is CPU-bound, so keep it off the async runtime
+        let duplicates = tokio::task::spawn_blocking(move || find_duplicates(candidates, similarity_threshold)).await?;
+        for batch in duplicates.chunks(DEDUP_DELETE_BATCH) {
+            self.delete_by_doc_ids(batch).await?;
+        }
+        Ok(duplicates)
+    }
+
+    async fn reindex_file(&self, file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        anyhow::ensure!(
+            new_embeddings.len() == metadata.len(),
+            "{} embeddings but {} metadata rows", new_embeddings.len(), metadata.len()
+        );
+        let (doc_ids, batch) = if new_embeddings.is_empty() {
+            (Vec::new(), None)
+        } else {
+            let (ids, batch) = self.prepare_batch(&new_embeddings, metadata)?;
+            (ids, Some(batch))
+        };
+        
+        // LanceDB has no multi-statement transactions; holding the write lock across the
+        // delete and insert keeps searches through this store from seeing the file half-replaced
+        let mut table_guard = self.table.write().await;
+        if let Some(ref table) = *table_guard {
+            let filter = format!("file_path = '{}'", file_path.to_string_lossy().replace('\'', "''"));
+            table.delete(&filter).await?;
+        }
+        if let Some(batch) = batch {
+            self.append_batch(&mut table_guard, batch).await?;
+        }
+        drop(table_guard);
+        
+        if let Some(ref state) = self.state {
+            state.replace_doc_ids(file_path, &doc_ids)?;
+        }
+        Ok(doc_ids)
+    }
+
+    async fn save(&self) -> Result<()> {
+        // LanceDB automatically persists to disk, no explicit save needed
+        Ok(())
+    }
+
     async fn count(&self) -> usize {
         let table_guard = self.table.read().await;
         
@@ -397,6 +1265,58 @@ impl VectorStore for LanceVectorStore {
         }
     }
 
+    /// Rewrite the table's data files without deleted rows, then drop the old
+    /// table versions that still reference them. Lance keeps files written in
+    /// the last week in case they belong to an unfinished write, so space from
+    /// recent deletions may only be freed by a later compaction.
+    async fn compact(&self) -> Result<()> {
+        let table_guard = self.table.read().await;
+        let table = match &*table_guard {
+            Some(t) => t,
+            None => return Ok(()),
+        };
+
+        table.optimize(OptimizeAction::Compact { options: CompactionOptions::default(), remap_options: None }).await
+            .context("Failed to compact embeddings table")?;
+        table.optimize(OptimizeAction::Prune {
+            older_than: Some(lancedb::table::Duration::zero()),
+            delete_unverified: None,
+            error_if_tagged_old_versions: None,
+        }).await
+            .context("Failed to prune old table versions")?;
+        Ok(())
+    }
+
+    /// Build an IVF-PQ index over the `vector` column, replacing any existing one.
+    /// Needs at least `MIN_VECTOR_INDEX_ROWS` vectors, and `num_partitions` may not
+    /// exceed the row count; the index pays off from about 10 000 vectors, below
+    /// that a full scan is fast enough. `num_sub_vectors` must divide the vector
+    /// dimension. Rows added later are searched exhaustively until the index is rebuilt.
+    async fn create_vector_index(&self, num_partitions: Option<u32>, num_sub_vectors: Option<u32>) -> Result<()> {
+        let table_guard = self.table.read().await;
+        let table = match &*table_guard {
+            Some(t) => t,
+            None => anyhow::bail!("Cannot build a vector index on an empty store"),
+        };
+
+        let rows = table.count_rows(None).await?;
+        anyhow::ensure!(
+            rows >= MIN_VECTOR_INDEX_ROWS,
+            "A vector index needs at least {} embeddings, the store has {}", MIN_VECTOR_INDEX_ROWS, rows
+        );
+
+        let mut builder = IvfPqIndexBuilder::default().distance_type(self.metric.distance_type());
+        if let Some(n) = num_partitions {
+            builder = builder.num_partitions(n);
+        }
+        if let Some(n) = num_sub_vectors {
+            builder = builder.num_sub_vectors(n);
+        }
+        table.create_index(&["vector"], Index::IvfPq(builder)).execute().await
+            .context("Failed to build vector index")?;
+        Ok(())
+    }
+
     async fn delete_by_doc_ids(&self, doc_ids: &[String]) -> Result<usize> {
         if doc_ids.is_empty() {
             return Ok(0);
@@ -410,19 +1330,27 @@ impl VectorStore for LanceVectorStore {
         };
 
         let count_before = table.count_rows(None).await.unwrap_or(0) as usize;
-
-        // Build filter: doc_id IN ('id1', 'id2', ...)
-        let escaped_ids: Vec<String> = doc_ids
-            .iter()
-            .map(|id| format!("'{}'", id.replace('\'', "''")))
-            .collect();
-        let filter = format!("doc_id IN ({})", escaped_ids.join(", "));
-
-        table.delete(&filter).await?;
+        table.delete(&doc_id_filter(doc_ids)).await?;
 
         let count_after = table.count_rows(None).await.unwrap_or(0) as usize;
         Ok(count_before.saturating_sub(count_after))
     }
+
+    async fn delete_by_file_path(&self, path: &Path) -> Result<usize> {
+        // Write lock so rows added between the count and the delete aren't miscounted
+        let table_guard = self.table.write().await;
+        let table = match &*table_guard {
+            Some(t) => t,
+            None => return Ok(0),
+        };
+
+        let filter = format!("file_path = '{}'", path.to_string_lossy().replace('\'', "''"));
+        let matching = table.count_rows(Some(filter.clone())).await?;
+        if matching > 0 {
+            table.delete(&filter).await?;
+        }
+        Ok(matching)
+    }
 }
 
 // Stub implementation for testing without persistence
@@ -438,7 +1366,7 @@ impl VectorStore for DummyStore {
         Ok(metadata.into_iter().map(|m| m.doc_id).collect())
     }
 
-    async fn search(&self, _query: Vec<f32>, _top_k: usize) -> Result<Vec<SearchResult>> {
+    async fn search(&self, _query: Vec<f32>, _options: &SearchOptions) -> Result<Vec<SearchResult>> {
         Ok(vec![])
     }
 
@@ -446,6 +1374,14 @@ impl VectorStore for DummyStore {
         Ok(None)
     }
 
+    async fn get_by_file_path(&self, _path: &Path) -> Result<Vec<DocumentMetadata>> {
+        Ok(vec![])
+    }
+
+    async fn reindex_file(&self, _file_path: &Path, _new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        Ok(metadata.into_iter().map(|m| m.doc_id).collect())
+    }
+
     async fn save(&self) -> Result<()> {
         Ok(())
     }
@@ -457,6 +1393,161 @@ impl VectorStore for DummyStore {
     async fn delete_by_doc_ids(&self, _doc_ids: &[String]) -> Result<usize> {
         Ok(0)
     }
+
+    async fn delete_by_file_path(&self, _path: &Path) -> Result<usize> {
+        Ok(0)
+    }
+
+    async fn deduplicate(&self, _similarity_threshold: f32) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Vector store held entirely in memory, with brute-force cosine search.
+/// Nothing is persisted; meant for tests and short-lived indexes. Clones share the same rows.
+#[derive(Clone, Default)]
+pub struct InMemoryVectorStore {
+    /// Each row's vector, metadata and index time (Unix seconds).
+    rows: Arc<RwLock<Vec<(Vec<f32>, DocumentMetadata, i64)>>>,
+}
+
+impl InMemoryVectorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metadata of every row, in insertion order.
+    pub async fn all_metadata(&self) -> Vec<DocumentMetadata> {
+        self.rows.read().await.iter().map(|(_, m, _)| m.clone()).collect()
+    }
+
+    /// Append rows, assigning doc_ids to those without one.
+    fn push_rows(rows: &mut Vec<(Vec<f32>, DocumentMetadata, i64)>, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        anyhow::ensure!(
+            embeddings.len() == metadata.len(),
+            "{} embeddings but {} metadata rows", embeddings.len(), metadata.len()
+        );
+        let indexed_at = unix_now();
+        let mut doc_ids = Vec::with_capacity(embeddings.len());
+        for (embedding, mut metadata) in embeddings.into_iter().zip(metadata) {
+            if metadata.doc_id.is_empty() {
+                metadata.doc_id = Uuid::new_v4().to_string();
+            }
+            doc_ids.push(metadata.doc_id.clone());
+            rows.push((embedding, metadata, indexed_at));
+        }
+        Ok(doc_ids)
+    }
+}
+
+#[async_trait]
+impl VectorStore for InMemoryVectorStore {
+    async fn add_embedding(&self, embedding: Vec<f32>, metadata: DocumentMetadata) -> Result<String> {
+        Ok(self.add_embeddings_batch(vec![embedding], vec![metadata]).await?.remove(0))
+    }
+
+    async fn add_embeddings_batch(&self, embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        Self::push_rows(&mut *self.rows.write().await, embeddings, metadata)
+    }
+
+    async fn search(&self, query: Vec<f32>, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+        let rows = self.rows.read().await;
+        let mut results: Vec<SearchResult> = rows
+            .iter()
+            .filter(|(_, m, at)| options.matches(&m.file_path, &m.file_type, *at))
+            .map(|(vector, metadata, _)| SearchResult {
+                doc_id: metadata.doc_id.clone(),
+                score: cosine_similarity(&query, vector),
+                snippet: metadata.snippet.clone(),
+                metadata: metadata.clone(),
+                context_chunks: Vec::new(),
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(options.top_k);
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, doc_id: &str) -> Result<Option<DocumentMetadata>> {
+        // Prefix match, like LanceVectorStore, so short IDs work
+        let rows = self.rows.read().await;
+        Ok(rows.iter().find(|(_, m, _)| m.doc_id.starts_with(doc_id)).map(|(_, m, _)| m.clone()))
+    }
+
+    async fn get_metadata_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, DocumentMetadata>> {
+        let rows = self.rows.read().await;
+        Ok(rows.iter()
+            .filter(|(_, m, _)| doc_ids.contains(&m.doc_id))
+            .map(|(_, m, _)| (m.doc_id.clone(), m.clone()))
+            .collect())
+    }
+
+    async fn get_embedding_by_doc_id(&self, doc_id: &str) -> Result<Option<Vec<f32>>> {
+        let rows = self.rows.read().await;
+        Ok(rows.iter().find(|(_, m, _)| m.doc_id == doc_id).map(|(v, _, _)| v.clone()))
+    }
+
+    async fn get_embeddings_by_doc_ids(&self, doc_ids: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        let rows = self.rows.read().await;
+        Ok(rows.iter()
+            .filter(|(_, m, _)| doc_ids.contains(&m.doc_id))
+            .map(|(v, m, _)| (m.doc_id.clone(), v.clone()))
+            .collect())
+    }
+
+    async fn get_by_file_path(&self, path: &Path) -> Result<Vec<DocumentMetadata>> {
+        let rows = self.rows.read().await;
+        let mut chunks: Vec<DocumentMetadata> = rows
+            .iter()
+            .filter(|(_, m, _)| m.file_path == path)
+            .map(|(_, m, _)| m.clone())
+            .collect();
+        chunks.sort_by_key(|m| m.chunk_index);
+        Ok(chunks)
+    }
+
+    async fn delete_by_doc_ids(&self, doc_ids: &[String]) -> Result<usize> {
+        let mut rows = self.rows.write().await;
+        let before = rows.len();
+        rows.retain(|(_, m, _)| !doc_ids.contains(&m.doc_id));
+        Ok(before - rows.len())
+    }
+
+    async fn delete_by_file_path(&self, path: &Path) -> Result<usize> {
+        let mut rows = self.rows.write().await;
+        let before = rows.len();
+        rows.retain(|(_, m, _)| m.file_path != path);
+        Ok(before - rows.len())
+    }
+
+    async fn deduplicate(&self, similarity_threshold: f32) -> Result<Vec<String>> {
+        let mut rows = self.rows.write().await;
+        let candidates = rows.iter()
+            .map(|(vector, metadata, indexed_at)| DedupCandidate {
+                doc_id: metadata.doc_id.clone(),
+                file_type: metadata.file_type.clone(),
+                vector: vector.clone(),
+                indexed_at: *indexed_at,
+            })
+            .collect();
+        let duplicates = find_duplicates(candidates, similarity_threshold);
+        rows.retain(|(_, m, _)| !duplicates.contains(&m.doc_id));
+        Ok(duplicates)
+    }
+
+    async fn reindex_file(&self, file_path: &Path, new_embeddings: Vec<Vec<f32>>, metadata: Vec<DocumentMetadata>) -> Result<Vec<String>> {
+        let mut rows = self.rows.write().await;
+        rows.retain(|(_, m, _)| m.file_path != file_path);
+        Self::push_rows(&mut rows, new_embeddings, metadata)
+    }
+
+    async fn save(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn count(&self) -> usize {
+        self.rows.read().await.len()
+    }
 }
 
 #[cfg(test)]
@@ -467,7 +1558,7 @@ mod tests {
     #[tokio::test]
     async fn test_lance_store_basic() {
         let dir = tempdir().unwrap();
-        let store = LanceVectorStore::new(dir.path().to_path_buf()).await.unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
         
         // Add an embedding
         let embedding = vec![0.1f32; 384];
@@ -477,6 +1568,9 @@ mod tests {
             file_type: "txt".to_string(),
             chunk_index: 0,
             snippet: Some("test snippet".to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
         };
         
         let doc_id = store.add_embedding(embedding.clone(), metadata).await.unwrap();
@@ -486,15 +1580,100 @@ mod tests {
         assert_eq!(store.count().await, 1);
         
         // Search should return the document
-        let results = store.search(embedding, 10).await.unwrap();
+        let results = store.search(embedding, &SearchOptions::new(10)).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, doc_id);
+    }
+
+    #[tokio::test]
+    async fn test_lance_store_custom_dimension() {
+        let dir = tempdir().unwrap();
+        let embedding = vec![0.1f32; 768];
+        let metadata = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from("/test/wide.txt"),
+            file_type: "txt".to_string(),
+            chunk_index: 0,
+            snippet: None,
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let doc_id = {
+            let store = LanceVectorStore::new(dir.path().to_path_buf(), 768).await.unwrap();
+            assert!(store.add_embedding(vec![0.1f32; 384], metadata.clone()).await.is_err());
+            store.add_embedding(embedding.clone(), metadata).await.unwrap()
+        };
+        
+        // Reopening with the same dimension finds the row; a different one is refused
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 768).await.unwrap();
+        let results = store.search(embedding, &SearchOptions::new(10)).await.unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].doc_id, doc_id);
+        assert!(LanceVectorStore::new(dir.path().to_path_buf(), 384).await.is_err());
+
+        // Opening without a dimension takes the stored one
+        let store = LanceVectorStore::open_existing(dir.path().to_path_buf()).await.unwrap();
+        assert_eq!(store.dim, 768);
+        assert_eq!(store.count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_lance_store_migrates_old_table() {
+        let dir = tempdir().unwrap();
+        // Schema of tables written before byte offsets and document properties were stored
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("doc_id", DataType::Utf8, false),
+            Field::new("file_path", DataType::Utf8, false),
+            Field::new("file_type", DataType::Utf8, false),
+            Field::new("chunk_index", DataType::Int32, false),
+            Field::new("snippet", DataType::Utf8, true),
+            Field::new(
+                "vector",
+                DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), 4),
+                false,
+            ),
+        ]));
+        let mut vectors = FixedSizeListBuilder::new(Float32Builder::new(), 4);
+        vectors.values().append_slice(&[0.1, 0.2, 0.3, 0.4]);
+        vectors.append(true);
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(vec!["old"])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["/test/old.txt"])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["txt"])) as ArrayRef,
+            Arc::new(Int32Array::from(vec![0])) as ArrayRef,
+            Arc::new(StringArray::from(vec![Some("old snippet")])) as ArrayRef,
+            Arc::new(vectors.finish()) as ArrayRef,
+        ]).unwrap();
+        let db = connect(&dir.path().to_string_lossy()).execute().await.unwrap();
+        db.create_table(TABLE_NAME, RecordBatchIterator::new(vec![Ok(batch)], schema))
+            .execute().await.unwrap();
+
+        let store = LanceVectorStore::open_existing(dir.path().to_path_buf()).await.unwrap();
+        let old = store.get_metadata("old").await.unwrap().unwrap();
+        assert_eq!((old.byte_start, old.byte_end), (0, 0));
+
+        // New rows keep offsets past what 32 bits hold
+        let metadata = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from("/test/huge.txt"),
+            file_type: "txt".to_string(),
+            chunk_index: 0,
+            snippet: None,
+            byte_start: 5_000_000_000,
+            byte_end: 5_000_000_100,
+            ..Default::default()
+        };
+        let doc_id = store.add_embedding(vec![0.4, 0.3, 0.2, 0.1], metadata).await.unwrap();
+        let new = store.get_metadata(&doc_id).await.unwrap().unwrap();
+        assert_eq!((new.byte_start, new.byte_end), (5_000_000_000, 5_000_000_100));
+        assert_eq!(store.count().await, 2);
     }
 
     #[tokio::test]
     async fn test_lance_store_get_metadata() {
         let dir = tempdir().unwrap();
-        let store = LanceVectorStore::new(dir.path().to_path_buf()).await.unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
         
         let embedding = vec![0.5f32; 384];
         let metadata = DocumentMetadata {
@@ -503,6 +1682,9 @@ mod tests {
             file_type: "pdf".to_string(),
             chunk_index: 5,
             snippet: Some("hello world".to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
         };
         
         let doc_id = store.add_embedding(embedding, metadata).await.unwrap();
@@ -513,5 +1695,320 @@ mod tests {
         assert_eq!(m.file_type, "pdf");
         assert_eq!(m.chunk_index, 5);
     }
-}
 
+    #[tokio::test]
+    async fn test_lance_store_deduplicate() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        
+        let make_metadata = |path: &str, snippet: &str| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "md".to_string(),
+            chunk_index: 0,
+            snippet: Some(snippet.to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        
+        // Two copies of the same README plus an unrelated file
+        store.add_embedding(vec![0.3f32; 384], make_metadata("/a/README.md", "# Project")).await.unwrap();
+        store.add_embedding(vec![0.3f32; 384], make_metadata("/b/README.md", "# Project")).await.unwrap();
+        let mut other = vec![0.0f32; 384];
+        other[0] = 1.0;
+        store.add_embedding(other, make_metadata("/c/notes.md", "Meeting notes")).await.unwrap();
+        
+        assert_eq!(store.deduplicate(0.98).await.unwrap().len(), 1);
+        assert_eq!(store.count().await, 2);
+        
+        // Running again finds nothing left to remove
+        assert!(store.deduplicate(0.98).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_within_date_range() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        
+        let metadata = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from("/test/recent.txt"),
+            file_type: "txt".to_string(),
+            chunk_index: 0,
+            snippet: Some("recent".to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let doc_id = store.add_embedding(vec![0.2f32; 384], metadata).await.unwrap();
+        let now = unix_now();
+        
+        let in_range = store.search(vec![0.2f32; 384], &SearchOptions::new(10).with_date_range(Some(now - 3600), Some(now + 3600))).await.unwrap();
+        assert_eq!(in_range.len(), 1);
+        assert_eq!(in_range[0].doc_id, doc_id);
+        
+        // Indexed after the upper bound
+        let older = store.search(vec![0.2f32; 384], &SearchOptions::new(10).with_date_range(None, Some(now - 3600))).await.unwrap();
+        assert!(older.is_empty());
+        
+        // Indexed before the lower bound
+        let newer = store.search(vec![0.2f32; 384], &SearchOptions::new(10).with_date_range(Some(now + 3600), None)).await.unwrap();
+        assert!(newer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_file_paths() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        assert!(store.get_all_file_paths().await.unwrap().is_empty());
+        
+        let make_metadata = |path: &str, chunk_index: usize| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "txt".to_string(),
+            chunk_index,
+            snippet: None,
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let metadata = vec![
+            make_metadata("/docs/a.txt", 0),
+            make_metadata("/docs/a.txt", 1),
+            make_metadata("/docs/b.txt", 0),
+            make_metadata("/docs/c.txt", 0),
+            make_metadata("/docs/c.txt", 1),
+        ];
+        store.add_embeddings_batch(vec![vec![0.1f32; 384]; 5], metadata).await.unwrap();
+        
+        let paths = store.get_all_file_paths().await.unwrap();
+        assert_eq!(paths, vec![
+            PathBuf::from("/docs/a.txt"),
+            PathBuf::from("/docs/b.txt"),
+            PathBuf::from("/docs/c.txt"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_lexical_rebuild_from_lance_snapshot() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        
+        let metadata: Vec<DocumentMetadata> = (0..50)
+            .map(|i| DocumentMetadata {
+                doc_id: String::new(),
+                file_path: PathBuf::from(format!("/docs/file{}.txt", i / 5)),
+                file_type: "txt".to_string(),
+                chunk_index: i % 5,
+                snippet: Some(if i == 42 { "the zebra escaped".to_string() } else { format!("ordinary chunk {}", i) }),
+                byte_start: 0,
+                byte_end: 0,
+                ..Default::default()
+            })
+            .collect();
+        let doc_ids = store.add_embeddings_batch(vec![vec![0.1f32; 384]; 50], metadata).await.unwrap();
+        assert_eq!(store.scan_all().await.unwrap().len(), 50);
+        
+        let lexical = LexicalIndex::create_from_lance_snapshot(&store, dir.path().to_path_buf()).await.unwrap();
+        assert_eq!(lexical.count().unwrap(), 50);
+        
+        let results = lexical.search("zebra", 10, SearchMode::Auto).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, doc_ids[42]);
+        assert_eq!(results[0].file_path, "/docs/file8.txt");
+        assert_eq!(results[0].chunk_index, 2);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_file_replaces_rows() {
+        let dir = tempdir().unwrap();
+        let state = Arc::new(StateManager::new(dir.path()).unwrap());
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap()
+            .with_state(state.clone());
+        
+        let make_metadata = |path: &str, chunk_index: usize| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "txt".to_string(),
+            chunk_index,
+            snippet: Some(format!("chunk {}", chunk_index)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let old_ids = store.add_embeddings_batch(
+            vec![vec![0.1f32; 384]; 3],
+            vec![make_metadata("/docs/a.txt", 0), make_metadata("/docs/a.txt", 1), make_metadata("/docs/b.txt", 0)],
+        ).await.unwrap();
+        
+        let new_ids = store.reindex_file(
+            Path::new("/docs/a.txt"),
+            vec![vec![0.9f32; 384]; 2],
+            vec![make_metadata("/docs/a.txt", 0), make_metadata("/docs/a.txt", 1)],
+        ).await.unwrap();
+        
+        assert_eq!(new_ids.len(), 2);
+        assert_eq!(store.count().await, 3);
+        assert!(store.get_metadata(&old_ids[0]).await.unwrap().is_none());
+        assert!(store.get_metadata(&old_ids[2]).await.unwrap().is_some());
+        assert_eq!(state.get_doc_ids(Path::new("/docs/a.txt")).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_file_path() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        
+        let make_metadata = |path: &str, chunk_index: usize| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "txt".to_string(),
+            chunk_index,
+            snippet: Some(format!("chunk {}", chunk_index)),
+            byte_start: chunk_index * 100,
+            byte_end: chunk_index * 100 + 100,
+            ..Default::default()
+        };
+        // Insert out of order, interleaved with another file
+        let mut metadata: Vec<_> = [3, 7, 0, 9, 1, 5, 2, 8, 6, 4].iter().map(|&i| make_metadata("/docs/long.txt", i)).collect();
+        metadata.push(make_metadata("/docs/other.txt", 0));
+        store.add_embeddings_batch(vec![vec![0.1f32; 384]; 11], metadata).await.unwrap();
+        
+        let chunks = store.get_by_file_path(Path::new("/docs/long.txt")).await.unwrap();
+        assert_eq!(chunks.iter().map(|m| m.chunk_index).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        assert_eq!(chunks[4].snippet.as_deref(), Some("chunk 4"));
+        assert_eq!(chunks[4].byte_start, 400);
+        assert!(store.get_by_file_path(Path::new("/docs/missing.txt")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_file_path() {
+        let dir = tempdir().unwrap();
+        let store = LanceVectorStore::new(dir.path().to_path_buf(), 384).await.unwrap();
+        
+        let make_metadata = |path: &str, chunk_index: usize| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "txt".to_string(),
+            chunk_index,
+            snippet: Some(format!("chunk {}", chunk_index)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let mut metadata: Vec<_> = (0..3).map(|i| make_metadata("/a.txt", i)).collect();
+        metadata.extend((0..2).map(|i| make_metadata("/b.txt", i)));
+        store.add_embeddings_batch(vec![vec![0.1f32; 384]; 5], metadata).await.unwrap();
+        
+        assert_eq!(store.delete_by_file_path(Path::new("/a.txt")).await.unwrap(), 3);
+        assert_eq!(store.count().await, 2);
+        assert_eq!(store.count_by_file_path("/b.txt").await.unwrap(), 2);
+        assert_eq!(store.delete_by_file_path(Path::new("/a.txt")).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store() {
+        let store = InMemoryVectorStore::new();
+        let make_metadata = |path: &str, chunk_index: usize, snippet: &str| DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(path),
+            file_type: "txt".to_string(),
+            chunk_index,
+            snippet: Some(snippet.to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let ids = store.add_embeddings_batch(
+            vec![vec![1.0, 0.0], vec![0.6, 0.8], vec![0.0, 1.0], vec![0.0, 1.0]],
+            vec![
+                make_metadata("/a.txt", 1, "east"),
+                make_metadata("/a.txt", 0, "north-east"),
+                make_metadata("/b.txt", 0, "north"),
+                make_metadata("/c.txt", 0, "north"),
+            ],
+        ).await.unwrap();
+        assert_eq!(store.count().await, 4);
+        
+        let results = store.search(vec![1.0, 0.0], &SearchOptions::new(2)).await.unwrap();
+        assert_eq!(results.iter().map(|r| r.doc_id.clone()).collect::<Vec<_>>(), vec![ids[0].clone(), ids[1].clone()]);
+        assert!((results[1].score - 0.6).abs() < 1e-6);
+        assert!(store.search(vec![1.0, 0.0], &SearchOptions::new(2).with_date_range(Some(unix_now() + 60), None)).await.unwrap().is_empty());
+        let in_b = SearchOptions::new(4).with_filter_path(Some(glob::Pattern::new("/b*").unwrap()));
+        let results = store.search(vec![1.0, 0.0], &in_b).await.unwrap();
+        assert_eq!(results.iter().map(|r| r.doc_id.clone()).collect::<Vec<_>>(), vec![ids[2].clone()]);
+        let no_txt = SearchOptions::new(4).with_filter_type(Some(vec!["pdf".to_string()]));
+        assert!(store.search(vec![1.0, 0.0], &no_txt).await.unwrap().is_empty());
+        assert_eq!(store.get_metadata(&ids[2][..8]).await.unwrap().unwrap().doc_id, ids[2]);
+        assert_eq!(store.get_embedding_by_doc_id(&ids[1]).await.unwrap(), Some(vec![0.6, 0.8]));
+        assert_eq!(store.get_embedding_by_doc_id(&ids[1][..8]).await.unwrap(), None);
+        let embeddings = store.get_embeddings_by_doc_ids(&[ids[1].clone(), ids[1][..8].to_string()]).await.unwrap();
+        assert_eq!(embeddings, HashMap::from([(ids[1].clone(), vec![0.6, 0.8])]));
+        let found = store.get_metadata_by_doc_ids(&[ids[0].clone(), ids[2].clone(), ids[1][..8].to_string()]).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[&ids[2]].file_path, PathBuf::from("/b.txt"));
+        let chunks = store.get_by_file_path(Path::new("/a.txt")).await.unwrap();
+        assert_eq!(chunks.iter().map(|m| m.chunk_index).collect::<Vec<_>>(), vec![0, 1]);
+        
+        // /b.txt and /c.txt hold the same chunk
+        assert_eq!(store.deduplicate(0.99).await.unwrap(), vec![ids[3].clone()]);
+        assert_eq!(store.delete_by_file_path(Path::new("/a.txt")).await.unwrap(), 2);
+        assert_eq!(store.count().await, 1);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let candidate = |doc_id: &str, file_type: &str, vector: Vec<f32>, indexed_at: i64| DedupCandidate {
+            doc_id: doc_id.to_string(),
+            file_type: file_type.to_string(),
+            vector,
+            indexed_at,
+        };
+        let candidates = vec![
+            candidate("old", "md", vec![1.0, 0.0], 10),
+            candidate("new", "md", vec![0.99, 0.05], 20),
+            candidate("pdf", "pdf", vec![1.0, 0.0], 5),
+            candidate("other", "md", vec![0.0, 1.0], 5),
+        ];
+        // Near-identical vectors of the same type collapse into the newest copy
+        assert_eq!(find_duplicates(candidates, 0.98), vec!["old".to_string()]);
+        let candidates = vec![
+            candidate("old", "md", vec![1.0, 0.0], 10),
+            candidate("new", "md", vec![0.99, 0.05], 20),
+        ];
+        assert!(find_duplicates(candidates, 0.999).is_empty());
+    }
+
+    #[test]
+    fn test_doc_id_filter() {
+        assert_eq!(doc_id_filter(&["a".to_string(), "o'b".to_string()]), "doc_id IN ('a', 'o''b')");
+    }
+
+    #[test]
+    fn test_search_filter() {
+        assert_eq!(search_filter(&SearchOptions::new(5)), None);
+        let options = SearchOptions::new(5).with_date_range(Some(10), None);
+        assert_eq!(search_filter(&options).as_deref(), Some("indexed_at >= 10"));
+        let options = SearchOptions::new(5).with_date_range(Some(10), Some(20));
+        assert_eq!(search_filter(&options).as_deref(), Some("indexed_at >= 10 AND indexed_at < 20"));
+        let options = SearchOptions::new(5).with_filter_path(Some(glob::Pattern::new("/home/o'neil/Work/**").unwrap()));
+        assert_eq!(search_filter(&options).as_deref(), Some("file_path LIKE '/home/o''neil/Work/%'"));
+        let options = SearchOptions::new(5).with_filter_type(Some(vec!["PDF".to_string(), ".txt".to_string()]));
+        assert_eq!(search_filter(&options).as_deref(), Some("lower(file_type) IN ('pdf', 'txt')"));
+    }
+
+    #[test]
+    fn test_glob_to_like() {
+        assert_eq!(glob_to_like("/docs/**/*.md"), "/docs/%/%.md");
+        assert_eq!(glob_to_like("/docs/report-?.[tp]df"), "/docs/report-_._df");
+        assert_eq!(glob_to_like("/my_docs/100%"), "/my_docs/100_");
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -4,13 +4,22 @@
 //! - File paths and modification timestamps
 //! - Which files have been indexed and when
 //! - Doc IDs associated with each file (for garbage collection)
+//! - Optional content hashes, for change detection that survives mtime changes
+//! - A schema version, so older databases are upgraded on open (see `MIGRATIONS`)
+//!
+//! `check_integrity` cross-checks these records against the vector store and disk.
 
 use anyhow::{Result, Context};
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::SystemTime;
 
+use crate::{LexicalIndex, VectorStore};
+
 /// File state in the index
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileState {
@@ -34,6 +43,129 @@ pub struct FileInfo {
     pub doc_ids: Vec<String>,
 }
 
+/// Order of `StateManager::list_files` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListSort {
+    /// By path, ascending.
+    #[default]
+    Path,
+    /// Most chunks first.
+    Chunks,
+    /// Most recently indexed first.
+    Date,
+}
+
+impl std::str::FromStr for ListSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "path" => Ok(ListSort::Path),
+            "chunks" => Ok(ListSort::Chunks),
+            "date" => Ok(ListSort::Date),
+            _ => anyhow::bail!("Unknown sort field '{}' (expected path, chunks or date)", s),
+        }
+    }
+}
+
+/// Which files `StateManager::list_files` returns, and in what order.
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    /// Only files with this extension (without the dot, case-insensitive).
+    pub file_type: Option<String>,
+    /// Only this path and, if it is a directory, the files below it.
+    pub path_prefix: Option<PathBuf>,
+    pub sort_by: ListSort,
+    /// Return at most this many files.
+    pub limit: Option<usize>,
+}
+
+/// File and chunk counts returned by `StateManager::stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Tracked files.
+    pub total_files: usize,
+    /// Recorded doc IDs, one per indexed chunk.
+    pub total_doc_ids: usize,
+    /// Tracked files per lowercase extension; files without one count as "unknown".
+    pub by_extension: HashMap<String, usize>,
+    /// Lexical documents per file path, from `LexicalIndex::count_by_file`.
+    /// Only filled in by `StateManager::stats_with_lexical`.
+    pub lexical_docs_by_file: Option<HashMap<String, usize>>,
+}
+
+/// What `StateManager::vacuum` purged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VacuumResult {
+    /// Tracked files that were missing on disk and are no longer tracked.
+    pub removed_files: usize,
+    /// Doc IDs those files had, to delete from the vector store and lexical index.
+    pub removed_doc_ids: Vec<String>,
+}
+
+/// Inconsistencies found by `StateManager::check_integrity`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// Doc IDs recorded in `file_docs` that do not exist in the vector store.
+    pub orphaned_doc_ids: Vec<String>,
+    /// Tracked files that no longer exist on disk.
+    pub missing_files: Vec<PathBuf>,
+    /// Paths with `file_docs` rows but no `files` row.
+    pub extra_files: Vec<PathBuf>,
+}
+
+impl IntegrityReport {
+    /// True if no inconsistencies were found.
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_doc_ids.is_empty() && self.missing_files.is_empty() && self.extra_files.is_empty()
+    }
+}
+
+/// State of a tracked file given its stored mtime: deleted, modified or indexed.
+fn state_on_disk(path: &Path, stored_mtime: i64) -> FileState {
+    if !path.exists() {
+        return FileState::Deleted;
+    }
+    let current_mtime = path
+        .metadata()
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    if current_mtime > stored_mtime {
+        FileState::Modified
+    } else {
+        FileState::Indexed
+    }
+}
+
+/// Escape `%`, `_` and `\` for a `LIKE ... ESCAPE '\'` pattern.
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Number of leading bytes hashed by `content_hash`.
+const CONTENT_HASH_BYTES: u64 = 64 * 1024;
+
+/// SHA-256 (hex) of the first 64 KB of a file.
+pub fn content_hash(path: &Path) -> Result<String> {
+    let mut data = Vec::new();
+    std::fs::File::open(path)?
+        .take(CONTENT_HASH_BYTES)
+        .read_to_end(&mut data)?;
+    Ok(Sha256::digest(&data).iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Columns added to `files` in order on open, as `(version, column, definition)`.
+/// Version 1 is the original `files`/`file_docs` layout; append new entries, never
+/// edit old ones.
+const MIGRATIONS: &[(u32, &str, &str)] = &[
+    (2, "next_chunk_index", "INTEGER DEFAULT 0"),
+    (3, "content_hash", "TEXT"),
+    (4, "file_size", "INTEGER"),
+];
+
 /// SQLite-based state manager for tracking indexed files.
 pub struct StateManager {
     conn: Mutex<Connection>,
@@ -46,8 +178,10 @@ impl StateManager {
         let db_path = data_dir.join("state.db");
         let conn = Connection::open(&db_path)
             .context("Failed to open state database")?;
+        // SQLite leaves foreign keys unenforced unless asked, per connection
+        conn.pragma_update(None, "foreign_keys", true)?;
         
-        // Create tables
+        // Create the version 1 tables; `migrate` brings them up to date
         conn.execute_batch(r#"
             CREATE TABLE IF NOT EXISTS files (
                 path TEXT PRIMARY KEY,
@@ -67,11 +201,48 @@ impl StateManager {
             
             CREATE INDEX IF NOT EXISTS idx_file_docs_path ON file_docs(path);
             CREATE INDEX IF NOT EXISTS idx_file_docs_doc_id ON file_docs(doc_id);
+            
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
         "#).context("Failed to create tables")?;
         
+        Self::migrate(&conn).context("Failed to migrate state database")?;
+        
         Ok(Self { conn: Mutex::new(conn) })
     }
     
+    /// Apply the `MIGRATIONS` newer than the stored `schema_version`, each in
+    /// its own transaction. Databases without a version count as version 1.
+    fn migrate(conn: &Connection) -> Result<()> {
+        let version: u32 = conn
+            .query_row("SELECT value FROM meta WHERE key = 'schema_version'", [], |row| row.get::<_, String>(0))
+            .optional()?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1);
+        
+        for &(target, column, definition) in MIGRATIONS.iter().filter(|(v, _, _)| *v > version) {
+            let tx = conn.unchecked_transaction()?;
+            // Columns added before versioning existed are already there
+            let exists = tx
+                .query_row("SELECT 1 FROM pragma_table_info('files') WHERE name = ?1", params![column], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if !exists {
+                tx.execute(&format!("ALTER TABLE files ADD COLUMN {} {}", column, definition), [])
+                    .with_context(|| format!("migration to schema version {} failed", target))?;
+            }
+            tx.execute(
+                "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = ?1",
+                params![target.to_string()],
+            )?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+    
     /// Mark a file as indexed with its current modification time.
     /// Also records the doc_ids generated for this file.
     pub fn mark_indexed(&self, path: &Path, mtime: SystemTime, doc_ids: &[String]) -> Result<()> {
@@ -84,14 +255,16 @@ impl StateManager {
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
         
+        let file_size = std::fs::metadata(path).ok().map(|m| m.len() as i64);
+        
         let path_str = path.to_string_lossy().to_string();
         let conn = self.conn.lock().unwrap();
         
         // Upsert file record
         conn.execute(
-            "INSERT INTO files (path, file_mtime, indexed_at, total_pages, pages_indexed) VALUES (?1, ?2, ?3, 1, 1)
-             ON CONFLICT(path) DO UPDATE SET file_mtime = ?2, indexed_at = ?3, total_pages = 1, pages_indexed = 1",
-            params![path_str, mtime_secs, now],
+            "INSERT INTO files (path, file_mtime, indexed_at, total_pages, pages_indexed, file_size) VALUES (?1, ?2, ?3, 1, 1, ?4)
+             ON CONFLICT(path) DO UPDATE SET file_mtime = ?2, indexed_at = ?3, total_pages = 1, pages_indexed = 1, file_size = ?4",
+            params![path_str, mtime_secs, now, file_size],
         )?;
         
         // Clear old doc_ids and insert new ones
@@ -109,7 +282,8 @@ impl StateManager {
     
     /// Mark a page as indexed (for paged documents like PDFs).
     /// This enables resumable indexing - if interrupted, we can continue from last page.
-    pub fn mark_page_indexed(&self, path: &Path, mtime: SystemTime, page_num: usize, total_pages: usize, doc_ids: &[String]) -> Result<()> {
+    /// `chunk_offset` is the global chunk index of the page's first chunk.
+    pub fn mark_page_indexed(&self, path: &Path, mtime: SystemTime, page_num: usize, total_pages: usize, chunk_offset: usize, doc_ids: &[String]) -> Result<()> {
         let mtime_secs = mtime
             .duration_since(SystemTime::UNIX_EPOCH)
             .map(|d| d.as_secs() as i64)
@@ -119,14 +293,17 @@ impl StateManager {
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0);
         
+        let file_size = std::fs::metadata(path).ok().map(|m| m.len() as i64);
+        
         let path_str = path.to_string_lossy().to_string();
         let conn = self.conn.lock().unwrap();
         
         // Upsert file record with page progress
+        let next_chunk_index = (chunk_offset + doc_ids.len()) as i64;
         conn.execute(
-            "INSERT INTO files (path, file_mtime, indexed_at, total_pages, pages_indexed) VALUES (?1, ?2, ?3, ?4, ?5)
-             ON CONFLICT(path) DO UPDATE SET file_mtime = ?2, indexed_at = ?3, total_pages = ?4, pages_indexed = ?5",
-            params![path_str, mtime_secs, now, total_pages as i64, (page_num + 1) as i64],
+            "INSERT INTO files (path, file_mtime, indexed_at, total_pages, pages_indexed, next_chunk_index, file_size) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(path) DO UPDATE SET file_mtime = ?2, indexed_at = ?3, total_pages = ?4, pages_indexed = ?5, next_chunk_index = ?6, file_size = ?7",
+            params![path_str, mtime_secs, now, total_pages as i64, (page_num + 1) as i64, next_chunk_index, file_size],
         )?;
         
         // Insert doc_ids for this page
@@ -140,6 +317,21 @@ impl StateManager {
         Ok(())
     }
     
+    /// Get the global chunk index to continue from when resuming a paged file.
+    pub fn get_chunk_offset(&self, path: &Path) -> Result<usize> {
+        let path_str = path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        
+        let offset: Option<i64> = conn
+            .query_row(
+                "SELECT next_chunk_index FROM files WHERE path = ?1",
+                params![path_str],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(offset.unwrap_or(0).max(0) as usize)
+    }
+    
     /// Get the last indexed page for a file (for resuming).
     /// Returns None if file not indexed, or the 0-indexed last completed page.
     pub fn get_resume_page(&self, path: &Path, current_mtime: SystemTime) -> Result<Option<usize>> {
@@ -222,6 +414,88 @@ impl StateManager {
         Ok(matches!(state, FileState::NotIndexed | FileState::Modified))
     }
     
+    /// Like `needs_indexing`, but uses the stored content hash to settle mtime changes.
+    ///
+    /// If the mtime is unchanged but the hash differs, the file is treated as modified.
+    /// If the mtime changed but the hash matches, the file is unchanged and its stored
+    /// mtime is refreshed so later plain mtime checks agree. Files indexed without a
+    /// hash fall back to the mtime comparison.
+    pub fn needs_indexing_with_hash(&self, path: &Path, hash: &str) -> Result<bool> {
+        let state = self.get_file_state(path)?;
+        if matches!(state, FileState::NotIndexed | FileState::Deleted) {
+            return Ok(state == FileState::NotIndexed);
+        }
+        
+        let path_str = path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        let stored_hash: Option<String> = conn
+            .query_row(
+                "SELECT content_hash FROM files WHERE path = ?1",
+                params![path_str],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        let stored_hash = match stored_hash {
+            Some(h) => h,
+            None => return Ok(state == FileState::Modified),
+        };
+        
+        match state {
+            FileState::Modified if stored_hash == hash => {
+                let mtime_secs = path
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0);
+                conn.execute(
+                    "UPDATE files SET file_mtime = ?2 WHERE path = ?1",
+                    params![path_str, mtime_secs],
+                )?;
+                Ok(false)
+            }
+            FileState::Modified => Ok(true),
+            _ => Ok(stored_hash != hash),
+        }
+    }
+    
+    /// `needs_indexing_with_hash` with the file's current `content_hash`.
+    /// Files that can't be read fall back to the mtime comparison.
+    pub fn needs_indexing_by_hash(&self, path: &Path) -> Result<bool> {
+        match content_hash(path) {
+            Ok(hash) => self.needs_indexing_with_hash(path, &hash),
+            Err(_) => self.needs_indexing(path),
+        }
+    }
+    
+    /// Get the stored content hash of a file, if one was recorded.
+    pub fn get_file_hash(&self, path: &Path) -> Result<Option<String>> {
+        let path_str = path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        let hash: Option<String> = conn
+            .query_row(
+                "SELECT content_hash FROM files WHERE path = ?1",
+                params![path_str],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(hash)
+    }
+    
+    /// Record the content hash of an indexed file.
+    pub fn set_content_hash(&self, path: &Path, hash: &str) -> Result<()> {
+        let path_str = path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE files SET content_hash = ?2 WHERE path = ?1",
+            params![path_str, hash],
+        )?;
+        Ok(())
+    }
+    
     /// Get all doc_ids for a file (for deletion during re-indexing or garbage collection).
     pub fn get_doc_ids(&self, path: &Path) -> Result<Vec<String>> {
         let path_str = path.to_string_lossy().to_string();
@@ -236,6 +510,55 @@ impl StateManager {
         Ok(doc_ids)
     }
     
+    /// Replace the doc_ids recorded for a file, leaving its mtime and page progress as is.
+    /// Used when a file's embeddings are regenerated without the file changing.
+    /// A file that isn't recorded yet gets no doc_ids; `mark_indexed` adds them with the file.
+    pub fn replace_doc_ids(&self, path: &Path, doc_ids: &[String]) -> Result<()> {
+        let path_str = path.to_string_lossy().to_string();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        tx.execute("DELETE FROM file_docs WHERE path = ?1", params![path_str])?;
+        for doc_id in doc_ids {
+            tx.execute(
+                "INSERT INTO file_docs (path, doc_id, page_num)
+                 SELECT ?1, ?2, 0 WHERE EXISTS (SELECT 1 FROM files WHERE path = ?1)",
+                params![path_str, doc_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Forget individual doc_ids, e.g. after deduplication removed their embeddings.
+    /// The files they belonged to stay tracked. Returns how many rows were removed.
+    pub fn remove_doc_ids(&self, doc_ids: &[String]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        let mut removed = 0;
+        for doc_id in doc_ids {
+            removed += tx.execute("DELETE FROM file_docs WHERE doc_id = ?1", params![doc_id])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+    
+    /// Get when a file was last indexed (unix seconds), if it is tracked.
+    pub fn indexed_at(&self, path: &Path) -> Result<Option<i64>> {
+        let path_str = path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        
+        let indexed_at = conn
+            .query_row(
+                "SELECT indexed_at FROM files WHERE path = ?1",
+                params![path_str],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(indexed_at)
+    }
+    
     /// Get all files that are marked as deleted (exist in DB but not on disk).
     pub fn get_deleted_files(&self) -> Result<Vec<PathBuf>> {
         let conn = self.conn.lock().unwrap();
@@ -273,6 +596,38 @@ impl StateManager {
         Ok(doc_ids)
     }
     
+    /// Stop tracking every file that no longer exists on disk, in one transaction.
+    /// Only the state database changes; the caller deletes `removed_doc_ids`
+    /// from the stores.
+    pub fn vacuum(&self) -> Result<VacuumResult> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        let missing: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT path FROM files")?;
+            let paths = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .filter_map(|r| r.ok())
+                .filter(|p| !Path::new(p).exists())
+                .collect();
+            paths
+        };
+        
+        let mut result = VacuumResult::default();
+        for path_str in &missing {
+            let mut stmt = tx.prepare("SELECT doc_id FROM file_docs WHERE path = ?1")?;
+            let doc_ids = stmt.query_map(params![path_str], |row| row.get::<_, String>(0))?;
+            for doc_id in doc_ids {
+                result.removed_doc_ids.push(doc_id?);
+            }
+            drop(stmt);
+            tx.execute("DELETE FROM file_docs WHERE path = ?1", params![path_str])?;
+            result.removed_files += tx.execute("DELETE FROM files WHERE path = ?1", params![path_str])?;
+        }
+        tx.commit()?;
+        
+        Ok(result)
+    }
+    
     /// Get total number of tracked files.
     pub fn file_count(&self) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
@@ -280,6 +635,53 @@ impl StateManager {
         Ok(count as usize)
     }
     
+    /// Number of recorded doc_ids per file path.
+    ///
+    /// A single `GROUP BY` over `file_docs`, so this is cheap even for large
+    /// indexes. It reflects what indexing recorded; see
+    /// `LexicalIndex::count_by_file` for the counts actually in the lexical index.
+    pub fn count_by_file(&self) -> Result<HashMap<String, usize>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT path, COUNT(*) FROM file_docs GROUP BY path")?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(counts)
+    }
+    
+    /// Total files and doc IDs, and the number of files per extension.
+    pub fn stats(&self) -> Result<IndexStats> {
+        let conn = self.conn.lock().unwrap();
+        let total_files: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))?;
+        let total_doc_ids: i64 = conn.query_row("SELECT COUNT(*) FROM file_docs", [], |row| row.get(0))?;
+        
+        // SQLite cannot split off an extension, so group the paths here
+        let mut stmt = conn.prepare("SELECT path FROM files")?;
+        let mut by_extension = HashMap::new();
+        for path in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let extension = Path::new(&path?)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "unknown".to_string());
+            *by_extension.entry(extension).or_insert(0) += 1;
+        }
+        
+        Ok(IndexStats {
+            total_files: total_files as usize,
+            total_doc_ids: total_doc_ids as usize,
+            by_extension,
+            lexical_docs_by_file: None,
+        })
+    }
+    
+    /// Like `stats`, with the per-file document counts of `lexical` added.
+    pub fn stats_with_lexical(&self, lexical: &LexicalIndex) -> Result<IndexStats> {
+        let mut stats = self.stats()?;
+        stats.lexical_docs_by_file = Some(lexical.count_by_file()?);
+        Ok(stats)
+    }
+    
     /// Get all tracked files with their info.
     pub fn get_all_files(&self) -> Result<Vec<FileInfo>> {
         let conn = self.conn.lock().unwrap();
@@ -305,25 +707,7 @@ impl StateManager {
                 .collect();
             drop(stmt);
             
-            // Determine state
-            let file_state = if !path.exists() {
-                FileState::Deleted
-            } else {
-                let current_mtime = path
-                    .metadata()
-                    .ok()
-                    .and_then(|m| m.modified().ok())
-                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs() as i64)
-                    .unwrap_or(0);
-                
-                if current_mtime > file_mtime {
-                    FileState::Modified
-                } else {
-                    FileState::Indexed
-                }
-            };
-            
+            let file_state = state_on_disk(&path, file_mtime);
             result.push(FileInfo {
                 path,
                 file_state,
@@ -335,11 +719,114 @@ impl StateManager {
         
         Ok(result)
     }
+    
+    /// Tracked files matching `filter`, each with its doc_ids (one per chunk).
+    pub fn list_files(&self, filter: &ListFilter) -> Result<Vec<FileInfo>> {
+        let conn = self.conn.lock().unwrap();
+        
+        let mut sql = String::from(
+            "SELECT f.path, f.file_mtime, f.indexed_at, COUNT(d.doc_id) AS chunks
+             FROM files f LEFT JOIN file_docs d ON d.path = f.path",
+        );
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut args: Vec<String> = Vec::new();
+        if let Some(ref ext) = filter.file_type {
+            // LIKE is case-insensitive for ASCII in SQLite
+            conditions.push("f.path LIKE ? ESCAPE '\\'");
+            args.push(format!("%.{}", escape_like(ext.trim_start_matches('.'))));
+        }
+        if let Some(ref prefix) = filter.path_prefix {
+            let prefix = prefix.to_string_lossy();
+            let dir = prefix.trim_end_matches(std::path::MAIN_SEPARATOR);
+            conditions.push("(f.path = ? OR f.path LIKE ? ESCAPE '\\')");
+            args.push(prefix.to_string());
+            args.push(format!("{}%", escape_like(&format!("{}{}", dir, std::path::MAIN_SEPARATOR))));
+        }
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" GROUP BY f.path");
+        sql.push_str(match filter.sort_by {
+            ListSort::Path => " ORDER BY f.path",
+            ListSort::Chunks => " ORDER BY chunks DESC, f.path",
+            ListSort::Date => " ORDER BY f.indexed_at DESC, f.path",
+        });
+        if let Some(limit) = filter.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        
+        let mut stmt = conn.prepare(&sql)?;
+        let files: Vec<(String, i64, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(&args), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        
+        let mut stmt = conn.prepare("SELECT doc_id FROM file_docs WHERE path = ?1")?;
+        let mut result = Vec::with_capacity(files.len());
+        for (path_str, file_mtime, indexed_at) in files {
+            let doc_ids = stmt
+                .query_map(params![path_str], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            let path = PathBuf::from(path_str);
+            result.push(FileInfo {
+                file_state: state_on_disk(&path, file_mtime),
+                path,
+                indexed_at: Some(indexed_at),
+                file_mtime: Some(file_mtime),
+                doc_ids,
+            });
+        }
+        Ok(result)
+    }
+    
+    /// Compare the state database with the vector store and the file system.
+    /// Every recorded doc ID is looked up in `store` with one `get_metadata_by_doc_ids` call.
+    pub async fn check_integrity(&self, store: &dyn VectorStore) -> Result<IntegrityReport> {
+        let (doc_ids, missing_files, extra_files) = {
+            let conn = self.conn.lock().unwrap();
+            
+            let mut stmt = conn.prepare("SELECT DISTINCT doc_id FROM file_docs ORDER BY doc_id")?;
+            let doc_ids: Vec<String> = stmt
+                .query_map([], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            drop(stmt);
+            
+            let mut stmt = conn.prepare("SELECT path FROM files ORDER BY path")?;
+            let missing_files: Vec<PathBuf> = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .filter_map(|r| r.ok())
+                .map(PathBuf::from)
+                .filter(|p| !p.exists())
+                .collect();
+            drop(stmt);
+            
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT path FROM file_docs WHERE path NOT IN (SELECT path FROM files) ORDER BY path",
+            )?;
+            let extra_files: Vec<PathBuf> = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .filter_map(|r| r.ok())
+                .map(PathBuf::from)
+                .collect();
+            
+            (doc_ids, missing_files, extra_files)
+        };
+        
+        let found = store.get_metadata_by_doc_ids(&doc_ids).await?;
+        let orphaned_doc_ids = doc_ids.into_iter().filter(|id| !found.contains_key(id)).collect();
+        
+        Ok(IntegrityReport { orphaned_doc_ids, missing_files, extra_files })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{DocumentMetadata, InMemoryVectorStore};
     use std::fs;
     use tempfile::TempDir;
     
@@ -372,6 +859,10 @@ mod tests {
         
         // File count
         assert_eq!(state.file_count().unwrap(), 1);
+        
+        // Indexed timestamp is recorded for tracked files only
+        assert!(state.indexed_at(&test_file).unwrap().is_some());
+        assert!(state.indexed_at(&tmp.path().join("other.txt")).unwrap().is_none());
     }
     
     #[test]
@@ -403,4 +894,349 @@ mod tests {
         // Now not tracked
         assert_eq!(state.file_count().unwrap(), 0);
     }
+    
+    #[test]
+    fn test_page_chunk_offsets() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        
+        let test_file = tmp.path().join("doc.pdf");
+        fs::write(&test_file, "pdf").unwrap();
+        let mtime = test_file.metadata().unwrap().modified().unwrap();
+        
+        assert_eq!(state.get_chunk_offset(&test_file).unwrap(), 0);
+        let page0: Vec<String> = (0..1001).map(|i| format!("p0-{}", i)).collect();
+        state.mark_page_indexed(&test_file, mtime, 0, 2, 0, &page0).unwrap();
+        assert_eq!(state.get_chunk_offset(&test_file).unwrap(), 1001);
+        
+        state.mark_page_indexed(&test_file, mtime, 1, 2, 1001, &["p1-0".to_string()]).unwrap();
+        assert_eq!(state.get_chunk_offset(&test_file).unwrap(), 1002);
+    }
+    
+    #[test]
+    fn test_content_hash_change_detection() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        
+        let test_file = tmp.path().join("notes.txt");
+        fs::write(&test_file, "same content").unwrap();
+        let mtime = test_file.metadata().unwrap().modified().unwrap();
+        let hash = content_hash(&test_file).unwrap();
+        state.mark_indexed(&test_file, mtime, &["doc1".to_string()]).unwrap();
+        state.set_content_hash(&test_file, &hash).unwrap();
+        
+        // New mtime, same content: unchanged
+        let later = mtime + std::time::Duration::from_secs(10);
+        fs::File::options().write(true).open(&test_file).unwrap().set_modified(later).unwrap();
+        assert!(state.needs_indexing(&test_file).unwrap());
+        assert!(!state.needs_indexing_with_hash(&test_file, &content_hash(&test_file).unwrap()).unwrap());
+        assert!(!state.needs_indexing(&test_file).unwrap());
+        
+        // Same mtime, different content: modified
+        fs::write(&test_file, "other content").unwrap();
+        fs::File::options().write(true).open(&test_file).unwrap().set_modified(later).unwrap();
+        assert!(state.needs_indexing_with_hash(&test_file, &content_hash(&test_file).unwrap()).unwrap());
+    }
+    
+    #[test]
+    fn test_needs_indexing_by_hash() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        
+        let test_file = tmp.path().join("notes.txt");
+        fs::write(&test_file, "first draft").unwrap();
+        let mtime = test_file.metadata().unwrap().modified().unwrap();
+        state.mark_indexed(&test_file, mtime, &["doc1".to_string()]).unwrap();
+        assert_eq!(state.get_file_hash(&test_file).unwrap(), None);
+        
+        let hash = content_hash(&test_file).unwrap();
+        state.set_content_hash(&test_file, &hash).unwrap();
+        assert_eq!(state.get_file_hash(&test_file).unwrap(), Some(hash));
+        assert!(!state.needs_indexing_by_hash(&test_file).unwrap());
+        
+        // Edited in place with the mtime put back: only the hash notices
+        fs::write(&test_file, "second draft").unwrap();
+        fs::File::options().write(true).open(&test_file).unwrap().set_modified(mtime).unwrap();
+        assert!(!state.needs_indexing(&test_file).unwrap());
+        assert!(state.needs_indexing_by_hash(&test_file).unwrap());
+    }
+    
+    #[test]
+    fn test_replace_doc_ids() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let test_file = tmp.path().join("notes.txt");
+        fs::write(&test_file, "hello").unwrap();
+        let mtime = test_file.metadata().unwrap().modified().unwrap();
+        
+        state.mark_indexed(&test_file, mtime, &["old-1".to_string(), "old-2".to_string()]).unwrap();
+        state.replace_doc_ids(&test_file, &["new-1".to_string()]).unwrap();
+        
+        assert_eq!(state.get_doc_ids(&test_file).unwrap(), vec!["new-1".to_string()]);
+        // The file itself is still up to date
+        assert!(!state.needs_indexing(&test_file).unwrap());
+        
+        // Files not recorded yet are left to `mark_indexed`
+        let new_file = tmp.path().join("new.txt");
+        state.replace_doc_ids(&new_file, &["new-2".to_string()]).unwrap();
+        assert!(state.get_doc_ids(&new_file).unwrap().is_empty());
+    }
+    
+    fn file_columns(data_dir: &Path) -> Vec<String> {
+        let conn = Connection::open(data_dir.join("state.db")).unwrap();
+        let mut stmt = conn.prepare("PRAGMA table_info(files)").unwrap();
+        let columns = stmt.query_map([], |row| row.get(1)).unwrap().map(|r| r.unwrap()).collect();
+        columns
+    }
+    
+    #[test]
+    fn test_migrate_v1_database() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let conn = Connection::open(tmp.path().join("state.db")).unwrap();
+            conn.execute_batch(r#"
+                CREATE TABLE files (
+                    path TEXT PRIMARY KEY,
+                    file_mtime INTEGER NOT NULL,
+                    indexed_at INTEGER NOT NULL,
+                    total_pages INTEGER DEFAULT 1,
+                    pages_indexed INTEGER DEFAULT 0
+                );
+                INSERT INTO files (path, file_mtime, indexed_at) VALUES ('/docs/old.txt', 1, 1);
+            "#).unwrap();
+        }
+        assert!(!file_columns(tmp.path()).contains(&"file_size".to_string()));
+        
+        let state = StateManager::new(tmp.path()).unwrap();
+        let columns = file_columns(tmp.path());
+        for column in ["next_chunk_index", "content_hash", "file_size"] {
+            assert!(columns.contains(&column.to_string()), "missing {}", column);
+        }
+        // Existing rows survive the upgrade
+        assert_eq!(state.file_count().unwrap(), 1);
+        
+        // Reopening an up-to-date database is a no-op
+        drop(state);
+        StateManager::new(tmp.path()).unwrap();
+    }
+    
+    #[test]
+    fn test_migrate_unversioned_database_with_newer_columns() {
+        let tmp = TempDir::new().unwrap();
+        {
+            // Written before schema versions were recorded: some columns exist, no `meta` table
+            let conn = Connection::open(tmp.path().join("state.db")).unwrap();
+            conn.execute_batch(r#"
+                CREATE TABLE files (
+                    path TEXT PRIMARY KEY,
+                    file_mtime INTEGER NOT NULL,
+                    indexed_at INTEGER NOT NULL,
+                    total_pages INTEGER DEFAULT 1,
+                    pages_indexed INTEGER DEFAULT 0,
+                    next_chunk_index INTEGER DEFAULT 0,
+                    content_hash TEXT
+                );
+            "#).unwrap();
+        }
+        
+        StateManager::new(tmp.path()).unwrap();
+        assert!(file_columns(tmp.path()).contains(&"file_size".to_string()));
+    }
+    
+    #[test]
+    fn test_count_by_file() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let mtime = SystemTime::now();
+        
+        for (name, chunks) in [("a.txt", 2), ("b.txt", 4), ("c.txt", 6)] {
+            let path = tmp.path().join(name);
+            let doc_ids: Vec<String> = (0..chunks).map(|i| format!("{}-{}", name, i)).collect();
+            state.mark_indexed(&path, mtime, &doc_ids).unwrap();
+        }
+        
+        let counts = state.count_by_file().unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&tmp.path().join("a.txt").to_string_lossy().to_string()], 2);
+        assert_eq!(counts[&tmp.path().join("b.txt").to_string_lossy().to_string()], 4);
+        assert_eq!(counts[&tmp.path().join("c.txt").to_string_lossy().to_string()], 6);
+    }
+    
+    #[test]
+    fn test_vacuum() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        
+        let mtime = SystemTime::now();
+        let mut paths = Vec::new();
+        for i in 0..5 {
+            let path = tmp.path().join(format!("file{}.txt", i));
+            fs::write(&path, "content").unwrap();
+            let doc_ids = vec![format!("file{}-0", i), format!("file{}-1", i)];
+            state.mark_indexed(&path, mtime, &doc_ids).unwrap();
+            paths.push(path);
+        }
+        for path in &paths[..3] {
+            fs::remove_file(path).unwrap();
+        }
+        
+        let result = state.vacuum().unwrap();
+        assert_eq!(result.removed_files, 3);
+        let mut removed = result.removed_doc_ids;
+        removed.sort();
+        assert_eq!(removed, vec!["file0-0", "file0-1", "file1-0", "file1-1", "file2-0", "file2-1"]);
+        
+        assert_eq!(state.file_count().unwrap(), 2);
+        let mut remaining: Vec<PathBuf> = state.get_all_files().unwrap().into_iter().map(|f| f.path).collect();
+        remaining.sort();
+        assert_eq!(remaining, paths[3..].to_vec());
+        assert_eq!(state.get_doc_ids(&paths[0]).unwrap(), Vec::<String>::new());
+        
+        // Nothing left to purge
+        assert_eq!(state.vacuum().unwrap(), VacuumResult::default());
+    }
+    
+    #[test]
+    fn test_stats() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        assert_eq!(state.stats().unwrap(), IndexStats::default());
+        
+        let mtime = SystemTime::now();
+        for (name, chunks) in [("a.txt", 2), ("b.TXT", 1), ("c.pdf", 7), ("Makefile", 1), ("d.tar.gz", 3)] {
+            let path = tmp.path().join(name);
+            let doc_ids: Vec<String> = (0..chunks).map(|i| format!("{}-{}", name, i)).collect();
+            state.mark_indexed(&path, mtime, &doc_ids).unwrap();
+        }
+        
+        let stats = state.stats().unwrap();
+        assert_eq!(stats.total_files, 5);
+        assert_eq!(stats.total_doc_ids, 14);
+        let expected: HashMap<String, usize> = [("txt", 2), ("pdf", 1), ("unknown", 1), ("gz", 1)]
+            .into_iter()
+            .map(|(ext, n)| (ext.to_string(), n))
+            .collect();
+        assert_eq!(stats.by_extension, expected);
+        assert_eq!(stats.lexical_docs_by_file, None);
+    }
+    
+    #[test]
+    fn test_stats_with_lexical() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let lexical = LexicalIndex::new(tmp.path().join("lexical")).unwrap();
+        
+        let mtime = SystemTime::now();
+        let mut docs = Vec::new();
+        for (name, chunks) in [("a.txt", 2), ("b.md", 5), ("c.pdf", 1)] {
+            let path = tmp.path().join(name);
+            let doc_ids: Vec<String> = (0..chunks).map(|i| format!("{}-{}", name, i)).collect();
+            state.mark_indexed(&path, mtime, &doc_ids).unwrap();
+            for (i, doc_id) in doc_ids.into_iter().enumerate() {
+                docs.push(crate::LexicalDoc {
+                    doc_id,
+                    file_path: path.to_string_lossy().to_string(),
+                    content: format!("chunk {}", i),
+                    chunk_index: i,
+                });
+            }
+        }
+        lexical.add_documents(docs).unwrap();
+        lexical.commit().unwrap();
+        
+        let stats = state.stats_with_lexical(&lexical).unwrap();
+        assert_eq!(stats.total_doc_ids, 8);
+        let by_file = stats.lexical_docs_by_file.unwrap();
+        assert_eq!(by_file.len(), 3);
+        assert_eq!(by_file[&tmp.path().join("a.txt").to_string_lossy().to_string()], 2);
+        assert_eq!(by_file[&tmp.path().join("b.md").to_string_lossy().to_string()], 5);
+        assert_eq!(by_file[&tmp.path().join("c.pdf").to_string_lossy().to_string()], 1);
+    }
+    
+    #[test]
+    fn test_list_files() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let mtime = SystemTime::now();
+        
+        for (i, (name, chunks)) in [("a.txt", 2), ("b.md", 5), ("c.TXT", 3), ("d_txt", 1)].into_iter().enumerate() {
+            let path = tmp.path().join(name);
+            let doc_ids: Vec<String> = (0..chunks).map(|j| format!("{}-{}", name, j)).collect();
+            state.mark_indexed(&path, mtime, &doc_ids).unwrap();
+            state.conn.lock().unwrap().execute(
+                "UPDATE files SET indexed_at = ?2 WHERE path = ?1",
+                params![path.to_string_lossy(), 1_000 + i as i64],
+            ).unwrap();
+        }
+        let names = |files: Vec<FileInfo>| -> Vec<String> {
+            files.iter().map(|f| f.path.file_name().unwrap().to_string_lossy().to_string()).collect()
+        };
+        
+        let all = state.list_files(&ListFilter::default()).unwrap();
+        assert_eq!(all[1].doc_ids.len(), 5);
+        assert_eq!(names(all), ["a.txt", "b.md", "c.TXT", "d_txt"]);
+        
+        let by_chunks = ListFilter { sort_by: ListSort::Chunks, ..Default::default() };
+        assert_eq!(names(state.list_files(&by_chunks).unwrap()), ["b.md", "c.TXT", "a.txt", "d_txt"]);
+        
+        let newest = ListFilter { sort_by: ListSort::Date, limit: Some(2), ..Default::default() };
+        assert_eq!(names(state.list_files(&newest).unwrap()), ["d_txt", "c.TXT"]);
+        
+        // Extension match is case-insensitive and needs the dot
+        let txt = ListFilter { file_type: Some("txt".to_string()), ..Default::default() };
+        assert_eq!(names(state.list_files(&txt).unwrap()), ["a.txt", "c.TXT"]);
+    }
+    
+    #[test]
+    fn test_list_files_under_prefix() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let mtime = SystemTime::now();
+        for path in ["/docs/a.txt", "/docs/sub/b.txt", "/docs_old/c.txt", "/other/d.txt"] {
+            state.mark_indexed(Path::new(path), mtime, &[path.to_string()]).unwrap();
+        }
+        let paths = |prefix: &str| -> Vec<PathBuf> {
+            let filter = ListFilter { path_prefix: Some(PathBuf::from(prefix)), ..Default::default() };
+            state.list_files(&filter).unwrap().into_iter().map(|f| f.path).collect()
+        };
+        
+        assert_eq!(paths("/docs"), [PathBuf::from("/docs/a.txt"), PathBuf::from("/docs/sub/b.txt")]);
+        assert_eq!(paths("/docs/"), paths("/docs"));
+        assert_eq!(paths("/docs/a.txt"), [PathBuf::from("/docs/a.txt")]);
+        assert!(paths("/missing").is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_check_integrity() {
+        let tmp = TempDir::new().unwrap();
+        let state = StateManager::new(tmp.path()).unwrap();
+        let store = InMemoryVectorStore::new();
+        for doc_id in ["doc1", "doc2"] {
+            let metadata = DocumentMetadata { doc_id: doc_id.to_string(), ..Default::default() };
+            store.add_embedding(vec![1.0, 0.0], metadata).await.unwrap();
+        }
+        
+        let kept = tmp.path().join("kept.txt");
+        fs::write(&kept, "kept").unwrap();
+        let mtime = kept.metadata().unwrap().modified().unwrap();
+        state.mark_indexed(&kept, mtime, &["doc1".to_string()]).unwrap();
+        assert!(state.check_integrity(&store).await.unwrap().is_clean());
+        
+        // A file deleted from disk, plus rows left behind by a manual edit
+        // (the sqlite3 shell does not enforce foreign keys by default)
+        let gone = tmp.path().join("gone.txt");
+        state.mark_indexed(&gone, mtime, &["doc2".to_string()]).unwrap();
+        {
+            let conn = state.conn.lock().unwrap();
+            assert!(conn.execute("INSERT INTO file_docs (path, doc_id) VALUES ('/nowhere.txt', 'doc2')", []).is_err());
+            conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+            conn.execute("INSERT INTO file_docs (path, doc_id) VALUES (?1, 'orphan')", params![kept.to_string_lossy()]).unwrap();
+            conn.execute("INSERT INTO file_docs (path, doc_id) VALUES ('/nowhere.txt', 'doc2')", []).unwrap();
+        }
+        
+        let report = state.check_integrity(&store).await.unwrap();
+        assert_eq!(report.orphaned_doc_ids, vec!["orphan".to_string()]);
+        assert_eq!(report.missing_files, vec![gone]);
+        assert_eq!(report.extra_files, vec![PathBuf::from("/nowhere.txt")]);
+    }
 }
@@ -1,4 +1,4 @@
-use store::{VectorStore, DummyStore, DocumentMetadata};
+use store::{VectorStore, DummyStore, DocumentMetadata, SearchOptions};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -11,9 +11,12 @@ async fn test_dummy_store_add_and_search() -> Result<()> {
         file_type: "txt".to_string(),
         chunk_index: 0,
         snippet: None,
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
     };
     store.add_embedding(vec![1.0, 2.0, 3.0], meta.clone()).await?;
-    let results = store.search(vec![1.0, 2.0, 3.0], 5).await?;
+    let results = store.search(vec![1.0, 2.0, 3.0], &SearchOptions::new(5)).await?;
     assert!(results.is_empty()); // DummyStore always returns empty
     let meta_result = store.get_metadata("doc1").await?;
     assert!(meta_result.is_none()); // DummyStore always returns None
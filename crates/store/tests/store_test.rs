@@ -1,4 +1,4 @@
-use store::{VectorStore, LanceVectorStore, DocumentMetadata};
+use store::{VectorStore, LanceVectorStore, DocumentMetadata, DistanceMetric, SearchOptions, SearchResult};
 use std::path::PathBuf;
 use std::fs;
 
@@ -18,7 +18,7 @@ async fn test_lance_store_add_and_search() {
     let tmp_dir = std::env::temp_dir().join("nexus_lance_store_test");
     let _ = fs::remove_dir_all(&tmp_dir);
     
-    let store = LanceVectorStore::new(tmp_dir.clone()).await.unwrap();
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
     
     // Add some embeddings
     let meta1 = DocumentMetadata {
@@ -27,6 +27,9 @@ async fn test_lance_store_add_and_search() {
         file_type: "txt".to_string(),
         chunk_index: 0,
         snippet: Some("Hello world".to_string()),
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
     };
     let embedding1 = make_embedding(&[1.0, 0.0, 0.0]);
     let doc_id1 = store.add_embedding(embedding1.clone(), meta1).await.unwrap();
@@ -38,13 +41,16 @@ async fn test_lance_store_add_and_search() {
         file_type: "txt".to_string(),
         chunk_index: 0,
         snippet: Some("Goodbye world".to_string()),
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
     };
     let embedding2 = make_embedding(&[0.0, 1.0, 0.0]);
     let doc_id2 = store.add_embedding(embedding2.clone(), meta2).await.unwrap();
     
     // Search with query similar to embedding1
     let query = make_embedding(&[0.9, 0.1, 0.0]);
-    let results = store.search(query, 2).await.unwrap();
+    let results = store.search(query, &SearchOptions::new(2)).await.unwrap();
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].doc_id, doc_id1, "First result should be most similar");
     assert!(results[0].score > results[1].score);
@@ -57,6 +63,13 @@ async fn test_lance_store_add_and_search() {
     assert!(meta.is_some());
     assert_eq!(meta.unwrap().file_path, PathBuf::from("/test/file1.txt"));
     
+    // Vectors come back by exact doc ID only
+    assert_eq!(store.get_embedding_by_doc_id(&doc_id2).await.unwrap(), Some(embedding2.clone()));
+    assert_eq!(store.get_embedding_by_doc_id(&doc_id2[..8]).await.unwrap(), None);
+    let embeddings = store.get_embeddings_by_doc_ids(&[doc_id2.clone(), "missing".to_string()]).await.unwrap();
+    assert_eq!(embeddings.len(), 1);
+    assert_eq!(embeddings[&doc_id2], embedding2);
+    
     let _ = fs::remove_dir_all(&tmp_dir);
 }
 
@@ -69,13 +82,16 @@ async fn test_lance_store_persistence() {
     
     // Create store and add data
     {
-        let store = LanceVectorStore::new(tmp_dir.clone()).await.unwrap();
+        let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
         let meta = DocumentMetadata {
             doc_id: String::new(),
             file_path: PathBuf::from("/test/persist.txt"),
             file_type: "txt".to_string(),
             chunk_index: 0,
             snippet: Some("Persisted content".to_string()),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
         };
         doc_id = store.add_embedding(make_embedding(&[1.0, 2.0, 3.0]), meta).await.unwrap();
         store.save().await.unwrap();
@@ -83,7 +99,7 @@ async fn test_lance_store_persistence() {
     
     // Reload store and verify data persisted
     {
-        let store = LanceVectorStore::new(tmp_dir.clone()).await.unwrap();
+        let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
         assert_eq!(store.count().await, 1);
         let meta = store.get_metadata(&doc_id).await.unwrap();
         assert!(meta.is_some());
@@ -98,7 +114,7 @@ async fn test_l2_similarity_search() {
     let tmp_dir = std::env::temp_dir().join("nexus_lance_l2_test");
     let _ = fs::remove_dir_all(&tmp_dir);
     
-    let store = LanceVectorStore::new(tmp_dir.clone()).await.unwrap();
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
     
     // Add vectors pointing in different directions
     let mut doc_ids = Vec::new();
@@ -111,6 +127,9 @@ async fn test_l2_similarity_search() {
             file_type: "txt".to_string(),
             chunk_index: 0,
             snippet: Some(format!("Document {}", i)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
         };
         let id = store.add_embedding(make_embedding(&seed), meta).await.unwrap();
         doc_ids.push(id);
@@ -118,7 +137,7 @@ async fn test_l2_similarity_search() {
     
     // Search for vector aligned with doc-1 (second document)
     let query = make_embedding(&[0.0, 1.0, 0.0]);
-    let results = store.search(query, 3).await.unwrap();
+    let results = store.search(query, &SearchOptions::new(3)).await.unwrap();
     
     // The closest (smallest L2 distance) should be doc_ids[1]
     assert_eq!(results[0].doc_id, doc_ids[1]);
@@ -127,3 +146,366 @@ async fn test_l2_similarity_search() {
     
     let _ = fs::remove_dir_all(&tmp_dir);
 }
+
+#[tokio::test]
+async fn test_cosine_matches_l2_ranking_on_unit_vectors() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_cosine_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let mut doc_ids = Vec::new();
+    for (i, seed) in [[1.0, 0.0, 0.0], [0.8, 0.6, 0.0], [0.0, 0.0, 1.0]].iter().enumerate() {
+        let meta = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(format!("/test/unit{}.txt", i)),
+            file_type: "txt".to_string(),
+            chunk_index: 0,
+            snippet: None,
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        doc_ids.push(store.add_embedding(make_embedding(seed), meta).await.unwrap());
+    }
+    drop(store);
+    
+    let query = make_embedding(&[0.6, 0.8, 0.0]);
+    let l2 = LanceVectorStore::new_with_metric(tmp_dir.clone(), 384, DistanceMetric::L2).await.unwrap()
+        .search(query.clone(), &SearchOptions::new(3)).await.unwrap();
+    let cosine = LanceVectorStore::new_with_metric(tmp_dir.clone(), 384, DistanceMetric::Cosine).await.unwrap()
+        .search(query, &SearchOptions::new(3)).await.unwrap();
+    
+    // L2 and cosine order unit vectors the same way
+    let order = |results: &[SearchResult]| results.iter().map(|r| r.doc_id.clone()).collect::<Vec<_>>();
+    assert_eq!(order(&l2), vec![doc_ids[1].clone(), doc_ids[0].clone(), doc_ids[2].clone()]);
+    assert_eq!(order(&cosine), order(&l2));
+    // Cosine scores are the cosine similarities: 0.96, 0.6 and 0
+    assert!((cosine[0].score - 0.96).abs() < 1e-3, "got {}", cosine[0].score);
+    assert!((cosine[1].score - 0.6).abs() < 1e-3, "got {}", cosine[1].score);
+    assert!(cosine[2].score.abs() < 1e-3, "got {}", cosine[2].score);
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_add_embeddings_batch_100() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_batch_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let embeddings: Vec<Vec<f32>> = (0..100).map(|i| make_embedding(&[1.0, i as f32])).collect();
+    let metadata: Vec<DocumentMetadata> = (0..100).map(|i| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from(format!("/test/batch{}.txt", i)),
+        file_type: "txt".to_string(),
+        chunk_index: 0,
+        snippet: Some(format!("Batch document {}", i)),
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
+    }).collect();
+    let doc_ids = store.add_embeddings_batch(embeddings.clone(), metadata).await.unwrap();
+    
+    assert_eq!(doc_ids.len(), 100);
+    let distinct: std::collections::HashSet<_> = doc_ids.iter().collect();
+    assert_eq!(distinct.len(), 100);
+    assert!(doc_ids.iter().all(|id| uuid::Uuid::parse_str(id).is_ok()));
+    assert_eq!(store.count().await, 100);
+    
+    // Every row comes back as its own nearest neighbour
+    for (embedding, doc_id) in embeddings.into_iter().zip(&doc_ids) {
+        let results = store.search(embedding, &SearchOptions::new(1)).await.unwrap();
+        assert_eq!(&results[0].doc_id, doc_id);
+    }
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_compact_after_deletes() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_compact_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    // Nothing to compact before the table exists
+    store.compact().await.unwrap();
+    
+    let embeddings: Vec<Vec<f32>> = (0..100).map(|i| make_embedding(&[1.0, i as f32])).collect();
+    let metadata: Vec<DocumentMetadata> = (0..100).map(|i| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from(format!("/test/compact{}.txt", i)),
+        file_type: "txt".to_string(),
+        chunk_index: 0,
+        snippet: Some(format!("Compacted document {}", i)),
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
+    }).collect();
+    let doc_ids = store.add_embeddings_batch(embeddings, metadata).await.unwrap();
+    assert_eq!(store.delete_by_doc_ids(&doc_ids).await.unwrap(), 100);
+    
+    store.compact().await.unwrap();
+    assert_eq!(store.count().await, 0);
+    
+    // The compacted table still takes writes
+    let meta = DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from("/test/after.txt"),
+        file_type: "txt".to_string(),
+        chunk_index: 0,
+        snippet: None,
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
+    };
+    store.add_embedding(make_embedding(&[0.5]), meta).await.unwrap();
+    assert_eq!(store.count().await, 1);
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_create_vector_index() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_vector_index_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    assert!(store.create_vector_index(None, None).await.is_err());
+    
+    let embeddings: Vec<Vec<f32>> = (0..500)
+        .map(|i| make_embedding(&[(i % 7) as f32, (i % 13) as f32, i as f32 / 500.0, 1.0]))
+        .collect();
+    let metadata: Vec<DocumentMetadata> = (0..500).map(|i| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from(format!("/test/indexed{}.txt", i)),
+        file_type: "txt".to_string(),
+        chunk_index: 0,
+        snippet: None,
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
+    }).collect();
+    store.add_embeddings_batch(embeddings.clone(), metadata).await.unwrap();
+    
+    store.create_vector_index(Some(4), Some(16)).await.unwrap();
+    // Rebuilding replaces the existing index
+    store.create_vector_index(None, None).await.unwrap();
+    
+    let results = store.search(embeddings[42].clone(), &SearchOptions::new(5)).await.unwrap();
+    assert_eq!(results.len(), 5);
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_count_by_query() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_count_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    
+    for (i, file_type) in ["txt", "txt", "txt", "pdf", "pdf"].iter().enumerate() {
+        let meta = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(format!("/test/file{}.{}", i, file_type)),
+            file_type: file_type.to_string(),
+            chunk_index: 0,
+            snippet: Some(format!("Document {}", i)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        store.add_embedding(make_embedding(&[i as f32]), meta).await.unwrap();
+    }
+    
+    assert_eq!(store.count_by_query("file_type = 'pdf'").await.unwrap(), 2);
+    assert_eq!(store.count_by_file_type("txt").await.unwrap(), 3);
+    assert_eq!(store.count_by_file_path("/test/file3.pdf").await.unwrap(), 1);
+    assert_eq!(store.file_types().await.unwrap(), vec!["pdf".to_string(), "txt".to_string()]);
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_byte_offsets_round_trip() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_offsets_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let path = PathBuf::from("/test/offsets.md");
+    let ranges = [(0, 412), (380, 1024)];
+    let metadata = ranges.iter().enumerate().map(|(i, &(start, end))| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: path.clone(),
+        file_type: "md".to_string(),
+        chunk_index: i,
+        snippet: Some(format!("Chunk {}", i)),
+        byte_start: start,
+        byte_end: end,
+        ..Default::default()
+    }).collect();
+    let embeddings = vec![make_embedding(&[1.0, 0.0]), make_embedding(&[0.0, 1.0])];
+    let doc_ids = store.add_embeddings_batch(embeddings, metadata).await.unwrap();
+    
+    // Search results, point lookups and per-file listings all carry the offsets
+    let results = store.search(make_embedding(&[0.0, 1.0]), &SearchOptions::new(1)).await.unwrap();
+    assert_eq!((results[0].metadata.byte_start, results[0].metadata.byte_end), ranges[1]);
+    let meta = store.get_metadata(&doc_ids[0]).await.unwrap().unwrap();
+    assert_eq!((meta.byte_start, meta.byte_end), ranges[0]);
+    let mut chunks = store.get_by_file_path(&path).await.unwrap();
+    chunks.sort_by_key(|m| m.chunk_index);
+    let stored: Vec<_> = chunks.iter().map(|m| (m.byte_start, m.byte_end)).collect();
+    assert_eq!(stored, ranges);
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_document_properties_round_trip() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_properties_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let make_metadata = |path: &str, title: Option<&str>, author: Option<&str>, created_at: Option<i64>| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from(path),
+        file_type: "pdf".to_string(),
+        chunk_index: 0,
+        snippet: Some("Quarterly numbers".to_string()),
+        byte_start: 0,
+        byte_end: 0,
+        title: title.map(String::from),
+        author: author.map(String::from),
+        created_at,
+    };
+    let doc_ids = store.add_embeddings_batch(
+        vec![make_embedding(&[1.0, 0.0]), make_embedding(&[0.0, 1.0])],
+        vec![
+            make_metadata("/test/report.pdf", Some("Q3 Report"), Some("Finance"), Some(1_700_000_000)),
+            make_metadata("/test/scan.pdf", None, None, None),
+        ],
+    ).await.unwrap();
+    
+    let results = store.search(make_embedding(&[1.0, 0.0]), &SearchOptions::new(1)).await.unwrap();
+    assert_eq!(results[0].metadata.title.as_deref(), Some("Q3 Report"));
+    let meta = store.get_metadata(&doc_ids[0]).await.unwrap().unwrap();
+    assert_eq!(meta.author.as_deref(), Some("Finance"));
+    assert_eq!(meta.created_at, Some(1_700_000_000));
+    let untitled = store.get_by_file_path(&PathBuf::from("/test/scan.pdf")).await.unwrap();
+    assert_eq!((untitled[0].title.clone(), untitled[0].created_at), (None, None));
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_search_filter_path() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_filter_path_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let mut metadata = Vec::new();
+    let mut embeddings = Vec::new();
+    for (i, dir) in ["work", "home", "work", "home"].iter().enumerate() {
+        metadata.push(DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(format!("/docs/{}/note_{}.md", dir, i)),
+            file_type: "md".to_string(),
+            chunk_index: 0,
+            snippet: Some(format!("Note {}", i)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        });
+        embeddings.push(make_embedding(&[1.0, i as f32 * 0.1]));
+    }
+    store.add_embeddings_batch(embeddings, metadata).await.unwrap();
+    
+    let query = make_embedding(&[1.0, 0.0]);
+    assert_eq!(store.search(query.clone(), &SearchOptions::new(10)).await.unwrap().len(), 4);
+    let work = SearchOptions::new(10).with_filter_path(Some(glob::Pattern::new("/docs/work/**").unwrap()));
+    let results = store.search(query, &work).await.unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.metadata.file_path.starts_with("/docs/work")));
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_search_filter_type() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_filter_type_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let mut txt_ids = Vec::new();
+    for (i, file_type) in ["txt", "pdf", "txt", "pdf", "pdf"].iter().enumerate() {
+        let meta = DocumentMetadata {
+            doc_id: String::new(),
+            file_path: PathBuf::from(format!("/test/doc{}.{}", i, file_type)),
+            file_type: file_type.to_string(),
+            chunk_index: 0,
+            snippet: Some(format!("Document {}", i)),
+            byte_start: 0,
+            byte_end: 0,
+            ..Default::default()
+        };
+        let doc_id = store.add_embedding(make_embedding(&[1.0, i as f32 * 0.1]), meta).await.unwrap();
+        if *file_type == "txt" {
+            txt_ids.push(doc_id);
+        }
+    }
+    
+    let query = make_embedding(&[1.0, 0.0]);
+    let txt = SearchOptions::new(10).with_filter_type(Some(vec!["txt".to_string()]));
+    let mut ids: Vec<String> = store.search(query.clone(), &txt).await.unwrap().into_iter().map(|r| r.doc_id).collect();
+    ids.sort();
+    txt_ids.sort();
+    assert_eq!(ids, txt_ids);
+    
+    let either = SearchOptions::new(10).with_filter_type(Some(vec!["pdf".to_string(), "txt".to_string()]));
+    assert_eq!(store.search(query.clone(), &either).await.unwrap().len(), 5);
+    assert!(store.search(query, &SearchOptions::new(10).with_filter_type(Some(vec![]))).await.unwrap().is_empty());
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[tokio::test]
+async fn test_recreate_for_new_dimension() {
+    let tmp_dir = std::env::temp_dir().join("nexus_lance_recreate_test");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    
+    let store = LanceVectorStore::new(tmp_dir.clone(), 384).await.unwrap();
+    let meta = |i: usize| DocumentMetadata {
+        doc_id: String::new(),
+        file_path: PathBuf::from(format!("/test/model{}.txt", i % 3)),
+        file_type: "txt".to_string(),
+        chunk_index: i,
+        snippet: None,
+        byte_start: 0,
+        byte_end: 0,
+        ..Default::default()
+    };
+    let embeddings: Vec<Vec<f32>> = (0..300).map(|i| make_embedding(&[(i % 7) as f32, i as f32 / 300.0, 1.0])).collect();
+    store.add_embeddings_batch(embeddings, (0..300).map(meta).collect()).await.unwrap();
+    store.create_vector_index(Some(2), Some(16)).await.unwrap();
+    drop(store);
+    
+    // A model with another dimension can't use the old table
+    assert!(LanceVectorStore::new(tmp_dir.clone(), 768).await.is_err());
+    
+    let store = LanceVectorStore::recreate(tmp_dir.clone(), 768).await.unwrap();
+    assert_eq!(store.count().await, 0);
+    // The vector index went with the old embeddings
+    assert!(store.create_vector_index(None, None).await.is_err());
+    
+    // Re-embedding a file with the new model stores 768-dimensional vectors
+    let path = PathBuf::from("/test/model0.txt");
+    let doc_ids = store.reindex_file(&path, vec![vec![0.5; 768]; 2], vec![meta(0), meta(3)]).await.unwrap();
+    assert_eq!(doc_ids.len(), 2);
+    let results = store.search(vec![0.5; 768], &SearchOptions::new(5)).await.unwrap();
+    assert_eq!(results.len(), 2);
+    drop(store);
+    
+    LanceVectorStore::new(tmp_dir.clone(), 768).await.unwrap();
+    
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
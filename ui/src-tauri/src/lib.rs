@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -6,38 +7,86 @@ use tauri::Emitter;
 
 use nexus_core::{
     IndexOptions, Indexer, Embedder, IndexEvent, SyncTextExtractor, VectorStore, 
-    PagedExtractor, ExtractedPage, LexicalIndex
+    PagedExtractor, ExtractedPage, LexicalIndex, NexusConfig, CancellationToken, context_snippets
 };
-use ocr::{PlainTextExtractor, SyncOcrEngine};
+use ocr::{ExtractorOptions, OcrOptions, PlainTextExtractor, SyncOcrEngine};
 use embed::{LocalEmbedder, Embedder as EmbedderTrait};
-use store::{LanceVectorStore, StateManager};
+use store::{HighlightedSnippet, LanceVectorStore, SearchMode, SearchOptions, SearchResult, SortField, StateManager};
+use search::{HybridSearchResult, group_by_file, reciprocal_rank_fusion};
+
+/// Progress events buffered between the indexer and the frontend emitter.
+const INDEX_EVENT_BUFFER: usize = 1024;
+/// Default fragment length for the file preview pane.
+const PREVIEW_CONTEXT_CHARS: usize = 2000;
+/// Chunks kept per file when search results are grouped.
+const GROUPED_CHUNKS_PER_FILE: usize = 3;
+
+/// Cancellation token of the indexing run in progress, if any. Only one run
+/// may be in progress at a time.
+#[derive(Default)]
+struct IndexJob(std::sync::Mutex<Option<CancellationToken>>);
+
+impl IndexJob {
+    /// Claim the slot for a new run, failing if another run holds it.
+    /// The slot is freed when the returned guard is dropped.
+    fn start(&self) -> Result<(IndexJobGuard<'_>, CancellationToken), String> {
+        let mut slot = self.0.lock().unwrap();
+        if slot.is_some() {
+            return Err("An indexing run is already in progress".to_string());
+        }
+        let token = CancellationToken::new();
+        *slot = Some(token.clone());
+        Ok((IndexJobGuard(self), token))
+    }
+}
 
-// Result types for frontend
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SearchResult {
-    pub doc_id: String,
-    pub file_path: String,
-    pub chunk_index: usize,
-    pub snippet: Option<String>,
-    pub score: f32,
-    pub source: String,
+/// Frees the `IndexJob` slot when the run holding it ends, however it ends.
+struct IndexJobGuard<'a>(&'a IndexJob);
+
+impl Drop for IndexJobGuard<'_> {
+    fn drop(&mut self) {
+        self.0 .0.lock().unwrap().take();
+    }
 }
 
+// Result types for frontend
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndexStatus {
     pub store_path: String,
     pub vector_embeddings: u64,
     pub lexical_documents: u64,
+    pub lexical_segments: u64,
+    pub lexical_deleted_documents: u64,
+    pub lexical_size_bytes: u64,
+    pub indexed_files: u64,
+    pub indexed_chunks: u64,
+    /// Indexed files per lowercase extension ("unknown" for none).
+    pub files_by_extension: HashMap<String, u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndexProgress {
+    /// Matches the `session_id` of the run's "indexing-started" event.
+    pub session_id: String,
     pub files_indexed: usize,
     pub files_unchanged: usize,
     pub files_skipped: usize,
     pub chunks_indexed: usize,
     pub embeddings_stored: usize,
     pub errors: Vec<String>,
+    /// Wall-clock duration of the run in milliseconds.
+    pub elapsed_ms: u64,
+    pub bytes_processed: u64,
+}
+
+/// One stored chunk of a file, for the document outline.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkInfo {
+    pub doc_id: String,
+    pub chunk_index: usize,
+    pub snippet: Option<String>,
+    pub byte_start: usize,
+    pub byte_end: usize,
 }
 
 // Wrapper to adapt PlainTextExtractor to SyncTextExtractor trait
@@ -57,6 +106,18 @@ impl PagedExtractor for OcrExtractor {
     fn is_paged(&self, path: &PathBuf) -> bool {
         ocr::PagedExtractor::is_paged(&self.0, path)
     }
+    
+    fn extract_info(&self, path: &PathBuf) -> anyhow::Result<ocr::DocumentInfo> {
+        ocr::PagedExtractor::extract_info(&self.0, path)
+    }
+    
+    fn extract_pages_with_info(&self, path: &PathBuf) -> anyhow::Result<(Vec<ExtractedPage>, anyhow::Result<ocr::DocumentInfo>)> {
+        ocr::PagedExtractor::extract_pages_with_info(&self.0, path)
+    }
+    
+    fn extract_pages_parallel(&self, path: &PathBuf) -> anyhow::Result<(Vec<ExtractedPage>, anyhow::Result<ocr::DocumentInfo>)> {
+        ocr::PagedExtractor::extract_pages_parallel(&self.0, path)
+    }
 }
 
 // Wrapper to adapt LocalEmbedder to nexus_core::Embedder trait
@@ -73,16 +134,35 @@ impl Embedder for EmbedWrapper {
     fn dimension(&self) -> usize {
         self.0.dimension()
     }
+    fn max_input_tokens(&self) -> Option<usize> {
+        self.0.max_input_tokens()
+    }
+    fn token_spans(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        self.0.token_spans(text)
+    }
 }
 
 #[tauri::command]
 async fn search(
+    config: tauri::State<'_, NexusConfig>,
     query: String,
     mode: Option<String>,
     limit: Option<usize>,
-) -> Result<Vec<SearchResult>, String> {
+    context: Option<usize>,
+    group: Option<bool>,
+    filter_path: Option<String>,
+    file_types: Option<Vec<String>>,
+) -> Result<Vec<serde_json::Value>, String> {
     let mode = mode.unwrap_or_else(|| "hybrid".to_string());
     let limit = limit.unwrap_or(5);
+    let context = context.unwrap_or(0);
+    let group = group.unwrap_or(false);
+    let filter_path = filter_path
+        .map(|p| glob::Pattern::new(&shellexpand::tilde(&p)).map_err(|e| format!("Invalid path filter '{}': {}", p, e)))
+        .transpose()?;
+    let filters = SearchOptions::default()
+        .with_filter_path(filter_path)
+        .with_filter_type(file_types);
 
     let data_dir = dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -94,42 +174,46 @@ async fn search(
 
     let embedder = LocalEmbedder::new()
         .map_err(|e| format!("Failed to load embedder: {}", e))?;
-    let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await
+    let store = Arc::new(LanceVectorStore::new(data_dir.clone(), embedder.dimension()).await
         .map_err(|e| format!("Failed to open store: {}", e))?);
     let lexical = LexicalIndex::new(data_dir)
         .map_err(|e| format!("Failed to open lexical index: {}", e))?;
 
-    let results = match mode.as_str() {
+    let mut results: Vec<HybridSearchResult> = match mode.as_str() {
         "semantic" | "vector" => {
             let query_embedding = embedder.embed(&query).await
                 .map_err(|e| format!("Failed to embed query: {}", e))?;
-            let vector_results = store.search(query_embedding, limit).await
+            let options = SearchOptions { top_k: limit, ..filters.clone() };
+            let vector_results = store.search(query_embedding, &options).await
                 .map_err(|e| format!("Failed to search: {}", e))?;
-            vector_results.into_iter().map(|r| SearchResult {
+            vector_results.into_iter().enumerate().map(|(rank, r)| HybridSearchResult {
                 doc_id: r.doc_id,
-                file_path: r.metadata.file_path.to_string_lossy().to_string(),
+                file_path: r.metadata.file_path,
                 chunk_index: r.metadata.chunk_index,
                 snippet: r.snippet,
                 score: r.score,
                 source: "semantic".to_string(),
+                semantic_rank: Some(rank),
+                ..Default::default()
             }).collect()
         }
         "lexical" | "keyword" => {
-            let lexical_results = lexical.search(&query, limit)
+            let lexical_results = lexical.search_sorted(&query, limit, SearchMode::Auto, SortField::Score, &filters)
                 .map_err(|e| format!("Failed to search: {}", e))?;
+            let doc_ids: Vec<String> = lexical_results.iter().map(|r| r.doc_id.clone()).collect();
+            let mut metadata = store.get_metadata_by_doc_ids(&doc_ids).await.unwrap_or_default();
             let mut results = Vec::new();
-            for r in lexical_results {
-                let snippet = store.get_metadata(&r.doc_id).await
-                    .ok()
-                    .flatten()
-                    .and_then(|m| m.snippet);
-                results.push(SearchResult {
+            for (rank, r) in lexical_results.into_iter().enumerate() {
+                let snippet = metadata.remove(&r.doc_id).and_then(|m| m.snippet);
+                results.push(HybridSearchResult {
                     doc_id: r.doc_id,
-                    file_path: r.file_path,
+                    file_path: PathBuf::from(r.file_path),
                     chunk_index: r.chunk_index,
                     snippet,
                     score: r.score,
                     source: "lexical".to_string(),
+                    lexical_rank: Some(rank),
+                    ..Default::default()
                 });
             }
             results
@@ -137,56 +221,117 @@ async fn search(
         "hybrid" | _ => {
             let query_embedding = embedder.embed(&query).await
                 .map_err(|e| format!("Failed to embed query: {}", e))?;
-            let vector_results = store.search(query_embedding, limit * 2).await
+            let options = SearchOptions { top_k: limit * 2, ..filters.clone() };
+            let vector_results = store.search(query_embedding, &options).await
                 .map_err(|e| format!("Failed to search: {}", e))?;
-            let lexical_results = lexical.search(&query, limit * 2)
+            let lexical_results = lexical.search_sorted(&query, limit * 2, SearchMode::Auto, SortField::Score, &filters)
                 .map_err(|e| format!("Failed to search: {}", e))?;
             
-            // Apply Reciprocal Rank Fusion (RRF)
-            let k = 60.0;
-            let mut doc_scores: std::collections::HashMap<String, (f32, Option<String>, PathBuf, usize)> = 
-                std::collections::HashMap::new();
-            
-            for (rank, r) in vector_results.iter().enumerate() {
-                let rrf_score = 1.0 / (k + rank as f32 + 1.0);
-                let entry = doc_scores.entry(r.doc_id.clone()).or_insert((
-                    0.0,
-                    r.snippet.clone(),
-                    r.metadata.file_path.clone(),
-                    r.metadata.chunk_index,
-                ));
-                entry.0 += rrf_score;
-            }
-            
-            for (rank, r) in lexical_results.iter().enumerate() {
-                let rrf_score = 1.0 / (k + rank as f32 + 1.0);
-                let entry = doc_scores.entry(r.doc_id.clone()).or_insert((
-                    0.0,
-                    None,
-                    PathBuf::from(&r.file_path),
-                    r.chunk_index,
-                ));
-                entry.0 += rrf_score;
-            }
+            // Apply Reciprocal Rank Fusion (RRF) with the weights from the config
+            let params = config.search.rrf_params();
+            let semantic_ids: Vec<&str> = vector_results.iter().map(|r| r.doc_id.as_str()).collect();
+            let lexical_ids: Vec<&str> = lexical_results.iter().map(|r| r.doc_id.as_str()).collect();
             
-            let mut sorted: Vec<_> = doc_scores.into_iter().collect();
-            sorted.sort_by(|a, b| b.1.0.partial_cmp(&a.1.0).unwrap_or(std::cmp::Ordering::Equal));
-            
-            sorted.into_iter()
+            reciprocal_rank_fusion(&semantic_ids, &lexical_ids, &params)
+                .into_iter()
                 .take(limit)
-                .map(|(doc_id, (score, snippet, file_path, chunk_index))| SearchResult {
-                    doc_id,
-                    file_path: file_path.to_string_lossy().to_string(),
-                    chunk_index,
-                    snippet,
-                    score,
-                    source: "hybrid".to_string(),
+                .map(|f| {
+                    let mut result = match (f.semantic_rank, f.lexical_rank) {
+                        (Some(rank), _) => {
+                            let r = &vector_results[rank];
+                            HybridSearchResult {
+                                file_path: r.metadata.file_path.clone(),
+                                chunk_index: r.metadata.chunk_index,
+                                snippet: r.snippet.clone(),
+                                ..Default::default()
+                            }
+                        }
+                        (None, Some(rank)) => {
+                            let r = &lexical_results[rank];
+                            HybridSearchResult {
+                                file_path: PathBuf::from(&r.file_path),
+                                chunk_index: r.chunk_index,
+                                ..Default::default()
+                            }
+                        }
+                        (None, None) => unreachable!("fused results come from one of the rankings"),
+                    };
+                    result.doc_id = f.doc_id;
+                    result.score = f.score;
+                    result.source = "hybrid".to_string();
+                    result.semantic_rank = f.semantic_rank;
+                    result.lexical_rank = f.lexical_rank;
+                    result
                 })
                 .collect()
         }
     };
 
-    Ok(results)
+    if context > 0 {
+        for result in results.iter_mut() {
+            result.context_chunks = context_snippets(store.as_ref(), &lexical, &result.file_path.to_string_lossy(), result.chunk_index, context).await
+                .map_err(|e| format!("Failed to load context: {}", e))?;
+        }
+    }
+
+    if group {
+        // One object per file: its path, best score and best chunks
+        let chunks = results.iter().cloned().map(SearchResult::from).collect();
+        let mut by_id: HashMap<String, HybridSearchResult> = results.into_iter().map(|r| (r.doc_id.clone(), r)).collect();
+        return Ok(group_by_file(chunks, GROUPED_CHUNKS_PER_FILE)
+            .into_iter()
+            .map(|file| serde_json::json!({
+                "file_path": file.file_path.to_string_lossy(),
+                "score": file.score,
+                "chunks": file.chunks.iter()
+                    .filter_map(|c| by_id.remove(&c.doc_id))
+                    .map(serde_json::Value::from)
+                    .collect::<Vec<_>>(),
+            }))
+            .collect());
+    }
+
+    Ok(results.into_iter().map(serde_json::Value::from).collect())
+}
+
+#[tauri::command]
+async fn highlight_document(
+    doc_id: String,
+    query: String,
+    context_chars: Option<usize>,
+) -> Result<Option<HighlightedSnippet>, String> {
+    let data_dir = dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("nexus_local");
+
+    let lexical = LexicalIndex::new(data_dir)
+        .map_err(|e| format!("Failed to open lexical index: {}", e))?;
+    lexical.highlight_in_document(&doc_id, &query, context_chars.unwrap_or(PREVIEW_CONTEXT_CHARS))
+        .map_err(|e| format!("Failed to highlight: {}", e))
+}
+
+#[tauri::command]
+async fn get_file_chunks(file_path: String) -> Result<Vec<ChunkInfo>, String> {
+    let data_dir = dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("nexus_local");
+
+    if !data_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let store = LanceVectorStore::open_existing(data_dir).await
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    let chunks = store.get_by_file_path(Path::new(&file_path)).await
+        .map_err(|e| format!("Failed to load chunks: {}", e))?;
+
+    Ok(chunks.into_iter().map(|m| ChunkInfo {
+        doc_id: m.doc_id,
+        chunk_index: m.chunk_index,
+        snippet: m.snippet,
+        byte_start: m.byte_start,
+        byte_end: m.byte_end,
+    }).collect())
 }
 
 #[tauri::command]
@@ -200,31 +345,50 @@ async fn get_status() -> Result<IndexStatus, String> {
             store_path: data_dir.to_string_lossy().to_string(),
             vector_embeddings: 0,
             lexical_documents: 0,
+            lexical_segments: 0,
+            lexical_deleted_documents: 0,
+            lexical_size_bytes: 0,
+            indexed_files: 0,
+            indexed_chunks: 0,
+            files_by_extension: HashMap::new(),
         });
     }
 
-    let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await
+    let store = Arc::new(LanceVectorStore::open_existing(data_dir.clone()).await
         .map_err(|e| format!("Failed to open store: {}", e))?);
     let lexical = LexicalIndex::new(data_dir.clone())
         .map_err(|e| format!("Failed to open lexical index: {}", e))?;
 
     let count = store.count().await;
     let lexical_count = lexical.count().unwrap_or(0);
+    let lexical_stats = lexical.stats().unwrap_or_default();
+    let stats = StateManager::new(&data_dir)
+        .and_then(|state| state.stats())
+        .map_err(|e| format!("Failed to read index state: {}", e))?;
 
     Ok(IndexStatus {
         store_path: data_dir.to_string_lossy().to_string(),
         vector_embeddings: count as u64,
         lexical_documents: lexical_count as u64,
+        lexical_segments: lexical_stats.num_segments as u64,
+        lexical_deleted_documents: lexical_stats.deleted_docs as u64,
+        lexical_size_bytes: lexical_stats.index_size_bytes,
+        indexed_files: stats.total_files as u64,
+        indexed_chunks: stats.total_doc_ids as u64,
+        files_by_extension: stats.by_extension.into_iter().map(|(ext, n)| (ext, n as u64)).collect(),
     })
 }
 
 #[tauri::command]
 async fn index_directory(
     app: tauri::AppHandle,
+    job: tauri::State<'_, IndexJob>,
     path: String,
     gpu: Option<bool>,
     max_file_mb: Option<u64>,
     max_memory_mb: Option<u64>,
+    snippet_length: Option<usize>,
+    ocr_lang: Option<String>,
 ) -> Result<IndexProgress, String> {
     let path = shellexpand::tilde(&path).to_string();
     let root = PathBuf::from(&path);
@@ -232,9 +396,19 @@ async fn index_directory(
     if !root.exists() {
         return Err(format!("Directory does not exist: {}", path));
     }
+    let (_job_guard, token) = job.start()?;
 
     let gpu = gpu.unwrap_or(false);
     let max_file_mb = max_file_mb.unwrap_or(50);
+    let ocr_options = match &ocr_lang {
+        Some(lang) => OcrOptions::new(lang).map_err(|e| e.to_string())?,
+        None => OcrOptions::default(),
+    };
+    let pdf_passwords = match NexusConfig::pdf_passwords_path() {
+        Some(path) => NexusConfig::load_pdf_passwords(&path)
+            .map_err(|e| format!("Failed to read saved PDF passwords: {}", e))?,
+        None => Vec::new(),
+    };
     let max_memory_mb = max_memory_mb.unwrap_or_else(|| {
         let sys = sysinfo::System::new_all();
         (sys.total_memory() / 1024 / 1024 * 3 / 4) as u64
@@ -248,7 +422,7 @@ async fn index_directory(
 
     let embedder = LocalEmbedder::new_with_options(gpu)
         .map_err(|e| format!("Failed to load embedder: {}", e))?;
-    let store = Arc::new(LanceVectorStore::new(data_dir.clone()).await
+    let store = Arc::new(LanceVectorStore::new(data_dir.clone(), embedder.dimension()).await
         .map_err(|e| format!("Failed to open store: {}", e))?);
     let state = Arc::new(StateManager::new(&data_dir)
         .map_err(|e| format!("Failed to create state manager: {}", e))?);
@@ -263,102 +437,103 @@ async fn index_directory(
         max_chunks_per_file: 500,
         skip_extensions: vec![],
         skip_files: vec![],
+        snippet_length: snippet_length.unwrap_or(200),
+        extractor: ExtractorOptions {
+            pdf_passwords,
+            ocr: ocr_options,
+            ..Default::default()
+        },
+        ..Default::default()
     };
 
-    let extractor = OcrExtractor(PlainTextExtractor);
+    let extractor = OcrExtractor(options.plain_text_extractor());
     let embed_wrapper = EmbedWrapper(embedder);
     let indexer = Indexer::new(options, extractor, embed_wrapper, store.clone())
         .with_state(state)
-        .with_lexical(lexical);
+        .with_lexical(lexical)
+        .with_cancellation(token);
 
     // Run garbage collection first
     let _ = indexer.garbage_collect().await;
 
+    // Events cross from the indexing task to the emitter through a channel
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<IndexEvent>(INDEX_EVENT_BUFFER);
     let app_handle = app.clone();
-    let mut indexer = indexer;
-    let result = indexer.run_with_progress(move |event| {
-        let app = app_handle.clone();
-        let event_name = "index-progress".to_string();
-        
-        let payload = match event {
-            IndexEvent::FileStarted(path) => {
-                serde_json::json!({
-                    "type": "file-started",
-                    "path": path.to_string_lossy().to_string()
-                })
-            }
-            IndexEvent::FileIndexed(path) => {
-                serde_json::json!({
-                    "type": "file-indexed",
-                    "path": path.to_string_lossy().to_string()
-                })
-            }
-            IndexEvent::FileSkipped(path, reason) => {
-                serde_json::json!({
-                    "type": "file-skipped",
-                    "path": path.to_string_lossy().to_string(),
-                    "reason": reason
-                })
-            }
-            IndexEvent::FileUnchanged(path) => {
-                serde_json::json!({
-                    "type": "file-unchanged",
-                    "path": path.to_string_lossy().to_string()
-                })
-            }
-            IndexEvent::ChunkEmbedded(_, _, _) => {
-                serde_json::json!({
-                    "type": "chunk-embedded"
-                })
-            }
-            IndexEvent::PageProcessed(path, page, total) => {
-                serde_json::json!({
-                    "type": "page-processed",
-                    "path": path.to_string_lossy().to_string(),
-                    "page": page,
-                    "total": total
-                })
-            }
-            IndexEvent::FileError(path, error) => {
-                serde_json::json!({
-                    "type": "error",
-                    "path": path.to_string_lossy().to_string(),
-                    "error": error
-                })
-            }
-            IndexEvent::Done => {
-                serde_json::json!({
-                    "type": "done"
-                })
-            }
-            _ => return, // Skip other events
-        };
-
-        // Emit event to frontend
-        let _ = app.emit(&event_name, payload);
-    }).await.map_err(|e| format!("Indexing failed: {}", e))?;
+    let forwarder = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let Ok(payload) = serde_json::to_value(&event) else {
+                continue;
+            };
+
+            // Emit event to frontend
+            let _ = app_handle.emit("index-progress", payload);
+        }
+    });
 
-    // Emit final done event
-    let _ = app.emit("index-progress", serde_json::json!({ "type": "done" }));
+    let mut indexer = indexer;
+    let result = indexer.run_with_channel(tx).await;
+    let _ = forwarder.await;
+    let result = result.map_err(|e| format!("Indexing failed: {}", e))?;
 
     Ok(IndexProgress {
+        session_id: result.session_id.to_string(),
         files_indexed: result.files_indexed,
         files_unchanged: result.files_unchanged,
         files_skipped: result.files_skipped,
         chunks_indexed: result.chunks_indexed,
         embeddings_stored: result.embeddings_stored,
         errors: result.errors.into_iter().map(|(_, e)| e).collect(),
+        elapsed_ms: u64::try_from(result.elapsed.as_millis()).unwrap_or(u64::MAX),
+        bytes_processed: result.bytes_processed,
     })
 }
 
+/// Stop the indexing run in progress after the file it is on. The run keeps
+/// its slot until it has stopped. Returns whether there was a run to cancel.
+#[tauri::command]
+fn cancel_index(job: tauri::State<'_, IndexJob>) -> bool {
+    match job.0.lock().unwrap().as_ref() {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Why the config file was rejected at startup, if it was. The app then runs
+/// on the default settings.
+struct ConfigLoadError(Option<String>);
+
+/// The problem with the config file, for the UI to show.
+#[tauri::command]
+fn get_config_error(error: tauri::State<'_, ConfigLoadError>) -> Option<String> {
+    error.0.clone()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Read once at startup; edits to the config file apply after a restart
+    let (config, config_error) = match NexusConfig::load() {
+        Ok(config) => (config, None),
+        Err(e) => {
+            eprintln!("warning: failed to load the Nexus config, using the defaults: {:#}", e);
+            (NexusConfig::default(), Some(format!("{:#}", e)))
+        }
+    };
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .manage(config)
+        .manage(ConfigLoadError(config_error))
+        .manage(IndexJob::default())
         .invoke_handler(tauri::generate_handler![
             search,
+            highlight_document,
+            get_file_chunks,
             get_status,
             index_directory,
+            cancel_index,
+            get_config_error,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");